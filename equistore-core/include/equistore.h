@@ -13,6 +13,44 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * Data type for 64-bit floating point values (`double` in C)
+ */
+#define EQS_DTYPE_FLOAT64 1
+
+/**
+ * Data type for 32-bit floating point values (`float` in C)
+ */
+#define EQS_DTYPE_FLOAT32 2
+
+/**
+ * Data type for 16-bit floating point values (IEEE 754 half-precision)
+ */
+#define EQS_DTYPE_FLOAT16 3
+
+/**
+ * Data type for 32-bit signed integers (`int32_t` in C)
+ */
+#define EQS_DTYPE_INT32 4
+
+/**
+ * Data type for 64-bit signed integers (`int64_t` in C)
+ */
+#define EQS_DTYPE_INT64 5
+
+/**
+ * The data lives in host memory (RAM), and can be accessed directly with
+ * `eqs_array_t.data`
+ */
+#define EQS_DEVICE_CPU 1
+
+/**
+ * The data lives outside of host memory (GPU memory, memory-mapped file,
+ * remote machine, ...), and must be copied with `eqs_array_t.copy_to_host`
+ * before being accessed
+ */
+#define EQS_DEVICE_OTHER 2
+
 /**
  * Status code used when a function succeeded
  */
@@ -93,12 +131,12 @@ typedef struct eqs_labels_t {
    */
   const char *const *names;
   /**
-   * Pointer to the first element of a 2D row-major array of 32-bit signed
+   * Pointer to the first element of a 2D row-major array of 64-bit signed
    * integer containing the values taken by the different variables in
    * `names`. Each row has `size` elements, and there are `count` rows in
    * total.
    */
-  const int32_t *values;
+  const int64_t *values;
   /**
    * Number of variables/size of a single entry in the set of labels
    */
@@ -115,6 +153,18 @@ typedef struct eqs_labels_t {
  */
 typedef uint64_t eqs_data_origin_t;
 
+/**
+ * Type of the data stored inside an `eqs_array_t`, as one of the
+ * `EQS_DTYPE_XXX` constants.
+ */
+typedef int32_t eqs_dtype_t;
+
+/**
+ * Device where the data of an `eqs_array_t` lives, as one of the
+ * `EQS_DEVICE_XXX` constants.
+ */
+typedef int32_t eqs_device_t;
+
 /**
  * Representation of a single sample moved from an array to another one
  */
@@ -134,7 +184,8 @@ typedef struct eqs_sample_mapping_t {
  * map. The array itself if opaque to this library and can come from multiple
  * sources: Rust program, a C/C++ program, a Fortran program, Python with numpy
  * or torch. The data does not have to live on CPU, or even on the same machine
- * where this code is executed.
+ * where this code is executed: arrays living elsewhere should report it with
+ * `device`, and implement `copy_to_host` to make their data available.
  *
  * This struct contains a C-compatible manual implementation of a virtual table
  * (vtable, i.e. trait in Rust, pure virtual class in C++); allowing
@@ -158,13 +209,37 @@ typedef struct eqs_array_t {
    */
   eqs_status_t (*origin)(const void *array, eqs_data_origin_t *origin);
   /**
-   * Get a pointer to the underlying data storage.
+   * Get the type of the data stored in this array in `dtype`, as one of the
+   * `EQS_DTYPE_XXX` constants. This function can be set to `NULL`, in
+   * which case the data is assumed to contain 64-bit floating point values.
+   */
+  eqs_status_t (*dtype)(const void *array, eqs_dtype_t *dtype);
+  /**
+   * Get a pointer to the underlying data storage. The data is interpreted
+   * according to the type given by `eqs_array_t.dtype`.
    *
    * This function is allowed to fail if the data is not accessible in RAM,
-   * not stored as 64-bit floating point values, or not stored as a
-   * C-contiguous array.
+   * or not stored as a C-contiguous array.
+   */
+  eqs_status_t (*data)(void *array, void **data);
+  /**
+   * Get the device where the data of this array lives in `device`, as one
+   * of the `EQS_DEVICE_XXX` constants. This function can be set to `NULL`,
+   * in which case the data is assumed to live in host memory
+   * (`EQS_DEVICE_CPU`).
+   */
+  eqs_status_t (*device)(const void *array, eqs_device_t *device);
+  /**
+   * Copy all the data of this array to host memory in `buffer`, as a
+   * C-contiguous array with the type given by `eqs_array_t.dtype`.
+   * `buffer_size` is the size of `buffer` in bytes, and will always be the
+   * number of elements in the array times the size of the data type.
+   *
+   * This function is only used for arrays which are not on
+   * `EQS_DEVICE_CPU`, and can be set to `NULL` for arrays living in host
+   * memory.
    */
-  eqs_status_t (*data)(void *array, double **data);
+  eqs_status_t (*copy_to_host)(const void *array, void *buffer, uintptr_t buffer_size);
   /**
    * Get the shape of the array managed by this `eqs_array_t` in the `*shape`
    * pointer, and the number of dimension (size of the `*shape` array) in
@@ -189,7 +264,10 @@ typedef struct eqs_array_t {
    *
    * The new array should be filled with zeros.
    */
-  eqs_status_t (*create)(const void *array, const uintptr_t *shape, uintptr_t shape_count, struct eqs_array_t *new_array);
+  eqs_status_t (*create)(const void *array,
+                         const uintptr_t *shape,
+                         uintptr_t shape_count,
+                         struct eqs_array_t *new_array);
   /**
    * Make a copy of this `array` and return the new array in `new_array`.
    *
@@ -215,7 +293,76 @@ typedef struct eqs_array_t {
    * `array[samples[i].output, ..., property_start:property_end]` for `i` up
    * to `samples_count`. All indexes are 0-based.
    */
-  eqs_status_t (*move_samples_from)(void *output, const void *input, const struct eqs_sample_mapping_t *samples, uintptr_t samples_count, uintptr_t property_start, uintptr_t property_end);
+  eqs_status_t (*move_samples_from)(void *output,
+                                    const void *input,
+                                    const struct eqs_sample_mapping_t *samples,
+                                    uintptr_t samples_count,
+                                    uintptr_t property_start,
+                                    uintptr_t property_end);
+  /**
+   * Set entries in the `output` array (the current array) by selecting data
+   * from the `input` array along a single `axis`. The `output` array is
+   * guaranteed to be created by calling `eqs_array_t::create` with one of
+   * the arrays in the same block or tensor map as the `input`, and has the
+   * same shape as `input` except along `axis`, where it has `indices_count`
+   * entries.
+   *
+   * This function should copy data from `input[..., indices[i], ...]` to
+   * `array[..., i, ...]` (where the indexing is done along `axis`) for `i`
+   * up to `indices_count`. All indexes are 0-based.
+   */
+  eqs_status_t (*gather_from)(void *output,
+                              const void *input,
+                              uintptr_t axis,
+                              const uintptr_t *indices,
+                              uintptr_t indices_count);
+  /**
+   * Add entries from the `input` array to the `output` array (the current
+   * array) along a single `axis`. The `output` array is guaranteed to be
+   * created by calling `eqs_array_t::create` with one of the arrays in the
+   * same block or tensor map as the `input`, and has the same shape as
+   * `input` except along `axis`. The `input` array has `indices_count`
+   * entries along `axis`.
+   *
+   * This function should add data from `input[..., i, ...]` to
+   * `array[..., indices[i], ...]` (where the indexing is done along `axis`)
+   * for `i` up to `indices_count`. Multiple entries in `indices` can have
+   * the same value, in which case the data from all the corresponding
+   * `input` entries should be summed together. All indexes are 0-based.
+   */
+  eqs_status_t (*scatter_add_from)(void *output,
+                                   const void *input,
+                                   uintptr_t axis,
+                                   const uintptr_t *indices,
+                                   uintptr_t indices_count);
+  /**
+   * Set entries in the `output` array (the current array) taking data from
+   * the `input` array, remapping both samples and properties. The `output`
+   * array is guaranteed to be created by calling `eqs_array_t::create` with
+   * one of the arrays in the same block or tensor map as the `input`, and
+   * all dimensions except the first (samples) and last (properties) have
+   * the same size in `input` and `output`.
+   *
+   * The `samples` array of size `samples_count` indicate where the data
+   * should be moved along the sample axis. The `properties` array of size
+   * `properties_count` contains one entry for each property in `input`,
+   * and indicates where this property should be moved in `output`.
+   *
+   * This function should copy data from `input[samples[i].input, ..., j]`
+   * to `array[samples[i].output, ..., properties[j]]` for `i` up to
+   * `samples_count` and `j` up to `properties_count`. All indexes are
+   * 0-based.
+   *
+   * This function can be set to `NULL`, in which case `move_samples_from`
+   * is used instead when the properties are moved to a contiguous range in
+   * `output`, and an error is returned otherwise.
+   */
+  eqs_status_t (*move_data)(void *output,
+                            const void *input,
+                            const struct eqs_sample_mapping_t *samples,
+                            uintptr_t samples_count,
+                            const uintptr_t *properties,
+                            uintptr_t properties_count);
 } eqs_array_t;
 
 /**
@@ -223,14 +370,18 @@ typedef struct eqs_array_t {
  * maps.
  *
  * This function gets the `shape` of the array (the `shape` contains
- * `shape_count` elements) and should return a new valid `eqs_array_t` or a
- * non-zero `eqs_status_t`.
+ * `shape_count` elements) and the data type of the array (`dtype`, one of the
+ * `EQS_DTYPE_XXX` constants), and should return a new valid `eqs_array_t` or
+ * a non-zero `eqs_status_t`.
  *
- * The newly created array should contains 64-bit floating points (`double`)
- * data, and live on CPU, since equistore will use `eqs_array_t.data` to get
- * the data pointer and write to it.
+ * The newly created array should contains data of the requested type, and
+ * live on CPU, since equistore will use `eqs_array_t.data` to get the data
+ * pointer and write to it.
  */
-typedef eqs_status_t (*eqs_create_array_callback_t)(const uintptr_t *shape, uintptr_t shape_count, struct eqs_array_t *array);
+typedef eqs_status_t (*eqs_create_array_callback_t)(const uintptr_t *shape,
+                                                    uintptr_t shape_count,
+                                                    eqs_dtype_t dtype,
+                                                    struct eqs_array_t *array);
 
 #ifdef __cplusplus
 extern "C" {
@@ -276,10 +427,46 @@ const char *eqs_last_error(void);
  *          error message.
  */
 eqs_status_t eqs_labels_position(struct eqs_labels_t labels,
-                                 const int32_t *values,
+                                 const int64_t *values,
                                  uintptr_t values_count,
                                  int64_t *result);
 
+/**
+ * Get the positions of multiple entries in the given set of `labels` at once.
+ *
+ * `values` should contain the `count` entries to look for, stored as a 2D
+ * row-major array of shape `(count, labels.size)`. The position of each entry
+ * is written in the corresponding element of `results`, or -1 if this entry
+ * is not part of the labels.
+ *
+ * Unlike `eqs_labels_position`, this function also accepts labels which did
+ * not go through `eqs_labels_create`. The data of such labels is copied on
+ * each call, so you should still use `eqs_labels_create` first if you need to
+ * call this function multiple times.
+ *
+ * If `parallel` is true, the entries are looked for using multiple threads.
+ * This is only supported if equistore-core was compiled with the `rayon`
+ * feature (`EQUISTORE_ENABLE_RAYON=ON` in CMake), and the lookup happens on
+ * the current thread otherwise.
+ *
+ * @param labels set of labels in which to look for the entries
+ * @param values pointer to the first element of a 2D array containing the
+ *               entries to look for
+ * @param count number of entries (i.e. rows) in `values`
+ * @param parallel whether to look for the entries in parallel
+ * @param results pointer to an array containing `count` elements, to be
+ *                filled with the positions of the entries in `labels`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_positions(struct eqs_labels_t labels,
+                                  const int64_t *values,
+                                  uintptr_t count,
+                                  bool parallel,
+                                  int64_t *results);
+
 /**
  * Finish the creation of `eqs_labels_t` by associating it to Rust-owned
  * labels.
@@ -315,6 +502,312 @@ eqs_status_t eqs_labels_create(struct eqs_labels_t *labels);
  */
 eqs_status_t eqs_labels_clone(struct eqs_labels_t labels, struct eqs_labels_t *clone);
 
+/**
+ * Check whether the entries in `labels` are sorted in lexicographic order.
+ *
+ * This information is tracked when creating the labels, making this check
+ * cheap for labels with a non-NULL `internal_ptr_`. Lookup in sorted labels
+ * (with `eqs_labels_position` and similar functions) uses binary search
+ * instead of a hash table, and bindings can rely on this ordering as well.
+ *
+ * @param labels set of labels to check
+ * @param is_sorted pointer to a boolean, will be set to `true` if the labels
+ *                  are sorted and `false` otherwise
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_is_sorted(struct eqs_labels_t labels, bool *is_sorted);
+
+/**
+ * Sort the entries in `labels` in lexicographic order.
+ *
+ * If requested, this function can also give the permutation used to sort
+ * the labels: the entry at position `i` in `result` was at position
+ * `permutation[i]` in `labels`.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param labels set of labels to sort
+ * @param result empty labels, on output will contain the sorted labels
+ * @param permutation if you want the permutation used to sort the labels,
+ *        this should be a pointer to an array containing `labels.count`
+ *        elements, to be filled by this function. Otherwise it should be a
+ *        `NULL` pointer.
+ * @param permutation_count number of elements in `permutation`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_sort(struct eqs_labels_t labels,
+                             struct eqs_labels_t *result,
+                             int64_t *permutation,
+                             uintptr_t permutation_count);
+
+/**
+ * Select entries in the `labels` that match the `selection`.
+ *
+ * The `selection` should have a subset of the names/variables of the
+ * `labels`, and an entry of the `labels` is selected if the values of these
+ * variables match any of the entries in the `selection`. If the `selection`
+ * does not contain any variable, all entries are selected.
+ *
+ * When calling this function, `*selected_count` should contain the number of
+ * entries in `selected`, which must be the same as `labels.count`. When the
+ * function returns successfully, `*selected_count` will contain the number of
+ * selected entries, i.e. how many values were written to `selected`. The
+ * positions are written in increasing order.
+ *
+ * @param labels labels on which to run the selection
+ * @param selection definition of the selection criteria
+ * @param selected array to be filled with the positions of the selected
+ *                 entries in `labels`
+ * @param selected_count number of entries in `selected`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_select(struct eqs_labels_t labels,
+                               struct eqs_labels_t selection,
+                               uintptr_t *selected,
+                               uintptr_t *selected_count);
+
+/**
+ * Take the union of two `eqs_labels_t`.
+ *
+ * The result contains all the entries of `first`, followed by the entries of
+ * `second` which are not in `first`. Both labels must have the same names.
+ *
+ * If requested, this function can also give the positions in the union where
+ * each entry of the input `eqs_labels_t` ended up.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param first first set of labels
+ * @param second second set of labels
+ * @param result empty labels, on output will contain the union of `first` and
+ *        `second`
+ * @param first_mapping if you want the mapping from the positions of entries
+ *        in `first` to the positions in `result`, this should be a pointer
+ *        to an array containing `first.count` elements, to be filled by this
+ *        function. Otherwise it should be a `NULL` pointer.
+ * @param first_mapping_count number of elements in `first_mapping`
+ * @param second_mapping if you want the mapping from the positions of entries
+ *        in `second` to the positions in `result`, this should be a pointer
+ *        to an array containing `second.count` elements, to be filled by this
+ *        function. Otherwise it should be a `NULL` pointer.
+ * @param second_mapping_count number of elements in `second_mapping`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_union(struct eqs_labels_t first,
+                              struct eqs_labels_t second,
+                              struct eqs_labels_t *result,
+                              int64_t *first_mapping,
+                              uintptr_t first_mapping_count,
+                              int64_t *second_mapping,
+                              uintptr_t second_mapping_count);
+
+/**
+ * Take the intersection of two `eqs_labels_t`.
+ *
+ * The result contains all the entries of `first` which are also in `second`,
+ * in the same order as in `first`. Both labels must have the same names.
+ *
+ * If requested, this function can also give the positions in the
+ * intersection where each entry of the input `eqs_labels_t` ended up, or -1
+ * if the entry is not part of the intersection.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param first first set of labels
+ * @param second second set of labels
+ * @param result empty labels, on output will contain the intersection of
+ *        `first` and `second`
+ * @param first_mapping if you want the mapping from the positions of entries
+ *        in `first` to the positions in `result`, this should be a pointer
+ *        to an array containing `first.count` elements, to be filled by this
+ *        function. Otherwise it should be a `NULL` pointer.
+ * @param first_mapping_count number of elements in `first_mapping`
+ * @param second_mapping if you want the mapping from the positions of entries
+ *        in `second` to the positions in `result`, this should be a pointer
+ *        to an array containing `second.count` elements, to be filled by this
+ *        function. Otherwise it should be a `NULL` pointer.
+ * @param second_mapping_count number of elements in `second_mapping`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_intersection(struct eqs_labels_t first,
+                                     struct eqs_labels_t second,
+                                     struct eqs_labels_t *result,
+                                     int64_t *first_mapping,
+                                     uintptr_t first_mapping_count,
+                                     int64_t *second_mapping,
+                                     uintptr_t second_mapping_count);
+
+/**
+ * Take the difference of two `eqs_labels_t`.
+ *
+ * The result contains all the entries of `first` which are not in `second`,
+ * in the same order as in `first`. Both labels must have the same names.
+ *
+ * If requested, this function can also give the positions in the difference
+ * where each entry of `first` ended up, or -1 if the entry is not part of the
+ * difference.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param first first set of labels
+ * @param second second set of labels
+ * @param result empty labels, on output will contain the entries of `first`
+ *        which are not in `second`
+ * @param first_mapping if you want the mapping from the positions of entries
+ *        in `first` to the positions in `result`, this should be a pointer
+ *        to an array containing `first.count` elements, to be filled by this
+ *        function. Otherwise it should be a `NULL` pointer.
+ * @param first_mapping_count number of elements in `first_mapping`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_difference(struct eqs_labels_t first,
+                                   struct eqs_labels_t second,
+                                   struct eqs_labels_t *result,
+                                   int64_t *first_mapping,
+                                   uintptr_t first_mapping_count);
+
+/**
+ * Project `labels` onto the variables with the given `names`, keeping only
+ * these variables (in the given order) and removing duplicated entries. The
+ * remaining entries are kept in the order of their first appearance.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param labels set of labels to project
+ * @param names names of the variables to keep, as an array of NULL-terminated
+ *              UTF-8 strings
+ * @param names_count number of entries in `names`
+ * @param result empty labels, on output will contain the projected labels
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_project(struct eqs_labels_t labels,
+                                const char *const *names,
+                                uintptr_t names_count,
+                                struct eqs_labels_t *result);
+
+/**
+ * Insert a new variable with the given `name` at position `index` in the
+ * `labels`.
+ *
+ * `values` must either contain a single value, used for all entries, or one
+ * value for each entry in the `labels`.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param labels set of labels in which to insert a new variable
+ * @param index position of the new variable, between 0 and `labels.size`
+ * @param name NULL-terminated UTF-8 string containing the name of the new
+ *             variable
+ * @param values values of the new variable
+ * @param values_count number of entries in `values`, either 1 or
+ *                     `labels.count`
+ * @param result empty labels, on output will contain the new labels
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_insert(struct eqs_labels_t labels,
+                               uintptr_t index,
+                               const char *name,
+                               const int64_t *values,
+                               uintptr_t values_count,
+                               struct eqs_labels_t *result);
+
+/**
+ * Remove the variable with the given `name` from the `labels`.
+ *
+ * This returns an error if removing the variable would create duplicated
+ * entries, use `eqs_labels_project` to remove the duplicates instead.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param labels set of labels from which to remove a variable
+ * @param name NULL-terminated UTF-8 string containing the name of the
+ *             variable to remove
+ * @param result empty labels, on output will contain the new labels
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_remove(struct eqs_labels_t labels,
+                               const char *name,
+                               struct eqs_labels_t *result);
+
+/**
+ * Rename the variable `old_name` to `new_name` in the `labels`.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param labels set of labels in which to rename a variable
+ * @param old_name NULL-terminated UTF-8 string containing the current name of
+ *                 the variable
+ * @param new_name NULL-terminated UTF-8 string containing the new name of the
+ *                 variable
+ * @param result empty labels, on output will contain the new labels
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_rename(struct eqs_labels_t labels,
+                               const char *old_name,
+                               const char *new_name,
+                               struct eqs_labels_t *result);
+
+/**
+ * Reorder the variables in the `labels`, following the given `names`. The
+ * `names` must contain all the variables of the `labels` exactly once. The
+ * order of the entries is not modified.
+ *
+ * This function allocates memory for `result` which must be released with
+ * `eqs_labels_free` when you don't need it anymore.
+ *
+ * @param labels set of labels to reorder
+ * @param names new order of the variables, as an array of NULL-terminated
+ *              UTF-8 strings
+ * @param names_count number of entries in `names`
+ * @param result empty labels, on output will contain the reordered labels
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_labels_reorder(struct eqs_labels_t labels,
+                                const char *const *names,
+                                uintptr_t names_count,
+                                struct eqs_labels_t *result);
+
 /**
  * Decrease the reference count of `labels`, and release the corresponding
  * memory once the reference count reaches 0.
@@ -353,6 +846,29 @@ eqs_status_t eqs_register_data_origin(const char *name, eqs_data_origin_t *origi
  */
 eqs_status_t eqs_get_data_origin(eqs_data_origin_t origin, char *buffer, uintptr_t buffer_size);
 
+/**
+ * Check that the given `array` correctly implements the functions in
+ * `eqs_array_t`, by creating new arrays from it and exercising `origin`,
+ * `dtype`, `device`, `data`/`copy_to_host`, `shape`, `reshape`, `swap_axes`,
+ * `create`, `copy`, `move_samples_from`, `move_data` (if it is set),
+ * `gather_from`, `scatter_add_from` and `destroy`.
+ *
+ * The `array` itself is not modified by this function. The checks on the
+ * values stored in the arrays are only meaningful for arrays on
+ * `EQS_DEVICE_CPU`, since equistore can not write data to other devices.
+ *
+ * This function is intended to be used in the test suite of code
+ * implementing `eqs_array_t`.
+ *
+ * @param array array to check
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, the array does not correctly implement
+ *          `eqs_array_t`, and you can use `eqs_last_error()` to get an error
+ *          message describing which contract was violated.
+ */
+eqs_status_t eqs_array_check(const struct eqs_array_t *array);
+
 /**
  * Create a new `eqs_block_t` with the given `data` and `samples`, `components`
  * and `properties` labels.
@@ -494,6 +1010,119 @@ eqs_status_t eqs_block_gradients_list(const struct eqs_block_t *block,
                                       const char *const **parameters,
                                       uintptr_t *parameters_count);
 
+/**
+ * Get the value associated with `key` in the info of the values or one of
+ * the gradients of this `block`.
+ *
+ * The `values_gradients` parameter controls whether this function looks up
+ * the info for `"values"` or one of the gradients in this block. `value` is
+ * set to a NULL-terminated UTF-8 string, or to `NULL` if there is no info
+ * associated with `key`. The string memory is still managed by the block,
+ * and is invalidated when the block is freed or its info modified with
+ * `eqs_block_set_info`.
+ *
+ * @param block pointer to an existing block
+ * @param values_gradients either `"values"` or the name of gradients to lookup
+ * @param key NULL-terminated UTF-8 string containing the info key
+ * @param value pointer to be filled with the info value
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_block_info(const struct eqs_block_t *block,
+                            const char *values_gradients,
+                            const char *key,
+                            const char **value);
+
+/**
+ * Set the value associated with `key` in the info of the values or one of
+ * the gradients of this `block`, overwriting any existing value.
+ *
+ * If `value` is `NULL`, the info associated with `key` is removed instead.
+ *
+ * @param block pointer to an existing block
+ * @param values_gradients either `"values"` or the name of gradients to modify
+ * @param key NULL-terminated UTF-8 string containing the info key
+ * @param value NULL-terminated UTF-8 string containing the info value, or
+ *              `NULL` to remove the info
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_block_set_info(struct eqs_block_t *block,
+                                const char *values_gradients,
+                                const char *key,
+                                const char *value);
+
+/**
+ * Get the list of all keys in the info of the values or one of the gradients
+ * of this `block`, sorted alphabetically.
+ *
+ * The strings memory is still managed by the block, and is invalidated when
+ * the block is freed or its info modified with `eqs_block_set_info`.
+ *
+ * @param block pointer to an existing block
+ * @param values_gradients either `"values"` or the name of gradients to lookup
+ * @param keys will be set to the first element of an array of NULL-terminated
+ *             UTF-8 strings containing all the info keys
+ * @param keys_count will be set to the number of elements in `keys`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_block_info_keys(const struct eqs_block_t *block,
+                                 const char *values_gradients,
+                                 const char *const **keys,
+                                 uintptr_t *keys_count);
+
+/**
+ * Slice this `block` along the given `axis`, keeping only the samples or
+ * properties matching the `selection`.
+ *
+ * The `selection` must contain a subset of the names of the samples or
+ * properties labels, and an entry is kept if the values of these variables
+ * match one of the entries in the `selection`. When slicing along samples,
+ * the gradient samples referring to removed samples are removed as well.
+ *
+ * This function requires `eqs_array_t.gather_from` to be implemented. The
+ * result is a new block, which should be freed with `eqs_block_free`.
+ *
+ * @param block pointer to an existing block
+ * @param axis name of the axis along which the block should be sliced, either
+ *             `"samples"` or `"properties"`
+ * @param selection labels describing which entries should be kept
+ *
+ * @returns A pointer to the newly allocated block, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_block_t *eqs_block_slice(const struct eqs_block_t *block,
+                                    const char *axis,
+                                    struct eqs_labels_t selection);
+
+/**
+ * Sort the entries of this `block` along the given `axis` in lexicographic
+ * order.
+ *
+ * When sorting along samples, the gradient samples are updated to refer to the
+ * new samples, and then sorted as well.
+ *
+ * This function requires `eqs_array_t.gather_from` to be implemented. The
+ * result is a new block, which should be freed with `eqs_block_free`.
+ *
+ * @param block pointer to an existing block
+ * @param axis axis along which to sort the block, either `"samples"` or
+ *             `"properties"`
+ *
+ * @returns A pointer to the newly allocated block, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_block_t *eqs_block_sort(const struct eqs_block_t *block, const char *axis);
+
 /**
  * Create a new `eqs_tensormap_t` with the given `keys` and `blocks`.
  * `blocks_count` must be set to the number of entries in the blocks array.
@@ -545,6 +1174,69 @@ eqs_status_t eqs_tensormap_free(struct eqs_tensormap_t *tensor);
  */
 eqs_status_t eqs_tensormap_keys(const struct eqs_tensormap_t *tensor, struct eqs_labels_t *keys);
 
+/**
+ * Get the value associated with `key` in the tensor-level metadata of this
+ * `tensor` map.
+ *
+ * `value` is set to a NULL-terminated UTF-8 string, or to `NULL` if there is
+ * no metadata associated with `key`. The string memory is still managed by
+ * the tensor map, and is invalidated when the tensor map is freed or its
+ * metadata modified with `eqs_tensormap_set_metadata`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param key NULL-terminated UTF-8 string containing the metadata key
+ * @param value pointer to be filled with the metadata value
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_tensormap_metadata(const struct eqs_tensormap_t *tensor,
+                                    const char *key,
+                                    const char **value);
+
+/**
+ * Set the value associated with `key` in the tensor-level metadata of this
+ * `tensor` map, overwriting any existing value.
+ *
+ * If `value` is `NULL`, the metadata associated with `key` is removed
+ * instead. The metadata is saved and loaded together with the data by
+ * `eqs_tensormap_save` and `eqs_tensormap_load`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param key NULL-terminated UTF-8 string containing the metadata key
+ * @param value NULL-terminated UTF-8 string containing the metadata value,
+ *              or `NULL` to remove the metadata
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_tensormap_set_metadata(struct eqs_tensormap_t *tensor,
+                                        const char *key,
+                                        const char *value);
+
+/**
+ * Get the list of all keys in the tensor-level metadata of this `tensor` map,
+ * sorted alphabetically.
+ *
+ * The strings memory is still managed by the tensor map, and is invalidated
+ * when the tensor map is freed or its metadata modified with
+ * `eqs_tensormap_set_metadata`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param keys will be set to the first element of an array of NULL-terminated
+ *             UTF-8 strings containing all the metadata keys
+ * @param keys_count will be set to the number of elements in `keys`
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_tensormap_metadata_keys(const struct eqs_tensormap_t *tensor,
+                                         const char *const **keys,
+                                         uintptr_t *keys_count);
+
 /**
  * Get a pointer to the `index`-th block in this tensor map.
  *
@@ -568,8 +1260,9 @@ eqs_status_t eqs_tensormap_block_by_id(struct eqs_tensormap_t *tensor,
 /**
  * Get indices of the blocks in this `tensor` corresponding to the given
  * `selection`. The `selection` should have a subset of the names/variables of
- * the keys for this tensor map, and only one entry, describing the requested
- * blocks.
+ * the keys for this tensor map, and one or more entries describing the
+ * requested blocks. A block is selected if its key matches any of the entries
+ * in the `selection`.
  *
  * When calling this function, `*count` should contain the number of entries in
  * `block_indexes`. When the function returns successfully, `*count` will
@@ -580,7 +1273,7 @@ eqs_status_t eqs_tensormap_block_by_id(struct eqs_tensormap_t *tensor,
  * @param block_indexes array to be filled with indexes of blocks in the tensor
  *                      map matching the `selection`
  * @param count number of entries in `block_indexes`
- * @param selection labels with a single entry describing which blocks are requested
+ * @param selection labels describing which blocks are requested
  *
  * @returns The status code of this operation. If the status is not
  *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
@@ -619,12 +1312,23 @@ eqs_status_t eqs_tensormap_blocks_matching(const struct eqs_tensormap_t *tensor,
  * lexicographically sorted. Otherwise they are kept in the order in which
  * they appear in the blocks.
  *
+ * Similarly, if `sort_properties` is true, the new property labels are
+ * re-ordered to keep them lexicographically sorted. Otherwise they are kept
+ * in the order in which they appear in the blocks.
+ *
+ * This function uses `eqs_array_t.move_data` to move the data of all arrays
+ * in this tensor map. If this function is NULL, `eqs_array_t.move_samples_from`
+ * is used instead, which only works if the properties of each block are moved
+ * to a contiguous range in the new block.
+ *
  * The result is a new tensor map, which should be freed with `eqs_tensormap_free`.
  *
  * @param tensor pointer to an existing tensor map
  * @param keys_to_move description of the keys to move
  * @param sort_samples whether to sort the samples lexicographically after
  *                     merging blocks
+ * @param sort_properties whether to sort the properties lexicographically
+ *                        after merging blocks
  *
  * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
  *          case of error. In case of error, you can use `eqs_last_error()`
@@ -632,7 +1336,8 @@ eqs_status_t eqs_tensormap_blocks_matching(const struct eqs_tensormap_t *tensor,
  */
 struct eqs_tensormap_t *eqs_tensormap_keys_to_properties(const struct eqs_tensormap_t *tensor,
                                                          struct eqs_labels_t keys_to_move,
-                                                         bool sort_samples);
+                                                         bool sort_samples,
+                                                         bool sort_properties);
 
 /**
  * Move the given variables from the component labels to the property labels
@@ -653,6 +1358,52 @@ struct eqs_tensormap_t *eqs_tensormap_components_to_properties(struct eqs_tensor
                                                                const char *const *variables,
                                                                uintptr_t variables_count);
 
+/**
+ * Move the given variables from the component labels to the sample labels
+ * for each block in this tensor map.
+ *
+ * `variables` must be an array of `variables_count` NULL-terminated strings,
+ * encoded as UTF-8. The component is appended to the samples, with the
+ * component values varying faster than the old samples. Each gradient sample
+ * is duplicated for all the values of the component.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param variables names of the component variables to move to the samples
+ * @param variables_count number of entries in the `variables` array
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_components_to_samples(const struct eqs_tensormap_t *tensor,
+                                                            const char *const *variables,
+                                                            uintptr_t variables_count);
+
+/**
+ * Move the given variables from the property labels to a new component for
+ * each block in this tensor map.
+ *
+ * `variables` must be an array of `variables_count` NULL-terminated strings,
+ * encoded as UTF-8. This is the inverse of
+ * `eqs_tensormap_components_to_properties`: the new component is added after
+ * the existing ones, and the properties must contain all the combinations of
+ * the values of `variables` with the values of the remaining property
+ * variables, with the moved variables varying either slower or faster than
+ * the remaining ones. If all the property variables are moved, the new
+ * properties contain a single `"_"` variable.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param variables names of the property variables to move to the components
+ * @param variables_count number of entries in the `variables` array
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_properties_to_components(const struct eqs_tensormap_t *tensor,
+                                                               const char *const *variables,
+                                                               uintptr_t variables_count);
+
 /**
  * Merge blocks with the same value for selected keys variables along the
  * samples axis.
@@ -661,15 +1412,22 @@ struct eqs_tensormap_t *eqs_tensormap_components_to_properties(struct eqs_tensor
  * the sample labels, and blocks with the same remaining keys variables
  * will be merged together along the sample axis.
  *
- * `keys_to_move` must be empty (`keys_to_move.count == 0`), and the new
- * sample labels will contain entries corresponding to the merged blocks'
- * keys.
- *
- * The new sample labels will contains all of the merged blocks sample
- * labels. The order of the samples is controlled by `sort_samples`. If
+ * If `keys_to_move` does not contains any entries (`keys_to_move.count
+ * == 0`), then the new sample labels will contain entries corresponding
+ * to the merged blocks' keys only.
+ *
+ * If `keys_to_move` contains entries, then the new sample labels will
+ * contain all the merged samples, combined with each of the entries of
+ * `keys_to_move`. For example, using `a=2, 3` in `keys_to_move` with blocks
+ * with samples `s=1, 2` and `s=1, 3` will result in `s, a = (1, 2), (2, 2),
+ * (3, 2), (1, 3), (2, 3), (3, 3)`. The data for samples which were not
+ * present in any of the merged blocks is filled with zeros, and blocks with
+ * a key not in `keys_to_move` are ignored.
+ *
+ * The order of the samples is controlled by `sort_samples`. If
  * `sort_samples` is true, samples are re-ordered to keep them
  * lexicographically sorted. Otherwise they are kept in the order in which
- * they appear in the blocks.
+ * they appear in the blocks (and in `keys_to_move`).
  *
  * This function is only implemented if all merged block have the same
  * property labels.
@@ -687,6 +1445,192 @@ struct eqs_tensormap_t *eqs_tensormap_keys_to_samples(const struct eqs_tensormap
                                                       struct eqs_labels_t keys_to_move,
                                                       bool sort_samples);
 
+/**
+ * Join the `tensors_count` tensor maps in `tensors` along the given `axis`,
+ * creating a single tensor map.
+ *
+ * `axis` must be either `"samples"` or `"properties"`. All tensor maps must
+ * have the same keys, and blocks with the same key must have the same
+ * components, gradients, and the same samples or properties labels (i.e. the
+ * labels for the axis which is not joined).
+ *
+ * Clashes between the labels along the joined `axis` are resolved by keeping
+ * the same names if all entries are unique; adding a new `"tensor"` variable
+ * containing the index of the tensor map if the names are the same but some
+ * entries are duplicated; and using `"tensor", "sample"` or `"tensor",
+ * "property"` (depending on `axis`) as new names if the labels have different
+ * names.
+ *
+ * The result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+ *
+ * @param tensors pointer to the first element of an array of tensor maps
+ * @param tensors_count number of elements in the `tensors` array
+ * @param axis name of the axis along which the tensor maps should be joined
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_join(const struct eqs_tensormap_t *const *tensors,
+                                           uintptr_t tensors_count,
+                                           const char *axis);
+
+/**
+ * Reduce the blocks in this `tensor` over the sample variables in `names`,
+ * combining together all the samples which only differ by the value of these
+ * variables.
+ *
+ * `names` must be an array of `names_count` NULL-terminated strings, encoded
+ * as UTF-8. `reduction` must be one of `"sum"`, `"mean"`, `"variance"` or
+ * `"std"`.
+ *
+ * The new samples contain the remaining sample variables (or a single `"_"`
+ * variable if all variables are reduced over), and are sorted
+ * lexicographically. The gradients are reduced accordingly.
+ *
+ * The `"sum"` reduction only requires `eqs_array_t.scatter_add_from`, while
+ * the other reductions also need `eqs_array_t.data` to be available, and only
+ * support arrays containing 64-bit floating point values.
+ *
+ * The result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param names names of the sample variables to reduce over
+ * @param names_count number of entries in the `names` array
+ * @param reduction name of the reduction to perform
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_reduce_over_samples(const struct eqs_tensormap_t *tensor,
+                                                          const char *const *names,
+                                                          uintptr_t names_count,
+                                                          const char *reduction);
+
+/**
+ * Slice all the blocks in this `tensor` along the given `axis`, keeping only
+ * the samples or properties matching the `selection`.
+ *
+ * The `selection` must contain a subset of the names of the samples or
+ * properties labels, and an entry is kept if the values of these variables
+ * match one of the entries in the `selection`. The keys of the new tensor map
+ * are the same as the keys of `tensor`, and blocks can end up with zero
+ * samples or properties if none of them match the `selection`.
+ *
+ * This function requires `eqs_array_t.gather_from` to be implemented. The
+ * result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param axis name of the axis along which the tensor map should be sliced,
+ *             either `"samples"` or `"properties"`
+ * @param selection labels describing which entries should be kept
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_slice(const struct eqs_tensormap_t *tensor,
+                                            const char *axis,
+                                            struct eqs_labels_t selection);
+
+/**
+ * Split this `tensor` into multiple tensor maps along the given `axis`,
+ * creating one new tensor map for each of the `selections`.
+ *
+ * This is equivalent to calling `eqs_tensormap_slice` with each of the
+ * `selections`, which must all have the same names. On success, `results`
+ * will contain `selections_count` newly allocated tensor maps, which should
+ * be freed with `eqs_tensormap_free`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param axis name of the axis along which the tensor map should be split,
+ *             either `"samples"` or `"properties"`
+ * @param selections array of labels describing which entries should be kept
+ *                   in each of the new tensor maps
+ * @param selections_count number of entries in the `selections` array
+ * @param results array of size `selections_count`, which will be filled with
+ *                pointers to the new tensor maps
+ *
+ * @returns The status code of this operation. If the status is not
+ *          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+ *          error message.
+ */
+eqs_status_t eqs_tensormap_split(const struct eqs_tensormap_t *tensor,
+                                 const char *axis,
+                                 const struct eqs_labels_t *selections,
+                                 uintptr_t selections_count,
+                                 struct eqs_tensormap_t **results);
+
+/**
+ * Split the blocks in this `tensor` according to the values of the sample
+ * `variables`, moving these variables from the samples to the keys.
+ *
+ * `variables` must be an array of `variables_count` NULL-terminated strings,
+ * encoded as UTF-8. Each block is split into one new block for each of the
+ * different values taken by `variables` in its samples, and the
+ * corresponding key is extended with these values. The remaining sample
+ * variables are kept in the new blocks, or replaced by a single `"_"`
+ * variable if all sample variables are moved. Gradients are split
+ * accordingly.
+ *
+ * This function requires `eqs_array_t.gather_from` to be implemented. The
+ * result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param variables names of the sample variables to move to the keys
+ * @param variables_count number of entries in the `variables` array
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_samples_to_keys(const struct eqs_tensormap_t *tensor,
+                                                      const char *const *variables,
+                                                      uintptr_t variables_count);
+
+/**
+ * Split the blocks in this `tensor` according to the values of the property
+ * `variables`, moving these variables from the properties to the keys.
+ *
+ * `variables` must be an array of `variables_count` NULL-terminated strings,
+ * encoded as UTF-8. Each block is split into one new block for each of the
+ * different values taken by `variables` in its properties, and the
+ * corresponding key is extended with these values. The remaining property
+ * variables are kept in the new blocks, or replaced by a single `"_"`
+ * variable if all property variables are moved. Gradients are split
+ * accordingly.
+ *
+ * This function requires `eqs_array_t.gather_from` to be implemented. The
+ * result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+ *
+ * @param tensor pointer to an existing tensor map
+ * @param variables names of the property variables to move to the keys
+ * @param variables_count number of entries in the `variables` array
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_properties_to_keys(const struct eqs_tensormap_t *tensor,
+                                                         const char *const *variables,
+                                                         uintptr_t variables_count);
+
+/**
+ * Sort the keys of this `tensor` map in lexicographic order, reordering the
+ * blocks accordingly.
+ *
+ * This function requires `eqs_array_t.copy` to be implemented. The result is
+ * a new tensor map, which should be freed with `eqs_tensormap_free`.
+ *
+ * @param tensor pointer to an existing tensor map
+ *
+ * @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+ *          case of error. In case of error, you can use `eqs_last_error()`
+ *          to get the error message.
+ */
+struct eqs_tensormap_t *eqs_tensormap_sort_keys(const struct eqs_tensormap_t *tensor);
+
 /**
  * Load a tensor map from the file at the given path.
  *
@@ -706,8 +1650,11 @@ struct eqs_tensormap_t *eqs_tensormap_keys_to_samples(const struct eqs_tensormap
  *
  * We add other restriction on top of these formats when saving/loading data.
  * First, `Labels` instances are saved as structured array, see the `labels`
- * module for more information. Only 32-bit integers are supported for Labels,
- * and only 64-bit floats are supported for data (values and gradients).
+ * module for more information. Labels are saved using 64-bit integers (32-bit
+ * integers are also supported when loading older files). Data (values and
+ * gradients) can contain 64-bit, 32-bit or 16-bit floating point values
+ * (`<f8`, `<f4` and `<f2` in numpy notation), as well as 32-bit or 64-bit
+ * signed integers (`<i4` and `<i8`).
  *
  * Second, the path of the files in the archive also carry meaning. The keys of
  * the `TensorMap` are stored in `/keys.npy`, and then different blocks are
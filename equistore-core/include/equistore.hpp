@@ -58,6 +58,30 @@ namespace details {
         }
     }
 
+    /// Get a pointer to the data of `array`, checking that this array contains
+    /// 64-bit floating point values
+    inline double* float64_data(const eqs_array_t& array) {
+        if (array.device != nullptr) {
+            eqs_device_t device = 0;
+            check_status(array.device(array.ptr, &device));
+            if (device != EQS_DEVICE_CPU) {
+                throw Error("can only access arrays living in host memory as NDArray<double>");
+            }
+        }
+
+        if (array.dtype != nullptr) {
+            eqs_dtype_t dtype = 0;
+            check_status(array.dtype(array.ptr, &dtype));
+            if (dtype != EQS_DTYPE_FLOAT64) {
+                throw Error("can only access arrays containing 64-bit floating point values as NDArray<double>");
+            }
+        }
+
+        void* data = nullptr;
+        check_status(array.data(array.ptr, &data));
+        return static_cast<double*>(data);
+    }
+
     /// Compute the product of all values in the `shape` vector
     inline size_t product(const std::vector<size_t>& shape) {
         size_t result = 1;
@@ -71,9 +95,9 @@ namespace details {
     /// and array `shape`
     inline std::vector<size_t> cartesian_index(const std::vector<size_t>& shape, size_t index) {
         auto result = std::vector<size_t>(shape.size(), 0);
-        for (size_t i=0; i<shape.size(); i++) {
-            result[i] = index % shape[i];
-            index = index / shape[i];
+        for (size_t i=shape.size(); i>0; i--) {
+            result[i - 1] = index % shape[i - 1];
+            index = index / shape[i - 1];
         }
         assert(index == 0);
         return result;
@@ -376,7 +400,7 @@ bool operator!=(const NDArray<T>& lhs, const NDArray<T>& rhs) {
 }
 
 namespace details {
-    Labels labels_from_cxx(const std::vector<std::string>& names, NDArray<int32_t> values);
+    Labels labels_from_cxx(const std::vector<std::string>& names, NDArray<int64_t> values);
 }
 
 
@@ -386,7 +410,7 @@ namespace details {
 /// of shape `(count, size)`, with a set of names associated with the columns of
 /// this array (often called *variables*). Each row/entry in this array is
 /// unique, and they are often (but not always) sorted in lexicographic order.
-class Labels final: public NDArray<int32_t> {
+class Labels final: public NDArray<int64_t> {
 public:
     /// Create a new set of Labels from the given `names` and `values`.
     ///
@@ -400,7 +424,7 @@ public:
     ///    {2, 3},
     /// });
     /// ```
-    Labels(const std::vector<std::string>& names, std::vector<std::initializer_list<int32_t>> values):
+    Labels(const std::vector<std::string>& names, std::vector<std::initializer_list<int64_t>> values):
         Labels(details::labels_from_cxx(names, NDArray(std::move(values), names.size()))) {}
 
 
@@ -408,7 +432,7 @@ public:
     Labels(const std::vector<std::string>& names):
         Labels(details::labels_from_cxx(
             names,
-            NDArray(static_cast<const int32_t*>(nullptr), {0, names.size()}))
+            NDArray(static_cast<const int64_t*>(nullptr), {0, names.size()}))
         ) {}
 
     ~Labels() {
@@ -427,7 +451,7 @@ public:
 
     /// Labels can be move-assigned
     Labels& operator=(Labels&& other) noexcept {
-        NDArray<int32_t>::operator=(std::move(other));
+        NDArray<int64_t>::operator=(std::move(other));
 
         this->names_ = std::move(other.names_);
 
@@ -468,7 +492,7 @@ public:
     ///
     /// sThis operation is only available if the labels correspond to a set of
     /// Rust Labels (i.e. `labels.labels_ptr` is not NULL).
-    int64_t position(std::initializer_list<int32_t> label) const {
+    int64_t position(std::initializer_list<int64_t> label) const {
         assert(labels_.internal_ptr_ != nullptr);
 
         int64_t result = 0;
@@ -478,7 +502,7 @@ public:
 
     /// Variant of `Labels::position` taking a fixed-size array as input
     template<size_t N>
-    int64_t position(std::array<int32_t, N> label) const {
+    int64_t position(std::array<int64_t, N> label) const {
         assert(labels_.internal_ptr_ != nullptr);
 
         int64_t result = 0;
@@ -486,13 +510,256 @@ public:
         return result;
     }
 
+    /// Get the positions of multiple entries in these Labels at once. `values`
+    /// should contain the entries to look for as a row-major 2D array, with
+    /// `this->names().size()` columns. The position of each entry is returned,
+    /// or -1 for entries which are not part of these Labels.
+    ///
+    /// If `parallel` is true, the entries are looked for using multiple
+    /// threads.
+    std::vector<int64_t> positions(const std::vector<int64_t>& values, bool parallel = false) const {
+        auto size = labels_.size;
+        if (size == 0 && values.empty()) {
+            return {};
+        }
+
+        if (size == 0 || values.size() % size != 0) {
+            throw Error(
+                "invalid size for values in Labels::positions: expected a "
+                "multiple of " + std::to_string(size) + ", got " + std::to_string(values.size())
+            );
+        }
+
+        auto results = std::vector<int64_t>(values.size() / size, -1);
+        details::check_status(eqs_labels_positions(labels_, values.data(), results.size(), parallel, results.data()));
+        return results;
+    }
+
+    /// Check whether the entries in these `Labels` are sorted in
+    /// lexicographic order.
+    bool is_sorted() const {
+        bool result = false;
+        details::check_status(eqs_labels_is_sorted(labels_, &result));
+        return result;
+    }
+
+    /// Get a sorted copy of these `Labels`.
+    Labels sort() const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_sort(labels_, &result, nullptr, 0));
+        return Labels(result);
+    }
+
+    /// Get a sorted copy of these `Labels`, and fill `permutation` such that
+    /// the entry at position `i` in the result was at position
+    /// `permutation[i]` in these `Labels`.
+    Labels sort(std::vector<int64_t>& permutation) const {
+        permutation.resize(this->count());
+
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_sort(
+            labels_, &result, permutation.data(), permutation.size()
+        ));
+        return Labels(result);
+    }
+
+    /// Project these `Labels` onto the variables with the given `names`, only
+    /// keeping these variables (in the given order) and removing duplicated
+    /// entries.
+    Labels project(const std::vector<std::string>& names) const {
+        auto c_names = std::vector<const char*>();
+        for (const auto& name: names) {
+            c_names.push_back(name.c_str());
+        }
+
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_project(
+            labels_, c_names.data(), c_names.size(), &result
+        ));
+        return Labels(result);
+    }
+
+    /// Insert a new variable with the given `name` at position `index` in
+    /// these `Labels`, with one of the `values` for each entry.
+    Labels insert(size_t index, const std::string& name, const std::vector<int64_t>& values) const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_insert(
+            labels_, index, name.c_str(), values.data(), values.size(), &result
+        ));
+        return Labels(result);
+    }
+
+    /// Insert a new variable with the given `name` at position `index` in
+    /// these `Labels`, using the same `value` for all entries.
+    Labels insert(size_t index, const std::string& name, int64_t value) const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_insert(
+            labels_, index, name.c_str(), &value, 1, &result
+        ));
+        return Labels(result);
+    }
+
+    /// Remove the variable with the given `name` from these `Labels`.
+    Labels remove(const std::string& name) const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_remove(labels_, name.c_str(), &result));
+        return Labels(result);
+    }
+
+    /// Rename the variable `old_name` to `new_name` in these `Labels`.
+    Labels rename(const std::string& old_name, const std::string& new_name) const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_rename(
+            labels_, old_name.c_str(), new_name.c_str(), &result
+        ));
+        return Labels(result);
+    }
+
+    /// Reorder the variables in these `Labels` following the given `names`,
+    /// which must contain all the variables exactly once.
+    Labels reorder(const std::vector<std::string>& names) const {
+        auto c_names = std::vector<const char*>();
+        for (const auto& name: names) {
+            c_names.push_back(name.c_str());
+        }
+
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_reorder(
+            labels_, c_names.data(), c_names.size(), &result
+        ));
+        return Labels(result);
+    }
+
+    /// Get the positions of the entries in these `Labels` matching the
+    /// `selection`, i.e. the entries for which the values of the variables in
+    /// `selection` match any of the entries in `selection`.
+    std::vector<uintptr_t> select(const Labels& selection) const {
+        auto selected = std::vector<uintptr_t>(this->count());
+        uintptr_t count = selected.size();
+
+        details::check_status(eqs_labels_select(
+            labels_,
+            selection.labels_,
+            selected.data(),
+            &count
+        ));
+
+        assert(count <= selected.size());
+        selected.resize(count);
+        return selected;
+    }
+
+    /// Take the union of these `Labels` with `other`.
+    ///
+    /// The result contains all the entries of these `Labels`, followed by the
+    /// entries of `other` which are not already present.
+    Labels set_union(const Labels& other) const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_union(
+            labels_, other.labels_, &result, nullptr, 0, nullptr, 0
+        ));
+        return Labels(result);
+    }
+
+    /// Take the union of these `Labels` with `other`, and fill
+    /// `first_mapping` and `second_mapping` with the positions of the entries
+    /// of these `Labels` and `other` (respectively) in the union.
+    Labels set_union(const Labels& other, std::vector<int64_t>& first_mapping, std::vector<int64_t>& second_mapping) const {
+        first_mapping.resize(this->count());
+        second_mapping.resize(other.count());
+
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_union(
+            labels_,
+            other.labels_,
+            &result,
+            first_mapping.data(),
+            first_mapping.size(),
+            second_mapping.data(),
+            second_mapping.size()
+        ));
+        return Labels(result);
+    }
+
+    /// Take the intersection of these `Labels` with `other`.
+    ///
+    /// The result contains all the entries of these `Labels` which are also
+    /// present in `other`.
+    Labels set_intersection(const Labels& other) const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_intersection(
+            labels_, other.labels_, &result, nullptr, 0, nullptr, 0
+        ));
+        return Labels(result);
+    }
+
+    /// Take the intersection of these `Labels` with `other`, and fill
+    /// `first_mapping` and `second_mapping` with the positions of the entries
+    /// of these `Labels` and `other` (respectively) in the intersection, or -1
+    /// for entries which are not part of the intersection.
+    Labels set_intersection(const Labels& other, std::vector<int64_t>& first_mapping, std::vector<int64_t>& second_mapping) const {
+        first_mapping.resize(this->count());
+        second_mapping.resize(other.count());
+
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_intersection(
+            labels_,
+            other.labels_,
+            &result,
+            first_mapping.data(),
+            first_mapping.size(),
+            second_mapping.data(),
+            second_mapping.size()
+        ));
+        return Labels(result);
+    }
+
+    /// Take the difference of these `Labels` with `other`.
+    ///
+    /// The result contains all the entries of these `Labels` which are not
+    /// present in `other`.
+    Labels set_difference(const Labels& other) const {
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_difference(
+            labels_, other.labels_, &result, nullptr, 0
+        ));
+        return Labels(result);
+    }
+
+    /// Take the difference of these `Labels` with `other`, and fill `mapping`
+    /// with the positions of the entries of these `Labels` in the difference,
+    /// or -1 for entries which are not part of the difference.
+    Labels set_difference(const Labels& other, std::vector<int64_t>& mapping) const {
+        mapping.resize(this->count());
+
+        eqs_labels_t result;
+        std::memset(&result, 0, sizeof(result));
+        details::check_status(eqs_labels_difference(
+            labels_, other.labels_, &result, mapping.data(), mapping.size()
+        ));
+        return Labels(result);
+    }
+
     /// Get the value inside these `Labels` at the given index
-    int32_t operator()(size_t i, size_t j) const {
-        return NDArray<int32_t>::operator()(i, j);
+    int64_t operator()(size_t i, size_t j) const {
+        return NDArray<int64_t>::operator()(i, j);
     }
 
 private:
-    Labels(): NDArray(static_cast<const int32_t*>(nullptr), {0, 0}) {
+    Labels(): NDArray(static_cast<const int64_t*>(nullptr), {0, 0}) {
         std::memset(&labels_, 0, sizeof(labels_));
     }
 
@@ -507,7 +774,7 @@ private:
         }
     }
 
-    friend Labels details::labels_from_cxx(const std::vector<std::string>&, NDArray<int32_t>);
+    friend Labels details::labels_from_cxx(const std::vector<std::string>&, NDArray<int64_t>);
     friend class TensorMap;
     friend class TensorBlock;
     friend class GradientProxy;
@@ -519,7 +786,7 @@ private:
 };
 
 namespace details {
-    inline equistore::Labels labels_from_cxx(const std::vector<std::string>& names, equistore::NDArray<int32_t> values) {
+    inline equistore::Labels labels_from_cxx(const std::vector<std::string>& names, equistore::NDArray<int64_t> values) {
         assert(values.shape().size() == 2);
 
         eqs_labels_t labels;
@@ -533,7 +800,7 @@ namespace details {
         labels.names = c_names.data();
         labels.size = c_names.size();
         labels.count = values.shape()[0];
-        labels.values = const_cast<const NDArray<int32_t>&>(values).data();
+        labels.values = const_cast<const NDArray<int64_t>&>(values).data();
 
         details::check_status(eqs_labels_create(&labels));
 
@@ -555,7 +822,7 @@ inline bool operator==(const Labels& lhs, const Labels& rhs) {
         }
     }
 
-    return static_cast<const NDArray<int32_t>&>(lhs) == static_cast<const NDArray<int32_t>&>(rhs);
+    return static_cast<const NDArray<int64_t>&>(lhs) == static_cast<const NDArray<int64_t>&>(rhs);
 }
 
 /// Two Labels compare equal only if they have the same names and values in the
@@ -647,7 +914,19 @@ public:
         };
 
 
-        array.data = [](void* array, double** data) {
+        array.dtype = [](const void* array, eqs_dtype_t* dtype) {
+            try {
+                auto cxx_array = static_cast<const DataArrayBase*>(array);
+                *dtype = cxx_array->dtype();
+                return EQS_SUCCESS;
+            } catch (const std::exception&) {
+                return -1;
+            } catch (...) {
+                return -128;
+            }
+        };
+
+        array.data = [](void* array, void** data) {
             try {
                 auto cxx_array = static_cast<DataArrayBase*>(array);
                 *data = cxx_array->data();
@@ -659,6 +938,30 @@ public:
             }
         };
 
+        array.device = [](const void* array, eqs_device_t* device) {
+            try {
+                auto cxx_array = static_cast<const DataArrayBase*>(array);
+                *device = cxx_array->device();
+                return EQS_SUCCESS;
+            } catch (const std::exception&) {
+                return -1;
+            } catch (...) {
+                return -128;
+            }
+        };
+
+        array.copy_to_host = [](const void* array, void* buffer, uintptr_t buffer_size) {
+            try {
+                auto cxx_array = static_cast<const DataArrayBase*>(array);
+                cxx_array->copy_to_host(buffer, static_cast<size_t>(buffer_size));
+                return EQS_SUCCESS;
+            } catch (const std::exception&) {
+                return -1;
+            } catch (...) {
+                return -128;
+            }
+        };
+
         array.shape = [](const void* array, const uintptr_t** shape, uintptr_t* shape_count) {
             try {
                 auto cxx_array = static_cast<const DataArrayBase*>(array);
@@ -720,6 +1023,71 @@ public:
             }
         };
 
+        array.gather_from = [](
+            void* array,
+            const void* input,
+            uintptr_t axis,
+            const uintptr_t* indices,
+            uintptr_t indices_count
+        ) {
+            try {
+                auto cxx_array = static_cast<DataArrayBase*>(array);
+                auto cxx_input = static_cast<const DataArrayBase*>(input);
+                auto cxx_indices = std::vector<uintptr_t>(indices, indices + indices_count);
+
+                cxx_array->gather_from(*cxx_input, axis, cxx_indices);
+                return EQS_SUCCESS;
+            } catch (const std::exception&) {
+                return -1;
+            } catch (...) {
+                return -128;
+            }
+        };
+
+        array.scatter_add_from = [](
+            void* array,
+            const void* input,
+            uintptr_t axis,
+            const uintptr_t* indices,
+            uintptr_t indices_count
+        ) {
+            try {
+                auto cxx_array = static_cast<DataArrayBase*>(array);
+                auto cxx_input = static_cast<const DataArrayBase*>(input);
+                auto cxx_indices = std::vector<uintptr_t>(indices, indices + indices_count);
+
+                cxx_array->scatter_add_from(*cxx_input, axis, cxx_indices);
+                return EQS_SUCCESS;
+            } catch (const std::exception&) {
+                return -1;
+            } catch (...) {
+                return -128;
+            }
+        };
+
+        array.move_data = [](
+            void* array,
+            const void* input,
+            const eqs_sample_mapping_t* samples,
+            uintptr_t samples_count,
+            const uintptr_t* properties,
+            uintptr_t properties_count
+        ) {
+            try {
+                auto cxx_array = static_cast<DataArrayBase*>(array);
+                auto cxx_input = static_cast<const DataArrayBase*>(input);
+                auto cxx_samples = std::vector<eqs_sample_mapping_t>(samples, samples + samples_count);
+                auto cxx_properties = std::vector<uintptr_t>(properties, properties + properties_count);
+
+                cxx_array->move_data(*cxx_input, cxx_samples, cxx_properties);
+                return EQS_SUCCESS;
+            } catch (const std::exception&) {
+                return -1;
+            } catch (...) {
+                return -128;
+            }
+        };
+
         return array;
     }
 
@@ -743,12 +1111,36 @@ public:
     virtual std::unique_ptr<DataArrayBase> create(std::vector<uintptr_t> shape) const = 0;
 
 
-    /// Get a pointer to the underlying data storage.
+    /// Get the type of the data stored in this array, as one of the
+    /// `EQS_DTYPE_XXX` constants.
+    virtual eqs_dtype_t dtype() const = 0;
+
+    /// Get a pointer to the underlying data storage. The data should have the
+    /// type given by `dtype()`.
     ///
     /// This function is allowed to fail if the data is not accessible in RAM,
-    /// not stored as 64-bit floating point values, or not stored as a
-    /// C-contiguous array.
-    virtual double* data() = 0;
+    /// or not stored as a C-contiguous array.
+    virtual void* data() = 0;
+
+    /// Get the device where the data of this array lives, as one of the
+    /// `EQS_DEVICE_XXX` constants. The default implementation returns
+    /// `EQS_DEVICE_CPU`.
+    virtual eqs_device_t device() const {
+        return EQS_DEVICE_CPU;
+    }
+
+    /// Copy all the data of this array to host memory in `buffer`, as a
+    /// C-contiguous array with the type given by `dtype()`. `size` is the size
+    /// of `buffer` in bytes.
+    ///
+    /// This function is only called for arrays which are not on
+    /// `EQS_DEVICE_CPU`, and must be overridden by such arrays. The default
+    /// implementation throws an exception.
+    virtual void copy_to_host(void* buffer, size_t size) const {
+        (void)buffer;
+        (void)size;
+        throw Error("copy_to_host is not implemented for this array");
+    }
 
     /// Get the shape of this array
     virtual const std::vector<uintptr_t>& shape() const = 0;
@@ -778,6 +1170,58 @@ public:
         uintptr_t property_end
     ) = 0;
 
+    /// Set entries in the current array by selecting data from the `input`
+    /// array along a single `axis`.
+    ///
+    /// This array is guaranteed to be created by calling `eqs_array_t::create`
+    /// with one of the arrays in the same block or tensor map as the `input`,
+    /// and has the same shape as `input` except along `axis`.
+    ///
+    /// This function should copy data from `input[..., indices[i], ...]` to
+    /// `array[..., i, ...]` (indexing along `axis`) for `i` up to
+    /// `indices.size()`. All indexes are 0-based.
+    virtual void gather_from(
+        const DataArrayBase& input,
+        uintptr_t axis,
+        std::vector<uintptr_t> indices
+    ) = 0;
+
+    /// Add entries to the current array taking data from the `input` array
+    /// along a single `axis`.
+    ///
+    /// This array is guaranteed to be created by calling `eqs_array_t::create`
+    /// with one of the arrays in the same block or tensor map as the `input`,
+    /// and has the same shape as `input` except along `axis`.
+    ///
+    /// This function should add data from `input[..., i, ...]` to
+    /// `array[..., indices[i], ...]` (indexing along `axis`) for `i` up to
+    /// `indices.size()`. Multiple entries in `indices` can have the same value,
+    /// in which case the corresponding data should be summed together. All
+    /// indexes are 0-based.
+    virtual void scatter_add_from(
+        const DataArrayBase& input,
+        uintptr_t axis,
+        std::vector<uintptr_t> indices
+    ) = 0;
+
+    /// Set entries in the current array taking data from the `input` array,
+    /// remapping both samples and properties.
+    ///
+    /// This array is guaranteed to be created by calling `eqs_array_t::create`
+    /// with one of the arrays in the same block or tensor map as the `input`,
+    /// and all dimensions except the first (samples) and last (properties)
+    /// have the same size in `input` and in this array.
+    ///
+    /// This function should copy data from `input[samples[i].input, ..., j]`
+    /// to `array[samples[i].output, ..., properties[j]]` for `i` up to
+    /// `samples.size()` and `j` up to `properties.size()`. All indexes are
+    /// 0-based.
+    virtual void move_data(
+        const DataArrayBase& input,
+        std::vector<eqs_sample_mapping_t> samples,
+        std::vector<uintptr_t> properties
+    ) = 0;
+
 };
 
 
@@ -823,7 +1267,11 @@ public:
         return origin;
     }
 
-    double* data() override {
+    eqs_dtype_t dtype() const override {
+        return EQS_DTYPE_FLOAT64;
+    }
+
+    void* data() override {
         return data_.data();
     }
 
@@ -927,6 +1375,107 @@ public:
         }
     }
 
+    void gather_from(
+        const DataArrayBase& input,
+        uintptr_t axis,
+        std::vector<uintptr_t> indices
+    ) override {
+        const auto& input_array = dynamic_cast<const SimpleDataArray&>(input);
+        assert(input_array.shape_.size() == this->shape_.size());
+        assert(axis < this->shape_.size());
+        assert(this->shape_[axis] == indices.size());
+
+        // view the arrays as (before, axis, after), where `before` and `after`
+        // contain the product of the dimensions before and after `axis`
+        size_t before = 1;
+        for (size_t i=0; i<axis; i++) {
+            before *= shape_[i];
+        }
+
+        size_t after = 1;
+        for (size_t i=axis + 1; i<shape_.size(); i++) {
+            after *= shape_[i];
+        }
+
+        auto input_axis_size = input_array.shape_[axis];
+        for (size_t b=0; b<before; b++) {
+            for (size_t i=0; i<indices.size(); i++) {
+                for (size_t a=0; a<after; a++) {
+                    auto output_index = (b * indices.size() + i) * after + a;
+                    auto input_index = (b * input_axis_size + indices[i]) * after + a;
+                    this->data_[output_index] = input_array.data_[input_index];
+                }
+            }
+        }
+    }
+
+    void scatter_add_from(
+        const DataArrayBase& input,
+        uintptr_t axis,
+        std::vector<uintptr_t> indices
+    ) override {
+        const auto& input_array = dynamic_cast<const SimpleDataArray&>(input);
+        assert(input_array.shape_.size() == this->shape_.size());
+        assert(axis < this->shape_.size());
+        assert(input_array.shape_[axis] == indices.size());
+
+        // view the arrays as (before, axis, after), where `before` and `after`
+        // contain the product of the dimensions before and after `axis`
+        size_t before = 1;
+        for (size_t i=0; i<axis; i++) {
+            before *= shape_[i];
+        }
+
+        size_t after = 1;
+        for (size_t i=axis + 1; i<shape_.size(); i++) {
+            after *= shape_[i];
+        }
+
+        auto output_axis_size = this->shape_[axis];
+        for (size_t b=0; b<before; b++) {
+            for (size_t i=0; i<indices.size(); i++) {
+                for (size_t a=0; a<after; a++) {
+                    auto output_index = (b * output_axis_size + indices[i]) * after + a;
+                    auto input_index = (b * indices.size() + i) * after + a;
+                    this->data_[output_index] += input_array.data_[input_index];
+                }
+            }
+        }
+    }
+
+    void move_data(
+        const DataArrayBase& input,
+        std::vector<eqs_sample_mapping_t> samples,
+        std::vector<uintptr_t> properties
+    ) override {
+        const auto& input_array = dynamic_cast<const SimpleDataArray&>(input);
+        assert(input_array.shape_.size() == this->shape_.size());
+
+        size_t property_dim = shape_.size() - 1;
+        assert(input_array.shape_[property_dim] == properties.size());
+
+        // all dimensions except the first and last one are the same in input
+        // and output, so we can view the arrays as (samples, components,
+        // properties) with all the components merged together
+        size_t components_size = 1;
+        for (size_t i=1; i<property_dim; i++) {
+            assert(shape_[i] == input_array.shape_[i]);
+            components_size *= shape_[i];
+        }
+
+        auto input_properties = properties.size();
+        auto output_properties = shape_[property_dim];
+        for (const auto& sample: samples) {
+            for (size_t c=0; c<components_size; c++) {
+                auto input_start = (sample.input * components_size + c) * input_properties;
+                auto output_start = (sample.output * components_size + c) * output_properties;
+                for (size_t p=0; p<input_properties; p++) {
+                    this->data_[output_start + properties[p]] = input_array.data_[input_start + p];
+                }
+            }
+        }
+    }
+
     /// Get a const view of the data managed by this SimpleDataArray
     NDArray<double> view() const {
         return NDArray<double>(data_.data(), shape_);
@@ -1004,6 +1553,34 @@ inline bool operator!=(const SimpleDataArray& lhs, const SimpleDataArray& rhs) {
 /******************************************************************************/
 
 
+namespace details {
+    /// Get the info associated with `key` for `"values"` or one gradient in
+    /// the given `block`, throwing an exception if there is no such info.
+    inline std::string block_info(const eqs_block_t* block, const char* values_gradients, const std::string& key) {
+        const char* value = nullptr;
+        details::check_status(eqs_block_info(block, values_gradients, key.c_str(), &value));
+        if (value == nullptr) {
+            throw Error("there is no info associated with '" + key + "'");
+        }
+        return std::string(value);
+    }
+
+    /// Get the list of all info keys for `"values"` or one gradient in the
+    /// given `block`.
+    inline std::vector<std::string> block_info_keys(const eqs_block_t* block, const char* values_gradients) {
+        const char*const * keys = nullptr;
+        uintptr_t count = 0;
+        details::check_status(eqs_block_info_keys(block, values_gradients, &keys, &count));
+
+        auto result = std::vector<std::string>();
+        for (uint64_t i=0; i<count; i++) {
+            result.push_back(std::string(keys[i]));
+        }
+
+        return result;
+    }
+}
+
 /// This is a proxy class allowing to access the information associated with a
 /// gradient inside a `TensorBlock`.
 ///
@@ -1025,8 +1602,7 @@ public:
     /// Get a view of the data for this gradient
     NDArray<double> data() {
         auto array = this->eqs_array();
-        double* data = nullptr;
-        details::check_status(array.data(array.ptr, &data));
+        auto data = details::float64_data(array);
 
         return NDArray<double>(data, this->data_shape());
     }
@@ -1075,6 +1651,30 @@ public:
         return this->labels(shape.size() - 1);
     }
 
+    /// Get the value associated with `key` in the info of this gradient,
+    /// throwing an exception if there is no such info.
+    std::string info(const std::string& key) const {
+        return details::block_info(block_, parameter_.c_str(), key);
+    }
+
+    /// Set the value associated with `key` in the info of this gradient,
+    /// overwriting any existing value.
+    void set_info(const std::string& key, const std::string& value) {
+        details::check_status(eqs_block_set_info(block_, parameter_.c_str(), key.c_str(), value.c_str()));
+    }
+
+    /// Remove the value associated with `key` in the info of this gradient,
+    /// if any.
+    void remove_info(const std::string& key) {
+        details::check_status(eqs_block_set_info(block_, parameter_.c_str(), key.c_str(), nullptr));
+    }
+
+    /// Get the list of all keys in the info of this gradient, sorted
+    /// alphabetically.
+    std::vector<std::string> info_keys() const {
+        return details::block_info_keys(block_, parameter_.c_str());
+    }
+
 
 private:
     /// Get the labels for the given axis
@@ -1208,11 +1808,44 @@ public:
         return copy;
     }
 
+    /// Slice this block along the given `axis`, keeping only the samples or
+    /// properties matching the `selection`.
+    ///
+    /// The `selection` must contain a subset of the names of the samples or
+    /// properties labels, and an entry is kept if the values of these
+    /// variables match one of the entries in the `selection`. When slicing
+    /// along samples, the gradient samples referring to removed samples are
+    /// removed as well.
+    ///
+    /// @param axis axis along which to slice the block, either `"samples"` or
+    ///             `"properties"`
+    /// @param selection labels describing which entries should be kept
+    TensorBlock slice(const std::string& axis, const Labels& selection) const {
+        auto sliced = TensorBlock();
+        sliced.is_view_ = false;
+        sliced.block_ = eqs_block_slice(this->block_, axis.c_str(), selection.as_eqs_labels_t());
+        details::check_pointer(sliced.block_);
+        return sliced;
+    }
+
+    /// Create a new block with the same data as this one, where the entries
+    /// along the given `axis` are sorted in lexicographic order. When sorting
+    /// the samples, the gradient samples are updated and sorted as well.
+    ///
+    /// @param axis axis along which to sort the block, either `"samples"` or
+    ///             `"properties"`
+    TensorBlock sort(const std::string& axis) const {
+        auto sorted = TensorBlock();
+        sorted.is_view_ = false;
+        sorted.block_ = eqs_block_sort(this->block_, axis.c_str());
+        details::check_pointer(sorted.block_);
+        return sorted;
+    }
+
     /// Get a view in the values in this block
     NDArray<double> values() {
         auto array = this->eqs_array("values");
-        double* data = nullptr;
-        details::check_status(array.data(array.ptr, &data));
+        auto data = details::float64_data(array);
 
         return NDArray<double>(data, this->values_shape());
     }
@@ -1304,6 +1937,32 @@ public:
         return result;
     }
 
+    /// Get the value associated with `key` in the info of the values in this
+    /// block, throwing an exception if there is no such info.
+    ///
+    /// Use `gradient(parameter).info(key)` to access the info of gradients.
+    std::string info(const std::string& key) const {
+        return details::block_info(block_, "values", key);
+    }
+
+    /// Set the value associated with `key` in the info of the values in this
+    /// block, overwriting any existing value.
+    void set_info(const std::string& key, const std::string& value) {
+        details::check_status(eqs_block_set_info(block_, "values", key.c_str(), value.c_str()));
+    }
+
+    /// Remove the value associated with `key` in the info of the values in
+    /// this block, if any.
+    void remove_info(const std::string& key) {
+        details::check_status(eqs_block_set_info(block_, "values", key.c_str(), nullptr));
+    }
+
+    /// Get the list of all keys in the info of the values in this block,
+    /// sorted alphabetically.
+    std::vector<std::string> info_keys() const {
+        return details::block_info_keys(block_, "values");
+    }
+
     /// Get the gradient of the `values()` in this block with respect to
     /// the given `parameter`.
     ///
@@ -1499,6 +2158,44 @@ public:
         return Labels(keys);
     }
 
+    /// Get the value associated with `key` in the tensor-level metadata of
+    /// this tensor map, throwing an exception if there is no such metadata.
+    std::string metadata(const std::string& key) const {
+        const char* value = nullptr;
+        details::check_status(eqs_tensormap_metadata(tensor_, key.c_str(), &value));
+        if (value == nullptr) {
+            throw Error("there is no metadata associated with '" + key + "'");
+        }
+        return std::string(value);
+    }
+
+    /// Set the value associated with `key` in the tensor-level metadata of
+    /// this tensor map, overwriting any existing value.
+    void set_metadata(const std::string& key, const std::string& value) {
+        details::check_status(eqs_tensormap_set_metadata(tensor_, key.c_str(), value.c_str()));
+    }
+
+    /// Remove the value associated with `key` in the tensor-level metadata of
+    /// this tensor map, if any.
+    void remove_metadata(const std::string& key) {
+        details::check_status(eqs_tensormap_set_metadata(tensor_, key.c_str(), nullptr));
+    }
+
+    /// Get the list of all keys in the tensor-level metadata of this tensor
+    /// map, sorted alphabetically.
+    std::vector<std::string> metadata_keys() const {
+        const char*const * keys = nullptr;
+        uintptr_t count = 0;
+        details::check_status(eqs_tensormap_metadata_keys(tensor_, &keys, &count));
+
+        auto result = std::vector<std::string>();
+        for (uint64_t i=0; i<count; i++) {
+            result.push_back(std::string(keys[i]));
+        }
+
+        return result;
+    }
+
     /// Get a (possibly empty) list of block indexes matching the `selection`
     std::vector<uintptr_t> blocks_matching(const Labels& selection) const {
         auto matching = std::vector<uintptr_t>(this->keys().count());
@@ -1556,14 +2253,22 @@ public:
     /// lexicographically sorted. Otherwise they are kept in the order in which
     /// they appear in the blocks.
     ///
+    /// Similarly, if `sort_properties` is true, the new property labels are
+    /// re-ordered to keep them lexicographically sorted. Otherwise they are
+    /// kept in the order in which they appear in the blocks.
+    ///
     /// @param keys_to_move description of the keys to move
     /// @param sort_samples whether to sort the merged samples or keep them in
     ///                     the order in which they appear in the original blocks
-    TensorMap keys_to_properties(const Labels& keys_to_move, bool sort_samples = true) const {
+    /// @param sort_properties whether to sort the merged properties or keep
+    ///                        them in the order in which they appear in the
+    ///                        original blocks
+    TensorMap keys_to_properties(const Labels& keys_to_move, bool sort_samples = true, bool sort_properties = false) const {
         auto ptr = eqs_tensormap_keys_to_properties(
             tensor_,
             keys_to_move.as_eqs_labels_t(),
-            sort_samples
+            sort_samples,
+            sort_properties
         );
 
         details::check_pointer(ptr);
@@ -1572,14 +2277,14 @@ public:
 
     /// This function calls `keys_to_properties` with an empty set of `Labels`
     /// with the variables defined in `keys_to_move`
-    TensorMap keys_to_properties(const std::vector<std::string>& keys_to_move, bool sort_samples = true) const {
-        return keys_to_properties(Labels(keys_to_move), sort_samples);
+    TensorMap keys_to_properties(const std::vector<std::string>& keys_to_move, bool sort_samples = true, bool sort_properties = false) const {
+        return keys_to_properties(Labels(keys_to_move), sort_samples, sort_properties);
     }
 
     /// This function calls `keys_to_properties` with an empty set of `Labels`
     /// with a single variable: `key_to_move`
-    TensorMap keys_to_properties(const std::string& key_to_move, bool sort_samples = true) const {
-        return keys_to_properties(std::vector<std::string>{key_to_move}, sort_samples);
+    TensorMap keys_to_properties(const std::string& key_to_move, bool sort_samples = true, bool sort_properties = false) const {
+        return keys_to_properties(std::vector<std::string>{key_to_move}, sort_samples, sort_properties);
     }
 
     /// Merge blocks with the same value for selected keys variables along the
@@ -1589,14 +2294,22 @@ public:
     /// the sample labels, and blocks with the same remaining keys variables
     /// will be merged together along the sample axis.
     ///
-    /// If `keys_to_move` must be an empty set of `Labels`
-    /// (`keys_to_move.count() == 0`). The new sample labels will contain
-    /// entries corresponding to the merged blocks' keys.
+    /// If `keys_to_move` does not contains any entries (`keys_to_move.count()
+    /// == 0`), then the new sample labels will contain entries corresponding
+    /// to the merged blocks' keys only.
+    ///
+    /// If `keys_to_move` contains entries, then the new sample labels will
+    /// contain all the merged samples, combined with each of the entries of
+    /// `keys_to_move`. For example, using `a=2, 3` in `keys_to_move` with blocks
+    /// with samples `s=1, 2` and `s=1, 3` will result in `s, a = (1, 2), (2, 2),
+    /// (3, 2), (1, 3), (2, 3), (3, 3)`. The data for samples which were not
+    /// present in any of the merged blocks is filled with zeros, and blocks with
+    /// a key not in `keys_to_move` are ignored.
     ///
     /// The order of the samples is controlled by `sort_samples`. If
     /// `sort_samples` is true, samples are re-ordered to keep them
     /// lexicographically sorted. Otherwise they are kept in the order in which
-    /// they appear in the blocks.
+    /// they appear in the blocks (and in `keys_to_move`).
     ///
     /// This function is only implemented if all merged block have the same
     /// property labels.
@@ -1659,16 +2372,281 @@ public:
         return TensorMap(ptr);
     }
 
+    /// Move the given `variables` from the component labels to the sample
+    /// labels for each block.
+    ///
+    /// The component is appended to the samples, with the component values
+    /// varying faster than the old samples. Each gradient sample is duplicated
+    /// for all the values of the component.
+    ///
+    /// @param variables name of the component variables to move to the
+    ///                  samples
+    TensorMap components_to_samples(const std::vector<std::string>& variables) const {
+        auto c_variables = std::vector<const char*>();
+        for (const auto& v: variables) {
+            c_variables.push_back(v.c_str());
+        }
+
+        auto ptr = eqs_tensormap_components_to_samples(
+            tensor_,
+            c_variables.data(),
+            c_variables.size()
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Call `components_to_samples` with a single variable
+    TensorMap components_to_samples(const std::string& variable) const {
+        const char* c_str = variable.c_str();
+        auto ptr = eqs_tensormap_components_to_samples(
+            tensor_,
+            &c_str,
+            1
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Move the given `variables` from the property labels to a new component
+    /// for each block.
+    ///
+    /// This is the inverse of `components_to_properties`. The properties must
+    /// contain all the combinations of the values of `variables` with the
+    /// values of the remaining property variables, with the moved variables
+    /// varying either slower or faster than the remaining ones.
+    ///
+    /// @param variables name of the property variables to move to the
+    ///                  components
+    TensorMap properties_to_components(const std::vector<std::string>& variables) const {
+        auto c_variables = std::vector<const char*>();
+        for (const auto& v: variables) {
+            c_variables.push_back(v.c_str());
+        }
+
+        auto ptr = eqs_tensormap_properties_to_components(
+            tensor_,
+            c_variables.data(),
+            c_variables.size()
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Call `properties_to_components` with a single variable
+    TensorMap properties_to_components(const std::string& variable) const {
+        const char* c_str = variable.c_str();
+        auto ptr = eqs_tensormap_properties_to_components(
+            tensor_,
+            &c_str,
+            1
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Split the blocks in this `TensorMap` according to the values of the
+    /// sample `variables`, moving these variables from the samples to the
+    /// keys.
+    ///
+    /// This is the inverse of `keys_to_samples`. The remaining sample variables
+    /// are kept in the new blocks, or replaced by a single `"_"` variable if
+    /// all sample variables are moved. Gradients are split accordingly.
+    ///
+    /// @param variables name of the sample variables to move to the keys
+    TensorMap samples_to_keys(const std::vector<std::string>& variables) const {
+        auto c_variables = std::vector<const char*>();
+        for (const auto& v: variables) {
+            c_variables.push_back(v.c_str());
+        }
+
+        auto ptr = eqs_tensormap_samples_to_keys(
+            tensor_,
+            c_variables.data(),
+            c_variables.size()
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Call `samples_to_keys` with a single variable
+    TensorMap samples_to_keys(const std::string& variable) const {
+        const char* c_str = variable.c_str();
+        auto ptr = eqs_tensormap_samples_to_keys(
+            tensor_,
+            &c_str,
+            1
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Split the blocks in this `TensorMap` according to the values of the
+    /// property `variables`, moving these variables from the properties to the
+    /// keys.
+    ///
+    /// This is the inverse of `keys_to_properties`. The remaining property variables
+    /// are kept in the new blocks, or replaced by a single `"_"` variable if
+    /// all property variables are moved. Gradients are split accordingly.
+    ///
+    /// @param variables name of the property variables to move to the keys
+    TensorMap properties_to_keys(const std::vector<std::string>& variables) const {
+        auto c_variables = std::vector<const char*>();
+        for (const auto& v: variables) {
+            c_variables.push_back(v.c_str());
+        }
+
+        auto ptr = eqs_tensormap_properties_to_keys(
+            tensor_,
+            c_variables.data(),
+            c_variables.size()
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Call `properties_to_keys` with a single variable
+    TensorMap properties_to_keys(const std::string& variable) const {
+        const char* c_str = variable.c_str();
+        auto ptr = eqs_tensormap_properties_to_keys(
+            tensor_,
+            &c_str,
+            1
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Slice all the blocks in this `TensorMap` along the given `axis`,
+    /// keeping only the samples or properties matching the `selection`.
+    ///
+    /// See `TensorBlock::slice` for more information. The keys of the new
+    /// tensor map are the same as the keys of this one, and blocks can end up
+    /// with zero samples or properties if none of them match the `selection`.
+    ///
+    /// @param axis axis along which to slice the blocks, either `"samples"` or
+    ///             `"properties"`
+    /// @param selection labels describing which entries should be kept
+    TensorMap slice(const std::string& axis, const Labels& selection) const {
+        auto ptr = eqs_tensormap_slice(tensor_, axis.c_str(), selection.as_eqs_labels_t());
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Create a new `TensorMap` with the keys (and the corresponding blocks)
+    /// sorted in lexicographic order.
+    TensorMap sort_keys() const {
+        auto ptr = eqs_tensormap_sort_keys(tensor_);
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Split this `TensorMap` into multiple tensor maps along the given
+    /// `axis`, creating one new tensor map for each of the `selections`.
+    ///
+    /// This is equivalent to calling `TensorMap::slice` with each of the
+    /// `selections`, which must all have the same names.
+    ///
+    /// @param axis axis along which to split the tensor map, either
+    ///             `"samples"` or `"properties"`
+    /// @param selections labels describing which entries should be kept in
+    ///                   each of the new tensor maps
+    std::vector<TensorMap> split(const std::string& axis, const std::vector<Labels>& selections) const {
+        auto c_selections = std::vector<eqs_labels_t>();
+        for (const auto& selection: selections) {
+            c_selections.push_back(selection.as_eqs_labels_t());
+        }
+
+        auto results = std::vector<eqs_tensormap_t*>(selections.size(), nullptr);
+        details::check_status(eqs_tensormap_split(
+            tensor_,
+            axis.c_str(),
+            c_selections.data(),
+            c_selections.size(),
+            results.data()
+        ));
+
+        auto tensors = std::vector<TensorMap>();
+        for (auto* ptr: results) {
+            tensors.emplace_back(TensorMap(ptr));
+        }
+        return tensors;
+    }
+
+    /// Reduce the blocks in this `TensorMap` over the sample variables in
+    /// `names`, combining together all the samples which only differ by the
+    /// value of these variables.
+    ///
+    /// The new samples contain the remaining sample variables (or a single
+    /// `"_"` variable if all variables are reduced over), and are sorted
+    /// lexicographically. The gradients are reduced accordingly.
+    ///
+    /// @param names names of the sample variables to reduce over
+    /// @param reduction how to reduce the samples, one of `"sum"`, `"mean"`,
+    ///                  `"variance"` or `"std"`
+    TensorMap reduce_over_samples(const std::vector<std::string>& names, const std::string& reduction) const {
+        auto c_names = std::vector<const char*>();
+        for (const auto& name: names) {
+            c_names.push_back(name.c_str());
+        }
+
+        auto ptr = eqs_tensormap_reduce_over_samples(
+            tensor_,
+            c_names.data(),
+            c_names.size(),
+            reduction.c_str()
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
+    /// Join the given `tensors` along `axis`, creating a single `TensorMap`.
+    ///
+    /// All the tensor maps must have the same keys, and blocks with the same
+    /// key must have the same components, gradients, and the same samples or
+    /// properties labels (i.e. the labels for the axis which is not joined).
+    ///
+    /// Clashes between the labels along the joined `axis` are resolved by
+    /// keeping the same names if all entries are unique; adding a new
+    /// `"tensor"` variable containing the index of the tensor map if the names
+    /// are the same but some entries are duplicated; and using `"tensor",
+    /// "property"` as new names if the labels have different names.
+    ///
+    /// @param tensors tensor maps to join
+    /// @param axis axis along which to join the tensor maps, either
+    ///             `"samples"` or `"properties"`
+    static TensorMap join(const std::vector<const TensorMap*>& tensors, const std::string& axis) {
+        auto c_tensors = std::vector<const eqs_tensormap_t*>();
+        for (const auto* tensor: tensors) {
+            c_tensors.push_back(tensor->tensor_);
+        }
+
+        auto ptr = eqs_tensormap_join(
+            c_tensors.data(),
+            c_tensors.size(),
+            axis.c_str()
+        );
+        details::check_pointer(ptr);
+        return TensorMap(ptr);
+    }
+
     /// Load a previously saved `TensorMap` from the given path.
     ///
     /// `TensorMap` are serialized using numpy's `.npz` format, i.e. a ZIP
     /// file without compression (storage method is `STORED`), where each file
     /// is stored as a `.npy` array. See the C API documentation for more
     /// information on the format.
+    ///
+    /// The data is loaded in `SimpleDataArray`, which only supports 64-bit
+    /// floating point values.
     static TensorMap load(const std::string& path) {
         auto ptr = eqs_tensormap_load(
             path.c_str(),
-            [](const uintptr_t* shape_ptr, uintptr_t shape_count, eqs_array_t *array){
+            [](const uintptr_t* shape_ptr, uintptr_t shape_count, eqs_dtype_t dtype, eqs_array_t *array){
+                if (dtype != EQS_DTYPE_FLOAT64) {
+                    return -1;
+                }
+
                 auto shape = std::vector<size_t>();
                 for (size_t i=0; i<shape_count; i++) {
                     shape.push_back(static_cast<size_t>(shape_ptr[i]));
@@ -2,9 +2,12 @@ use std::sync::Arc;
 use std::ffi::CString;
 use std::collections::{HashMap, BTreeSet};
 
+use indexmap::IndexSet;
+
 use crate::utils::ConstCString;
-use crate::{Labels, LabelsBuilder};
+use crate::{Labels, LabelsBuilder, LabelValue};
 use crate::{eqs_array_t, get_data_origin};
+use crate::Metadata;
 use crate::Error;
 
 /// A `Vec` which can not be modified
@@ -29,6 +32,16 @@ impl<'a, T> IntoIterator for &'a ImmutableVec<T> {
     }
 }
 
+/// Axis of the data arrays in a block, used to select which labels an
+/// operation should act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// The first axis of the arrays, described by the samples labels
+    Samples,
+    /// The last axis of the arrays, described by the properties labels
+    Properties,
+}
+
 /// Single data array with the corresponding metadata inside a `TensorBlock`
 #[derive(Debug, Clone)]
 pub struct BasicBlock {
@@ -36,6 +49,9 @@ pub struct BasicBlock {
     pub samples: Arc<Labels>,
     pub components: ImmutableVec<Arc<Labels>>,
     pub properties: Arc<Labels>,
+    /// Additional information about the data in this block (e.g. the
+    /// physical units), as key/value pairs
+    pub info: Metadata,
 }
 
 fn check_data_and_labels(
@@ -118,24 +134,22 @@ impl BasicBlock {
 
         check_component_labels(&components)?;
         let components = ImmutableVec(components);
-        return Ok(BasicBlock { data, samples, components, properties });
+        return Ok(BasicBlock { data, samples, components, properties, info: Metadata::default() });
+    }
+
+    /// Get the index of the component with the given `variables` names
+    fn component_index(&self, variables: &[&str]) -> Result<usize, Error> {
+        return self.components.iter()
+            .position(|component| component.names() == variables)
+            .ok_or_else(|| Error::InvalidParameter(format!(
+                "unable to find [{}] in the components ", variables.join(", ")
+            )));
     }
 
     fn components_to_properties(&mut self, variables: &[&str]) -> Result<(), Error> {
         debug_assert!(!variables.is_empty());
 
-        let mut component_axis = None;
-        for (component_i, component) in self.components.iter().enumerate() {
-            if component.names() == variables {
-                component_axis = Some(component_i);
-                break;
-            }
-        }
-
-        let component_axis = component_axis.ok_or_else(|| Error::InvalidParameter(format!(
-            "unable to find [{}] in the components ", variables.join(", ")
-        )))?;
-
+        let component_axis = self.component_index(variables)?;
         let moved_component = self.components.0.remove(component_axis);
 
         // construct the new property with old properties and the components
@@ -167,6 +181,67 @@ impl BasicBlock {
 
         Ok(())
     }
+
+    /// Merge the component at `component_index` with the samples, using
+    /// `new_samples` as the new samples labels. The values of the component
+    /// are varying faster than the old samples in the merged axis.
+    fn merge_component_with_samples(&mut self, component_index: usize, new_samples: Arc<Labels>) -> Result<(), Error> {
+        let mut new_shape = self.data.shape()?.to_vec();
+        let component_size = new_shape.remove(component_index + 1);
+        new_shape[0] *= component_size;
+        debug_assert_eq!(new_shape[0], new_samples.count());
+
+        // move the component axis right after the samples axis, keeping the
+        // order of the other components
+        for axis in (1..=component_index).rev() {
+            self.data.swap_axes(axis, axis + 1)?;
+        }
+        self.data.reshape(&new_shape)?;
+
+        self.components.0.remove(component_index);
+        self.samples = new_samples;
+
+        Ok(())
+    }
+
+    /// Split the properties of this block into a new last `component` and
+    /// `new_properties`. If `component_first` is true, the values of the
+    /// component are varying slower than the new properties in the old
+    /// properties, otherwise they are varying faster.
+    fn split_properties_to_component(
+        &mut self,
+        component: Arc<Labels>,
+        new_properties: Arc<Labels>,
+        component_first: bool,
+    ) -> Result<(), Error> {
+        let mut new_shape = self.data.shape()?.to_vec();
+        let properties_axis = new_shape.len() - 1;
+        new_shape.pop();
+
+        if component_first {
+            new_shape.push(component.count());
+            new_shape.push(new_properties.count());
+            self.data.reshape(&new_shape)?;
+        } else {
+            new_shape.push(new_properties.count());
+            new_shape.push(component.count());
+            self.data.reshape(&new_shape)?;
+            self.data.swap_axes(properties_axis, properties_axis + 1)?;
+
+            // `swap_axes` can leave the data with a non-contiguous layout,
+            // copy it to a new array to make it contiguous again
+            new_shape.swap(properties_axis, properties_axis + 1);
+            let mut new_data = self.data.create(&new_shape)?;
+            let samples = (0..new_shape[0]).collect::<Vec<_>>();
+            new_data.gather_from(&self.data, 0, &samples)?;
+            self.data = new_data;
+        }
+
+        self.components.0.push(component);
+        self.properties = new_properties;
+
+        Ok(())
+    }
 }
 
 /// A single block in a `TensorMap`, containing both values & optionally
@@ -258,6 +333,14 @@ impl TensorBlock {
             )))
         }
 
+        if data.dtype()? != self.values.data.dtype()? {
+            return Err(Error::InvalidParameter(format!(
+                "the gradient array has a different data type ({}) than the value array ({})",
+                data.dtype()?,
+                self.values.data.dtype()?,
+            )))
+        }
+
         // this is used as a special marker in the C API
         if parameter == "values" {
             return Err(Error::InvalidParameter(
@@ -303,7 +386,8 @@ impl TensorBlock {
             data,
             samples,
             components,
-            properties
+            properties,
+            info: Metadata::default(),
         });
 
         let parameter = ConstCString::new(CString::new(parameter.to_owned()).expect("invalid C string"));
@@ -324,6 +408,157 @@ impl TensorBlock {
 
         Ok(())
     }
+
+    pub(crate) fn components_to_samples(&mut self, variables: &[&str]) -> Result<(), Error> {
+        if variables.is_empty() {
+            return Ok(());
+        }
+
+        let component_index = self.values.component_index(variables)?;
+        let component = Arc::clone(&self.values.components[component_index]);
+
+        let samples = &self.values.samples;
+        for variable in variables {
+            if samples.names().contains(variable) {
+                return Err(Error::InvalidParameter(format!(
+                    "'{}' is already part of the samples for this block", variable
+                )));
+            }
+        }
+
+        let mut new_samples_names = samples.names();
+        new_samples_names.extend_from_slice(variables);
+        let mut new_samples = LabelsBuilder::new(new_samples_names);
+        new_samples.reserve(samples.count() * component.count());
+        for sample in samples.iter() {
+            for value in component.iter() {
+                let mut new_sample = sample.to_vec();
+                new_sample.extend_from_slice(value);
+                new_samples.add(&new_sample)?;
+            }
+        }
+        self.values.merge_component_with_samples(component_index, Arc::new(new_samples.finish()))?;
+
+        // each gradient sample is duplicated for all the values of the
+        // component, and refers to the corresponding new sample
+        let component_size = component.count();
+        for gradient in self.gradients.values_mut() {
+            let component_index = gradient.component_index(variables)?;
+
+            let mut new_samples = LabelsBuilder::new(gradient.samples.names());
+            new_samples.reserve(gradient.samples.count() * component_size);
+            for grad_sample in gradient.samples.iter() {
+                for i in 0..component_size {
+                    let mut new_sample = grad_sample.to_vec();
+                    new_sample[0] = LabelValue::from(grad_sample[0].usize() * component_size + i);
+                    new_samples.add(&new_sample)?;
+                }
+            }
+
+            gradient.merge_component_with_samples(component_index, Arc::new(new_samples.finish()))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn properties_to_components(&mut self, variables: &[&str]) -> Result<(), Error> {
+        if variables.is_empty() {
+            return Ok(());
+        }
+
+        if variables.len() != 1 {
+            return Err(Error::InvalidParameter(format!(
+                "component labels must have a single variable, got {}: [{}]",
+                variables.len(), variables.join(", ")
+            )));
+        }
+
+        for component in self.gradients.values().flat_map(|g| &g.components).chain(&self.values.components) {
+            if component.names() == variables {
+                return Err(Error::InvalidParameter(format!(
+                    "'{}' is already part of the components for this block", variables[0]
+                )));
+            }
+        }
+
+        let properties = &self.values.properties;
+        let names = properties.names();
+        let mut moved_i = Vec::new();
+        for variable in variables {
+            let i = names.iter().position(|name| name == variable).ok_or_else(|| Error::InvalidParameter(format!(
+                "'{}' is not part of the properties for this block", variable
+            )))?;
+            moved_i.push(i);
+        }
+        let remaining_i = (0..names.len()).filter(|i| !moved_i.contains(i)).collect::<Vec<_>>();
+
+        // values taken by the moved and remaining variables, in order of
+        // appearance in the properties
+        let mut component_values = IndexSet::new();
+        let mut remaining_values = IndexSet::new();
+        let mut entries = Vec::with_capacity(properties.count());
+        for property in properties.iter() {
+            let moved = moved_i.iter().map(|&i| property[i]).collect::<Vec<_>>();
+            let remaining = remaining_i.iter().map(|&i| property[i]).collect::<Vec<_>>();
+            let (component_i, _) = component_values.insert_full(moved);
+            let (remaining_i, _) = remaining_values.insert_full(remaining);
+            entries.push((component_i, remaining_i));
+        }
+
+        let n_component = component_values.len();
+        let n_remaining = remaining_values.len();
+        let component_first = entries.iter().enumerate().all(|(i, &entry)| {
+            entry == (i / n_remaining, i % n_remaining)
+        });
+        let component_last = entries.iter().enumerate().all(|(i, &entry)| {
+            entry == (i % n_component, i / n_component)
+        });
+
+        if n_component * n_remaining != properties.count() || !(component_first || component_last) {
+            return Err(Error::InvalidParameter(format!(
+                "can not move [{}] to the components: the properties must \
+                contain all the combinations of these variables with the \
+                remaining ones, in a regular order",
+                variables.join(", ")
+            )));
+        }
+
+        let mut component = LabelsBuilder::new(variables.to_vec());
+        component.reserve(n_component);
+        for value in component_values {
+            component.add(&value)?;
+        }
+        let component = Arc::new(component.finish());
+
+        let new_properties = if remaining_i.is_empty() {
+            let mut builder = LabelsBuilder::new(vec!["_"]);
+            builder.add(&[0])?;
+            builder.finish()
+        } else {
+            let mut builder = LabelsBuilder::new(remaining_i.iter().map(|&i| names[i]).collect());
+            builder.reserve(n_remaining);
+            for value in remaining_values {
+                builder.add(&value)?;
+            }
+            builder.finish()
+        };
+        let new_properties = Arc::new(new_properties);
+
+        self.values.split_properties_to_component(
+            Arc::clone(&component),
+            Arc::clone(&new_properties),
+            component_first,
+        )?;
+        for gradient in self.gradients.values_mut() {
+            gradient.split_properties_to_component(
+                Arc::clone(&component),
+                Arc::clone(&new_properties),
+                component_first,
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
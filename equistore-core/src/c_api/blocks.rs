@@ -5,6 +5,7 @@ use std::ffi::CStr;
 use crate::{TensorBlock, Error, eqs_array_t};
 
 use super::labels::{eqs_labels_t, rust_to_eqs_labels, eqs_labels_to_rust};
+use super::utils::axis_from_c;
 
 use super::{catch_unwind, eqs_status_t};
 
@@ -333,3 +334,240 @@ pub unsafe extern fn eqs_block_gradients_list(
         Ok(())
     })
 }
+
+
+/// Get the value associated with `key` in the info of the values or one of
+/// the gradients of this `block`.
+///
+/// The `values_gradients` parameter controls whether this function looks up
+/// the info for `"values"` or one of the gradients in this block. `value` is
+/// set to a NULL-terminated UTF-8 string, or to `NULL` if there is no info
+/// associated with `key`. The string memory is still managed by the block,
+/// and is invalidated when the block is freed or its info modified with
+/// `eqs_block_set_info`.
+///
+/// @param block pointer to an existing block
+/// @param values_gradients either `"values"` or the name of gradients to lookup
+/// @param key NULL-terminated UTF-8 string containing the info key
+/// @param value pointer to be filled with the info value
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_block_info(
+    block: *const eqs_block_t,
+    values_gradients: *const c_char,
+    key: *const c_char,
+    value: *mut *const c_char,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(block, values_gradients, key, value);
+
+        let values_gradients = CStr::from_ptr(values_gradients).to_str().unwrap();
+        let basic_block = match values_gradients {
+            "values" => (*block).values(),
+            parameter => {
+                (*block).gradient(parameter).ok_or_else(|| Error::InvalidParameter(format!(
+                    "can not find gradients with respect to '{}' in this block", parameter
+                )))?
+            }
+        };
+
+        let key = CStr::from_ptr(key).to_str().expect("invalid utf8");
+        *value = match basic_block.info.get_c(key) {
+            Some(info) => info.as_c_str().as_ptr(),
+            None => std::ptr::null(),
+        };
+
+        Ok(())
+    })
+}
+
+/// Set the value associated with `key` in the info of the values or one of
+/// the gradients of this `block`, overwriting any existing value.
+///
+/// If `value` is `NULL`, the info associated with `key` is removed instead.
+///
+/// @param block pointer to an existing block
+/// @param values_gradients either `"values"` or the name of gradients to modify
+/// @param key NULL-terminated UTF-8 string containing the info key
+/// @param value NULL-terminated UTF-8 string containing the info value, or
+///              `NULL` to remove the info
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_block_set_info(
+    block: *mut eqs_block_t,
+    values_gradients: *const c_char,
+    key: *const c_char,
+    value: *const c_char,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(block, values_gradients, key);
+
+        let values_gradients = CStr::from_ptr(values_gradients).to_str().unwrap();
+        let basic_block = match values_gradients {
+            "values" => (*block).values_mut(),
+            parameter => {
+                (*block).gradient_mut(parameter).ok_or_else(|| Error::InvalidParameter(format!(
+                    "can not find gradients with respect to '{}' in this block", parameter
+                )))?
+            }
+        };
+
+        let key = CStr::from_ptr(key).to_str().expect("invalid utf8");
+        if value.is_null() {
+            basic_block.info.remove(key);
+        } else {
+            let value = CStr::from_ptr(value).to_str().expect("invalid utf8");
+            basic_block.info.set(key, value)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Get the list of all keys in the info of the values or one of the gradients
+/// of this `block`, sorted alphabetically.
+///
+/// The strings memory is still managed by the block, and is invalidated when
+/// the block is freed or its info modified with `eqs_block_set_info`.
+///
+/// @param block pointer to an existing block
+/// @param values_gradients either `"values"` or the name of gradients to lookup
+/// @param keys will be set to the first element of an array of NULL-terminated
+///             UTF-8 strings containing all the info keys
+/// @param keys_count will be set to the number of elements in `keys`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_block_info_keys(
+    block: *const eqs_block_t,
+    values_gradients: *const c_char,
+    keys: *mut *const *const c_char,
+    keys_count: *mut usize,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(block, values_gradients, keys, keys_count);
+
+        let values_gradients = CStr::from_ptr(values_gradients).to_str().unwrap();
+        let basic_block = match values_gradients {
+            "values" => (*block).values(),
+            parameter => {
+                (*block).gradient(parameter).ok_or_else(|| Error::InvalidParameter(format!(
+                    "can not find gradients with respect to '{}' in this block", parameter
+                )))?
+            }
+        };
+
+        let list = basic_block.info.keys_c();
+        (*keys_count) = list.len();
+
+        (*keys) = if list.is_empty() {
+            std::ptr::null()
+        } else {
+            list.as_ptr().cast()
+        };
+        Ok(())
+    })
+}
+
+
+/// Slice this `block` along the given `axis`, keeping only the samples or
+/// properties matching the `selection`.
+///
+/// The `selection` must contain a subset of the names of the samples or
+/// properties labels, and an entry is kept if the values of these variables
+/// match one of the entries in the `selection`. When slicing along samples,
+/// the gradient samples referring to removed samples are removed as well.
+///
+/// This function requires `eqs_array_t.gather_from` to be implemented. The
+/// result is a new block, which should be freed with `eqs_block_free`.
+///
+/// @param block pointer to an existing block
+/// @param axis name of the axis along which the block should be sliced, either
+///             `"samples"` or `"properties"`
+/// @param selection labels describing which entries should be kept
+///
+/// @returns A pointer to the newly allocated block, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_block_slice(
+    block: *const eqs_block_t,
+    axis: *const c_char,
+    selection: eqs_labels_t,
+) -> *mut eqs_block_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+    let status = catch_unwind(move || {
+        check_pointers!(block, axis);
+
+        let axis = axis_from_c(axis)?;
+        let selection = eqs_labels_to_rust(&selection)?;
+        let new_block = (*block).slice(axis, &selection)?;
+        let boxed = Box::new(eqs_block_t(new_block));
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *(unwind_wrapper.0) = Box::into_raw(boxed);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
+
+/// Sort the entries of this `block` along the given `axis` in lexicographic
+/// order.
+///
+/// When sorting along samples, the gradient samples are updated to refer to the
+/// new samples, and then sorted as well.
+///
+/// This function requires `eqs_array_t.gather_from` to be implemented. The
+/// result is a new block, which should be freed with `eqs_block_free`.
+///
+/// @param block pointer to an existing block
+/// @param axis axis along which to sort the block, either `"samples"` or
+///             `"properties"`
+///
+/// @returns A pointer to the newly allocated block, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_block_sort(
+    block: *const eqs_block_t,
+    axis: *const c_char,
+) -> *mut eqs_block_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+    let status = catch_unwind(move || {
+        check_pointers!(block, axis);
+
+        let axis = axis_from_c(axis)?;
+        let new_block = (*block).sort(axis)?;
+        let boxed = Box::new(eqs_block_t(new_block));
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *(unwind_wrapper.0) = Box::into_raw(boxed);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
@@ -1,7 +1,7 @@
 use std::os::raw::c_char;
 use std::ffi::CStr;
 
-use crate::eqs_data_origin_t;
+use crate::{eqs_array_t, eqs_data_origin_t};
 
 use super::{eqs_status_t, catch_unwind};
 use super::utils::copy_str_to_c;
@@ -55,3 +55,31 @@ pub unsafe extern fn eqs_get_data_origin(
         return copy_str_to_c(&origin, buffer, buffer_size);
     })
 }
+
+
+/// Check that the given `array` correctly implements the functions in
+/// `eqs_array_t`, by creating new arrays from it and exercising `origin`,
+/// `dtype`, `device`, `data`/`copy_to_host`, `shape`, `reshape`, `swap_axes`,
+/// `create`, `copy`, `move_samples_from`, `move_data` (if it is set),
+/// `gather_from`, `scatter_add_from` and `destroy`.
+///
+/// The `array` itself is not modified by this function. The checks on the
+/// values stored in the arrays are only meaningful for arrays on
+/// `EQS_DEVICE_CPU`, since equistore can not write data to other devices.
+///
+/// This function is intended to be used in the test suite of code
+/// implementing `eqs_array_t`.
+///
+/// @param array array to check
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, the array does not correctly implement
+///          `eqs_array_t`, and you can use `eqs_last_error()` to get an error
+///          message describing which contract was violated.
+#[no_mangle]
+pub unsafe extern fn eqs_array_check(array: *const eqs_array_t) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(array);
+        (*array).check()
+    })
+}
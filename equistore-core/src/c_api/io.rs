@@ -4,7 +4,7 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter};
 
 use crate::Error;
-use crate::data::eqs_array_t;
+use crate::data::{eqs_array_t, eqs_dtype_t};
 
 use super::status::{eqs_status_t, catch_unwind};
 use super::tensor::eqs_tensormap_t;
@@ -13,16 +13,18 @@ use super::tensor::eqs_tensormap_t;
 /// maps.
 ///
 /// This function gets the `shape` of the array (the `shape` contains
-/// `shape_count` elements) and should return a new valid `eqs_array_t` or a
-/// non-zero `eqs_status_t`.
+/// `shape_count` elements) and the data type of the array (`dtype`, one of the
+/// `EQS_DTYPE_XXX` constants), and should return a new valid `eqs_array_t` or
+/// a non-zero `eqs_status_t`.
 ///
-/// The newly created array should contains 64-bit floating points (`double`)
-/// data, and live on CPU, since equistore will use `eqs_array_t.data` to get
-/// the data pointer and write to it.
+/// The newly created array should contains data of the requested type, and
+/// live on CPU, since equistore will use `eqs_array_t.data` to get the data
+/// pointer and write to it.
 #[allow(non_camel_case_types)]
 type eqs_create_array_callback_t = unsafe extern fn(
     shape: *const usize,
     shape_count: usize,
+    dtype: eqs_dtype_t,
     array: *mut eqs_array_t,
 ) -> eqs_status_t;
 
@@ -44,8 +46,11 @@ type eqs_create_array_callback_t = unsafe extern fn(
 ///
 /// We add other restriction on top of these formats when saving/loading data.
 /// First, `Labels` instances are saved as structured array, see the `labels`
-/// module for more information. Only 32-bit integers are supported for Labels,
-/// and only 64-bit floats are supported for data (values and gradients).
+/// module for more information. Labels are saved using 64-bit integers (32-bit
+/// integers are also supported when loading older files). Data (values and
+/// gradients) can contain 64-bit, 32-bit or 16-bit floating point values
+/// (`<f8`, `<f4` and `<f2` in numpy notation), as well as 32-bit or 64-bit
+/// signed integers (`<i4` and `<i8`).
 ///
 /// Second, the path of the files in the archive also carry meaning. The keys of
 /// the `TensorMap` are stored in `/keys.npy`, and then different blocks are
@@ -84,11 +89,12 @@ pub unsafe extern fn eqs_tensormap_load(
     let status = catch_unwind(move || {
         check_pointers!(path);
 
-        let create_array = |shape: Vec<usize>| {
+        let create_array = |shape: Vec<usize>, dtype: eqs_dtype_t| {
             let mut array = eqs_array_t::null();
             let status = create_array(
                 shape.as_ptr(),
                 shape.len(),
+                dtype,
                 &mut array
             );
 
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use crate::{LabelValue, Labels, LabelsBuilder, Error};
 use super::status::{eqs_status_t, catch_unwind};
+use super::utils::strings_from_c;
 
 /// A set of labels used to carry metadata associated with a tensor map.
 ///
@@ -27,11 +28,11 @@ pub struct eqs_labels_t {
     /// Names of the variables composing this set of labels. There are `size`
     /// elements in this array, each being a NULL terminated UTF-8 string.
     pub names: *const *const c_char,
-    /// Pointer to the first element of a 2D row-major array of 32-bit signed
+    /// Pointer to the first element of a 2D row-major array of 64-bit signed
     /// integer containing the values taken by the different variables in
     /// `names`. Each row has `size` elements, and there are `count` rows in
     /// total.
-    pub values: *const i32,
+    pub values: *const i64,
     /// Number of variables/size of a single entry in the set of labels
     pub size: usize,
     /// Number entries in the set of labels
@@ -148,7 +149,7 @@ unsafe fn create_rust_labels(labels: &eqs_labels_t) -> Result<Arc<Labels>, Error
 #[no_mangle]
 pub unsafe extern fn eqs_labels_position(
     labels: eqs_labels_t,
-    values: *const i32,
+    values: *const i64,
     values_count: usize,
     result: *mut i64
 ) -> eqs_status_t {
@@ -175,6 +176,77 @@ pub unsafe extern fn eqs_labels_position(
     })
 }
 
+/// Get the positions of multiple entries in the given set of `labels` at once.
+///
+/// `values` should contain the `count` entries to look for, stored as a 2D
+/// row-major array of shape `(count, labels.size)`. The position of each entry
+/// is written in the corresponding element of `results`, or -1 if this entry
+/// is not part of the labels.
+///
+/// Unlike `eqs_labels_position`, this function also accepts labels which did
+/// not go through `eqs_labels_create`. The data of such labels is copied on
+/// each call, so you should still use `eqs_labels_create` first if you need to
+/// call this function multiple times.
+///
+/// If `parallel` is true, the entries are looked for using multiple threads.
+/// This is only supported if equistore-core was compiled with the `rayon`
+/// feature (`EQUISTORE_ENABLE_RAYON=ON` in CMake), and the lookup happens on
+/// the current thread otherwise.
+///
+/// @param labels set of labels in which to look for the entries
+/// @param values pointer to the first element of a 2D array containing the
+///               entries to look for
+/// @param count number of entries (i.e. rows) in `values`
+/// @param parallel whether to look for the entries in parallel
+/// @param results pointer to an array containing `count` elements, to be
+///                filled with the positions of the entries in `labels`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_positions(
+    labels: eqs_labels_t,
+    values: *const i64,
+    count: usize,
+    parallel: bool,
+    results: *mut i64,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        if count == 0 {
+            return Ok(());
+        }
+        check_pointers!(values, results);
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let results = std::slice::from_raw_parts_mut(results, count);
+        if labels.size() == 0 {
+            results.fill(-1);
+            return Ok(());
+        }
+
+        let values = std::slice::from_raw_parts(values.cast::<LabelValue>(), count * labels.size());
+
+        #[cfg(feature = "rayon")]
+        let positions = if parallel {
+            labels.par_positions(values)
+        } else {
+            labels.positions(values)
+        };
+
+        #[cfg(not(feature = "rayon"))]
+        let positions = {
+            let _ = parallel;
+            labels.positions(values)
+        };
+
+        for (result, position) in results.iter_mut().zip(positions) {
+            *result = position.map_or(-1, |p| p as i64);
+        }
+
+        Ok(())
+    })
+}
 
 /// Finish the creation of `eqs_labels_t` by associating it to Rust-owned
 /// labels.
@@ -257,6 +329,518 @@ pub unsafe extern fn eqs_labels_clone(
     })
 }
 
+/// Check whether the entries in `labels` are sorted in lexicographic order.
+///
+/// This information is tracked when creating the labels, making this check
+/// cheap for labels with a non-NULL `internal_ptr_`. Lookup in sorted labels
+/// (with `eqs_labels_position` and similar functions) uses binary search
+/// instead of a hash table, and bindings can rely on this ordering as well.
+///
+/// @param labels set of labels to check
+/// @param is_sorted pointer to a boolean, will be set to `true` if the labels
+///                  are sorted and `false` otherwise
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_is_sorted(
+    labels: eqs_labels_t,
+    is_sorted: *mut bool,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(is_sorted);
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        *is_sorted = labels.is_sorted();
+
+        Ok(())
+    })
+}
+
+/// Sort the entries in `labels` in lexicographic order.
+///
+/// If requested, this function can also give the permutation used to sort
+/// the labels: the entry at position `i` in `result` was at position
+/// `permutation[i]` in `labels`.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param labels set of labels to sort
+/// @param result empty labels, on output will contain the sorted labels
+/// @param permutation if you want the permutation used to sort the labels,
+///        this should be a pointer to an array containing `labels.count`
+///        elements, to be filled by this function. Otherwise it should be a
+///        `NULL` pointer.
+/// @param permutation_count number of elements in `permutation`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_sort(
+    labels: eqs_labels_t,
+    result: *mut eqs_labels_t,
+    permutation: *mut i64,
+    permutation_count: usize,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(result);
+        check_empty_output(result)?;
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let (sorted, rust_permutation) = labels.sort()?;
+
+        write_mapping(rust_permutation.into_iter().map(Some), permutation, permutation_count, "permutation")?;
+
+        *result = rust_to_eqs_labels(Arc::new(sorted));
+
+        Ok(())
+    })
+}
+
+/// Select entries in the `labels` that match the `selection`.
+///
+/// The `selection` should have a subset of the names/variables of the
+/// `labels`, and an entry of the `labels` is selected if the values of these
+/// variables match any of the entries in the `selection`. If the `selection`
+/// does not contain any variable, all entries are selected.
+///
+/// When calling this function, `*selected_count` should contain the number of
+/// entries in `selected`, which must be the same as `labels.count`. When the
+/// function returns successfully, `*selected_count` will contain the number of
+/// selected entries, i.e. how many values were written to `selected`. The
+/// positions are written in increasing order.
+///
+/// @param labels labels on which to run the selection
+/// @param selection definition of the selection criteria
+/// @param selected array to be filled with the positions of the selected
+///                 entries in `labels`
+/// @param selected_count number of entries in `selected`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_select(
+    labels: eqs_labels_t,
+    selection: eqs_labels_t,
+    selected: *mut usize,
+    selected_count: *mut usize,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(selected, selected_count);
+
+        if *selected_count != labels.count {
+            return Err(Error::InvalidParameter(format!(
+                "expected space for {} indices as input to eqs_labels_select, got space for {}",
+                labels.count, *selected_count
+            )));
+        }
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let selection = eqs_labels_to_rust(&selection)?;
+
+        let rust_selected = labels.select(&selection)?;
+        let selected = std::slice::from_raw_parts_mut(selected, *selected_count);
+        selected[..rust_selected.len()].copy_from_slice(&rust_selected);
+        *selected_count = rust_selected.len();
+
+        Ok(())
+    })
+}
+
+/// Check that the output `result` labels do not already contain any data
+unsafe fn check_empty_output(result: *const eqs_labels_t) -> Result<(), Error> {
+    if (*result).is_rust() {
+        return Err(Error::InvalidParameter(
+            "output labels already contain some data".into()
+        ));
+    }
+    return Ok(());
+}
+
+/// Write the given `mapping` to the C array `output` with `output_count`
+/// elements, using -1 for missing entries. Nothing is written if `output` is
+/// NULL.
+unsafe fn write_mapping(
+    mapping: impl ExactSizeIterator<Item=Option<usize>>,
+    output: *mut i64,
+    output_count: usize,
+    name: &str,
+) -> Result<(), Error> {
+    if output.is_null() {
+        return Ok(());
+    }
+
+    if output_count != mapping.len() {
+        return Err(Error::InvalidParameter(format!(
+            "{} has the wrong size: expected {} entries, got {}",
+            name, mapping.len(), output_count
+        )));
+    }
+
+    let output = std::slice::from_raw_parts_mut(output, output_count);
+    for (output, position) in output.iter_mut().zip(mapping) {
+        *output = position.map_or(-1, |p| p as i64);
+    }
+
+    return Ok(());
+}
+
+/// Take the union of two `eqs_labels_t`.
+///
+/// The result contains all the entries of `first`, followed by the entries of
+/// `second` which are not in `first`. Both labels must have the same names.
+///
+/// If requested, this function can also give the positions in the union where
+/// each entry of the input `eqs_labels_t` ended up.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param first first set of labels
+/// @param second second set of labels
+/// @param result empty labels, on output will contain the union of `first` and
+///        `second`
+/// @param first_mapping if you want the mapping from the positions of entries
+///        in `first` to the positions in `result`, this should be a pointer
+///        to an array containing `first.count` elements, to be filled by this
+///        function. Otherwise it should be a `NULL` pointer.
+/// @param first_mapping_count number of elements in `first_mapping`
+/// @param second_mapping if you want the mapping from the positions of entries
+///        in `second` to the positions in `result`, this should be a pointer
+///        to an array containing `second.count` elements, to be filled by this
+///        function. Otherwise it should be a `NULL` pointer.
+/// @param second_mapping_count number of elements in `second_mapping`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_union(
+    first: eqs_labels_t,
+    second: eqs_labels_t,
+    result: *mut eqs_labels_t,
+    first_mapping: *mut i64,
+    first_mapping_count: usize,
+    second_mapping: *mut i64,
+    second_mapping_count: usize,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(result);
+        check_empty_output(result)?;
+
+        let first = eqs_labels_to_rust(&first)?;
+        let second = eqs_labels_to_rust(&second)?;
+
+        let (union, rust_first_mapping, rust_second_mapping) = first.union(&second)?;
+
+        write_mapping(rust_first_mapping.into_iter().map(Some), first_mapping, first_mapping_count, "first_mapping")?;
+        write_mapping(rust_second_mapping.into_iter().map(Some), second_mapping, second_mapping_count, "second_mapping")?;
+
+        *result = rust_to_eqs_labels(Arc::new(union));
+
+        Ok(())
+    })
+}
+
+/// Take the intersection of two `eqs_labels_t`.
+///
+/// The result contains all the entries of `first` which are also in `second`,
+/// in the same order as in `first`. Both labels must have the same names.
+///
+/// If requested, this function can also give the positions in the
+/// intersection where each entry of the input `eqs_labels_t` ended up, or -1
+/// if the entry is not part of the intersection.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param first first set of labels
+/// @param second second set of labels
+/// @param result empty labels, on output will contain the intersection of
+///        `first` and `second`
+/// @param first_mapping if you want the mapping from the positions of entries
+///        in `first` to the positions in `result`, this should be a pointer
+///        to an array containing `first.count` elements, to be filled by this
+///        function. Otherwise it should be a `NULL` pointer.
+/// @param first_mapping_count number of elements in `first_mapping`
+/// @param second_mapping if you want the mapping from the positions of entries
+///        in `second` to the positions in `result`, this should be a pointer
+///        to an array containing `second.count` elements, to be filled by this
+///        function. Otherwise it should be a `NULL` pointer.
+/// @param second_mapping_count number of elements in `second_mapping`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_intersection(
+    first: eqs_labels_t,
+    second: eqs_labels_t,
+    result: *mut eqs_labels_t,
+    first_mapping: *mut i64,
+    first_mapping_count: usize,
+    second_mapping: *mut i64,
+    second_mapping_count: usize,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(result);
+        check_empty_output(result)?;
+
+        let first = eqs_labels_to_rust(&first)?;
+        let second = eqs_labels_to_rust(&second)?;
+
+        let (intersection, rust_first_mapping, rust_second_mapping) = first.intersection(&second)?;
+
+        write_mapping(rust_first_mapping.into_iter(), first_mapping, first_mapping_count, "first_mapping")?;
+        write_mapping(rust_second_mapping.into_iter(), second_mapping, second_mapping_count, "second_mapping")?;
+
+        *result = rust_to_eqs_labels(Arc::new(intersection));
+
+        Ok(())
+    })
+}
+
+/// Take the difference of two `eqs_labels_t`.
+///
+/// The result contains all the entries of `first` which are not in `second`,
+/// in the same order as in `first`. Both labels must have the same names.
+///
+/// If requested, this function can also give the positions in the difference
+/// where each entry of `first` ended up, or -1 if the entry is not part of the
+/// difference.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param first first set of labels
+/// @param second second set of labels
+/// @param result empty labels, on output will contain the entries of `first`
+///        which are not in `second`
+/// @param first_mapping if you want the mapping from the positions of entries
+///        in `first` to the positions in `result`, this should be a pointer
+///        to an array containing `first.count` elements, to be filled by this
+///        function. Otherwise it should be a `NULL` pointer.
+/// @param first_mapping_count number of elements in `first_mapping`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_difference(
+    first: eqs_labels_t,
+    second: eqs_labels_t,
+    result: *mut eqs_labels_t,
+    first_mapping: *mut i64,
+    first_mapping_count: usize,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(result);
+        check_empty_output(result)?;
+
+        let first = eqs_labels_to_rust(&first)?;
+        let second = eqs_labels_to_rust(&second)?;
+
+        let (difference, rust_first_mapping) = first.difference(&second)?;
+
+        write_mapping(rust_first_mapping.into_iter(), first_mapping, first_mapping_count, "first_mapping")?;
+
+        *result = rust_to_eqs_labels(Arc::new(difference));
+
+        Ok(())
+    })
+}
+
+/// Project `labels` onto the variables with the given `names`, keeping only
+/// these variables (in the given order) and removing duplicated entries. The
+/// remaining entries are kept in the order of their first appearance.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param labels set of labels to project
+/// @param names names of the variables to keep, as an array of NULL-terminated
+///              UTF-8 strings
+/// @param names_count number of entries in `names`
+/// @param result empty labels, on output will contain the projected labels
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_project(
+    labels: eqs_labels_t,
+    names: *const *const c_char,
+    names_count: usize,
+    result: *mut eqs_labels_t,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(result);
+        check_empty_output(result)?;
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let names = strings_from_c(names, names_count)?;
+
+        *result = rust_to_eqs_labels(Arc::new(labels.project(&names)?));
+
+        Ok(())
+    })
+}
+
+/// Insert a new variable with the given `name` at position `index` in the
+/// `labels`.
+///
+/// `values` must either contain a single value, used for all entries, or one
+/// value for each entry in the `labels`.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param labels set of labels in which to insert a new variable
+/// @param index position of the new variable, between 0 and `labels.size`
+/// @param name NULL-terminated UTF-8 string containing the name of the new
+///             variable
+/// @param values values of the new variable
+/// @param values_count number of entries in `values`, either 1 or
+///                     `labels.count`
+/// @param result empty labels, on output will contain the new labels
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_insert(
+    labels: eqs_labels_t,
+    index: usize,
+    name: *const c_char,
+    values: *const i64,
+    values_count: usize,
+    result: *mut eqs_labels_t,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(name, values, result);
+        check_empty_output(result)?;
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let name = CStr::from_ptr(name).to_str().expect("invalid utf8");
+        let values = std::slice::from_raw_parts(values.cast::<LabelValue>(), values_count);
+
+        *result = rust_to_eqs_labels(Arc::new(labels.insert(index, name, values)?));
+
+        Ok(())
+    })
+}
+
+/// Remove the variable with the given `name` from the `labels`.
+///
+/// This returns an error if removing the variable would create duplicated
+/// entries, use `eqs_labels_project` to remove the duplicates instead.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param labels set of labels from which to remove a variable
+/// @param name NULL-terminated UTF-8 string containing the name of the
+///             variable to remove
+/// @param result empty labels, on output will contain the new labels
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_remove(
+    labels: eqs_labels_t,
+    name: *const c_char,
+    result: *mut eqs_labels_t,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(name, result);
+        check_empty_output(result)?;
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let name = CStr::from_ptr(name).to_str().expect("invalid utf8");
+
+        *result = rust_to_eqs_labels(Arc::new(labels.remove(name)?));
+
+        Ok(())
+    })
+}
+
+/// Rename the variable `old_name` to `new_name` in the `labels`.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param labels set of labels in which to rename a variable
+/// @param old_name NULL-terminated UTF-8 string containing the current name of
+///                 the variable
+/// @param new_name NULL-terminated UTF-8 string containing the new name of the
+///                 variable
+/// @param result empty labels, on output will contain the new labels
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_rename(
+    labels: eqs_labels_t,
+    old_name: *const c_char,
+    new_name: *const c_char,
+    result: *mut eqs_labels_t,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(old_name, new_name, result);
+        check_empty_output(result)?;
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let old_name = CStr::from_ptr(old_name).to_str().expect("invalid utf8");
+        let new_name = CStr::from_ptr(new_name).to_str().expect("invalid utf8");
+
+        *result = rust_to_eqs_labels(Arc::new(labels.rename(old_name, new_name)?));
+
+        Ok(())
+    })
+}
+
+/// Reorder the variables in the `labels`, following the given `names`. The
+/// `names` must contain all the variables of the `labels` exactly once. The
+/// order of the entries is not modified.
+///
+/// This function allocates memory for `result` which must be released with
+/// `eqs_labels_free` when you don't need it anymore.
+///
+/// @param labels set of labels to reorder
+/// @param names new order of the variables, as an array of NULL-terminated
+///              UTF-8 strings
+/// @param names_count number of entries in `names`
+/// @param result empty labels, on output will contain the reordered labels
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_labels_reorder(
+    labels: eqs_labels_t,
+    names: *const *const c_char,
+    names_count: usize,
+    result: *mut eqs_labels_t,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(result);
+        check_empty_output(result)?;
+
+        let labels = eqs_labels_to_rust(&labels)?;
+        let names = strings_from_c(names, names_count)?;
+
+        *result = rust_to_eqs_labels(Arc::new(labels.reorder(&names)?));
+
+        Ok(())
+    })
+}
+
 /// Decrease the reference count of `labels`, and release the corresponding
 /// memory once the reference count reaches 0.
 ///
@@ -8,6 +8,7 @@ use crate::{TensorMap, TensorBlock, Error};
 use super::labels::{eqs_labels_t, rust_to_eqs_labels, eqs_labels_to_rust};
 use super::blocks::eqs_block_t;
 use super::status::{eqs_status_t, catch_unwind};
+use super::utils::{axis_from_c, reduction_from_c, strings_from_c};
 
 /// Opaque type representing a `TensorMap`.
 #[allow(non_camel_case_types)]
@@ -157,6 +158,115 @@ pub unsafe extern fn eqs_tensormap_keys(
 }
 
 
+/// Get the value associated with `key` in the tensor-level metadata of this
+/// `tensor` map.
+///
+/// `value` is set to a NULL-terminated UTF-8 string, or to `NULL` if there is
+/// no metadata associated with `key`. The string memory is still managed by
+/// the tensor map, and is invalidated when the tensor map is freed or its
+/// metadata modified with `eqs_tensormap_set_metadata`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param key NULL-terminated UTF-8 string containing the metadata key
+/// @param value pointer to be filled with the metadata value
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_metadata(
+    tensor: *const eqs_tensormap_t,
+    key: *const c_char,
+    value: *mut *const c_char,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(tensor, key, value);
+
+        let key = CStr::from_ptr(key).to_str().expect("invalid utf8");
+        *value = match (*tensor).metadata().get_c(key) {
+            Some(metadata) => metadata.as_c_str().as_ptr(),
+            None => std::ptr::null(),
+        };
+
+        Ok(())
+    })
+}
+
+
+/// Set the value associated with `key` in the tensor-level metadata of this
+/// `tensor` map, overwriting any existing value.
+///
+/// If `value` is `NULL`, the metadata associated with `key` is removed
+/// instead. The metadata is saved and loaded together with the data by
+/// `eqs_tensormap_save` and `eqs_tensormap_load`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param key NULL-terminated UTF-8 string containing the metadata key
+/// @param value NULL-terminated UTF-8 string containing the metadata value,
+///              or `NULL` to remove the metadata
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_set_metadata(
+    tensor: *mut eqs_tensormap_t,
+    key: *const c_char,
+    value: *const c_char,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(tensor, key);
+
+        let key = CStr::from_ptr(key).to_str().expect("invalid utf8");
+        if value.is_null() {
+            (*tensor).metadata_mut().remove(key);
+        } else {
+            let value = CStr::from_ptr(value).to_str().expect("invalid utf8");
+            (*tensor).metadata_mut().set(key, value)?;
+        }
+
+        Ok(())
+    })
+}
+
+
+/// Get the list of all keys in the tensor-level metadata of this `tensor` map,
+/// sorted alphabetically.
+///
+/// The strings memory is still managed by the tensor map, and is invalidated
+/// when the tensor map is freed or its metadata modified with
+/// `eqs_tensormap_set_metadata`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param keys will be set to the first element of an array of NULL-terminated
+///             UTF-8 strings containing all the metadata keys
+/// @param keys_count will be set to the number of elements in `keys`
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_metadata_keys(
+    tensor: *const eqs_tensormap_t,
+    keys: *mut *const *const c_char,
+    keys_count: *mut usize,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(tensor, keys, keys_count);
+
+        let list = (*tensor).metadata().keys_c();
+        (*keys_count) = list.len();
+
+        (*keys) = if list.is_empty() {
+            std::ptr::null()
+        } else {
+            list.as_ptr().cast()
+        };
+        Ok(())
+    })
+}
+
+
 /// Get a pointer to the `index`-th block in this tensor map.
 ///
 /// The block memory is still managed by the tensor map, this block should not
@@ -189,8 +299,9 @@ pub unsafe extern fn eqs_tensormap_block_by_id(
 
 /// Get indices of the blocks in this `tensor` corresponding to the given
 /// `selection`. The `selection` should have a subset of the names/variables of
-/// the keys for this tensor map, and only one entry, describing the requested
-/// blocks.
+/// the keys for this tensor map, and one or more entries describing the
+/// requested blocks. A block is selected if its key matches any of the entries
+/// in the `selection`.
 ///
 /// When calling this function, `*count` should contain the number of entries in
 /// `block_indexes`. When the function returns successfully, `*count` will
@@ -201,7 +312,7 @@ pub unsafe extern fn eqs_tensormap_block_by_id(
 /// @param block_indexes array to be filled with indexes of blocks in the tensor
 ///                      map matching the `selection`
 /// @param count number of entries in `block_indexes`
-/// @param selection labels with a single entry describing which blocks are requested
+/// @param selection labels describing which blocks are requested
 ///
 /// @returns The status code of this operation. If the status is not
 ///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
@@ -263,12 +374,23 @@ pub unsafe extern fn eqs_tensormap_blocks_matching(
 /// lexicographically sorted. Otherwise they are kept in the order in which
 /// they appear in the blocks.
 ///
+/// Similarly, if `sort_properties` is true, the new property labels are
+/// re-ordered to keep them lexicographically sorted. Otherwise they are kept
+/// in the order in which they appear in the blocks.
+///
+/// This function uses `eqs_array_t.move_data` to move the data of all arrays
+/// in this tensor map. If this function is NULL, `eqs_array_t.move_samples_from`
+/// is used instead, which only works if the properties of each block are moved
+/// to a contiguous range in the new block.
+///
 /// The result is a new tensor map, which should be freed with `eqs_tensormap_free`.
 ///
 /// @param tensor pointer to an existing tensor map
 /// @param keys_to_move description of the keys to move
 /// @param sort_samples whether to sort the samples lexicographically after
 ///                     merging blocks
+/// @param sort_properties whether to sort the properties lexicographically
+///                        after merging blocks
 ///
 /// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
 ///          case of error. In case of error, you can use `eqs_last_error()`
@@ -278,6 +400,7 @@ pub unsafe extern fn eqs_tensormap_keys_to_properties(
     tensor: *const eqs_tensormap_t,
     keys_to_move: eqs_labels_t,
     sort_samples: bool,
+    sort_properties: bool,
 ) -> *mut eqs_tensormap_t {
     let mut result = std::ptr::null_mut();
     let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
@@ -286,7 +409,7 @@ pub unsafe extern fn eqs_tensormap_keys_to_properties(
         check_pointers!(tensor);
 
         let keys_to_move = eqs_labels_to_rust(&keys_to_move)?;
-        let moved = (*tensor).keys_to_properties(&keys_to_move, sort_samples)?;
+        let moved = (*tensor).keys_to_properties(&keys_to_move, sort_samples, sort_properties)?;
 
         // force the closure to capture the full unwind_wrapper, not just
         // unwind_wrapper.0
@@ -352,6 +475,98 @@ pub unsafe extern fn eqs_tensormap_components_to_properties(
     return result;
 }
 
+/// Move the given variables from the component labels to the sample labels
+/// for each block in this tensor map.
+///
+/// `variables` must be an array of `variables_count` NULL-terminated strings,
+/// encoded as UTF-8. The component is appended to the samples, with the
+/// component values varying faster than the old samples. Each gradient sample
+/// is duplicated for all the values of the component.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param variables names of the component variables to move to the samples
+/// @param variables_count number of entries in the `variables` array
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_components_to_samples(
+    tensor: *const eqs_tensormap_t,
+    variables: *const *const c_char,
+    variables_count: usize,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensor);
+
+        let variables = strings_from_c(variables, variables_count)?;
+        let moved = (*tensor).components_to_samples(&variables)?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(moved);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
+/// Move the given variables from the property labels to a new component for
+/// each block in this tensor map.
+///
+/// `variables` must be an array of `variables_count` NULL-terminated strings,
+/// encoded as UTF-8. This is the inverse of
+/// `eqs_tensormap_components_to_properties`: the new component is added after
+/// the existing ones, and the properties must contain all the combinations of
+/// the values of `variables` with the values of the remaining property
+/// variables, with the moved variables varying either slower or faster than
+/// the remaining ones. If all the property variables are moved, the new
+/// properties contain a single `"_"` variable.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param variables names of the property variables to move to the components
+/// @param variables_count number of entries in the `variables` array
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_properties_to_components(
+    tensor: *const eqs_tensormap_t,
+    variables: *const *const c_char,
+    variables_count: usize,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensor);
+
+        let variables = strings_from_c(variables, variables_count)?;
+        let moved = (*tensor).properties_to_components(&variables)?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(moved);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
 /// Merge blocks with the same value for selected keys variables along the
 /// samples axis.
 ///
@@ -359,15 +574,22 @@ pub unsafe extern fn eqs_tensormap_components_to_properties(
 /// the sample labels, and blocks with the same remaining keys variables
 /// will be merged together along the sample axis.
 ///
-/// `keys_to_move` must be empty (`keys_to_move.count == 0`), and the new
-/// sample labels will contain entries corresponding to the merged blocks'
-/// keys.
-///
-/// The new sample labels will contains all of the merged blocks sample
-/// labels. The order of the samples is controlled by `sort_samples`. If
+/// If `keys_to_move` does not contains any entries (`keys_to_move.count
+/// == 0`), then the new sample labels will contain entries corresponding
+/// to the merged blocks' keys only.
+///
+/// If `keys_to_move` contains entries, then the new sample labels will
+/// contain all the merged samples, combined with each of the entries of
+/// `keys_to_move`. For example, using `a=2, 3` in `keys_to_move` with blocks
+/// with samples `s=1, 2` and `s=1, 3` will result in `s, a = (1, 2), (2, 2),
+/// (3, 2), (1, 3), (2, 3), (3, 3)`. The data for samples which were not
+/// present in any of the merged blocks is filled with zeros, and blocks with
+/// a key not in `keys_to_move` are ignored.
+///
+/// The order of the samples is controlled by `sort_samples`. If
 /// `sort_samples` is true, samples are re-ordered to keep them
 /// lexicographically sorted. Otherwise they are kept in the order in which
-/// they appear in the blocks.
+/// they appear in the blocks (and in `keys_to_move`).
 ///
 /// This function is only implemented if all merged block have the same
 /// property labels.
@@ -408,3 +630,366 @@ pub unsafe extern fn eqs_tensormap_keys_to_samples(
 
     return result;
 }
+
+
+/// Join the `tensors_count` tensor maps in `tensors` along the given `axis`,
+/// creating a single tensor map.
+///
+/// `axis` must be either `"samples"` or `"properties"`. All tensor maps must
+/// have the same keys, and blocks with the same key must have the same
+/// components, gradients, and the same samples or properties labels (i.e. the
+/// labels for the axis which is not joined).
+///
+/// Clashes between the labels along the joined `axis` are resolved by keeping
+/// the same names if all entries are unique; adding a new `"tensor"` variable
+/// containing the index of the tensor map if the names are the same but some
+/// entries are duplicated; and using `"tensor", "sample"` or `"tensor",
+/// "property"` (depending on `axis`) as new names if the labels have different
+/// names.
+///
+/// The result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+///
+/// @param tensors pointer to the first element of an array of tensor maps
+/// @param tensors_count number of elements in the `tensors` array
+/// @param axis name of the axis along which the tensor maps should be joined
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_join(
+    tensors: *const *const eqs_tensormap_t,
+    tensors_count: usize,
+    axis: *const c_char,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensors, axis);
+
+        let mut rust_tensors = Vec::new();
+        for &tensor in std::slice::from_raw_parts(tensors, tensors_count) {
+            check_pointers!(tensor);
+            rust_tensors.push(&**tensor);
+        }
+
+        let axis = axis_from_c(axis)?;
+        let joined = TensorMap::join(&rust_tensors, axis)?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(joined);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
+
+/// Reduce the blocks in this `tensor` over the sample variables in `names`,
+/// combining together all the samples which only differ by the value of these
+/// variables.
+///
+/// `names` must be an array of `names_count` NULL-terminated strings, encoded
+/// as UTF-8. `reduction` must be one of `"sum"`, `"mean"`, `"variance"` or
+/// `"std"`.
+///
+/// The new samples contain the remaining sample variables (or a single `"_"`
+/// variable if all variables are reduced over), and are sorted
+/// lexicographically. The gradients are reduced accordingly.
+///
+/// The `"sum"` reduction only requires `eqs_array_t.scatter_add_from`, while
+/// the other reductions also need `eqs_array_t.data` to be available, and only
+/// support arrays containing 64-bit floating point values.
+///
+/// The result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param names names of the sample variables to reduce over
+/// @param names_count number of entries in the `names` array
+/// @param reduction name of the reduction to perform
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_reduce_over_samples(
+    tensor: *const eqs_tensormap_t,
+    names: *const *const c_char,
+    names_count: usize,
+    reduction: *const c_char,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensor, reduction);
+
+        let names = strings_from_c(names, names_count)?;
+        let reduction = reduction_from_c(reduction)?;
+        let reduced = (*tensor).reduce_over_samples(&names, reduction)?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(reduced);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
+
+/// Slice all the blocks in this `tensor` along the given `axis`, keeping only
+/// the samples or properties matching the `selection`.
+///
+/// The `selection` must contain a subset of the names of the samples or
+/// properties labels, and an entry is kept if the values of these variables
+/// match one of the entries in the `selection`. The keys of the new tensor map
+/// are the same as the keys of `tensor`, and blocks can end up with zero
+/// samples or properties if none of them match the `selection`.
+///
+/// This function requires `eqs_array_t.gather_from` to be implemented. The
+/// result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param axis name of the axis along which the tensor map should be sliced,
+///             either `"samples"` or `"properties"`
+/// @param selection labels describing which entries should be kept
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_slice(
+    tensor: *const eqs_tensormap_t,
+    axis: *const c_char,
+    selection: eqs_labels_t,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensor, axis);
+
+        let axis = axis_from_c(axis)?;
+        let selection = eqs_labels_to_rust(&selection)?;
+        let sliced = (*tensor).slice(axis, &selection)?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(sliced);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
+
+/// Split this `tensor` into multiple tensor maps along the given `axis`,
+/// creating one new tensor map for each of the `selections`.
+///
+/// This is equivalent to calling `eqs_tensormap_slice` with each of the
+/// `selections`, which must all have the same names. On success, `results`
+/// will contain `selections_count` newly allocated tensor maps, which should
+/// be freed with `eqs_tensormap_free`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param axis name of the axis along which the tensor map should be split,
+///             either `"samples"` or `"properties"`
+/// @param selections array of labels describing which entries should be kept
+///                   in each of the new tensor maps
+/// @param selections_count number of entries in the `selections` array
+/// @param results array of size `selections_count`, which will be filled with
+///                pointers to the new tensor maps
+///
+/// @returns The status code of this operation. If the status is not
+///          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full
+///          error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_split(
+    tensor: *const eqs_tensormap_t,
+    axis: *const c_char,
+    selections: *const eqs_labels_t,
+    selections_count: usize,
+    results: *mut *mut eqs_tensormap_t,
+) -> eqs_status_t {
+    catch_unwind(|| {
+        check_pointers!(tensor, axis);
+        if selections_count == 0 {
+            return Ok(());
+        }
+        check_pointers!(selections, results);
+
+        let axis = axis_from_c(axis)?;
+
+        let mut rust_selections = Vec::new();
+        for selection in std::slice::from_raw_parts(selections, selections_count) {
+            rust_selections.push((*eqs_labels_to_rust(selection)?).clone());
+        }
+
+        let split = (*tensor).split(axis, &rust_selections)?;
+
+        let results = std::slice::from_raw_parts_mut(results, selections_count);
+        for (result, tensor) in results.iter_mut().zip(split) {
+            *result = eqs_tensormap_t::into_boxed_raw(tensor);
+        }
+
+        Ok(())
+    })
+}
+
+
+/// Split the blocks in this `tensor` according to the values of the sample
+/// `variables`, moving these variables from the samples to the keys.
+///
+/// `variables` must be an array of `variables_count` NULL-terminated strings,
+/// encoded as UTF-8. Each block is split into one new block for each of the
+/// different values taken by `variables` in its samples, and the
+/// corresponding key is extended with these values. The remaining sample
+/// variables are kept in the new blocks, or replaced by a single `"_"`
+/// variable if all sample variables are moved. Gradients are split
+/// accordingly.
+///
+/// This function requires `eqs_array_t.gather_from` to be implemented. The
+/// result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param variables names of the sample variables to move to the keys
+/// @param variables_count number of entries in the `variables` array
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_samples_to_keys(
+    tensor: *const eqs_tensormap_t,
+    variables: *const *const c_char,
+    variables_count: usize,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensor);
+
+        let variables = strings_from_c(variables, variables_count)?;
+        let moved = (*tensor).samples_to_keys(&variables)?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(moved);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
+
+/// Split the blocks in this `tensor` according to the values of the property
+/// `variables`, moving these variables from the properties to the keys.
+///
+/// `variables` must be an array of `variables_count` NULL-terminated strings,
+/// encoded as UTF-8. Each block is split into one new block for each of the
+/// different values taken by `variables` in its properties, and the
+/// corresponding key is extended with these values. The remaining property
+/// variables are kept in the new blocks, or replaced by a single `"_"`
+/// variable if all property variables are moved. Gradients are split
+/// accordingly.
+///
+/// This function requires `eqs_array_t.gather_from` to be implemented. The
+/// result is a new tensor map, which should be freed with `eqs_tensormap_free`.
+///
+/// @param tensor pointer to an existing tensor map
+/// @param variables names of the property variables to move to the keys
+/// @param variables_count number of entries in the `variables` array
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_properties_to_keys(
+    tensor: *const eqs_tensormap_t,
+    variables: *const *const c_char,
+    variables_count: usize,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensor);
+
+        let variables = strings_from_c(variables, variables_count)?;
+        let moved = (*tensor).properties_to_keys(&variables)?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(moved);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
+
+
+/// Sort the keys of this `tensor` map in lexicographic order, reordering the
+/// blocks accordingly.
+///
+/// This function requires `eqs_array_t.copy` to be implemented. The result is
+/// a new tensor map, which should be freed with `eqs_tensormap_free`.
+///
+/// @param tensor pointer to an existing tensor map
+///
+/// @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in
+///          case of error. In case of error, you can use `eqs_last_error()`
+///          to get the error message.
+#[no_mangle]
+pub unsafe extern fn eqs_tensormap_sort_keys(
+    tensor: *const eqs_tensormap_t,
+) -> *mut eqs_tensormap_t {
+    let mut result = std::ptr::null_mut();
+    let unwind_wrapper = std::panic::AssertUnwindSafe(&mut result);
+
+    let status = catch_unwind(move || {
+        check_pointers!(tensor);
+
+        let sorted = (*tensor).sort_keys()?;
+
+        // force the closure to capture the full unwind_wrapper, not just
+        // unwind_wrapper.0
+        let _ = &unwind_wrapper;
+        *unwind_wrapper.0 = eqs_tensormap_t::into_boxed_raw(sorted);
+        Ok(())
+    });
+
+    if !status.is_success() {
+        return std::ptr::null_mut();
+    }
+
+    return result;
+}
@@ -1,6 +1,7 @@
+use std::ffi::CStr;
 use std::os::raw::c_char;
 
-use crate::Error;
+use crate::{Axis, Error, Reduction};
 
 pub unsafe fn copy_str_to_c(string: &str, buffer: *mut c_char, buflen: usize) -> Result<(), Error> {
     let size = std::cmp::min(string.len(), buflen - 1);
@@ -16,3 +17,43 @@ pub unsafe fn copy_str_to_c(string: &str, buffer: *mut c_char, buflen: usize) ->
     buffer.add(size).write(0);
     Ok(())
 }
+
+/// Convert the name of an axis given through the C API to an `Axis`
+pub unsafe fn axis_from_c(axis: *const c_char) -> Result<Axis, Error> {
+    let axis = CStr::from_ptr(axis).to_str().expect("invalid utf8");
+    match axis {
+        "samples" => Ok(Axis::Samples),
+        "properties" => Ok(Axis::Properties),
+        _ => Err(Error::InvalidParameter(format!(
+            "invalid axis '{}', expected 'samples' or 'properties'", axis
+        ))),
+    }
+}
+
+/// Convert the name of a reduction given through the C API to a `Reduction`
+pub unsafe fn reduction_from_c(reduction: *const c_char) -> Result<Reduction, Error> {
+    let reduction = CStr::from_ptr(reduction).to_str().expect("invalid utf8");
+    match reduction {
+        "sum" => Ok(Reduction::Sum),
+        "mean" => Ok(Reduction::Mean),
+        "variance" => Ok(Reduction::Variance),
+        "std" => Ok(Reduction::Std),
+        _ => Err(Error::InvalidParameter(format!(
+            "invalid reduction '{}', expected 'sum', 'mean', 'variance' or 'std'", reduction
+        ))),
+    }
+}
+
+/// Convert an array of `count` NULL-terminated strings given through the C API
+/// to a vector of `&str`
+pub unsafe fn strings_from_c<'a>(strings: *const *const c_char, count: usize) -> Result<Vec<&'a str>, Error> {
+    let mut rust_strings = Vec::new();
+    if count != 0 {
+        check_pointers!(strings);
+        for &string in std::slice::from_raw_parts(strings, count) {
+            check_pointers!(string);
+            rust_strings.push(CStr::from_ptr(string).to_str().expect("invalid utf8"));
+        }
+    }
+    return Ok(rust_strings);
+}
@@ -0,0 +1,350 @@
+use crate::Error;
+
+use super::{eqs_array_t, eqs_sample_mapping_t, eqs_data_origin_t, eqs_dtype_t, eqs_device_t};
+use super::{EQS_DTYPE_FLOAT64, EQS_DTYPE_FLOAT32, EQS_DTYPE_FLOAT16, EQS_DTYPE_INT32, EQS_DTYPE_INT64};
+use super::EQS_DEVICE_CPU;
+
+/// Shape of the arrays created to run the checks, corresponding to (samples,
+/// components, properties)
+const SHAPE: [usize; 3] = [3, 2, 4];
+
+impl eqs_array_t {
+    /// Check that this array correctly implements the functions in
+    /// `eqs_array_t`, and return an error describing the first violated
+    /// contract if it does not.
+    ///
+    /// This exercises `origin`, `dtype`, `device`, `data`/`copy_to_host`,
+    /// `shape`, `reshape`, `swap_axes`, `create`, `copy`, `move_samples_from`,
+    /// `move_data` (if it is set), `gather_from`, `scatter_add_from` and
+    /// `destroy`. The array itself is not modified, all the checks run on
+    /// new arrays created with `eqs_array_t.create` and `eqs_array_t.copy`.
+    /// Since arrays outside of host memory can not be written to from here,
+    /// the checks on values are only meaningful for arrays on CPU.
+    pub fn check(&self) -> Result<(), Error> {
+        check_functions(self, "the array")?;
+
+        let origin = self.origin()?;
+        let dtype = self.dtype()?;
+        let device = self.device()?;
+        check_data_function(self, device, "the array")?;
+        self.shape()?;
+
+        // create
+        let mut array = self.create(&SHAPE)?;
+        check_functions(&array, "the array returned by eqs_array_t.create")?;
+        check_settings(&array, "create", origin, dtype, device)?;
+        check_shape(&array, "create", &SHAPE)?;
+        let mut values = vec![0; SHAPE.iter().product()];
+        check_values(&array, "create", &values, "the new array should be filled with zeros")?;
+
+        // fill the array with known values to be able to track them through
+        // the other functions
+        if device.0 == EQS_DEVICE_CPU {
+            for (i, value) in values.iter_mut().enumerate() {
+                *value = u8::try_from(i + 1).expect("too many values");
+            }
+
+            let size = dtype.size();
+            let data = array.data_bytes_mut()?;
+            for (chunk, &value) in data.chunks_exact_mut(size).zip(&values) {
+                chunk.copy_from_slice(&encode(dtype, value));
+            }
+        }
+
+        // copy
+        let mut copy = copy_array(&array)?;
+        check_functions(&copy, "the array returned by eqs_array_t.copy")?;
+        check_settings(&copy, "copy", origin, dtype, device)?;
+        check_shape(&copy, "copy", &SHAPE)?;
+        check_values(&copy, "copy", &values, "the new array should contain the same values")?;
+        if device.0 == EQS_DEVICE_CPU {
+            copy.data_bytes_mut()?.fill(0);
+            check_values(&array, "copy", &values, "modifying the copy should not modify the original array")?;
+        }
+
+        // reshape
+        let mut reshaped = copy_array(&array)?;
+        let new_shape = [SHAPE[0] * SHAPE[1], SHAPE[2]];
+        reshaped.reshape(&new_shape)?;
+        check_shape(&reshaped, "reshape", &new_shape)?;
+        check_values(&reshaped, "reshape", &values, "the values should stay in the same (C) order")?;
+
+        // swap_axes, followed by reshape to get C-contiguous data back
+        let mut swapped = copy_array(&array)?;
+        swapped.swap_axes(0, 2)?;
+        check_shape(&swapped, "swap_axes", &[SHAPE[2], SHAPE[1], SHAPE[0]])?;
+
+        swapped.reshape(&[values.len()])?;
+        let mut expected = Vec::new();
+        for k in 0..SHAPE[2] {
+            for j in 0..SHAPE[1] {
+                for i in 0..SHAPE[0] {
+                    expected.push(values[(i * SHAPE[1] + j) * SHAPE[2] + k]);
+                }
+            }
+        }
+        check_values(&swapped, "swap_axes", &expected, "the values should be transposed between the two axes")?;
+
+        check_move_samples_from(&array, &values)?;
+        // move_data is optional, `move_samples_from` is used instead if it is
+        // not set
+        if array.move_data.is_some() {
+            check_move_data(&array, &values)?;
+        }
+        check_gather_from(&array, &values)?;
+        check_scatter_add_from(&array, &values)?;
+
+        // all the arrays created above are released with eqs_array_t.destroy
+        // when dropped
+        return Ok(());
+    }
+}
+
+/// Create the error corresponding to a violation of the contract of `function`
+fn violation(function: &str, message: &str) -> Error {
+    Error::InvalidParameter(format!(
+        "invalid implementation of eqs_array_t.{}: {}", function, message
+    ))
+}
+
+/// Check that all the required functions are set in `array`. `move_data` is
+/// optional and not checked here.
+fn check_functions(array: &eqs_array_t, context: &str) -> Result<(), Error> {
+    let functions = [
+        ("origin", array.origin.is_some()),
+        ("shape", array.shape.is_some()),
+        ("reshape", array.reshape.is_some()),
+        ("swap_axes", array.swap_axes.is_some()),
+        ("create", array.create.is_some()),
+        ("copy", array.copy.is_some()),
+        ("move_samples_from", array.move_samples_from.is_some()),
+        ("gather_from", array.gather_from.is_some()),
+        ("scatter_add_from", array.scatter_add_from.is_some()),
+    ];
+
+    for (name, is_set) in functions {
+        if !is_set {
+            return Err(Error::InvalidParameter(format!(
+                "eqs_array_t.{} function is NULL in {}", name, context
+            )));
+        }
+    }
+
+    return Ok(());
+}
+
+/// Make a copy of `array`, returning an error instead of panicking if the copy
+/// fails
+fn copy_array(array: &eqs_array_t) -> Result<eqs_array_t, Error> {
+    let function = array.copy.expect("eqs_array_t.copy function is NULL");
+
+    let mut new_array = eqs_array_t::null();
+    let status = unsafe { function(array.ptr, &mut new_array) };
+    if !status.is_success() {
+        return Err(Error::External {
+            status, context: "calling eqs_array_t.copy failed".into()
+        });
+    }
+
+    return Ok(new_array);
+}
+
+/// Check that `array` (produced by `function`) has the expected origin, data
+/// type and device
+fn check_settings(
+    array: &eqs_array_t,
+    function: &str,
+    origin: eqs_data_origin_t,
+    dtype: eqs_dtype_t,
+    device: eqs_device_t,
+) -> Result<(), Error> {
+    if array.origin()? != origin {
+        return Err(violation(function, "the new array should have the same origin"));
+    }
+
+    let new_dtype = array.dtype()?;
+    if new_dtype != dtype {
+        return Err(violation(function, &format!(
+            "the new array should have the same data type, expected {} got {}",
+            dtype, new_dtype
+        )));
+    }
+
+    let new_device = array.device()?;
+    if new_device != device {
+        return Err(violation(function, &format!(
+            "the new array should live on the same device, expected {} got {}",
+            device, new_device
+        )));
+    }
+
+    return check_data_function(array, device, &format!("the array returned by eqs_array_t.{}", function));
+}
+
+/// Check that the `data` function is set if the array lives on CPU
+fn check_data_function(array: &eqs_array_t, device: eqs_device_t, context: &str) -> Result<(), Error> {
+    if device.0 == EQS_DEVICE_CPU && array.data.is_none() {
+        return Err(Error::InvalidParameter(format!(
+            "eqs_array_t.data function is NULL in {}, but the array is on CPU", context
+        )));
+    }
+
+    return Ok(());
+}
+
+/// Check that `array` has the `expected` shape after calling `function`
+fn check_shape(array: &eqs_array_t, function: &str, expected: &[usize]) -> Result<(), Error> {
+    let shape = array.shape()?;
+    if shape != expected {
+        return Err(violation(function, &format!(
+            "expected the array to have shape {:?}, got {:?}", expected, shape
+        )));
+    }
+
+    return Ok(());
+}
+
+/// Check that `array` contains the `expected` values after calling `function`,
+/// using `message` to describe the contract otherwise.
+fn check_values(array: &eqs_array_t, function: &str, expected: &[u8], message: &str) -> Result<(), Error> {
+    let dtype = array.dtype()?;
+    let data = array.host_data_bytes()?;
+
+    let expected = expected.iter()
+        .flat_map(|&value| encode(dtype, value))
+        .collect::<Vec<_>>();
+
+    if *data != *expected {
+        return Err(violation(function, &format!(
+            "{}, but the array contains different values", message
+        )));
+    }
+
+    return Ok(());
+}
+
+/// Sample mapping used to check `move_samples_from` and `move_data`
+const SAMPLES: [eqs_sample_mapping_t; 2] = [
+    eqs_sample_mapping_t { input: 2, output: 0 },
+    eqs_sample_mapping_t { input: 0, output: 1 },
+];
+
+/// Check `move_samples_from` on an `array` with `SHAPE` containing `values`
+fn check_move_samples_from(array: &eqs_array_t, values: &[u8]) -> Result<(), Error> {
+    let output_shape = [2, SHAPE[1], 2 * SHAPE[2]];
+    let mut output = array.create(&output_shape)?;
+    let properties = 2..(2 + SHAPE[2]);
+    output.move_samples_from(array, &SAMPLES, properties.clone())?;
+    check_shape(&output, "move_samples_from", &output_shape)?;
+
+    let mut expected = vec![0; output_shape.iter().product()];
+    for sample in &SAMPLES {
+        for j in 0..SHAPE[1] {
+            for (k, property) in properties.clone().enumerate() {
+                let output_i = (sample.output * output_shape[1] + j) * output_shape[2] + property;
+                let input_i = (sample.input * SHAPE[1] + j) * SHAPE[2] + k;
+                expected[output_i] = values[input_i];
+            }
+        }
+    }
+    return check_values(&output, "move_samples_from", &expected, "the data should be moved to the requested samples and properties");
+}
+
+/// Check `move_data` on an `array` with `SHAPE` containing `values`
+fn check_move_data(array: &eqs_array_t, values: &[u8]) -> Result<(), Error> {
+    let output_shape = [2, SHAPE[1], 2 * SHAPE[2]];
+    let mut output = array.create(&output_shape)?;
+    let properties = [6, 1, 4, 3];
+    output.move_data(array, &SAMPLES, &properties)?;
+    check_shape(&output, "move_data", &output_shape)?;
+
+    let mut expected = vec![0; output_shape.iter().product()];
+    for sample in &SAMPLES {
+        for j in 0..SHAPE[1] {
+            for (k, &property) in properties.iter().enumerate() {
+                let output_i = (sample.output * output_shape[1] + j) * output_shape[2] + property;
+                let input_i = (sample.input * SHAPE[1] + j) * SHAPE[2] + k;
+                expected[output_i] = values[input_i];
+            }
+        }
+    }
+    return check_values(&output, "move_data", &expected, "the data should be moved to the requested samples and properties");
+}
+
+/// Check `gather_from` along the properties axis on an `array` with `SHAPE`
+/// containing `values`
+fn check_gather_from(array: &eqs_array_t, values: &[u8]) -> Result<(), Error> {
+    let indices = [3, 1];
+    let output_shape = [SHAPE[0], SHAPE[1], indices.len()];
+    let mut output = array.create(&output_shape)?;
+    output.gather_from(array, 2, &indices)?;
+    check_shape(&output, "gather_from", &output_shape)?;
+
+    let mut expected = Vec::new();
+    for i in 0..SHAPE[0] {
+        for j in 0..SHAPE[1] {
+            for &index in &indices {
+                expected.push(values[(i * SHAPE[1] + j) * SHAPE[2] + index]);
+            }
+        }
+    }
+    return check_values(&output, "gather_from", &expected, "the data should be selected from the requested indices");
+}
+
+/// Check `scatter_add_from` along the samples axis on an `array` with `SHAPE`
+/// containing `values`
+fn check_scatter_add_from(array: &eqs_array_t, values: &[u8]) -> Result<(), Error> {
+    let indices = [1, 0, 1];
+    let output_shape = [2, SHAPE[1], SHAPE[2]];
+    let mut output = array.create(&output_shape)?;
+    output.scatter_add_from(array, 0, &indices)?;
+    check_shape(&output, "scatter_add_from", &output_shape)?;
+
+    let mut expected = vec![0; output_shape.iter().product()];
+    let size = SHAPE[1] * SHAPE[2];
+    for (i, &index) in indices.iter().enumerate() {
+        for j in 0..size {
+            expected[index * size + j] += values[i * size + j];
+        }
+    }
+    return check_values(&output, "scatter_add_from", &expected, "the data should be summed in the requested indices");
+}
+
+/// Get the representation of a small integer `value` in the given data type
+#[allow(clippy::cast_possible_truncation)]
+fn encode(dtype: eqs_dtype_t, value: u8) -> Vec<u8> {
+    match dtype.0 {
+        EQS_DTYPE_FLOAT64 => f64::from(value).to_ne_bytes().to_vec(),
+        EQS_DTYPE_FLOAT32 => f32::from(value).to_ne_bytes().to_vec(),
+        EQS_DTYPE_INT32 => i32::from(value).to_ne_bytes().to_vec(),
+        EQS_DTYPE_INT64 => i64::from(value).to_ne_bytes().to_vec(),
+        EQS_DTYPE_FLOAT16 => {
+            // integers smaller than 256 are exactly representable as
+            // half-precision floats, with the mantissa containing all the bits
+            // after the leading one
+            let bits = if value == 0 {
+                0
+            } else {
+                let exponent = 7 - value.leading_zeros() as u16;
+                let mantissa = (u16::from(value) << (10 - exponent)) & 0x3ff;
+                ((exponent + 15) << 10) | mantissa
+            };
+            bits.to_ne_bytes().to_vec()
+        }
+        _ => panic!("unknown data type {}", dtype.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float16() {
+        assert_eq!(encode(eqs_dtype_t(EQS_DTYPE_FLOAT16), 0), 0x0000_u16.to_ne_bytes());
+        assert_eq!(encode(eqs_dtype_t(EQS_DTYPE_FLOAT16), 1), 0x3c00_u16.to_ne_bytes());
+        assert_eq!(encode(eqs_dtype_t(EQS_DTYPE_FLOAT16), 2), 0x4000_u16.to_ne_bytes());
+        assert_eq!(encode(eqs_dtype_t(EQS_DTYPE_FLOAT16), 3), 0x4200_u16.to_ne_bytes());
+        assert_eq!(encode(eqs_dtype_t(EQS_DTYPE_FLOAT16), 24), 0x4e00_u16.to_ne_bytes());
+    }
+}
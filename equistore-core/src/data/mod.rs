@@ -0,0 +1,1163 @@
+use std::borrow::Cow;
+use std::ops::Range;
+use std::os::raw::c_void;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::c_api::eqs_status_t;
+use crate::Error;
+
+mod check;
+
+/// A single 64-bit integer representing a data origin (numpy ndarray, rust
+/// ndarray, torch tensor, fortran array, ...).
+#[repr(transparent)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct eqs_data_origin_t(pub u64);
+
+static REGISTERED_DATA_ORIGIN: Lazy<Mutex<Vec<String>>> = Lazy::new(|| {
+    // start the registered origins at 1, this allow using 0 as a marker for
+    // "unknown data origin"
+    Mutex::new(vec!["unregistered origin".into()])
+});
+
+/// Register a new data origin with the given `name`, or get the
+/// `DataOrigin` corresponding to this name if it was already registered.
+pub fn register_data_origin(name: String) -> eqs_data_origin_t {
+    let mut registered_origins = REGISTERED_DATA_ORIGIN.lock().expect("mutex got poisoned");
+
+    for (i, registered) in registered_origins.iter().enumerate() {
+        if registered == &name {
+            return eqs_data_origin_t(i as u64);
+        }
+    }
+
+    // could not find the origin, register a new one
+    registered_origins.push(name);
+
+    return eqs_data_origin_t((registered_origins.len() - 1) as u64);
+}
+
+/// Get the name of the given (pre-registered) origin
+#[allow(clippy::cast_possible_truncation)]
+pub fn get_data_origin(origin: eqs_data_origin_t) -> String {
+    let registered_origins = REGISTERED_DATA_ORIGIN.lock().expect("mutex got poisoned");
+    let id = origin.0 as usize;
+
+    if id < registered_origins.len() {
+        return registered_origins[id].clone();
+    } else {
+        return registered_origins[0].clone();
+    }
+}
+
+/// Type of the data stored inside an `eqs_array_t`, as one of the
+/// `EQS_DTYPE_XXX` constants.
+#[repr(transparent)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct eqs_dtype_t(pub i32);
+
+/// Data type for 64-bit floating point values (`double` in C)
+pub const EQS_DTYPE_FLOAT64: i32 = 1;
+/// Data type for 32-bit floating point values (`float` in C)
+pub const EQS_DTYPE_FLOAT32: i32 = 2;
+/// Data type for 16-bit floating point values (IEEE 754 half-precision)
+pub const EQS_DTYPE_FLOAT16: i32 = 3;
+/// Data type for 32-bit signed integers (`int32_t` in C)
+pub const EQS_DTYPE_INT32: i32 = 4;
+/// Data type for 64-bit signed integers (`int64_t` in C)
+pub const EQS_DTYPE_INT64: i32 = 5;
+
+impl eqs_dtype_t {
+    /// Check that this is one of the known data types
+    fn is_valid(self) -> bool {
+        (EQS_DTYPE_FLOAT64..=EQS_DTYPE_INT64).contains(&self.0)
+    }
+
+    /// Get the size in bytes of a single element of this data type
+    pub fn size(self) -> usize {
+        match self.0 {
+            EQS_DTYPE_FLOAT64 | EQS_DTYPE_INT64 => 8,
+            EQS_DTYPE_FLOAT32 | EQS_DTYPE_INT32 => 4,
+            EQS_DTYPE_FLOAT16 => 2,
+            _ => panic!("unknown data type {}", self.0),
+        }
+    }
+}
+
+impl std::fmt::Display for eqs_dtype_t {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            EQS_DTYPE_FLOAT64 => write!(f, "float64"),
+            EQS_DTYPE_FLOAT32 => write!(f, "float32"),
+            EQS_DTYPE_FLOAT16 => write!(f, "float16"),
+            EQS_DTYPE_INT32 => write!(f, "int32"),
+            EQS_DTYPE_INT64 => write!(f, "int64"),
+            other => write!(f, "unknown data type ({})", other),
+        }
+    }
+}
+
+/// Device where the data of an `eqs_array_t` lives, as one of the
+/// `EQS_DEVICE_XXX` constants.
+#[repr(transparent)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct eqs_device_t(pub i32);
+
+/// The data lives in host memory (RAM), and can be accessed directly with
+/// `eqs_array_t.data`
+pub const EQS_DEVICE_CPU: i32 = 1;
+/// The data lives outside of host memory (GPU memory, memory-mapped file,
+/// remote machine, ...), and must be copied with `eqs_array_t.copy_to_host`
+/// before being accessed
+pub const EQS_DEVICE_OTHER: i32 = 2;
+
+impl std::fmt::Display for eqs_device_t {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            EQS_DEVICE_CPU => write!(f, "cpu"),
+            EQS_DEVICE_OTHER => write!(f, "other"),
+            other => write!(f, "unknown device ({})", other),
+        }
+    }
+}
+
+/// Rust types which can be used to access the data of an `eqs_array_t`
+pub trait DataType: Copy {
+    /// The `eqs_dtype_t` corresponding to this type
+    const DTYPE: eqs_dtype_t;
+}
+
+impl DataType for f64 {
+    const DTYPE: eqs_dtype_t = eqs_dtype_t(EQS_DTYPE_FLOAT64);
+}
+
+impl DataType for f32 {
+    const DTYPE: eqs_dtype_t = eqs_dtype_t(EQS_DTYPE_FLOAT32);
+}
+
+impl DataType for i32 {
+    const DTYPE: eqs_dtype_t = eqs_dtype_t(EQS_DTYPE_INT32);
+}
+
+impl DataType for i64 {
+    const DTYPE: eqs_dtype_t = eqs_dtype_t(EQS_DTYPE_INT64);
+}
+
+// SAFETY: this should be checked by the user/implementor of `eqs_array_t`.
+unsafe impl Sync for eqs_array_t {}
+unsafe impl Send for eqs_array_t {}
+
+/// `eqs_array_t` manages n-dimensional arrays used as data in a block or tensor
+/// map. The array itself if opaque to this library and can come from multiple
+/// sources: Rust program, a C/C++ program, a Fortran program, Python with numpy
+/// or torch. The data does not have to live on CPU, or even on the same machine
+/// where this code is executed: arrays living elsewhere should report it with
+/// `device`, and implement `copy_to_host` to make their data available.
+///
+/// This struct contains a C-compatible manual implementation of a virtual table
+/// (vtable, i.e. trait in Rust, pure virtual class in C++); allowing
+/// manipulation of the array in an opaque way.
+///
+/// **WARNING**: all function implementations **MUST** be thread-safe, and can
+/// be called from multiple threads at the same time. The `eqs_array_t` itself
+/// might be moved from one thread to another.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct eqs_array_t {
+    /// User-provided data should be stored here, it will be passed as the
+    /// first parameter to all function pointers below.
+    pub ptr: *mut c_void,
+
+    /// This function needs to store the "data origin" for this array in
+    /// `origin`. Users of `eqs_array_t` should register a single data
+    /// origin with `eqs_register_data_origin`, and use it for all compatible
+    /// arrays.
+    origin: Option<unsafe extern fn(
+        array: *const c_void,
+        origin: *mut eqs_data_origin_t
+    ) -> eqs_status_t>,
+
+    /// Get the type of the data stored in this array in `dtype`, as one of the
+    /// `EQS_DTYPE_XXX` constants. This function can be set to `NULL`, in
+    /// which case the data is assumed to contain 64-bit floating point values.
+    dtype: Option<unsafe extern fn(
+        array: *const c_void,
+        dtype: *mut eqs_dtype_t,
+    ) -> eqs_status_t>,
+
+    /// Get a pointer to the underlying data storage. The data is interpreted
+    /// according to the type given by `eqs_array_t.dtype`.
+    ///
+    /// This function is allowed to fail if the data is not accessible in RAM,
+    /// or not stored as a C-contiguous array.
+    data: Option<unsafe extern fn(
+        array: *mut c_void,
+        data: *mut *mut c_void,
+    ) -> eqs_status_t>,
+
+    /// Get the device where the data of this array lives in `device`, as one
+    /// of the `EQS_DEVICE_XXX` constants. This function can be set to `NULL`,
+    /// in which case the data is assumed to live in host memory
+    /// (`EQS_DEVICE_CPU`).
+    device: Option<unsafe extern fn(
+        array: *const c_void,
+        device: *mut eqs_device_t,
+    ) -> eqs_status_t>,
+
+    /// Copy all the data of this array to host memory in `buffer`, as a
+    /// C-contiguous array with the type given by `eqs_array_t.dtype`.
+    /// `buffer_size` is the size of `buffer` in bytes, and will always be the
+    /// number of elements in the array times the size of the data type.
+    ///
+    /// This function is only used for arrays which are not on
+    /// `EQS_DEVICE_CPU`, and can be set to `NULL` for arrays living in host
+    /// memory.
+    copy_to_host: Option<unsafe extern fn(
+        array: *const c_void,
+        buffer: *mut c_void,
+        buffer_size: usize,
+    ) -> eqs_status_t>,
+
+    /// Get the shape of the array managed by this `eqs_array_t` in the `*shape`
+    /// pointer, and the number of dimension (size of the `*shape` array) in
+    /// `*shape_count`.
+    shape: Option<unsafe extern fn(
+        array: *const c_void,
+        shape: *mut *const usize,
+        shape_count: *mut usize,
+    ) -> eqs_status_t>,
+
+    /// Change the shape of the array managed by this `eqs_array_t` to the given
+    /// `shape`. `shape_count` must contain the number of elements in the
+    /// `shape` array
+    reshape: Option<unsafe extern fn(
+        array: *mut c_void,
+        shape: *const usize,
+        shape_count: usize,
+    ) -> eqs_status_t>,
+
+    /// Swap the axes `axis_1` and `axis_2` in this `array`.
+    swap_axes: Option<unsafe extern fn(
+        array: *mut c_void,
+        axis_1: usize,
+        axis_2: usize,
+    ) -> eqs_status_t>,
+
+    /// Create a new array with the same options as the current one (data type,
+    /// data location, etc.) and the requested `shape`; and store it in
+    /// `new_array`. The number of elements in the `shape` array should be given
+    /// in `shape_count`.
+    ///
+    /// The new array should be filled with zeros.
+    create: Option<unsafe extern fn(
+        array: *const c_void,
+        shape: *const usize,
+        shape_count: usize,
+        new_array: *mut eqs_array_t,
+    ) -> eqs_status_t>,
+
+    /// Make a copy of this `array` and return the new array in `new_array`.
+    ///
+    /// The new array is expected to have the same data origin and parameters
+    /// (data type, data location, etc.)
+    copy: Option<unsafe extern fn(
+        array: *const c_void,
+        new_array: *mut eqs_array_t,
+    ) -> eqs_status_t>,
+
+    /// Remove this array and free the associated memory. This function can be
+    /// set to `NULL` is there is no memory management to do.
+    destroy: Option<unsafe extern fn(array: *mut c_void)>,
+
+    /// Set entries in the `output` array (the current array) taking data from
+    /// the `input` array. The `output` array is guaranteed to be created by
+    /// calling `eqs_array_t::create` with one of the arrays in the same block
+    /// or tensor map as the `input`.
+    ///
+    /// The `samples` array of size `samples_count` indicate where the data
+    /// should be moved from `input` to `output`.
+    ///
+    /// This function should copy data from `input[samples[i].input, ..., :]` to
+    /// `array[samples[i].output, ..., property_start:property_end]` for `i` up
+    /// to `samples_count`. All indexes are 0-based.
+    move_samples_from: Option<unsafe extern fn(
+        output: *mut c_void,
+        input: *const c_void,
+        samples: *const eqs_sample_mapping_t,
+        samples_count: usize,
+        property_start: usize,
+        property_end: usize,
+    ) -> eqs_status_t>,
+
+    /// Set entries in the `output` array (the current array) by selecting data
+    /// from the `input` array along a single `axis`. The `output` array is
+    /// guaranteed to be created by calling `eqs_array_t::create` with one of
+    /// the arrays in the same block or tensor map as the `input`, and has the
+    /// same shape as `input` except along `axis`, where it has `indices_count`
+    /// entries.
+    ///
+    /// This function should copy data from `input[..., indices[i], ...]` to
+    /// `array[..., i, ...]` (where the indexing is done along `axis`) for `i`
+    /// up to `indices_count`. All indexes are 0-based.
+    gather_from: Option<unsafe extern fn(
+        output: *mut c_void,
+        input: *const c_void,
+        axis: usize,
+        indices: *const usize,
+        indices_count: usize,
+    ) -> eqs_status_t>,
+
+    /// Add entries from the `input` array to the `output` array (the current
+    /// array) along a single `axis`. The `output` array is guaranteed to be
+    /// created by calling `eqs_array_t::create` with one of the arrays in the
+    /// same block or tensor map as the `input`, and has the same shape as
+    /// `input` except along `axis`. The `input` array has `indices_count`
+    /// entries along `axis`.
+    ///
+    /// This function should add data from `input[..., i, ...]` to
+    /// `array[..., indices[i], ...]` (where the indexing is done along `axis`)
+    /// for `i` up to `indices_count`. Multiple entries in `indices` can have
+    /// the same value, in which case the data from all the corresponding
+    /// `input` entries should be summed together. All indexes are 0-based.
+    scatter_add_from: Option<unsafe extern fn(
+        output: *mut c_void,
+        input: *const c_void,
+        axis: usize,
+        indices: *const usize,
+        indices_count: usize,
+    ) -> eqs_status_t>,
+
+    /// Set entries in the `output` array (the current array) taking data from
+    /// the `input` array, remapping both samples and properties. The `output`
+    /// array is guaranteed to be created by calling `eqs_array_t::create` with
+    /// one of the arrays in the same block or tensor map as the `input`, and
+    /// all dimensions except the first (samples) and last (properties) have
+    /// the same size in `input` and `output`.
+    ///
+    /// The `samples` array of size `samples_count` indicate where the data
+    /// should be moved along the sample axis. The `properties` array of size
+    /// `properties_count` contains one entry for each property in `input`,
+    /// and indicates where this property should be moved in `output`.
+    ///
+    /// This function should copy data from `input[samples[i].input, ..., j]`
+    /// to `array[samples[i].output, ..., properties[j]]` for `i` up to
+    /// `samples_count` and `j` up to `properties_count`. All indexes are
+    /// 0-based.
+    ///
+    /// This function can be set to `NULL`, in which case `move_samples_from`
+    /// is used instead when the properties are moved to a contiguous range in
+    /// `output`, and an error is returned otherwise.
+    move_data: Option<unsafe extern fn(
+        output: *mut c_void,
+        input: *const c_void,
+        samples: *const eqs_sample_mapping_t,
+        samples_count: usize,
+        properties: *const usize,
+        properties_count: usize,
+    ) -> eqs_status_t>,
+}
+
+/// Representation of a single sample moved from an array to another one
+#[derive(Debug, Clone)]
+#[repr(C)]
+pub struct eqs_sample_mapping_t {
+    /// index of the moved sample in the input array
+    pub input: usize,
+    /// index of the moved sample in the output array
+    pub output: usize,
+}
+
+impl std::fmt::Debug for eqs_array_t {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut origin = None;
+        let mut shape = None;
+        if !self.ptr.is_null() {
+            origin = self.origin().ok().map(get_data_origin);
+            shape = self.shape().ok();
+        }
+
+        f.debug_struct("eqs_array_t")
+            .field("ptr", &self.ptr)
+            .field("origin", &origin)
+            .field("shape", &shape)
+            .finish()
+    }
+}
+
+impl Drop for eqs_array_t {
+    fn drop(&mut self) {
+        if let Some(function) = self.destroy {
+            unsafe { function(self.ptr) }
+        }
+    }
+}
+
+impl Clone for eqs_array_t {
+    fn clone(&self) -> eqs_array_t {
+        if let Some(function) = self.copy {
+            let mut new_array = eqs_array_t::null();
+            let status  = unsafe { function(self.ptr, &mut new_array) };
+            assert!(status.is_success(), "calling eqs_array_t.copy failed");
+            return new_array;
+        } else {
+            panic!("function eqs_array_t.copy is not set")
+        }
+    }
+}
+
+impl eqs_array_t {
+    /// make a raw (member by member) copy of the array. Contrary to
+    /// `eqs_array_t::clone`, the returned array refers to the same
+    /// `eqs_array_t` instance, and as such should not be freed.
+    pub(crate) fn raw_copy(&self) -> eqs_array_t {
+        eqs_array_t {
+            ptr: self.ptr,
+            origin: self.origin,
+            dtype: self.dtype,
+            data: self.data,
+            device: self.device,
+            copy_to_host: self.copy_to_host,
+            shape: self.shape,
+            reshape: self.reshape,
+            swap_axes: self.swap_axes,
+            create: self.create,
+            copy: self.copy,
+            // do not copy destroy, the user should never call it
+            destroy: None,
+            move_samples_from: self.move_samples_from,
+            gather_from: self.gather_from,
+            scatter_add_from: self.scatter_add_from,
+            move_data: self.move_data,
+        }
+    }
+
+    /// Create an `eqs_array_t` with all fields set to null pointers.
+    pub(crate) fn null() -> eqs_array_t {
+        eqs_array_t {
+            ptr: std::ptr::null_mut(),
+            origin: None,
+            dtype: None,
+            data: None,
+            device: None,
+            copy_to_host: None,
+            shape: None,
+            reshape: None,
+            swap_axes: None,
+            create: None,
+            copy: None,
+            destroy: None,
+            move_samples_from: None,
+            gather_from: None,
+            scatter_add_from: None,
+            move_data: None,
+        }
+    }
+
+    /// Get the origin of this array
+    pub fn origin(&self) -> Result<eqs_data_origin_t, Error> {
+        let function = self.origin.expect("eqs_array_t.origin function is NULL");
+
+        let mut origin = eqs_data_origin_t(0);
+        let status = unsafe {
+            function(self.ptr, &mut origin)
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.origin failed".into()
+            });
+        }
+
+        return Ok(origin);
+    }
+
+    /// Get the type of the data stored in this array. Arrays without a
+    /// `dtype` function are assumed to contain 64-bit floating point values.
+    pub fn dtype(&self) -> Result<eqs_dtype_t, Error> {
+        let function = match self.dtype {
+            Some(function) => function,
+            None => return Ok(eqs_dtype_t(EQS_DTYPE_FLOAT64)),
+        };
+
+        let mut dtype = eqs_dtype_t(0);
+        let status = unsafe {
+            function(self.ptr, &mut dtype)
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.dtype failed".into()
+            });
+        }
+
+        if !dtype.is_valid() {
+            return Err(Error::InvalidParameter(format!(
+                "eqs_array_t.dtype returned an unknown data type ({})", dtype.0
+            )));
+        }
+
+        return Ok(dtype);
+    }
+
+    /// Get the device where the data of this array lives. Arrays without a
+    /// `device` function are assumed to live in host memory.
+    pub fn device(&self) -> Result<eqs_device_t, Error> {
+        let function = match self.device {
+            Some(function) => function,
+            None => return Ok(eqs_device_t(EQS_DEVICE_CPU)),
+        };
+
+        let mut device = eqs_device_t(0);
+        let status = unsafe {
+            function(self.ptr, &mut device)
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.device failed".into()
+            });
+        }
+
+        if device.0 != EQS_DEVICE_CPU && device.0 != EQS_DEVICE_OTHER {
+            return Err(Error::InvalidParameter(format!(
+                "eqs_array_t.device returned an unknown device ({})", device.0
+            )));
+        }
+
+        return Ok(device);
+    }
+
+    /// Get the number of elements in this array
+    fn len(&self) -> Result<usize, Error> {
+        let mut len = 1;
+        for s in self.shape()? {
+            len *= s;
+        }
+        return Ok(len);
+    }
+
+    /// Get a pointer to the underlying data for this array, and the number of
+    /// elements in the array.
+    fn data_ptr(&self) -> Result<(*mut c_void, usize), Error> {
+        let len = self.len()?;
+        if len == 0 {
+            return Ok((std::ptr::NonNull::<u64>::dangling().as_ptr().cast(), 0));
+        }
+
+        let device = self.device()?;
+        if device.0 != EQS_DEVICE_CPU {
+            return Err(Error::InvalidParameter(format!(
+                "can not directly access the data of an array on the '{}' device, \
+                it must be copied to host memory first", device
+            )));
+        }
+
+        let function = self.data.expect("eqs_array_t.data function is NULL");
+
+        let mut data_ptr = std::ptr::null_mut();
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                &mut data_ptr,
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.data failed".into()
+            });
+        }
+
+        if data_ptr.is_null() {
+            return Err(Error::InvalidParameter(
+                "eqs_array_t.data returned a NULL pointer".into()
+            ));
+        }
+
+        return Ok((data_ptr, len));
+    }
+
+    /// Check that the data in this array has the type corresponding to `T`
+    fn check_dtype<T: DataType>(&self) -> Result<(), Error> {
+        let dtype = self.dtype()?;
+        if dtype != T::DTYPE {
+            return Err(Error::InvalidParameter(format!(
+                "expected an array containing {} data, got {}", T::DTYPE, dtype
+            )));
+        }
+        return Ok(());
+    }
+
+    /// Get the underlying data for this array, as values of type `T`. This
+    /// function fails if the array does not contain data of this type.
+    pub fn data<T: DataType>(&self) -> Result<&[T], Error> {
+        self.check_dtype::<T>()?;
+        let (data_ptr, len) = self.data_ptr()?;
+        let data = unsafe {
+            std::slice::from_raw_parts(data_ptr.cast(), len)
+        };
+
+        return Ok(data);
+    }
+
+    /// Get the underlying data for this array, as mutable values of type `T`.
+    /// This function fails if the array does not contain data of this type.
+    pub fn data_mut<T: DataType>(&mut self) -> Result<&mut [T], Error> {
+        self.check_dtype::<T>()?;
+        let (data_ptr, len) = self.data_ptr()?;
+        let data = unsafe {
+            std::slice::from_raw_parts_mut(data_ptr.cast(), len)
+        };
+
+        return Ok(data);
+    }
+
+    /// Get the underlying data for this array as raw bytes, regardless of the
+    /// data type.
+    pub fn data_bytes(&self) -> Result<&[u8], Error> {
+        let dtype = self.dtype()?;
+        let (data_ptr, len) = self.data_ptr()?;
+        let data = unsafe {
+            std::slice::from_raw_parts(data_ptr.cast(), len * dtype.size())
+        };
+
+        return Ok(data);
+    }
+
+    /// Get the underlying data for this array as mutable raw bytes, regardless
+    /// of the data type.
+    pub fn data_bytes_mut(&mut self) -> Result<&mut [u8], Error> {
+        let dtype = self.dtype()?;
+        let (data_ptr, len) = self.data_ptr()?;
+        let data = unsafe {
+            std::slice::from_raw_parts_mut(data_ptr.cast(), len * dtype.size())
+        };
+
+        return Ok(data);
+    }
+
+    /// Copy all the data of this array to host memory in `buffer`, as raw
+    /// bytes. `buffer` must contain exactly as many bytes as the array data.
+    pub fn copy_to_host(&self, buffer: &mut [u8]) -> Result<(), Error> {
+        let expected_size = self.len()? * self.dtype()?.size();
+        if buffer.len() != expected_size {
+            return Err(Error::InvalidParameter(format!(
+                "the buffer should contain {} bytes to copy this array, got {}",
+                expected_size, buffer.len()
+            )));
+        }
+
+        if self.device()?.0 == EQS_DEVICE_CPU {
+            buffer.copy_from_slice(self.data_bytes()?);
+            return Ok(());
+        }
+
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let function = self.copy_to_host.ok_or_else(|| Error::InvalidParameter(
+            "eqs_array_t.copy_to_host function is NULL for an array which is not on CPU".into()
+        ))?;
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                buffer.as_mut_ptr().cast(),
+                buffer.len(),
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.copy_to_host failed".into()
+            });
+        }
+
+        return Ok(());
+    }
+
+    /// Get the data for this array as raw bytes in host memory. This borrows
+    /// the data for arrays on CPU, and copies it with
+    /// `eqs_array_t.copy_to_host` for arrays on other devices.
+    pub fn host_data_bytes(&self) -> Result<Cow<'_, [u8]>, Error> {
+        if self.device()?.0 == EQS_DEVICE_CPU {
+            return Ok(Cow::Borrowed(self.data_bytes()?));
+        }
+
+        let mut buffer = vec![0; self.len()? * self.dtype()?.size()];
+        self.copy_to_host(&mut buffer)?;
+        return Ok(Cow::Owned(buffer));
+    }
+
+    /// Get the shape of this array
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn shape(&self) -> Result<&[usize], Error> {
+        let function = self.shape.expect("eqs_array_t.shape function is NULL");
+
+        let mut shape = std::ptr::null();
+        let mut shape_count: usize = 0;
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                &mut shape,
+                &mut shape_count,
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.shape failed".into()
+            });
+        }
+
+        let shape = unsafe {
+            std::slice::from_raw_parts(shape, shape_count)
+        };
+
+        return Ok(shape);
+    }
+
+    /// Set the shape of this array to the given new `shape`
+    pub fn reshape(&mut self, shape: &[usize]) -> Result<(), Error> {
+        let function = self.reshape.expect("eqs_array_t.reshape function is NULL");
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                shape.as_ptr(),
+                shape.len(),
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.reshape failed".into()
+            });
+        }
+
+        return Ok(());
+    }
+
+    /// Swap the axes `axis_1` and `axis_2` in the dimensions of this array.
+    pub fn swap_axes(&mut self, axis_1: usize, axis_2: usize) -> Result<(), Error> {
+        let function = self.swap_axes.expect("eqs_array_t.swap_axes function is NULL");
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                axis_1,
+                axis_2,
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.swap_axes failed".into()
+            });
+        }
+
+        return Ok(());
+    }
+
+    /// Create a new array with the same settings as this one and the given `shape`
+    pub fn create(&self, shape: &[usize]) -> Result<eqs_array_t, Error> {
+        let function = self.create.expect("eqs_array_t.create function is NULL");
+
+        let mut data_storage = eqs_array_t::null();
+        let status = unsafe {
+            function(
+                self.ptr,
+                shape.as_ptr(),
+                shape.len(),
+                &mut data_storage
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.create failed".into()
+            });
+        }
+
+        return Ok(data_storage);
+    }
+
+    /// Set entries in `self` (the current array) taking data from the `input`
+    /// array. The `self` array is guaranteed to be created by calling
+    /// `Array::create` with one of the arrays in the same block or tensor
+    /// map as the `input`.
+    ///
+    /// The `samples` array indicate where the data should be moved from `input`
+    /// to `output`.
+    ///
+    /// This function should copy data from `input[sample.input, ..., :]` to
+    /// `array[sample.output, ..., properties]` for all `sample` in `samples`.
+    /// All indexes are 0-based.
+    pub fn move_samples_from(
+        &mut self,
+        input: &eqs_array_t,
+        samples: &[eqs_sample_mapping_t],
+        properties: Range<usize>,
+    ) -> Result<(), Error> {
+        let function = self.move_samples_from.expect("eqs_array_t.move_samples_from function is NULL");
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                input.ptr,
+                samples.as_ptr(),
+                samples.len(),
+                properties.start,
+                properties.end,
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.move_samples_from failed".into()
+            });
+        }
+
+        return Ok(());
+    }
+
+    /// Set entries in `self` (the current array) by selecting data from the
+    /// `input` array along `axis`. The `self` array is guaranteed to be created
+    /// by calling `Array::create` with one of the arrays in the same block or
+    /// tensor map as the `input`.
+    ///
+    /// This function should copy data from `input[..., indices[i], ...]` to
+    /// `array[..., i, ...]` (indexing along `axis`) for all `i` in `indices`.
+    /// All indexes are 0-based.
+    pub fn gather_from(
+        &mut self,
+        input: &eqs_array_t,
+        axis: usize,
+        indices: &[usize],
+    ) -> Result<(), Error> {
+        let function = self.gather_from.expect("eqs_array_t.gather_from function is NULL");
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                input.ptr,
+                axis,
+                indices.as_ptr(),
+                indices.len(),
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.gather_from failed".into()
+            });
+        }
+
+        return Ok(());
+    }
+
+    /// Add entries from the `input` array to `self` along a single `axis`.
+    ///
+    /// The `self` array must have been created by calling `eqs_array_t::create`
+    /// with one of the arrays in the same block or tensor map as the `input`.
+    ///
+    /// This function should add data from `input[..., i, ...]` to
+    /// `array[..., indices[i], ...]` (indexing along `axis`) for all `i` in
+    /// `indices`. All indexes are 0-based.
+    pub fn scatter_add_from(
+        &mut self,
+        input: &eqs_array_t,
+        axis: usize,
+        indices: &[usize],
+    ) -> Result<(), Error> {
+        let function = self.scatter_add_from.expect("eqs_array_t.scatter_add_from function is NULL");
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                input.ptr,
+                axis,
+                indices.as_ptr(),
+                indices.len(),
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.scatter_add_from failed".into()
+            });
+        }
+
+        return Ok(());
+    }
+
+    /// Set entries in `self` (the current array) taking data from the `input`
+    /// array, remapping both samples and properties. The `self` array is
+    /// guaranteed to be created by calling `Array::create` with one of the
+    /// arrays in the same block or tensor map as the `input`.
+    ///
+    /// The `samples` array indicate where the data should be moved along the
+    /// sample axis, and `properties` contains the new position of each of the
+    /// properties in `input`.
+    ///
+    /// This function should copy data from `input[sample.input, ..., i]` to
+    /// `array[sample.output, ..., properties[i]]` for all `sample` in
+    /// `samples` and all `i` in `0..properties.len()`. All indexes are
+    /// 0-based.
+    ///
+    /// If `eqs_array_t.move_data` is NULL, this uses `move_samples_from`
+    /// instead, which requires the properties to be moved to a contiguous
+    /// range.
+    pub fn move_data(
+        &mut self,
+        input: &eqs_array_t,
+        samples: &[eqs_sample_mapping_t],
+        properties: &[usize],
+    ) -> Result<(), Error> {
+        let function = match self.move_data {
+            Some(function) => function,
+            None => {
+                let start = properties.first().copied().unwrap_or(0);
+                let is_contiguous = properties.iter().enumerate().all(|(i, &property)| property == start + i);
+                if !is_contiguous {
+                    return Err(Error::InvalidParameter(
+                        "eqs_array_t.move_data function is NULL, and the properties \
+                        are not moved to a contiguous range, which is required to \
+                        use eqs_array_t.move_samples_from instead".into()
+                    ));
+                }
+
+                return self.move_samples_from(input, samples, start..(start + properties.len()));
+            }
+        };
+
+        let status = unsafe {
+            function(
+                self.ptr,
+                input.ptr,
+                samples.as_ptr(),
+                samples.len(),
+                properties.as_ptr(),
+                properties.len(),
+            )
+        };
+
+        if !status.is_success() {
+            return Err(Error::External {
+                status, context: "calling eqs_array_t.move_data failed".into()
+            });
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+pub(crate) use self::tests::TestArray;
+
+#[cfg(test)]
+mod tests {
+    use crate::c_api::EQS_SUCCESS;
+
+    use super::*;
+
+    pub struct TestArray {
+        shape: Vec<usize>,
+    }
+
+    impl TestArray {
+        #[allow(clippy::new_ret_no_self)]
+        pub fn new(shape: Vec<usize>) -> eqs_array_t {
+            let array = Box::new(TestArray {shape});
+
+            return eqs_array_t {
+                ptr: Box::into_raw(array).cast(),
+                origin: Some(TestArray::origin),
+                dtype: None,
+                data: None,
+                device: None,
+                copy_to_host: None,
+                shape: Some(TestArray::shape),
+                reshape: Some(TestArray::reshape),
+                swap_axes: Some(TestArray::swap_axes),
+                create: None,
+                copy: None,
+                destroy: Some(TestArray::destroy),
+                move_samples_from: None,
+                gather_from: None,
+                scatter_add_from: None,
+                move_data: None,
+            }
+        }
+
+        unsafe extern fn origin(_: *const c_void, origin: *mut eqs_data_origin_t) -> eqs_status_t {
+            *origin = register_data_origin("rust.TestArray".into());
+
+            return eqs_status_t(EQS_SUCCESS);
+        }
+
+        unsafe extern fn shape(ptr: *const c_void, shape: *mut *const usize, shape_count: *mut usize) -> eqs_status_t {
+            let ptr = ptr.cast::<TestArray>();
+
+            *shape = (*ptr).shape.as_ptr();
+            *shape_count = (*ptr).shape.len();
+
+            return eqs_status_t(EQS_SUCCESS);
+        }
+
+        unsafe extern fn reshape(ptr: *mut c_void, shape_ptr: *const usize, shape_count: usize) -> eqs_status_t {
+            let ptr = ptr.cast::<TestArray>();
+
+            let shape = &mut (*ptr).shape;
+            shape.clear();
+
+            for i in 0..shape_count {
+                shape.push(shape_ptr.add(i).read());
+            }
+
+            return eqs_status_t(EQS_SUCCESS);
+        }
+
+        unsafe extern fn swap_axes(ptr: *mut c_void, axis_1: usize, axis_2: usize) -> eqs_status_t {
+            let ptr = ptr.cast::<TestArray>();
+
+            let shape = &mut (*ptr).shape;
+            shape.swap(axis_1, axis_2);
+
+            return eqs_status_t(EQS_SUCCESS);
+        }
+
+        unsafe extern fn destroy(ptr: *mut c_void) {
+            let ptr = ptr.cast::<TestArray>();
+            let boxed = Box::from_raw(ptr);
+            std::mem::drop(boxed);
+        }
+    }
+
+    #[test]
+    fn data_origin() {
+        assert_eq!(get_data_origin(eqs_data_origin_t(0)), "unregistered origin");
+        assert_eq!(get_data_origin(eqs_data_origin_t(10000)), "unregistered origin");
+
+        let origin = register_data_origin("test origin".into());
+        assert_eq!(get_data_origin(origin), "test origin");
+    }
+
+    #[test]
+    fn dtype() {
+        // arrays without a dtype callback contain 64-bit floats
+        let data: eqs_array_t = TestArray::new(vec![3, 4, 5]);
+        assert_eq!(data.dtype().unwrap(), f64::DTYPE);
+        assert!(data.check_dtype::<f64>().is_ok());
+
+        let error = data.check_dtype::<i32>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid parameter: expected an array containing int32 data, got float64");
+
+        assert_eq!(eqs_dtype_t(EQS_DTYPE_FLOAT16).to_string(), "float16");
+        assert_eq!(eqs_dtype_t(EQS_DTYPE_INT64).size(), 8);
+        assert_eq!(eqs_dtype_t(42).to_string(), "unknown data type (42)");
+    }
+
+    #[test]
+    fn device() {
+        unsafe extern fn other_device(_: *const c_void, device: *mut eqs_device_t) -> eqs_status_t {
+            *device = eqs_device_t(EQS_DEVICE_OTHER);
+            return eqs_status_t(EQS_SUCCESS);
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        unsafe extern fn copy_to_host(_: *const c_void, buffer: *mut c_void, buffer_size: usize) -> eqs_status_t {
+            let buffer = std::slice::from_raw_parts_mut(buffer.cast::<f64>(), buffer_size / 8);
+            for (i, value) in buffer.iter_mut().enumerate() {
+                *value = i as f64;
+            }
+            return eqs_status_t(EQS_SUCCESS);
+        }
+
+        let mut data: eqs_array_t = TestArray::new(vec![3, 2]);
+        assert_eq!(data.device().unwrap(), eqs_device_t(EQS_DEVICE_CPU));
+
+        data.device = Some(other_device);
+        assert_eq!(data.device().unwrap().to_string(), "other");
+
+        let error = data.data::<f64>().unwrap_err();
+        assert_eq!(error.to_string(),
+            "invalid parameter: can not directly access the data of an array \
+            on the 'other' device, it must be copied to host memory first"
+        );
+
+        let error = data.host_data_bytes().unwrap_err();
+        assert_eq!(error.to_string(),
+            "invalid parameter: eqs_array_t.copy_to_host function is NULL for \
+            an array which is not on CPU"
+        );
+
+        data.copy_to_host = Some(copy_to_host);
+        let bytes = data.host_data_bytes().unwrap();
+        assert_eq!(bytes.len(), 6 * 8);
+        assert_eq!(bytes[8..16], 1.0_f64.to_ne_bytes());
+        assert_eq!(bytes[40..48], 5.0_f64.to_ne_bytes());
+
+        let error = data.copy_to_host(&mut [0; 12]).unwrap_err();
+        assert_eq!(error.to_string(),
+            "invalid parameter: the buffer should contain 48 bytes to copy this array, got 12"
+        );
+    }
+
+    #[test]
+    fn move_data_fallback() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static PROPERTY_START: AtomicUsize = AtomicUsize::new(0);
+        static PROPERTY_END: AtomicUsize = AtomicUsize::new(0);
+
+        unsafe extern fn move_samples_from(
+            _: *mut c_void,
+            _: *const c_void,
+            _: *const eqs_sample_mapping_t,
+            _: usize,
+            property_start: usize,
+            property_end: usize,
+        ) -> eqs_status_t {
+            PROPERTY_START.store(property_start, Ordering::SeqCst);
+            PROPERTY_END.store(property_end, Ordering::SeqCst);
+            return eqs_status_t(EQS_SUCCESS);
+        }
+
+        let input: eqs_array_t = TestArray::new(vec![2, 3]);
+        let mut output: eqs_array_t = TestArray::new(vec![2, 8]);
+        output.move_samples_from = Some(move_samples_from);
+        assert!(output.move_data.is_none());
+
+        let samples = [eqs_sample_mapping_t { input: 0, output: 1 }];
+        output.move_data(&input, &samples, &[2, 3, 4]).unwrap();
+        assert_eq!(PROPERTY_START.load(Ordering::SeqCst), 2);
+        assert_eq!(PROPERTY_END.load(Ordering::SeqCst), 5);
+
+        let error = output.move_data(&input, &samples, &[2, 4, 5]).unwrap_err();
+        assert_eq!(error.to_string(),
+            "invalid parameter: eqs_array_t.move_data function is NULL, and the \
+            properties are not moved to a contiguous range, which is required \
+            to use eqs_array_t.move_samples_from instead"
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let data: eqs_array_t = TestArray::new(vec![3, 4, 5]);
+
+        let debug_format = format!("{:?}", data);
+        assert_eq!(debug_format, format!(
+            "eqs_array_t {{ ptr: {:?}, origin: Some(\"rust.TestArray\"), shape: Some([3, 4, 5]) }}",
+            data.ptr
+        ));
+    }
+}
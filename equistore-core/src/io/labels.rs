@@ -9,10 +9,13 @@ use crate::{Error, Labels, LabelsBuilder, LabelValue};
 /// Read `Labels` stored using numpy's NPY format.
 ///
 /// The labels are stored as a structured array, as if each entry in the Labels
-/// was a C struct of 32-bit integers. The corresponding `dtype` is a list
-/// associating name and either "<i4" for little endian file or ">i4" for big
+/// was a C struct of 64-bit integers. The corresponding `dtype` is a list
+/// associating name and either "<i8" for little endian file or ">i8" for big
 /// endian file. Data is stored in exactly the same way as inside a `Labels`,
-/// i.e. a big blob of 32-bit integers.
+/// i.e. a big blob of 64-bit integers.
+///
+/// Files created with older versions of equistore store 32-bit integers
+/// instead ("<i4" or ">i4"), and can also be read by this function.
 pub fn read_npy_labels<R: std::io::Read>(mut reader: R) -> Result<Labels, Error> {
     let header = Header::from_reader(&mut reader)?;
     if header.fortran_order {
@@ -20,12 +23,23 @@ pub fn read_npy_labels<R: std::io::Read>(mut reader: R) -> Result<Labels, Error>
     } else if header.shape.len() != 1 {
         return Err(Error::Serialization("Expected a 1-D array when loading Labels".into()));
     }
-    let (names, endianness) = check_type_descriptor(header.type_descriptor)?;
+    let (names, endianness, integer_size) = check_type_descriptor(header.type_descriptor)?;
 
     let mut data = vec![0; header.shape[0] * names.len()];
-    match endianness {
-        Endianness::LittleEndian => reader.read_i32_into::<LittleEndian>(&mut data)?,
-        Endianness::BigEndian => reader.read_i32_into::<BigEndian>(&mut data)?,
+    match (endianness, integer_size) {
+        (Endianness::LittleEndian, IntegerSize::I64) => reader.read_i64_into::<LittleEndian>(&mut data)?,
+        (Endianness::BigEndian, IntegerSize::I64) => reader.read_i64_into::<BigEndian>(&mut data)?,
+        (endianness, IntegerSize::I32) => {
+            let mut data_i32 = vec![0; data.len()];
+            match endianness {
+                Endianness::LittleEndian => reader.read_i32_into::<LittleEndian>(&mut data_i32)?,
+                Endianness::BigEndian => reader.read_i32_into::<BigEndian>(&mut data_i32)?,
+            }
+
+            for (value, &value_i32) in data.iter_mut().zip(&data_i32) {
+                *value = i64::from(value_i32);
+            }
+        }
     }
 
     check_for_extra_bytes(&mut reader)?;
@@ -46,10 +60,10 @@ pub fn write_npy_labels<W: std::io::Write>(writer: &mut W, labels: &Labels) -> R
     let mut type_descriptor = String::from("[");
     for name in labels.names() {
         if cfg!(target_endian = "little") {
-            write!(type_descriptor, "('{}', '<i4'), ", name).expect("failed to write dtype");
+            write!(type_descriptor, "('{}', '<i8'), ", name).expect("failed to write dtype");
         } else {
             assert!(cfg!(target_endian = "big"));
-            write!(type_descriptor, "('{}', '>i4'), ", name).expect("failed to write dtype");
+            write!(type_descriptor, "('{}', '>i8'), ", name).expect("failed to write dtype");
         }
     }
     type_descriptor += "]";
@@ -63,7 +77,7 @@ pub fn write_npy_labels<W: std::io::Write>(writer: &mut W, labels: &Labels) -> R
 
     for entry in labels {
         for value in entry {
-            writer.write_i32::<NativeEndian>(value.i32())?;
+            writer.write_i64::<NativeEndian>(value.i64())?;
         }
     }
 
@@ -76,14 +90,22 @@ enum Endianness {
     LittleEndian,
 }
 
+/// Size of the integers used to store `Labels` in NPY files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntegerSize {
+    I32,
+    I64,
+}
+
 /// Check that the given type descriptor matches the expected one for Labels and
-/// return the corresponding set of names & endianness.
-fn check_type_descriptor(desc: PyValue) -> Result<(Vec<String>, Endianness), Error> {
+/// return the corresponding set of names, endianness and integer size.
+fn check_type_descriptor(desc: PyValue) -> Result<(Vec<String>, Endianness, IntegerSize), Error> {
     let mut names = Vec::new();
 
     let error = Error::Serialization("invalid dtype for labels".into());
 
     let mut endianness = None;
+    let mut integer_size = None;
     match desc {
         PyValue::List(list) => {
             for element in list {
@@ -98,23 +120,20 @@ fn check_type_descriptor(desc: PyValue) -> Result<(Vec<String>, Endianness), Err
                         let name = name.as_string().expect("name is not a string");
                         let typ = typ.as_string().expect("type is not a string");
 
+                        let (current_endianness, current_size) = match typ.as_str() {
+                            "<i4" => (Endianness::LittleEndian, IntegerSize::I32),
+                            ">i4" => (Endianness::BigEndian, IntegerSize::I32),
+                            "<i8" => (Endianness::LittleEndian, IntegerSize::I64),
+                            ">i8" => (Endianness::BigEndian, IntegerSize::I64),
+                            _ => return Err(error),
+                        };
+
                         if endianness.is_none() {
-                            if typ == "<i4" {
-                                endianness = Some(Endianness::LittleEndian);
-                            } else if typ == ">i4" {
-                                endianness = Some(Endianness::BigEndian);
-                            }
+                            endianness = Some(current_endianness);
+                            integer_size = Some(current_size);
                         }
 
-                        if typ == "<i4" {
-                            if endianness != Some(Endianness::LittleEndian) {
-                                return Err(error);
-                            }
-                        } else if typ == ">i4" {
-                            if endianness != Some(Endianness::BigEndian) {
-                                return Err(error);
-                            }
-                        } else {
+                        if endianness != Some(current_endianness) || integer_size != Some(current_size) {
                             return Err(error);
                         }
 
@@ -131,5 +150,9 @@ fn check_type_descriptor(desc: PyValue) -> Result<(Vec<String>, Endianness), Err
         }
     }
 
-    return Ok((names, endianness.expect("failed to find endianness")));
+    return Ok((
+        names,
+        endianness.expect("failed to find endianness"),
+        integer_size.expect("failed to find integer size"),
+    ));
 }
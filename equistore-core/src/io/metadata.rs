@@ -0,0 +1,110 @@
+use byteorder::{LittleEndian, BigEndian, ReadBytesExt, WriteBytesExt, NativeEndian};
+use py_literal::Value as PyValue;
+
+use super::{Header, check_for_extra_bytes};
+use crate::{Error, Metadata};
+
+/// Read `Metadata` (used for tensor-level metadata and per-block info) stored
+/// using numpy's NPY format.
+///
+/// The metadata is stored as a 2-dimensional array of numpy unicode strings,
+/// with shape `(n_entries, 2)`. The first column contains the keys and the
+/// second column the corresponding values. Following numpy's conventions, each
+/// string is stored as a fixed-size array of UTF-32 code points (`"<U{size}"`
+/// for little-endian files, `">U{size}"` for big-endian files), padded with
+/// zeros at the end.
+pub fn read_npy_metadata<R: std::io::Read>(mut reader: R) -> Result<Metadata, Error> {
+    let header = Header::from_reader(&mut reader)?;
+    if header.fortran_order {
+        return Err(Error::Serialization("metadata can not be loaded from fortran-order arrays".into()));
+    } else if header.shape.len() != 2 || header.shape[1] != 2 {
+        return Err(Error::Serialization("expected a (n, 2) array when loading metadata".into()));
+    }
+
+    let (size, little_endian) = match header.type_descriptor {
+        PyValue::String(ref s) if s.starts_with("<U") || s.starts_with(">U") => {
+            let size = s[2..].parse::<usize>().map_err(|_| Error::Serialization(format!(
+                "invalid type descriptor for metadata: {}", s
+            )))?;
+            (size, s.starts_with('<'))
+        }
+        _ => {
+            return Err(Error::Serialization(format!(
+                "unknown type for metadata, expected unicode strings, got {}",
+                header.type_descriptor
+            )));
+        }
+    };
+
+    let mut data = vec![0; header.shape[0] * 2 * size];
+    if little_endian {
+        reader.read_u32_into::<LittleEndian>(&mut data)?;
+    } else {
+        reader.read_u32_into::<BigEndian>(&mut data)?;
+    }
+
+    check_for_extra_bytes(&mut reader)?;
+
+    let mut metadata = Metadata::default();
+    if size == 0 {
+        // all keys and values are empty strings
+        if header.shape[0] != 0 {
+            metadata.set("", "")?;
+        }
+        return Ok(metadata);
+    }
+
+    let mut strings = Vec::with_capacity(2 * header.shape[0]);
+    for chunk in data.chunks_exact(size) {
+        let mut string = String::with_capacity(size);
+        for &code_point in chunk.iter().take_while(|&&c| c != 0) {
+            let c = char::from_u32(code_point).ok_or_else(|| Error::Serialization(format!(
+                "invalid unicode code point in metadata: {:#x}", code_point
+            )))?;
+            string.push(c);
+        }
+        strings.push(string);
+    }
+
+    for entry in strings.chunks_exact(2) {
+        metadata.set(&entry[0], &entry[1])?;
+    }
+
+    return Ok(metadata);
+}
+
+/// Write `Metadata` to the writer using numpy's NPY format.
+///
+/// See [`read_npy_metadata`] for more information on how metadata is stored
+/// to files.
+pub fn write_npy_metadata<W: std::io::Write>(writer: &mut W, metadata: &Metadata) -> Result<(), Error> {
+    let mut entries = Vec::new();
+    for (key, value) in metadata.iter() {
+        entries.push(key.chars().collect::<Vec<_>>());
+        entries.push(value.chars().collect::<Vec<_>>());
+    }
+    let size = entries.iter().map(Vec::len).max().unwrap_or(0);
+
+    let type_descriptor = if cfg!(target_endian = "little") {
+        format!("'<U{}'", size)
+    } else {
+        format!("'>U{}'", size)
+    };
+
+    let header = Header {
+        type_descriptor: type_descriptor.parse().expect("invalid dtype"),
+        fortran_order: false,
+        shape: vec![entries.len() / 2, 2],
+    };
+
+    header.write(&mut *writer)?;
+
+    for entry in entries {
+        for i in 0..size {
+            let code_point = entry.get(i).map_or(0, |&c| c as u32);
+            writer.write_u32::<NativeEndian>(code_point)?;
+        }
+    }
+
+    return Ok(());
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
+use std::collections::BTreeSet;
 
-use byteorder::{LittleEndian, BigEndian, ReadBytesExt, WriteBytesExt, NativeEndian};
 use py_literal::Value as PyValue;
 use zip::{ZipArchive, ZipWriter, DateTime};
 
 use crate::{TensorMap, Error, TensorBlock, eqs_array_t};
+use crate::data::{eqs_dtype_t, EQS_DTYPE_FLOAT64, EQS_DTYPE_FLOAT32, EQS_DTYPE_FLOAT16};
+use crate::data::{EQS_DTYPE_INT32, EQS_DTYPE_INT64};
 
 
 mod npy_header;
@@ -13,11 +15,14 @@ pub use self::npy_header::Header;
 mod labels;
 use self::labels::{read_npy_labels, write_npy_labels};
 
+mod metadata;
+use self::metadata::{read_npy_metadata, write_npy_metadata};
+
 /// Load the serialized tensor map from the given path.
 ///
 /// Arrays for the values and gradient data will be created with the given
 /// `create_array` callback, and filled by this function with the corresponding
-/// data.
+/// data. The callback gets the shape and data type of the array to create.
 ///
 /// `TensorMap` are serialized using numpy's `.npz` format, i.e. a ZIP file
 /// without compression (storage method is STORED), where each file is stored as
@@ -28,8 +33,11 @@ use self::labels::{read_npy_labels, write_npy_labels};
 ///
 /// We add other restriction on top of these formats when saving/loading data.
 /// First, `Labels` instances are saved as structured array, see the `labels`
-/// module for more information. Only 32-bit integers are supported for Labels,
-/// and only 64-bit floats are supported for data (values and gradients).
+/// module for more information. Labels are saved using 64-bit integers (32-bit
+/// integers are also supported when loading older files). Data (values and
+/// gradients) can contain 64-bit, 32-bit or 16-bit floating point values
+/// (`<f8`, `<f4` and `<f2` in numpy notation), as well as 32-bit or 64-bit
+/// signed integers (`<i4` and `<i8`).
 ///
 /// Second, the path of the files in the archive also carry meaning. The keys of
 /// the `TensorMap` are stored in `/keys.npy`, and then different blocks are
@@ -50,17 +58,29 @@ use self::labels::{read_npy_labels, write_npy_labels};
 ///                                                                     / <n_components>.npy
 ///                                                     /   data.npy
 /// ```
+///
+/// Finally, the tensor-level metadata (if any) is stored in `/metadata.npy`,
+/// and the info of the values or gradients of each block (if any) in
+/// `/blocks/<block_id>/values/info.npy` and
+/// `/blocks/<block_id>/gradients/<parameter>/info.npy` respectively. See the
+/// `metadata` module for more information on how these are stored. All these
+/// files are optional, and are only saved when the corresponding metadata is
+/// not empty.
 pub fn load<R, F>(reader: R, create_array: F) -> Result<TensorMap, Error>
     where R: std::io::Read + std::io::Seek,
-          F: Fn(Vec<usize>) -> Result<eqs_array_t, Error>
+          F: Fn(Vec<usize>, eqs_dtype_t) -> Result<eqs_array_t, Error>
 {
     let mut archive = ZipArchive::new(reader).map_err(|e| ("<root>".into(), e))?;
 
     let path = String::from("keys.npy");
     let keys = read_npy_labels(archive.by_name(&path).map_err(|e| (path, e))?)?;
 
+    // info and metadata were added after the initial version of this format,
+    // so older files do not contain them
+    let file_names = archive.file_names().map(String::from).collect::<BTreeSet<_>>();
+
     let mut parameters = Vec::new();
-    for name in archive.file_names() {
+    for name in &file_names {
         if name.starts_with("blocks/0/gradients/") && name.ends_with("/data.npy") {
             let (_, parameter) = name.split_at(19);
             let (parameter, _) = parameter.split_at(parameter.len() - 9);
@@ -91,6 +111,12 @@ pub fn load<R, F>(reader: R, create_array: F) -> Result<TensorMap, Error>
 
         let mut block = TensorBlock::new(data, samples, components, properties)?;
 
+        let path = format!("blocks/{}/values/info.npy", block_i);
+        if file_names.contains(&*path) {
+            let info_file = archive.by_name(&path).map_err(|e| (path, e))?;
+            block.values_mut().info = read_npy_metadata(info_file)?;
+        }
+
         for parameter in &parameters {
             let path = format!("blocks/{}/gradients/{}/data.npy", block_i, parameter);
             let data_file = archive.by_name(&path).map_err(|e| (path, e))?;
@@ -108,12 +134,26 @@ pub fn load<R, F>(reader: R, create_array: F) -> Result<TensorMap, Error>
             }
 
             block.add_gradient(parameter, data, samples, components)?;
+
+            let path = format!("blocks/{}/gradients/{}/info.npy", block_i, parameter);
+            if file_names.contains(&*path) {
+                let info_file = archive.by_name(&path).map_err(|e| (path, e))?;
+                block.gradient_mut(parameter).expect("missing gradient").info = read_npy_metadata(info_file)?;
+            }
         }
 
         blocks.push(block);
     }
 
-    return TensorMap::new(keys, blocks);
+    let mut tensor = TensorMap::new(keys, blocks)?;
+
+    let path = String::from("metadata.npy");
+    if file_names.contains(&path) {
+        let metadata_file = archive.by_name(&path).map_err(|e| (path, e))?;
+        *tensor.metadata_mut() = read_npy_metadata(metadata_file)?;
+    }
+
+    return Ok(tensor);
 }
 
 
@@ -151,6 +191,12 @@ pub fn save<W: std::io::Write + std::io::Seek>(writer: W, tensor: &TensorMap) ->
         archive.start_file(&path, options).map_err(|e| (path, e))?;
         write_npy_labels(&mut archive, &block.values().properties)?;
 
+        if !block.values().info.is_empty() {
+            let path = format!("blocks/{}/values/info.npy", block_i);
+            archive.start_file(&path, options).map_err(|e| (path, e))?;
+            write_npy_metadata(&mut archive, &block.values().info)?;
+        }
+
         for (parameter, gradient) in block.gradients() {
             let path = format!("blocks/{}/gradients/{}/data.npy", block_i, parameter);
             archive.start_file(&path, options).map_err(|e| (path, e))?;
@@ -165,9 +211,21 @@ pub fn save<W: std::io::Write + std::io::Seek>(writer: W, tensor: &TensorMap) ->
                 archive.start_file(&path, options).map_err(|e| (path, e))?;
                 write_npy_labels(&mut archive, component)?;
             }
+
+            if !gradient.info.is_empty() {
+                let path = format!("blocks/{}/gradients/{}/info.npy", block_i, parameter);
+                archive.start_file(&path, options).map_err(|e| (path, e))?;
+                write_npy_metadata(&mut archive, &gradient.info)?;
+            }
         }
     }
 
+    if !tensor.metadata().is_empty() {
+        let path = String::from("metadata.npy");
+        archive.start_file(&path, options).map_err(|e| (path, e))?;
+        write_npy_metadata(&mut archive, tensor.metadata())?;
+    }
+
     archive.finish().map_err(|e| ("<root>".into(), e))?;
 
     return Ok(());
@@ -175,28 +233,29 @@ pub fn save<W: std::io::Write + std::io::Seek>(writer: W, tensor: &TensorMap) ->
 
 // Read a data array from the given reader, using numpy's NPY format
 fn read_data<R, F>(mut reader: R, create_array: &F) -> Result<(eqs_array_t, Vec<usize>), Error>
-    where R: std::io::Read, F: Fn(Vec<usize>) -> Result<eqs_array_t, Error>
+    where R: std::io::Read, F: Fn(Vec<usize>, eqs_dtype_t) -> Result<eqs_array_t, Error>
 {
     let header = Header::from_reader(&mut reader)?;
     if header.fortran_order {
         return Err(Error::Serialization("data can not be loaded from fortran-order arrays".into()));
     }
 
+    let (dtype, little_endian) = dtype_from_descriptor(&header.type_descriptor)?;
+
     let shape = header.shape;
-    let mut array = create_array(shape.clone())?;
+    let mut array = create_array(shape.clone(), dtype)?;
+    if array.dtype()? != dtype {
+        return Err(Error::InvalidParameter(format!(
+            "create_array returned an array containing {} data, expected {}",
+            array.dtype()?, dtype
+        )));
+    }
 
-    match header.type_descriptor {
-        PyValue::String(s) if s == "<f8" => {
-            reader.read_f64_into::<LittleEndian>(array.data_mut()?)?;
-        }
-        PyValue::String(s) if s == ">f8" => {
-            reader.read_f64_into::<BigEndian>(array.data_mut()?)?;
-        }
-        _ => {
-            return Err(Error::Serialization(format!(
-                "unknown type for data array, expected 64-bit floating points, got {}",
-                header.type_descriptor
-            )));
+    let data = array.data_bytes_mut()?;
+    reader.read_exact(data)?;
+    if little_endian != cfg!(target_endian = "little") {
+        for value in data.chunks_mut(dtype.size()) {
+            value.reverse();
         }
     }
 
@@ -205,6 +264,36 @@ fn read_data<R, F>(mut reader: R, create_array: &F) -> Result<(eqs_array_t, Vec<
     return Ok((array, shape));
 }
 
+// Get the data type and endianness (`true` for little-endian) corresponding
+// to the given numpy type descriptor
+fn dtype_from_descriptor(descriptor: &PyValue) -> Result<(eqs_dtype_t, bool), Error> {
+    if let PyValue::String(descriptor) = descriptor {
+        let little_endian = match descriptor.chars().next() {
+            Some('<') => Some(true),
+            Some('>') => Some(false),
+            _ => None,
+        };
+
+        let dtype = match descriptor.get(1..) {
+            Some("f8") => Some(EQS_DTYPE_FLOAT64),
+            Some("f4") => Some(EQS_DTYPE_FLOAT32),
+            Some("f2") => Some(EQS_DTYPE_FLOAT16),
+            Some("i4") => Some(EQS_DTYPE_INT32),
+            Some("i8") => Some(EQS_DTYPE_INT64),
+            _ => None,
+        };
+
+        if let (Some(dtype), Some(little_endian)) = (dtype, little_endian) {
+            return Ok((eqs_dtype_t(dtype), little_endian));
+        }
+    }
+
+    return Err(Error::Serialization(format!(
+        "unknown type for data array, expected 64-bit, 32-bit or 16-bit floating \
+        points or 32-bit or 64-bit integers, got {}", descriptor
+    )));
+}
+
 // returns an error if the given reader contains any more data
 fn check_for_extra_bytes<R: std::io::Read>(reader: &mut R) -> Result<(), Error> {
     let extra = reader.read_to_end(&mut Vec::new())?;
@@ -217,23 +306,30 @@ fn check_for_extra_bytes<R: std::io::Read>(reader: &mut R) -> Result<(), Error>
 
 // Write an array to the given writer, using numpy's NPY format
 fn write_data<W: std::io::Write>(writer: &mut W, array: &eqs_array_t) -> Result<(), Error> {
-    let type_descriptor = if cfg!(target_endian = "little") {
-        "'<f8'"
+    let dtype = array.dtype()?;
+    let type_descriptor = match dtype.0 {
+        EQS_DTYPE_FLOAT64 => "f8",
+        EQS_DTYPE_FLOAT32 => "f4",
+        EQS_DTYPE_FLOAT16 => "f2",
+        EQS_DTYPE_INT32 => "i4",
+        EQS_DTYPE_INT64 => "i8",
+        _ => unreachable!("invalid data type"),
+    };
+
+    let endianness = if cfg!(target_endian = "little") {
+        "<"
     } else {
-        "'>f8'"
+        ">"
     };
 
     let header = Header {
-        type_descriptor: type_descriptor.parse().expect("invalid dtype"),
+        type_descriptor: format!("'{}{}'", endianness, type_descriptor).parse().expect("invalid dtype"),
         fortran_order: false,
         shape: array.shape()?.to_vec(),
     };
 
     header.write(&mut *writer)?;
-
-    for &value in array.data()? {
-        writer.write_f64::<NativeEndian>(value)?;
-    }
+    writer.write_all(&array.host_data_bytes()?)?;
 
     return Ok(());
 }
@@ -1,27 +1,40 @@
 #![allow(clippy::default_trait_access, clippy::module_name_repetitions)]
 
 use std::ffi::CString;
-use std::collections::{BTreeSet, HashMap};
-use std::collections::hash_map::Entry;
+use std::collections::BTreeSet;
+use std::hash::{BuildHasher, Hash, Hasher};
 
+use hashbrown::raw::RawTable;
 use smallvec::SmallVec;
 
 use crate::Error;
 use crate::utils::ConstCString;
 
-/// A single value inside a label. This is represented as a 32-bit signed
+/// A single value inside a label. This is represented as a 64-bit signed
 /// integer, with a couple of helper function to get its value as usize/isize.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
-pub struct LabelValue(i32);
+pub struct LabelValue(i64);
 
 impl PartialEq<i32> for LabelValue {
     fn eq(&self, other: &i32) -> bool {
-        self.0 == *other
+        self.0 == i64::from(*other)
     }
 }
 
 impl PartialEq<LabelValue> for i32 {
+    fn eq(&self, other: &LabelValue) -> bool {
+        i64::from(*self) == other.0
+    }
+}
+
+impl PartialEq<i64> for LabelValue {
+    fn eq(&self, other: &i64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<LabelValue> for i64 {
     fn eq(&self, other: &LabelValue) -> bool {
         *self == other.0
     }
@@ -39,56 +52,71 @@ impl std::fmt::Display for LabelValue {
     }
 }
 
-#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
 impl From<u32> for LabelValue {
     fn from(value: u32) -> LabelValue {
-        assert!(value < i32::MAX as u32);
-        LabelValue(value as i32)
+        LabelValue(i64::from(value))
     }
 }
 
 impl From<i32> for LabelValue {
     fn from(value: i32) -> LabelValue {
+        LabelValue(i64::from(value))
+    }
+}
+
+impl From<u64> for LabelValue {
+    fn from(value: u64) -> LabelValue {
+        LabelValue(i64::try_from(value).expect("label value does not fit in a 64-bit integer"))
+    }
+}
+
+impl From<i64> for LabelValue {
+    fn from(value: i64) -> LabelValue {
         LabelValue(value)
     }
 }
 
-#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
 impl From<usize> for LabelValue {
     fn from(value: usize) -> LabelValue {
-        assert!(value < i32::MAX as usize);
-        LabelValue(value as i32)
+        LabelValue(i64::try_from(value).expect("label value does not fit in a 64-bit integer"))
     }
 }
 
-#[allow(clippy::cast_possible_truncation)]
 impl From<isize> for LabelValue {
     fn from(value: isize) -> LabelValue {
-        assert!(value < i32::MAX as isize && value > i32::MIN as isize);
-        LabelValue(value as i32)
+        LabelValue(value as i64)
     }
 }
 
 impl LabelValue {
     /// Create a `LabelValue` with the given `value`
-    pub fn new(value: i32) -> LabelValue {
+    pub fn new(value: i64) -> LabelValue {
         LabelValue(value)
     }
 
     /// Get the integer value of this `LabelValue` as a usize
-    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
     pub fn usize(self) -> usize {
         debug_assert!(self.0 >= 0);
         self.0 as usize
     }
 
     /// Get the integer value of this `LabelValue` as an isize
+    #[allow(clippy::cast_possible_truncation)]
     pub fn isize(self) -> isize {
         self.0 as isize
     }
 
-    /// Get the integer value of this `LabelValue` as an i32
+    /// Get the integer value of this `LabelValue` as an i32.
+    ///
+    /// This function panics if the value does not fit in 32 bits, use
+    /// [`LabelValue::i64`] to get the full value.
     pub fn i32(self) -> i32 {
+        i32::try_from(self.0).expect("label value does not fit in a 32-bit integer")
+    }
+
+    /// Get the integer value of this `LabelValue` as an i64
+    pub fn i64(self) -> i64 {
         self.0
     }
 }
@@ -98,7 +126,7 @@ pub struct LabelsBuilder {
     // cf `Labels` for the documentation of the fields
     names: Vec<String>,
     values: Vec<LabelValue>,
-    positions: HashMap<SmallVec<[LabelValue; 4]>, usize, ahash::RandomState>,
+    positions: Positions,
 }
 
 impl LabelsBuilder {
@@ -114,14 +142,14 @@ impl LabelsBuilder {
         LabelsBuilder {
             names: names.into_iter().map(|s| s.into()).collect(),
             values: Vec::new(),
-            positions: Default::default(),
+            positions: Positions::default(),
         }
     }
 
     /// Reserve space for `additional` other entries in the labels.
     pub fn reserve(&mut self, additional: usize) {
         self.values.reserve(additional * self.names.len());
-        self.positions.reserve(additional);
+        self.positions.reserve(additional, &self.values, self.names.len());
     }
 
     /// Get the number of labels in a single value
@@ -140,26 +168,31 @@ impl LabelsBuilder {
             entry.len(), self.size()
         );
 
-        let entry = entry.iter().copied().map(Into::into).collect::<SmallVec<_>>();
-        self.values.extend(&entry);
-
-        let new_position = self.positions.len();
-        match self.positions.entry(entry) {
-            Entry::Occupied(entry) => {
-                let values_display = entry.key().iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
-                return Err(Error::InvalidParameter(format!(
-                    "can not have the same label value multiple time: [{}] is already present at position {}",
-                    values_display, entry.get()
-                )));
-            },
-            Entry::Vacant(entry) => {
-                entry.insert(new_position);
-            }
+        let entry = entry.iter().copied().map(Into::into).collect::<SmallVec<[LabelValue; 4]>>();
+        if let Some(position) = self.positions.get(&self.values, self.size(), &entry) {
+            let values_display = entry.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+            return Err(Error::InvalidParameter(format!(
+                "can not have the same label value multiple time: [{}] is already present at position {}",
+                values_display, position
+            )));
         }
 
+        self.values.extend(&entry);
+        self.positions.push(&self.values, self.size());
+
         Ok(())
     }
 
+    /// Get the number of entries already added to this builder
+    fn count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Check whether the given `entry` was already added to this builder
+    fn contains(&self, entry: &[LabelValue]) -> bool {
+        self.positions.get(&self.values, self.size(), entry).is_some()
+    }
+
     /// Finish building the `Labels`
     pub fn finish(self) -> Labels {
         if self.names.is_empty() {
@@ -167,7 +200,7 @@ impl LabelsBuilder {
             return Labels {
                 names: Vec::new(),
                 values: Vec::new(),
-                positions: Default::default(),
+                positions: Positions::default(),
             }
         }
 
@@ -211,7 +244,7 @@ pub fn is_valid_label_name(name: &str) -> bool {
 /// often (but not always) sorted in  lexicographic order.
 ///
 /// The main way to construct a new set of labels is to use a `LabelsBuilder`.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct Labels {
     /// Names of the labels, stored as const C strings for easier integration
     /// with the C API
@@ -219,12 +252,20 @@ pub struct Labels {
     /// Values of the labels, as a linearized 2D array in row-major order
     values: Vec<LabelValue>,
     /// Store the position of all the known labels, for faster access later.
-    /// This uses `XxHash64` instead of the default hasher in std since
-    /// `XxHash64` is much faster and we don't need the cryptographic strength
-    /// hash from std.
-    positions: HashMap<SmallVec<[LabelValue; 4]>, usize, ahash::RandomState>,
+    /// This uses binary search for sorted labels, and a hash table containing
+    /// indexes into `values` otherwise, so the entries are never stored twice
+    /// in memory.
+    positions: Positions,
+}
+
+impl PartialEq for Labels {
+    fn eq(&self, other: &Labels) -> bool {
+        self.names == other.names && self.values == other.values
+    }
 }
 
+impl Eq for Labels {}
+
 impl std::fmt::Debug for Labels {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Labels{{")?;
@@ -277,7 +318,11 @@ impl Labels {
 
     /// Check whether the given `label` is part of this set of labels
     pub fn contains(&self, label: &[LabelValue]) -> bool {
-        self.positions.contains_key(label)
+        if label.len() != self.size() {
+            return false;
+        }
+
+        self.positions.get(&self.values, self.size(), label).is_some()
     }
 
     /// Get the position (i.e. row index) of the given label in the full labels
@@ -285,7 +330,41 @@ impl Labels {
     pub fn position(&self, value: &[LabelValue]) -> Option<usize> {
         assert!(value.len() == self.size(), "invalid size of index in Labels::position");
 
-        self.positions.get(value).copied()
+        self.positions.get(&self.values, self.size(), value)
+    }
+
+    /// Get the positions of multiple entries in these labels at once, or None
+    /// for the entries which are not part of these labels.
+    ///
+    /// `entries` should contain the values of all the entries to look for, as
+    /// a row-major 2D array with `self.size()` columns.
+    pub fn positions(&self, entries: &[LabelValue]) -> Vec<Option<usize>> {
+        if self.size() == 0 {
+            assert!(entries.is_empty(), "invalid size of entries in Labels::positions");
+            return Vec::new();
+        }
+
+        assert!(entries.len() % self.size() == 0, "invalid size of entries in Labels::positions");
+        return entries.chunks_exact(self.size())
+            .map(|entry| self.positions.get(&self.values, self.size(), entry))
+            .collect();
+    }
+
+    /// Same as [`Labels::positions`], but looking for the entries in parallel
+    /// using multiple threads.
+    #[cfg(feature = "rayon")]
+    pub fn par_positions(&self, entries: &[LabelValue]) -> Vec<Option<usize>> {
+        use rayon::prelude::*;
+
+        if self.size() == 0 {
+            assert!(entries.is_empty(), "invalid size of entries in Labels::par_positions");
+            return Vec::new();
+        }
+
+        assert!(entries.len() % self.size() == 0, "invalid size of entries in Labels::par_positions");
+        return entries.par_chunks_exact(self.size())
+            .map(|entry| self.positions.get(&self.values, self.size(), entry))
+            .collect();
     }
 
     /// Iterate over the entries in this set of labels
@@ -297,6 +376,487 @@ impl Labels {
     }
 }
 
+impl Labels {
+    /// Check that `self` and `other` have the same names, to be used in set
+    /// operations (`operation` is used in the error message).
+    fn check_same_names(&self, other: &Labels, operation: &str) -> Result<(), Error> {
+        if self.names != other.names {
+            return Err(Error::InvalidParameter(format!(
+                "can not take the {} of these Labels, they have different names: [{}] and [{}]",
+                operation, self.names().join(", "), other.names().join(", ")
+            )));
+        }
+        return Ok(());
+    }
+
+    /// Get the union of `self` and `other`, i.e. all the entries which are in
+    /// either of them. The entries of `self` come first in the result, followed
+    /// by the entries of `other` which are not in `self`.
+    ///
+    /// This also returns the mapping from each entry of `self` (first mapping)
+    /// and `other` (second mapping) to the position of the same entry in the
+    /// union.
+    pub fn union(&self, other: &Labels) -> Result<(Labels, Vec<usize>, Vec<usize>), Error> {
+        self.check_same_names(other, "union")?;
+
+        let mut builder = LabelsBuilder::new(self.names());
+        builder.reserve(self.count() + other.count());
+
+        let mut first_mapping = Vec::with_capacity(self.count());
+        for entry in self {
+            first_mapping.push(builder.count());
+            builder.add(entry)?;
+        }
+
+        let mut second_mapping = Vec::with_capacity(other.count());
+        for entry in other {
+            if let Some(position) = self.position(entry) {
+                second_mapping.push(position);
+            } else {
+                second_mapping.push(builder.count());
+                builder.add(entry)?;
+            }
+        }
+
+        return Ok((builder.finish(), first_mapping, second_mapping));
+    }
+
+    /// Get the intersection of `self` and `other`, i.e. all the entries which
+    /// are in both of them, in the same order as in `self`.
+    ///
+    /// This also returns the mapping from each entry of `self` (first mapping)
+    /// and `other` (second mapping) to the position of the same entry in the
+    /// intersection, or `None` if the entry is not part of the intersection.
+    #[allow(clippy::type_complexity)]
+    pub fn intersection(&self, other: &Labels) -> Result<(Labels, Vec<Option<usize>>, Vec<Option<usize>>), Error> {
+        self.check_same_names(other, "intersection")?;
+
+        let mut builder = LabelsBuilder::new(self.names());
+        let mut first_mapping = vec![None; self.count()];
+        let mut second_mapping = vec![None; other.count()];
+
+        for (first_i, entry) in self.iter().enumerate() {
+            if let Some(second_i) = other.position(entry) {
+                let position = builder.count();
+                first_mapping[first_i] = Some(position);
+                second_mapping[second_i] = Some(position);
+                builder.add(entry)?;
+            }
+        }
+
+        return Ok((builder.finish(), first_mapping, second_mapping));
+    }
+
+    /// Get the difference of `self` and `other`, i.e. all the entries in
+    /// `self` which are not in `other`, in the same order as in `self`.
+    ///
+    /// This also returns the mapping from each entry of `self` to the position
+    /// of the same entry in the difference, or `None` if the entry is not part
+    /// of the difference.
+    pub fn difference(&self, other: &Labels) -> Result<(Labels, Vec<Option<usize>>), Error> {
+        self.check_same_names(other, "difference")?;
+
+        let mut builder = LabelsBuilder::new(self.names());
+        let mut mapping = vec![None; self.count()];
+
+        for (i, entry) in self.iter().enumerate() {
+            if !other.contains(entry) {
+                mapping[i] = Some(builder.count());
+                builder.add(entry)?;
+            }
+        }
+
+        return Ok((builder.finish(), mapping));
+    }
+
+    /// Check whether the entries in these labels are sorted in lexicographic
+    /// order.
+    ///
+    /// This information is tracked while building the labels, so this function
+    /// does not need to look at the entries.
+    pub fn is_sorted(&self) -> bool {
+        self.positions.is_sorted()
+    }
+
+    /// Sort the entries in these labels in lexicographic order.
+    ///
+    /// This returns the sorted labels, and the permutation used to sort them:
+    /// the entry at position `i` in the sorted labels was at position
+    /// `permutation[i]` in the original labels.
+    pub fn sort(&self) -> Result<(Labels, Vec<usize>), Error> {
+        let mut permutation = (0..self.count()).collect::<Vec<_>>();
+        if self.size() == 0 {
+            return Ok((self.clone(), permutation));
+        }
+
+        permutation.sort_unstable_by(|&i, &j| self[i].cmp(&self[j]));
+
+        let mut builder = LabelsBuilder::new(self.names());
+        builder.reserve(self.count());
+        for &i in &permutation {
+            builder.add(&self[i])?;
+        }
+
+        return Ok((builder.finish(), permutation));
+    }
+
+    /// Get the positions of all the entries in these labels matching the
+    /// `selection`.
+    ///
+    /// The `selection` must contain a subset of the names of these labels, and
+    /// an entry is selected if the values of these variables match any of the
+    /// entries in the `selection`. If the `selection` does not contain any
+    /// variable, all entries are selected. The positions are returned in
+    /// increasing order.
+    pub fn select(&self, selection: &Labels) -> Result<Vec<usize>, Error> {
+        if selection.size() == 0 {
+            return Ok((0..self.count()).collect());
+        }
+
+        let names = self.names();
+        let mut variables = Vec::with_capacity(selection.size());
+        for name in selection.names() {
+            let i = names.iter().position(|&n| n == name).ok_or_else(|| Error::InvalidParameter(format!(
+                "'{}' is not part of these labels", name
+            )))?;
+            variables.push(i);
+        }
+
+        if selection.is_empty() || self.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut selected = Vec::new();
+        let mut candidate = SmallVec::<[LabelValue; 4]>::with_capacity(variables.len());
+        for (entry_i, entry) in self.iter().enumerate() {
+            candidate.clear();
+            candidate.extend(variables.iter().map(|&i| entry[i]));
+
+            if selection.contains(&candidate) {
+                selected.push(entry_i);
+            }
+        }
+
+        return Ok(selected);
+    }
+}
+
+impl Labels {
+    /// Get the position of the variable with the given `name` in these labels
+    fn variable_index(&self, name: &str) -> Result<usize, Error> {
+        return self.names.iter().position(|n| n.as_str() == name).ok_or_else(|| Error::InvalidParameter(format!(
+            "'{}' is not part of these labels", name
+        )));
+    }
+
+    /// Get the positions of the variables with the given `names`, checking
+    /// that they are all part of these labels and not duplicated.
+    fn variables_indexes(&self, names: &[&str]) -> Result<Vec<usize>, Error> {
+        let mut indexes = Vec::with_capacity(names.len());
+        for name in names {
+            let i = self.variable_index(name)?;
+            if indexes.contains(&i) {
+                return Err(Error::InvalidParameter(format!(
+                    "'{}' is used multiple times", name
+                )));
+            }
+            indexes.push(i);
+        }
+        return Ok(indexes);
+    }
+
+    /// Project these labels onto the variables with the given `names`, i.e.
+    /// only keep these variables (in the given order), removing duplicated
+    /// entries. The remaining entries are kept in the order of their first
+    /// appearance.
+    pub fn project(&self, names: &[&str]) -> Result<Labels, Error> {
+        let indexes = self.variables_indexes(names)?;
+
+        let mut builder = LabelsBuilder::new(names.to_vec());
+        if indexes.is_empty() {
+            return Ok(builder.finish());
+        }
+
+        let mut projected = SmallVec::<[LabelValue; 4]>::with_capacity(indexes.len());
+        for entry in self {
+            projected.clear();
+            projected.extend(indexes.iter().map(|&i| entry[i]));
+
+            if !builder.contains(&projected) {
+                builder.add(&projected)?;
+            }
+        }
+
+        return Ok(builder.finish());
+    }
+
+    /// Insert a new variable with the given `name` at position `index` in
+    /// these labels.
+    ///
+    /// `values` must either contain a single value, used for all entries, or
+    /// one value for each entry in these labels.
+    pub fn insert(&self, index: usize, name: &str, values: &[LabelValue]) -> Result<Labels, Error> {
+        if !is_valid_label_name(name) {
+            return Err(Error::InvalidParameter(format!(
+                "'{}' is not a valid label name", name
+            )));
+        }
+
+        if self.names.iter().any(|n| n.as_str() == name) {
+            return Err(Error::InvalidParameter(format!(
+                "'{}' is already part of these labels", name
+            )));
+        }
+
+        if index > self.size() {
+            return Err(Error::InvalidParameter(format!(
+                "can not insert a variable at index {} in labels with {} variables",
+                index, self.size()
+            )));
+        }
+
+        if values.len() != 1 && values.len() != self.count() {
+            return Err(Error::InvalidParameter(format!(
+                "expected 1 or {} values to insert, got {}",
+                self.count(), values.len()
+            )));
+        }
+
+        let mut names = self.names();
+        names.insert(index, name);
+
+        let mut builder = LabelsBuilder::new(names);
+        builder.reserve(self.count());
+
+        let mut new_entry = Vec::with_capacity(self.size() + 1);
+        for (entry_i, entry) in self.iter().enumerate() {
+            let value = if values.len() == 1 { values[0] } else { values[entry_i] };
+
+            new_entry.clear();
+            new_entry.extend_from_slice(&entry[..index]);
+            new_entry.push(value);
+            new_entry.extend_from_slice(&entry[index..]);
+            builder.add(&new_entry)?;
+        }
+
+        return Ok(builder.finish());
+    }
+
+    /// Remove the variable with the given `name` from these labels.
+    ///
+    /// This returns an error if removing the variable would create duplicated
+    /// entries, use [`Labels::project`] to remove the duplicates instead.
+    pub fn remove(&self, name: &str) -> Result<Labels, Error> {
+        let removed = self.variable_index(name)?;
+
+        let mut names = self.names();
+        names.remove(removed);
+
+        let mut builder = LabelsBuilder::new(names);
+        if builder.size() == 0 {
+            return Ok(builder.finish());
+        }
+
+        builder.reserve(self.count());
+        let mut new_entry = Vec::with_capacity(self.size() - 1);
+        for entry in self {
+            new_entry.clear();
+            new_entry.extend_from_slice(&entry[..removed]);
+            new_entry.extend_from_slice(&entry[(removed + 1)..]);
+            builder.add(&new_entry)?;
+        }
+
+        return Ok(builder.finish());
+    }
+
+    /// Rename the variable `old_name` to `new_name` in these labels.
+    pub fn rename(&self, old_name: &str, new_name: &str) -> Result<Labels, Error> {
+        let index = self.variable_index(old_name)?;
+
+        if !is_valid_label_name(new_name) {
+            return Err(Error::InvalidParameter(format!(
+                "'{}' is not a valid label name", new_name
+            )));
+        }
+
+        if old_name != new_name && self.names.iter().any(|n| n.as_str() == new_name) {
+            return Err(Error::InvalidParameter(format!(
+                "'{}' is already part of these labels", new_name
+            )));
+        }
+
+        let mut renamed = self.clone();
+        renamed.names[index] = ConstCString::new(CString::new(new_name).expect("invalid C string"));
+
+        return Ok(renamed);
+    }
+
+    /// Reorder the variables in these labels, following the given `names`.
+    /// The `names` must contain all the variables of these labels exactly
+    /// once. The order of the entries is not modified.
+    pub fn reorder(&self, names: &[&str]) -> Result<Labels, Error> {
+        if names.len() != self.size() {
+            return Err(Error::InvalidParameter(format!(
+                "expected {} names to reorder these labels, got {}",
+                self.size(), names.len()
+            )));
+        }
+
+        let indexes = self.variables_indexes(names)?;
+
+        let mut builder = LabelsBuilder::new(names.to_vec());
+        if indexes.is_empty() {
+            return Ok(builder.finish());
+        }
+
+        builder.reserve(self.count());
+        let mut new_entry = Vec::with_capacity(indexes.len());
+        for entry in self {
+            new_entry.clear();
+            new_entry.extend(indexes.iter().map(|&i| entry[i]));
+            builder.add(&new_entry)?;
+        }
+
+        return Ok(builder.finish());
+    }
+}
+
+/// Lookup table mapping entries in some `Labels` to their position.
+///
+/// When the entries are added in lexicographic order (which is the most common
+/// case), positions are found with a binary search over the values, and no
+/// additional memory is used. Otherwise, the positions are stored in a hash
+/// table. Instead of storing a copy of each entry as the key, the table only
+/// stores the position of the entry, and uses the corresponding `values` to
+/// hash and compare entries.
+///
+/// The `values` and `size` passed to all functions must be the ones of the
+/// labels owning this lookup table.
+///
+/// This uses `ahash` instead of the default hasher in std since it is much
+/// faster and we don't need the cryptographic strength hash from std.
+#[derive(Clone)]
+struct Positions {
+    /// Number of entries in the labels
+    count: usize,
+    /// Are all the entries sorted? If this is `true`, the hash table is empty
+    /// and we use binary search instead.
+    sorted: bool,
+    hasher: ahash::RandomState,
+    table: RawTable<usize>,
+}
+
+impl Default for Positions {
+    fn default() -> Positions {
+        Positions {
+            count: 0,
+            sorted: true,
+            hasher: Default::default(),
+            table: RawTable::new(),
+        }
+    }
+}
+
+impl Positions {
+    /// Get the number of entries in this lookup table
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Check if the entries in this lookup table are sorted
+    fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    /// Compute the hash of a single entry
+    fn hash(hasher: &ahash::RandomState, entry: &[LabelValue]) -> u64 {
+        let mut hasher = hasher.build_hasher();
+        entry.hash(&mut hasher);
+        return hasher.finish();
+    }
+
+    /// Reserve space for `additional` entries in this lookup table
+    fn reserve(&mut self, additional: usize, values: &[LabelValue], size: usize) {
+        if self.sorted {
+            return;
+        }
+
+        let hasher = &self.hasher;
+        self.table.reserve(additional, |&position| {
+            Positions::hash(hasher, &values[position * size..(position + 1) * size])
+        });
+    }
+
+    /// Get the position of `entry` in `values`, if it is there
+    fn get(&self, values: &[LabelValue], size: usize, entry: &[LabelValue]) -> Option<usize> {
+        if self.sorted {
+            if self.count == 0 {
+                return None;
+            }
+
+            // fast path for entries after the last one, which is what we get
+            // when adding entries in order
+            let last = self.count - 1;
+            if &values[last * size..(last + 1) * size] < entry {
+                return None;
+            }
+
+            let mut low = 0;
+            let mut high = self.count;
+            while low < high {
+                let middle = low + (high - low) / 2;
+                match values[middle * size..(middle + 1) * size].cmp(entry) {
+                    std::cmp::Ordering::Less => low = middle + 1,
+                    std::cmp::Ordering::Greater => high = middle,
+                    std::cmp::Ordering::Equal => return Some(middle),
+                }
+            }
+
+            return None;
+        }
+
+        let hash = Positions::hash(&self.hasher, entry);
+        return self.table.get(hash, |&position| {
+            &values[position * size..(position + 1) * size] == entry
+        }).copied();
+    }
+
+    /// Add the last entry in `values` to this lookup table. The entry must not
+    /// already be in the table.
+    fn push(&mut self, values: &[LabelValue], size: usize) {
+        let position = self.count;
+
+        if self.sorted {
+            let entry = &values[position * size..(position + 1) * size];
+            if position == 0 || &values[(position - 1) * size..position * size] < entry {
+                self.count += 1;
+                return;
+            }
+
+            // this entry breaks the ordering, switch to a hash table
+            // containing all the previous entries
+            self.sorted = false;
+            self.reserve(position + 1, values, size);
+            for previous in 0..position {
+                self.insert(values, size, previous);
+            }
+        }
+
+        self.insert(values, size, position);
+        self.count += 1;
+    }
+
+    /// Insert the entry at the given `position` in `values` in the hash table
+    fn insert(&mut self, values: &[LabelValue], size: usize, position: usize) {
+        let hash = Positions::hash(&self.hasher, &values[position * size..(position + 1) * size]);
+
+        let hasher = &self.hasher;
+        self.table.insert(hash, position, |&position| {
+            Positions::hash(hasher, &values[position * size..(position + 1) * size])
+        });
+    }
+}
+
 /// iterator over `Labels` entries
 pub struct Iter<'a> {
     chunks: std::slice::ChunksExact<'a, LabelValue>,
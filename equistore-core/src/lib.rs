@@ -10,6 +10,9 @@
 
 mod utils;
 
+mod metadata;
+use self::metadata::Metadata;
+
 mod labels;
 use self::labels::{LabelsBuilder, LabelValue, Labels};
 
@@ -18,10 +21,10 @@ use self::data::{eqs_array_t, eqs_sample_mapping_t, eqs_data_origin_t};
 use self::data::{register_data_origin, get_data_origin};
 
 mod blocks;
-use self::blocks::{BasicBlock, TensorBlock};
+use self::blocks::{BasicBlock, TensorBlock, Axis};
 
 mod tensor;
-use self::tensor::TensorMap;
+use self::tensor::{TensorMap, Reduction};
 
 #[doc(hidden)]
 mod c_api;
@@ -0,0 +1,146 @@
+use std::ffi::CString;
+
+use crate::utils::ConstCString;
+use crate::Error;
+
+/// Small string metadata, stored as key/value pairs sorted by key.
+///
+/// This is used for the tensor-level metadata in `TensorMap` and the per-block
+/// info in `BasicBlock` (e.g. to record the provenance or physical units of
+/// the data). Both keys and values are stored as C-compatible strings, to be
+/// able to give access to them through the C API.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    keys: Vec<ConstCString>,
+    values: Vec<ConstCString>,
+}
+
+fn to_const_c_string(string: &str, context: &str) -> Result<ConstCString, Error> {
+    let string = CString::new(string).map_err(|_| Error::InvalidParameter(format!(
+        "{} can not contain a NULL byte, got {:?}", context, string
+    )))?;
+    return Ok(ConstCString::new(string));
+}
+
+impl Metadata {
+    fn position(&self, key: &str) -> Result<usize, usize> {
+        return self.keys.binary_search_by(|k| k.as_str().cmp(key));
+    }
+
+    /// Check if this metadata is empty
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Get the value associated with `key`, if any.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        return self.get_c(key).map(ConstCString::as_str);
+    }
+
+    /// Get the value associated with `key` as a C string, if any.
+    pub fn get_c(&self, key: &str) -> Option<&ConstCString> {
+        return self.position(key).ok().map(|i| &self.values[i]);
+    }
+
+    /// Get all the keys in this metadata as C strings, sorted alphabetically
+    pub fn keys_c(&self) -> &[ConstCString] {
+        &self.keys
+    }
+
+    /// Iterate over all the `(key, value)` pairs, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item=(&str, &str)> + '_ {
+        return self.keys.iter().zip(&self.values).map(|(k, v)| (k.as_str(), v.as_str()));
+    }
+
+    /// Set the value associated with `key` to `value`, overwriting any existing
+    /// value.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let value = to_const_c_string(value, "metadata values")?;
+
+        match self.position(key) {
+            Ok(i) => self.values[i] = value,
+            Err(i) => {
+                let key = to_const_c_string(key, "metadata keys")?;
+                self.keys.insert(i, key);
+                self.values.insert(i, value);
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Remove the value associated with `key`, returning it if it existed.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let i = self.position(key).ok()?;
+        self.keys.remove(i);
+        let value = self.values.remove(i);
+        return Some(value.as_str().to_owned());
+    }
+
+    /// Merge all the entries from `other` into this metadata. If the same key
+    /// is associated with different values in `self` and `other`, this returns
+    /// an error and the content of `self` is unspecified.
+    pub fn merge(&mut self, other: &Metadata) -> Result<(), Error> {
+        for (key, value) in other.iter() {
+            match self.get(key) {
+                None => self.set(key, value)?,
+                Some(existing) if existing == value => {},
+                Some(existing) => {
+                    return Err(Error::InvalidParameter(format!(
+                        "can not merge metadata with different values for '{}': '{}' and '{}'",
+                        key, existing, value
+                    )));
+                }
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata() {
+        let mut metadata = Metadata::default();
+        assert!(metadata.get("units").is_none());
+
+        metadata.set("units", "eV").unwrap();
+        metadata.set("cutoff", "4.5").unwrap();
+        assert_eq!(metadata.get("units"), Some("eV"));
+        assert_eq!(metadata.iter().collect::<Vec<_>>(), [("cutoff", "4.5"), ("units", "eV")]);
+
+        metadata.set("units", "kcal/mol").unwrap();
+        assert_eq!(metadata.get("units"), Some("kcal/mol"));
+        assert_eq!(metadata.keys_c().len(), 2);
+
+        assert_eq!(metadata.remove("units").as_deref(), Some("kcal/mol"));
+        assert!(metadata.remove("units").is_none());
+        assert_eq!(metadata.iter().collect::<Vec<_>>(), [("cutoff", "4.5")]);
+
+        let error = metadata.set("bad\0key", "").unwrap_err();
+        assert_eq!(error.to_string(), "invalid parameter: metadata keys can not contain a NULL byte, got \"bad\\0key\"");
+    }
+
+    #[test]
+    fn merge() {
+        let mut metadata = Metadata::default();
+        metadata.set("units", "eV").unwrap();
+
+        let mut other = Metadata::default();
+        other.set("units", "eV").unwrap();
+        other.set("cutoff", "4.5").unwrap();
+
+        metadata.merge(&other).unwrap();
+        assert_eq!(metadata.iter().collect::<Vec<_>>(), [("cutoff", "4.5"), ("units", "eV")]);
+
+        other.set("units", "Ha").unwrap();
+        let error = metadata.merge(&other).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid parameter: can not merge metadata with different values for 'units': 'eV' and 'Ha'"
+        );
+    }
+}
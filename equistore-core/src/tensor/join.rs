@@ -0,0 +1,437 @@
+use std::sync::Arc;
+use std::ops::Range;
+
+use crate::labels::{Labels, LabelsBuilder, LabelValue};
+use crate::{Error, TensorBlock, Axis};
+
+use crate::data::eqs_sample_mapping_t;
+
+use super::TensorMap;
+use super::utils::{KeyAndBlock, merge_gradient_samples, merge_info};
+
+impl TensorMap {
+    /// Join a set of `TensorMap` along the given `axis`, creating a single
+    /// `TensorMap`.
+    ///
+    /// All the tensor maps must have the same keys (potentially in a different
+    /// order), and the keys of the first tensor map are used for the result.
+    /// When joining along samples, the blocks with the same key must have the
+    /// same components and properties; and when joining along properties they
+    /// must have the same components and samples. All blocks must also have
+    /// the same set of gradients.
+    ///
+    /// Potential clashes in the joined labels are resolved with one of the
+    /// following strategies:
+    ///
+    /// 1. if all the labels have the same names and there are no duplicated
+    ///    entries, the names are kept and the entries concatenated. For
+    ///    example, `n = 0, 2, 3` and `n = 1, 4, 5` gives `n = 0, 2, 3, 1, 4, 5`;
+    /// 2. if all the labels have the same names but some entries are
+    ///    duplicated, a new `tensor` variable is added in front of the existing
+    ///    ones, containing the index of the corresponding tensor map. For
+    ///    example, `n = 0, 2, 3` and `n = 0, 2` gives `tensor, n = (0, 0), (0,
+    ///    2), (0, 3), (1, 0), (1, 2)`;
+    /// 3. if the labels have different names, they are replaced by `tensor,
+    ///    sample` or `tensor, property` (depending on `axis`), containing the
+    ///    index of the corresponding tensor map and the index of the entry in
+    ///    the initial labels. For example when joining along properties, `a =
+    ///    0, 2, 3` and `b, c = (0, 0), (1, 2)` gives `tensor, property = (0,
+    ///    0), (0, 1), (0, 2), (1, 0), (1, 1)`.
+    pub fn join(tensors: &[&TensorMap], axis: Axis) -> Result<TensorMap, Error> {
+        if tensors.is_empty() {
+            return Err(Error::InvalidParameter(
+                "provide at least one tensor map to join".into()
+            ));
+        }
+
+        let first = tensors[0];
+        for tensor in &tensors[1..] {
+            check_same_keys(first, tensor)?;
+        }
+
+        let mut new_blocks = Vec::new();
+        for (block_i, key) in first.keys.iter().enumerate() {
+            let mut blocks_to_join = vec![&first.blocks[block_i]];
+            for tensor in &tensors[1..] {
+                let position = tensor.keys.position(key).expect("missing key");
+                blocks_to_join.push(&tensor.blocks[position]);
+            }
+
+            let block = match axis {
+                Axis::Samples => join_blocks_along_samples(&blocks_to_join)?,
+                Axis::Properties => join_blocks_along_properties(&blocks_to_join)?,
+            };
+            new_blocks.push(block);
+        }
+
+        return TensorMap::new((*first.keys).clone(), new_blocks);
+    }
+}
+
+/// Check that `first` and `other` have the same set of keys, potentially in a
+/// different order.
+fn check_same_keys(first: &TensorMap, other: &TensorMap) -> Result<(), Error> {
+    if first.keys.names() != other.keys.names() {
+        return Err(Error::InvalidParameter(format!(
+            "all tensor maps must have the same keys to be joined, got keys \
+            with names [{}] and [{}]",
+            first.keys.names().join(", "),
+            other.keys.names().join(", "),
+        )));
+    }
+
+    if first.keys.count() != other.keys.count() {
+        return Err(Error::InvalidParameter(format!(
+            "all tensor maps must have the same keys to be joined, got {} \
+            and {} keys",
+            first.keys.count(),
+            other.keys.count(),
+        )));
+    }
+
+    for key in other.keys.iter() {
+        if !first.keys.contains(key) {
+            return Err(Error::InvalidParameter(
+                "all tensor maps must have the same keys to be joined, got \
+                different key values".into()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that all the `blocks` have the same components, the same gradients,
+/// and the same samples or properties labels (selected by `axis`, which is the
+/// axis along which the blocks are joined).
+fn check_blocks_can_be_joined(blocks: &[&TensorBlock], axis: Axis) -> Result<(), Error> {
+    let first = blocks[0];
+    for block in &blocks[1..] {
+        if block.values().components != first.values().components {
+            return Err(Error::InvalidParameter(
+                "can not join blocks with different components labels".into()
+            ));
+        }
+
+        match axis {
+            Axis::Samples => {
+                if block.values().properties != first.values().properties {
+                    return Err(Error::InvalidParameter(
+                        "can not join blocks along samples if they have different \
+                        property labels".into()
+                    ));
+                }
+            }
+            Axis::Properties => {
+                if block.values().samples != first.values().samples {
+                    return Err(Error::InvalidParameter(
+                        "can not join blocks along properties if they have \
+                        different sample labels".into()
+                    ));
+                }
+            }
+        }
+
+        if block.gradients().len() != first.gradients().len() {
+            return Err(Error::InvalidParameter(
+                "can not join blocks with different sets of gradients".into()
+            ));
+        }
+
+        for (parameter, first_gradient) in first.gradients() {
+            let gradient = block.gradient(parameter).ok_or_else(|| Error::InvalidParameter(format!(
+                "can not join blocks: missing gradient with respect to {} in one of the blocks",
+                parameter
+            )))?;
+
+            if gradient.components != first_gradient.components {
+                return Err(Error::InvalidParameter(format!(
+                    "can not join blocks with different components labels in \
+                    the gradients with respect to {}",
+                    parameter
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Join multiple `labels` together along the given `axis`, resolving clashes
+/// between them. This returns the new labels, as well as the position of the
+/// first entry of each of the `labels` in the new labels. All the entries from
+/// a given set of labels are kept together and in the same order in the new
+/// labels.
+fn join_labels(labels: &[&Labels], axis: Axis) -> Result<(Labels, Vec<usize>), Error> {
+    let mut offsets = Vec::new();
+    let mut total = 0;
+    for l in labels {
+        offsets.push(total);
+        total += l.count();
+    }
+
+    let first_names = labels[0].names();
+    let same_names = labels.iter().all(|l| l.names() == first_names);
+
+    if same_names {
+        let mut builder = LabelsBuilder::new(first_names.clone());
+        builder.reserve(total);
+
+        let mut unique = true;
+        'outer: for &l in labels {
+            for entry in l {
+                if builder.add(entry).is_err() {
+                    unique = false;
+                    break 'outer;
+                }
+            }
+        }
+
+        if unique {
+            return Ok((builder.finish(), offsets));
+        }
+
+        if first_names.contains(&"tensor") {
+            return Err(Error::InvalidParameter(
+                "can not join labels containing a 'tensor' variable and \
+                duplicated entries".into()
+            ));
+        }
+
+        let mut new_names = vec!["tensor"];
+        new_names.extend_from_slice(&first_names);
+
+        let mut builder = LabelsBuilder::new(new_names);
+        builder.reserve(total);
+        for (tensor_i, &l) in labels.iter().enumerate() {
+            for entry in l {
+                let mut new_entry = vec![LabelValue::from(tensor_i)];
+                new_entry.extend_from_slice(entry);
+                builder.add(&new_entry)?;
+            }
+        }
+
+        return Ok((builder.finish(), offsets));
+    }
+
+    let entry_name = match axis {
+        Axis::Samples => "sample",
+        Axis::Properties => "property",
+    };
+
+    let mut builder = LabelsBuilder::new(vec!["tensor", entry_name]);
+    builder.reserve(total);
+    for (tensor_i, l) in labels.iter().enumerate() {
+        for entry_i in 0..l.count() {
+            builder.add(&[tensor_i, entry_i])?;
+        }
+    }
+
+    return Ok((builder.finish(), offsets));
+}
+
+/// Join the given `blocks` along the sample axis.
+fn join_blocks_along_samples(blocks: &[&TensorBlock]) -> Result<TensorBlock, Error> {
+    check_blocks_can_be_joined(blocks, Axis::Samples)?;
+
+    let samples = blocks.iter().map(|b| &*b.values().samples).collect::<Vec<_>>();
+    let (new_samples, offsets) = join_labels(&samples, Axis::Samples)?;
+
+    let samples_mappings = blocks.iter().zip(offsets)
+        .map(|(block, offset)| {
+            (0..block.values().samples.count()).map(|sample_i| eqs_sample_mapping_t {
+                input: sample_i,
+                output: offset + sample_i,
+            }).collect()
+        })
+        .collect::<Vec<_>>();
+
+    let new_properties = Arc::clone(&blocks[0].values().properties);
+    let property_ranges = vec![0..new_properties.count(); blocks.len()];
+
+    return join_blocks(
+        blocks,
+        Arc::new(new_samples),
+        &samples_mappings,
+        new_properties,
+        &property_ranges,
+    );
+}
+
+/// Join the given `blocks` along the property axis.
+fn join_blocks_along_properties(blocks: &[&TensorBlock]) -> Result<TensorBlock, Error> {
+    check_blocks_can_be_joined(blocks, Axis::Properties)?;
+
+    let properties = blocks.iter().map(|b| &*b.values().properties).collect::<Vec<_>>();
+    let (new_properties, offsets) = join_labels(&properties, Axis::Properties)?;
+
+    let property_ranges = blocks.iter().zip(offsets)
+        .map(|(block, offset)| offset..(offset + block.values().properties.count()))
+        .collect::<Vec<_>>();
+
+    let new_samples = Arc::clone(&blocks[0].values().samples);
+    let samples_mapping = (0..new_samples.count())
+        .map(|sample_i| eqs_sample_mapping_t {
+            input: sample_i,
+            output: sample_i,
+        })
+        .collect::<Vec<_>>();
+    let samples_mappings = vec![samples_mapping; blocks.len()];
+
+    return join_blocks(
+        blocks,
+        new_samples,
+        &samples_mappings,
+        Arc::new(new_properties),
+        &property_ranges,
+    );
+}
+
+/// Create a new block with the given `new_samples` and `new_properties`, and
+/// move the data of all `blocks` inside it. The samples of each block are moved
+/// according to `samples_mappings`, and the properties are moved to the
+/// corresponding `property_ranges`.
+fn join_blocks(
+    blocks: &[&TensorBlock],
+    new_samples: Arc<Labels>,
+    samples_mappings: &[Vec<eqs_sample_mapping_t>],
+    new_properties: Arc<Labels>,
+    property_ranges: &[Range<usize>],
+) -> Result<TensorBlock, Error> {
+    debug_assert_eq!(blocks.len(), samples_mappings.len());
+    debug_assert_eq!(blocks.len(), property_ranges.len());
+
+    let first_block = blocks[0];
+    let new_components = first_block.values().components.to_vec();
+
+    let new_properties_count = new_properties.count();
+
+    let mut new_shape = first_block.values().data.shape()?.to_vec();
+    new_shape[0] = new_samples.count();
+    let property_axis = new_shape.len() - 1;
+    new_shape[property_axis] = new_properties_count;
+    let mut new_data = first_block.values().data.create(&new_shape)?;
+
+    for ((block, samples_mapping), property_range) in blocks.iter().zip(samples_mappings).zip(property_ranges) {
+        new_data.move_samples_from(
+            &block.values().data,
+            samples_mapping,
+            property_range.clone(),
+        )?;
+    }
+
+    let mut new_block = TensorBlock::new(
+        new_data,
+        new_samples,
+        new_components,
+        new_properties,
+    ).expect("constructed an invalid block");
+    new_block.values_mut().info = merge_info(blocks.iter().map(|block| block.values()))?;
+
+    // `merge_gradient_samples` expects keys together with the blocks, they
+    // are not used here
+    let blocks_with_keys = blocks.iter()
+        .map(|&block| (Vec::new(), block))
+        .collect::<Vec<KeyAndBlock>>();
+
+    for (parameter, first_gradient) in first_block.gradients() {
+        let new_gradient_samples = merge_gradient_samples(
+            &blocks_with_keys, parameter, samples_mappings
+        )?;
+
+        let mut new_shape = first_gradient.data.shape()?.to_vec();
+        new_shape[0] = new_gradient_samples.count();
+        let property_axis = new_shape.len() - 1;
+        new_shape[property_axis] = new_properties_count;
+
+        let mut new_gradient = first_block.values().data.create(&new_shape)?;
+        let new_components = first_gradient.components.to_vec();
+
+        for ((block, samples_mapping), property_range) in blocks.iter().zip(samples_mappings).zip(property_ranges) {
+            let gradient = block.gradient(parameter).expect("missing gradient");
+
+            let mut samples_to_move = Vec::new();
+            for (sample_i, grad_sample) in gradient.samples.iter().enumerate() {
+                // translate from the old sample id in gradients to the new ones
+                let mut grad_sample = grad_sample.to_vec();
+                let old_sample_i = grad_sample[0].usize();
+
+                let mapping = &samples_mapping[old_sample_i];
+                debug_assert_eq!(mapping.input, old_sample_i);
+                grad_sample[0] = mapping.output.into();
+
+                let new_sample_i = new_gradient_samples.position(&grad_sample).expect("missing entry in merged samples");
+                samples_to_move.push(eqs_sample_mapping_t {
+                    input: sample_i,
+                    output: new_sample_i,
+                });
+            }
+
+            new_gradient.move_samples_from(
+                &gradient.data,
+                &samples_to_move,
+                property_range.clone(),
+            )?;
+        }
+
+        new_block.add_gradient(
+            parameter, new_gradient, new_gradient_samples, new_components
+        ).expect("created invalid gradients");
+
+        new_block.gradient_mut(parameter).expect("missing gradient").info = merge_info(
+            blocks.iter().map(|block| block.gradient(parameter).expect("missing gradient"))
+        )?;
+    }
+
+    return Ok(new_block);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::utils::example_labels;
+
+    #[test]
+    fn labels() {
+        // same names, unique entries
+        let first = example_labels(vec!["n"], vec![[0], [2], [3]]);
+        let second = example_labels(vec!["n"], vec![[1], [4], [5]]);
+        let (joined, offsets) = join_labels(&[&first, &second], Axis::Properties).unwrap();
+        assert_eq!(joined.names(), ["n"]);
+        assert_eq!(offsets, [0, 3]);
+        assert_eq!(joined, *example_labels(vec!["n"], vec![[0], [2], [3], [1], [4], [5]]));
+
+        // same names, duplicated entries
+        let first = example_labels(vec!["n"], vec![[0], [2], [3]]);
+        let second = example_labels(vec!["n"], vec![[0], [2]]);
+        let (joined, offsets) = join_labels(&[&first, &second], Axis::Properties).unwrap();
+        assert_eq!(offsets, [0, 3]);
+        assert_eq!(joined, *example_labels(vec!["tensor", "n"], vec![
+            [0, 0], [0, 2], [0, 3], [1, 0], [1, 2]
+        ]));
+
+        // different names
+        let first = example_labels(vec!["a"], vec![[0], [2], [3]]);
+        let second = example_labels(vec!["b", "c"], vec![[0, 0], [1, 2]]);
+        let (joined, offsets) = join_labels(&[&first, &second], Axis::Properties).unwrap();
+        assert_eq!(offsets, [0, 3]);
+        assert_eq!(joined, *example_labels(vec!["tensor", "property"], vec![
+            [0, 0], [0, 1], [0, 2], [1, 0], [1, 1]
+        ]));
+
+        let (joined, _) = join_labels(&[&first, &second], Axis::Samples).unwrap();
+        assert_eq!(joined, *example_labels(vec!["tensor", "sample"], vec![
+            [0, 0], [0, 1], [0, 2], [1, 0], [1, 1]
+        ]));
+
+        // clash with an existing `tensor` variable
+        let first = example_labels(vec!["tensor"], vec![[0], [1]]);
+        let second = example_labels(vec!["tensor"], vec![[1], [2]]);
+        let result = join_labels(&[&first, &second], Axis::Properties);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid parameter: can not join labels containing a 'tensor' \
+            variable and duplicated entries"
+        );
+    }
+}
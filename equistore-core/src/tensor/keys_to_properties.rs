@@ -8,7 +8,7 @@ use crate::{Error, TensorBlock};
 use crate::data::eqs_sample_mapping_t;
 
 use super::TensorMap;
-use super::utils::{KeyAndBlock, remove_variables_from_keys, merge_samples, merge_gradient_samples};
+use super::utils::{KeyAndBlock, remove_variables_from_keys, merge_samples, merge_gradient_samples, merge_info};
 
 
 impl TensorMap {
@@ -38,7 +38,17 @@ impl TensorMap {
     /// `sort_samples` is true, samples are re-ordered to keep them
     /// lexicographically sorted. Otherwise they are kept in the order in which
     /// they appear in the blocks.
-    pub fn keys_to_properties(&self, keys_to_move: &Labels, sort_samples: bool) -> Result<TensorMap, Error> {
+    ///
+    /// Similarly, if `sort_properties` is true, the new property labels are
+    /// re-ordered to keep them lexicographically sorted. Otherwise they are
+    /// kept in the order in which they appear in the blocks.
+    ///
+    /// This function uses `eqs_array_t.move_data` to move the data of all
+    /// arrays in this tensor map. If this function is NULL,
+    /// `eqs_array_t.move_samples_from` is used instead, which only works if
+    /// the properties of each block are moved to a contiguous range in the new
+    /// block.
+    pub fn keys_to_properties(&self, keys_to_move: &Labels, sort_samples: bool, sort_properties: bool) -> Result<TensorMap, Error> {
         let names_to_move = keys_to_move.names();
         let splitted_keys = remove_variables_from_keys(&self.keys, &names_to_move)?;
 
@@ -68,6 +78,7 @@ impl TensorMap {
                 keys_to_move,
                 &names_to_move,
                 sort_samples,
+                sort_properties,
             )?;
             new_blocks.push(block);
         } else {
@@ -94,6 +105,7 @@ impl TensorMap {
                     keys_to_move,
                     &names_to_move,
                     sort_samples,
+                    sort_properties,
                 )?;
                 new_blocks.push(block);
             }
@@ -110,6 +122,7 @@ fn merge_blocks_along_properties(
     keys_to_move: Option<&Labels>,
     extracted_names: &[&str],
     sort_samples: bool,
+    sort_properties: bool,
 ) -> Result<TensorBlock, Error> {
     assert!(!blocks_to_merge.is_empty());
 
@@ -167,6 +180,10 @@ fn merge_blocks_along_properties(
         }
     }
 
+    if sort_properties {
+        new_properties.sort_unstable();
+    }
+
     let new_property_names = extracted_names.iter()
         .chain(first_block.values().properties.names().iter())
         .copied()
@@ -187,43 +204,38 @@ fn merge_blocks_along_properties(
     new_shape[property_axis] = new_properties_count;
     let mut new_data = first_block.values().data.create(&new_shape)?;
 
-    // compute the property range for each block, i.e. where we want to put
+    // compute the property mapping for each block, i.e. where we want to put
     // the corresponding data
-    let mut property_ranges = Vec::new();
+    let mut properties_mappings = Vec::new();
     for (new_property, block) in blocks_to_merge {
         if block.values().properties.is_empty() {
             // no properties, ignore this block
-            property_ranges.push(None);
+            properties_mappings.push(None);
             continue;
         }
 
-        let mut first = new_property.clone();
-        first.extend_from_slice(&block.values().properties[0]);
-
-        // we can lookup only the `first` new property here, since the new
-        // properties match exactly the old ones, just with an added "channel"
-        // of the moved key.
+        // the position can be None if the user requested a set of values
+        // which do not include the key for the current block
+        let mapping = block.values().properties.iter()
+            .map(|old_property| {
+                let mut property = new_property.clone();
+                property.extend_from_slice(old_property);
+                new_properties.position(&property)
+            })
+            .collect::<Option<Vec<_>>>();
 
-        // start can be None is the user requested a set of values which do not
-        // include the key for the current block
-        let start = new_properties.position(&first);
-        let size = block.values().properties.count();
-        if let Some(start) = start {
-            property_ranges.push(Some(start..(start + size)));
-        } else {
-            property_ranges.push(None);
-        }
+        properties_mappings.push(mapping);
     }
 
     debug_assert_eq!(blocks_to_merge.len(), samples_mappings.len());
-    debug_assert_eq!(blocks_to_merge.len(), property_ranges.len());
+    debug_assert_eq!(blocks_to_merge.len(), properties_mappings.len());
     // for each block, gather the data to be moved & send it in one go
-    for (((_, block), samples_mapping), property_range) in blocks_to_merge.iter().zip(&samples_mappings).zip(&property_ranges) {
-        if let Some(property_range) = property_range {
-            new_data.move_samples_from(
+    for (((_, block), samples_mapping), properties_mapping) in blocks_to_merge.iter().zip(&samples_mappings).zip(&properties_mappings) {
+        if let Some(properties_mapping) = properties_mapping {
+            new_data.move_data(
                 &block.values().data,
                 samples_mapping,
-                property_range.clone()
+                properties_mapping,
             )?;
         }
     }
@@ -235,6 +247,13 @@ fn merge_blocks_along_properties(
         new_properties
     ).expect("constructed an invalid block");
 
+    // only merge the info of blocks which are part of the new block
+    let merged_blocks = blocks_to_merge.iter().zip(&properties_mappings)
+        .filter(|(_, mapping)| mapping.is_some())
+        .map(|((_, block), _)| *block)
+        .collect::<Vec<_>>();
+    new_block.values_mut().info = merge_info(merged_blocks.iter().map(|block| block.values()))?;
+
     // now collect & merge the different gradients
     for (parameter, first_gradient) in first_block.gradients() {
         let new_gradient_samples = merge_gradient_samples(
@@ -249,11 +268,11 @@ fn merge_blocks_along_properties(
         let mut new_gradient = first_block.values().data.create(&new_shape)?;
         let new_components = first_gradient.components.to_vec();
 
-        for (((_, block), samples_mapping), property_range) in blocks_to_merge.iter().zip(&samples_mappings).zip(&property_ranges) {
-            if property_range.is_none() {
+        for (((_, block), samples_mapping), properties_mapping) in blocks_to_merge.iter().zip(&samples_mappings).zip(&properties_mappings) {
+            if properties_mapping.is_none() {
                 continue;
             }
-            let property_range = property_range.as_ref().unwrap();
+            let properties_mapping = properties_mapping.as_ref().unwrap();
 
             let gradient = block.gradient(parameter).expect("missing gradient");
             debug_assert!(*gradient.components == *new_components);
@@ -274,16 +293,20 @@ fn merge_blocks_along_properties(
                     output: new_sample_i,
                 });
             }
-            new_gradient.move_samples_from(
+            new_gradient.move_data(
                 &gradient.data,
                 &samples_to_move,
-                property_range.clone(),
+                properties_mapping,
             )?;
         }
 
         new_block.add_gradient(
             parameter, new_gradient, new_gradient_samples, new_components
         ).expect("created invalid gradients");
+
+        new_block.gradient_mut(parameter).expect("missing gradient").info = merge_info(
+            merged_blocks.iter().map(|block| block.gradient(parameter).expect("missing gradient"))
+        )?;
     }
 
     return Ok(new_block);
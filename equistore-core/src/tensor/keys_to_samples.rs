@@ -1,12 +1,14 @@
 use std::sync::Arc;
 
+use indexmap::IndexSet;
+
 use crate::labels::{Labels, LabelsBuilder};
 use crate::{Error, TensorBlock};
 
 use crate::data::eqs_sample_mapping_t;
 
 use super::TensorMap;
-use super::utils::{KeyAndBlock, remove_variables_from_keys, merge_samples, merge_gradient_samples};
+use super::utils::{KeyAndBlock, remove_variables_from_keys, merge_samples, merge_gradient_samples, merge_info};
 
 impl TensorMap {
     /// Merge blocks with the same value for selected keys variables along the
@@ -16,29 +18,38 @@ impl TensorMap {
     /// the sample labels, and blocks with the same remaining keys variables
     /// will be merged together along the sample axis.
     ///
-    /// `keys_to_move` must be empty (`keys_to_move.count() == 0`), and the new
-    /// sample labels will contain entries corresponding to the merged blocks'
-    /// keys.
+    /// If `keys_to_move` does not contains any entries (`keys_to_move.count()
+    /// == 0`), then the new sample labels will contain entries corresponding
+    /// to the merged blocks' keys only. For example, merging a block with key
+    /// `a=0` and samples `s=1, 2` with a block with key `a=2` and samples `s=1,
+    /// 3` will produce a block with samples `s, a = (1, 0), (2, 0), (1, 2),
+    /// (3, 2)`.
+    ///
+    /// If `keys_to_move` contains entries, then the new sample labels will
+    /// contain all the merged samples, combined with each of the entries of
+    /// `keys_to_move`. For example, using `a=2, 3` in `keys_to_move` with the
+    /// blocks above will result in `s, a = (1, 2), (2, 2), (3, 2), (1, 3), (2,
+    /// 3), (3, 3)`. The data for samples which were not present in any of the
+    /// merged blocks is filled with zeros, and blocks with a key not in
+    /// `keys_to_move` are ignored.
     ///
-    /// The new sample labels will contains all of the merged blocks sample
-    /// labels. The order of the samples is controlled by `sort_samples`. If
+    /// The order of the samples is controlled by `sort_samples`. If
     /// `sort_samples` is true, samples are re-ordered to keep them
     /// lexicographically sorted. Otherwise they are kept in the order in which
-    /// they appear in the blocks.
+    /// they appear in the blocks (and in `keys_to_move`).
     ///
     /// This function is only implemented if all merged block have the same
     /// property labels.
     pub fn keys_to_samples(&self, keys_to_move: &Labels, sort_samples: bool) -> Result<TensorMap, Error> {
-        if keys_to_move.count() > 0 {
-            return Err(Error::InvalidParameter(
-                "user provided values for the keys to move is not yet implemented, \
-                `keys_to_move` should not contain any entry when calling keys_to_samples".into()
-            ))
-        }
-
         let names_to_move = keys_to_move.names();
         let splitted_keys = remove_variables_from_keys(&self.keys, &names_to_move)?;
 
+        let keys_to_move = if keys_to_move.count() == 0 {
+            None
+        } else {
+            Some(keys_to_move)
+        };
+
         let mut new_blocks = Vec::new();
         if splitted_keys.new_keys.count() == 1 {
             // create a single block with everything
@@ -56,6 +67,7 @@ impl TensorMap {
 
             let block = merge_blocks_along_samples(
                 &blocks_to_merge,
+                keys_to_move,
                 &names_to_move,
                 sort_samples,
             )?;
@@ -81,6 +93,7 @@ impl TensorMap {
 
                 new_blocks.push(merge_blocks_along_samples(
                     &blocks_to_merge,
+                    keys_to_move,
                     &names_to_move,
                     sort_samples,
                 )?);
@@ -92,8 +105,10 @@ impl TensorMap {
 }
 
 /// Merge the given `blocks` along the sample axis.
+#[allow(clippy::too_many_lines)]
 fn merge_blocks_along_samples(
     blocks_to_merge: &[KeyAndBlock],
+    keys_to_move: Option<&Labels>,
     extracted_names: &[&str],
     sort_samples: bool,
 ) -> Result<TensorBlock, Error> {
@@ -124,11 +139,16 @@ fn merge_blocks_along_samples(
         .chain(extracted_names.iter())
         .copied()
         .collect();
-    let (merged_samples, samples_mappings) = merge_samples(
-        blocks_to_merge,
-        new_samples_names,
-        sort_samples,
-    );
+    let (merged_samples, blocks_to_merge, samples_mappings) = if let Some(keys_to_move) = keys_to_move {
+        samples_with_user_keys(blocks_to_merge, keys_to_move, new_samples_names, sort_samples)?
+    } else {
+        let (merged_samples, samples_mappings) = merge_samples(
+            blocks_to_merge,
+            new_samples_names,
+            sort_samples,
+        );
+        (merged_samples, blocks_to_merge.to_vec(), samples_mappings)
+    };
 
     let new_components = first_block.values().components.to_vec();
     let new_properties = Arc::clone(&first_block.values().properties);
@@ -154,12 +174,16 @@ fn merge_blocks_along_samples(
         new_components,
         new_properties
     ).expect("invalid block");
+    new_block.values_mut().info = merge_info(blocks_to_merge.iter().map(|(_, block)| block.values()))?;
 
     // now collect & merge the different gradients
     for (parameter, first_gradient) in first_block.gradients() {
-        let new_gradient_samples = merge_gradient_samples(
-            blocks_to_merge, parameter, &samples_mappings
-        )?;
+        let new_gradient_samples = if blocks_to_merge.is_empty() {
+            // none of the blocks matched the user-provided keys
+            Arc::new(LabelsBuilder::new(first_gradient.samples.names()).finish())
+        } else {
+            merge_gradient_samples(&blocks_to_merge, parameter, &samples_mappings)?
+        };
 
         let mut new_shape = first_gradient.data.shape()?.to_vec();
         new_shape[0] = new_gradient_samples.count();
@@ -196,7 +220,79 @@ fn merge_blocks_along_samples(
         new_block.add_gradient(
             parameter, new_gradient, new_gradient_samples, new_components
         ).expect("created invalid gradients");
+
+        new_block.gradient_mut(parameter).expect("missing gradient").info = merge_info(
+            blocks_to_merge.iter().map(|(_, block)| block.gradient(parameter).expect("missing gradient"))
+        )?;
     }
 
     return Ok(new_block);
 }
+
+/// New samples, selected blocks, and the mapping from the samples of these
+/// blocks to the new samples
+type SamplesAndMappings<'a> = (Arc<Labels>, Vec<KeyAndBlock<'a>>, Vec<Vec<eqs_sample_mapping_t>>);
+
+/// Create the new samples when merging `blocks` with user-provided
+/// `keys_to_move`. The new samples contains all the samples of the blocks,
+/// combined with all the entries in `keys_to_move`.
+///
+/// This returns the new samples, the blocks which have a key in `keys_to_move`,
+/// and the mapping from the samples of these blocks to the new samples.
+fn samples_with_user_keys<'a>(
+    blocks: &[KeyAndBlock<'a>],
+    keys_to_move: &Labels,
+    new_sample_names: Vec<&str>,
+    sort: bool,
+) -> Result<SamplesAndMappings<'a>, Error> {
+    let mut old_samples = IndexSet::new();
+    for (_, block) in blocks {
+        for sample in block.values().samples.iter() {
+            old_samples.insert(sample);
+        }
+    }
+
+    let mut new_samples = Vec::with_capacity(old_samples.len() * keys_to_move.count());
+    for key in keys_to_move {
+        for &sample in &old_samples {
+            let mut new_sample = sample.to_vec();
+            new_sample.extend_from_slice(key);
+            new_samples.push(new_sample);
+        }
+    }
+
+    if sort {
+        new_samples.sort_unstable();
+    }
+
+    let mut builder = LabelsBuilder::new(new_sample_names);
+    builder.reserve(new_samples.len());
+    for sample in new_samples {
+        builder.add(&sample)?;
+    }
+    let new_samples = Arc::new(builder.finish());
+
+    let mut selected_blocks = Vec::new();
+    let mut samples_mappings = Vec::new();
+    for (key, block) in blocks {
+        if !keys_to_move.contains(key) {
+            continue;
+        }
+
+        let mut mapping = Vec::new();
+        for (sample_i, sample) in block.values().samples.iter().enumerate() {
+            let mut sample = sample.to_vec();
+            sample.extend_from_slice(key);
+
+            mapping.push(eqs_sample_mapping_t {
+                input: sample_i,
+                output: new_samples.position(&sample).expect("missing entry in new samples"),
+            });
+        }
+
+        selected_blocks.push((key.clone(), *block));
+        samples_mappings.push(mapping);
+    }
+
+    return Ok((new_samples, selected_blocks, samples_mappings));
+}
@@ -1,13 +1,20 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::{TensorBlock, BasicBlock};
+use crate::{TensorBlock, BasicBlock, Metadata};
 use crate::{Labels, Error};
 
 mod utils;
 
 mod keys_to_samples;
 mod keys_to_properties;
+mod join;
+mod slice;
+mod sort;
+mod to_keys;
+
+mod reduce_over_samples;
+pub use self::reduce_over_samples::Reduction;
 
 
 /// A tensor map is the main user-facing struct of this library, and can store
@@ -23,7 +30,7 @@ mod keys_to_properties;
 pub struct TensorMap {
     keys: Arc<Labels>,
     blocks: Vec<TensorBlock>,
-    // TODO: arbitrary tensor-level metadata? e.g. using `HashMap<String, String>`
+    metadata: Metadata,
 }
 
 fn check_labels_names(
@@ -89,6 +96,7 @@ impl TensorMap {
                 .map(|c| c.names())
                 .collect::<Vec<_>>();
             let properties_names = blocks[0].values().properties.names();
+            let dtype = blocks[0].values().data.dtype()?;
 
             let gradients_data = blocks[0].gradients().iter()
                 .map(|(name, gradient)| {
@@ -111,6 +119,14 @@ impl TensorMap {
                     )));
                 }
 
+                let block_dtype = block.values().data.dtype()?;
+                if block_dtype != dtype {
+                    return Err(Error::InvalidParameter(format!(
+                        "all blocks must have the same data type, got {} and {}",
+                        block_dtype, dtype,
+                    )));
+                }
+
                 if block.gradients().len() != gradients_data.len() {
                     return Err(Error::InvalidParameter(
                         "all blocks must contains the same set of gradients".into(),
@@ -141,6 +157,7 @@ impl TensorMap {
         Ok(TensorMap {
             keys: Arc::new(keys),
             blocks,
+            metadata: Metadata::default(),
         })
     }
 
@@ -159,61 +176,88 @@ impl TensorMap {
         &self.keys
     }
 
+    /// Get the tensor-level metadata of this `TensorMap`.
+    ///
+    /// This can be used to record the provenance of the data in this tensor
+    /// map (hyper-parameters, units, code version, *etc.*), and is saved
+    /// together with the data by `io::save`.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Get mutable access to the tensor-level metadata of this `TensorMap`.
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
     /// Get the index of blocks matching the given selection.
     ///
-    /// The selection must contains a single entry, defining the requested key
-    /// or keys. If the selection contains only a subset of the variables of the
-    /// keys, there can be multiple matching blocks.
+    /// The selection must contain a subset of the variables of the keys, and
+    /// can contain multiple entries. A block is selected if its key matches
+    /// any of the entries in the selection. If the selection contains only a
+    /// subset of the variables of the keys, there can be multiple matching
+    /// blocks for a single entry.
     pub fn blocks_matching(&self, selection: &Labels) -> Result<Vec<usize>, Error> {
-        if selection.size() == 0 {
-            return Ok((0..self.blocks().len()).collect());
+        let keys_names = self.keys.names();
+        for requested in selection.names() {
+            if !keys_names.contains(&requested) {
+                return Err(Error::InvalidParameter(format!(
+                    "'{}' is not part of the keys for this tensor",
+                    requested
+                )));
+            }
         }
 
-        if selection.count() != 1 {
-            return Err(Error::InvalidParameter(format!(
-                "block selection labels must contain a single row, got {}",
-                selection.count()
-            )));
-        }
+        return self.keys.select(selection);
+    }
 
-        let mut variables = Vec::new();
-        'outer: for requested in selection.names() {
-            for (i, &name) in self.keys.names().iter().enumerate() {
-                if requested == name {
-                    variables.push(i);
-                    continue 'outer;
-                }
-            }
+    /// Move the given variables from the component labels to the property labels
+    /// for each block in this `TensorMap`.
+    pub fn components_to_properties(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        let mut clone = self.clone();
 
-            return Err(Error::InvalidParameter(format!(
-                "'{}' is not part of the keys for this tensor",
-                requested
-            )));
+        if variables.is_empty() {
+            return Ok(clone);
         }
 
-        let mut matching = Vec::new();
-        let selection = selection.iter().next().expect("empty selection");
+        for block in &mut clone.blocks {
+            block.components_to_properties(variables)?;
+        }
 
-        for (block_i, labels) in self.keys.iter().enumerate() {
-            let mut selected = true;
-            for (&requested_i, &value) in variables.iter().zip(selection) {
-                if labels[requested_i] != value {
-                    selected = false;
-                    break;
-                }
-            }
+        return Ok(clone);
+    }
 
-            if selected {
-                matching.push(block_i);
-            }
+    /// Move the given variables from the component labels to the sample labels
+    /// for each block in this `TensorMap`.
+    ///
+    /// The component is appended to the samples, with the component values
+    /// varying faster than the old samples. Each gradient sample is
+    /// duplicated for all the values of the component.
+    pub fn components_to_samples(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        let mut clone = self.clone();
+
+        if variables.is_empty() {
+            return Ok(clone);
         }
 
-        return Ok(matching);
+        for block in &mut clone.blocks {
+            block.components_to_samples(variables)?;
+        }
+
+        return Ok(clone);
     }
 
-    /// Move the given variables from the component labels to the property labels
+    /// Move the given variables from the property labels to a new component
     /// for each block in this `TensorMap`.
-    pub fn components_to_properties(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+    ///
+    /// This is the inverse of [`TensorMap::components_to_properties`]. The new
+    /// component is added after the existing ones, and contains the values
+    /// taken by `variables` in the properties. The properties must contain all
+    /// the combinations of these values with the values of the remaining
+    /// property variables, with the moved variables varying either slower or
+    /// faster than the remaining ones. If all the property variables are
+    /// moved, the new properties contain a single `"_"` variable.
+    pub fn properties_to_components(&self, variables: &[&str]) -> Result<TensorMap, Error> {
         let mut clone = self.clone();
 
         if variables.is_empty() {
@@ -221,7 +265,7 @@ impl TensorMap {
         }
 
         for block in &mut clone.blocks {
-            block.components_to_properties(variables)?;
+            block.properties_to_components(variables)?;
         }
 
         return Ok(clone);
@@ -398,19 +442,26 @@ mod tests {
         );
 
         let selection = LabelsBuilder::new(vec!["key_1"]);
-        let result = tensor.blocks_matching(&selection.finish());
         assert_eq!(
-            result.unwrap_err().to_string(),
-            "invalid parameter: block selection labels must contain a single row, got 0"
+            tensor.blocks_matching(&selection.finish()).unwrap(),
+            []
         );
 
         let mut selection = LabelsBuilder::new(vec!["key_1", "key_2"]);
+        selection.add(&[3, 0]).unwrap();
         selection.add(&[3, 4]).unwrap();
-        selection.add(&[1, 2]).unwrap();
-        let result = tensor.blocks_matching(&selection.finish());
+        selection.add(&[0, 1]).unwrap();
         assert_eq!(
-            result.unwrap_err().to_string(),
-            "invalid parameter: block selection labels must contain a single row, got 2"
+            tensor.blocks_matching(&selection.finish()).unwrap(),
+            [0, 4]
+        );
+
+        let mut selection = LabelsBuilder::new(vec!["key_2"]);
+        selection.add(&[2]).unwrap();
+        selection.add(&[3]).unwrap();
+        assert_eq!(
+            tensor.blocks_matching(&selection.finish()).unwrap(),
+            [1, 3, 5]
         );
 
         let mut selection = LabelsBuilder::new(vec!["key_3"]);
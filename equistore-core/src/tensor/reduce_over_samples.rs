@@ -0,0 +1,360 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::labels::{LabelsBuilder, LabelValue};
+use crate::data::eqs_array_t;
+use crate::{Error, Labels, TensorBlock, BasicBlock};
+
+use super::TensorMap;
+
+/// Different ways to reduce multiple samples into a single one in
+/// [`TensorMap::reduce_over_samples`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    /// Sum all the samples together
+    Sum,
+    /// Compute the average of all the samples
+    Mean,
+    /// Compute the variance of all the samples
+    Variance,
+    /// Compute the standard deviation of all the samples
+    Std,
+}
+
+impl TensorMap {
+    /// Reduce the blocks in this tensor map over the sample variables in
+    /// `names`, combining together all the samples which only differ by the
+    /// value of these variables.
+    ///
+    /// The new samples contain the remaining sample variables (or a single
+    /// `"_"` variable if all variables are reduced over), and are sorted
+    /// lexicographically. Gradients samples are updated to refer to the new
+    /// samples, and gradients are reduced accordingly. For mean, variance and
+    /// std reductions, the gradients are averaged over the gradient rows
+    /// reduced together, ignoring the samples without gradients.
+    ///
+    /// `Reduction::Sum` only uses `eqs_array_t::scatter_add_from`, while the
+    /// other reductions also need direct access to the data through
+    /// `eqs_array_t::data`, and only support arrays containing 64-bit floating
+    /// point values.
+    pub fn reduce_over_samples(&self, names: &[&str], reduction: Reduction) -> Result<TensorMap, Error> {
+        let sample_names = if let Some(block) = self.blocks.first() {
+            block.values().samples.names()
+        } else {
+            Vec::new()
+        };
+
+        for name in names {
+            if !sample_names.contains(name) {
+                return Err(Error::InvalidParameter(format!(
+                    "'{}' is not part of the samples for this tensor map",
+                    name
+                )));
+            }
+        }
+
+        let mut remaining_names = Vec::new();
+        let mut remaining_i = Vec::new();
+        for (i, &name) in sample_names.iter().enumerate() {
+            if !names.contains(&name) {
+                remaining_names.push(name);
+                remaining_i.push(i);
+            }
+        }
+
+        let mut new_blocks = Vec::new();
+        for block in &self.blocks {
+            new_blocks.push(reduce_block(block, &remaining_names, &remaining_i, reduction)?);
+        }
+
+        return TensorMap::new((*self.keys).clone(), new_blocks);
+    }
+}
+
+/// Get the sorted list of unique entries in `entries`, together with the
+/// position of each initial entry in this list.
+fn unique_entries(entries: &[Vec<LabelValue>]) -> (Vec<Vec<LabelValue>>, Vec<usize>) {
+    let unique = entries.iter().cloned().collect::<BTreeSet<_>>();
+    let unique = unique.into_iter().collect::<Vec<_>>();
+
+    let positions = entries.iter()
+        .map(|entry| unique.binary_search(entry).expect("missing unique entry"))
+        .collect();
+
+    return (unique, positions);
+}
+
+/// Number of elements in a single sample of `array`
+fn sample_size(array: &eqs_array_t) -> Result<usize, Error> {
+    return Ok(array.shape()?.iter().skip(1).product());
+}
+
+/// Sum the samples of `input` into a new array with `count` samples, where
+/// `positions` contains the new sample of each sample in `input`.
+fn sum_samples(
+    input: &eqs_array_t,
+    positions: &[usize],
+    count: usize,
+) -> Result<eqs_array_t, Error> {
+    let mut shape = input.shape()?.to_vec();
+    shape[0] = count;
+
+    let mut output = input.create(&shape)?;
+    output.scatter_add_from(input, 0, positions)?;
+
+    return Ok(output);
+}
+
+/// Divide each sample `i` of `array` by `counts[i]`
+#[allow(clippy::cast_precision_loss)]
+fn divide_samples(array: &mut eqs_array_t, counts: &[usize]) -> Result<(), Error> {
+    let size = sample_size(array)?;
+    if size == 0 {
+        return Ok(());
+    }
+
+    for (sample, &count) in array.data_mut::<f64>()?.chunks_mut(size).zip(counts) {
+        for value in sample {
+            *value /= count as f64;
+        }
+    }
+
+    return Ok(());
+}
+
+/// Count how many times each of the `count` possible positions appears in
+/// `positions`
+fn count_positions(positions: &[usize], count: usize) -> Vec<usize> {
+    let mut counts = vec![0; count];
+    for &position in positions {
+        counts[position] += 1;
+    }
+    return counts;
+}
+
+fn reduce_block(
+    block: &TensorBlock,
+    remaining_names: &[&str],
+    remaining_i: &[usize],
+    reduction: Reduction,
+) -> Result<TensorBlock, Error> {
+    let values = block.values();
+
+    let reduced_samples = values.samples.iter()
+        .map(|sample| remaining_i.iter().map(|&i| sample[i]).collect())
+        .collect::<Vec<_>>();
+    let (new_samples, positions) = unique_entries(&reduced_samples);
+
+    let new_samples = if remaining_names.is_empty() {
+        let mut builder = LabelsBuilder::new(vec!["_"]);
+        if !new_samples.is_empty() {
+            builder.add(&[0])?;
+        }
+        builder.finish()
+    } else {
+        let mut builder = LabelsBuilder::new(remaining_names.to_vec());
+        for entry in &new_samples {
+            builder.add(entry)?;
+        }
+        builder.finish()
+    };
+
+    let (new_values, values_mean, values_std) = reduce_values(
+        &values.data, &positions, new_samples.count(), reduction
+    )?;
+
+    let mut new_block = TensorBlock::new(
+        new_values,
+        Arc::new(new_samples),
+        values.components.to_vec(),
+        Arc::clone(&values.properties),
+    )?;
+    new_block.values_mut().info = values.info.clone();
+
+    for parameter in block.gradient_parameters_c() {
+        let parameter = parameter.as_str();
+        let gradient = block.gradient(parameter).expect("missing gradient");
+
+        let (new_gradient, new_gradient_samples) = reduce_gradient(
+            gradient, &values.data, &positions, &values_mean, &values_std, reduction
+        )?;
+
+        new_block.add_gradient(
+            parameter,
+            new_gradient,
+            Arc::new(new_gradient_samples),
+            gradient.components.to_vec(),
+        )?;
+        new_block.gradient_mut(parameter).expect("missing gradient").info = gradient.info.clone();
+    }
+
+    return Ok(new_block);
+}
+
+/// Reduce the `values` of a block, where `positions` contains the new sample
+/// of each of the `new_samples_count` samples.
+///
+/// This returns the reduced values, together with the mean of the values (for
+/// variance and std reductions) and their standard deviation (for std
+/// reductions), which are needed to reduce the gradients.
+fn reduce_values(
+    values: &eqs_array_t,
+    positions: &[usize],
+    new_samples_count: usize,
+    reduction: Reduction,
+) -> Result<(eqs_array_t, Vec<f64>, Vec<f64>), Error> {
+    let mut new_values = sum_samples(values, positions, new_samples_count)?;
+
+    let mut values_mean = Vec::new();
+    let mut values_std = Vec::new();
+    if reduction != Reduction::Sum {
+        let counts = count_positions(positions, new_samples_count);
+        divide_samples(&mut new_values, &counts)?;
+
+        if reduction == Reduction::Variance || reduction == Reduction::Std {
+            let mut squared = values.clone();
+            for value in squared.data_mut::<f64>()? {
+                *value *= *value;
+            }
+
+            let mut mean_squared = sum_samples(&squared, positions, new_samples_count)?;
+            divide_samples(&mut mean_squared, &counts)?;
+
+            values_mean = new_values.data::<f64>()?.to_vec();
+            for (value, mean_squared) in new_values.data_mut::<f64>()?.iter_mut().zip(mean_squared.data::<f64>()?) {
+                *value = mean_squared - *value * *value;
+                if reduction == Reduction::Std {
+                    *value = value.sqrt();
+                }
+            }
+
+            if reduction == Reduction::Std {
+                values_std = new_values.data::<f64>()?.to_vec();
+            }
+        }
+    }
+
+    return Ok((new_values, values_mean, values_std));
+}
+
+/// Reduce a single `gradient` of a block, where `positions` contains the new
+/// sample of each sample in the block `values`.
+///
+/// Like in the Python implementation, the mean of the gradients is taken over
+/// the gradient rows which are reduced together, and not over all the values
+/// samples. Samples without any gradient row do not contribute to the
+/// gradients.
+fn reduce_gradient(
+    gradient: &BasicBlock,
+    values: &eqs_array_t,
+    positions: &[usize],
+    values_mean: &[f64],
+    values_std: &[f64],
+    reduction: Reduction,
+) -> Result<(eqs_array_t, Labels), Error> {
+    let reduced_gradient_samples = gradient.samples.iter()
+        .map(|grad_sample| {
+            let mut grad_sample = grad_sample.to_vec();
+            grad_sample[0] = positions[grad_sample[0].usize()].into();
+            grad_sample
+        })
+        .collect::<Vec<_>>();
+    let (new_gradient_samples, gradient_positions) = unique_entries(&reduced_gradient_samples);
+
+    let mut new_gradient = sum_samples(&gradient.data, &gradient_positions, new_gradient_samples.len())?;
+
+    if reduction != Reduction::Sum {
+        let gradient_counts = count_positions(&gradient_positions, new_gradient_samples.len());
+        divide_samples(&mut new_gradient, &gradient_counts)?;
+
+        if reduction == Reduction::Variance || reduction == Reduction::Std {
+            reduce_variance_gradient(
+                &mut new_gradient,
+                gradient,
+                values,
+                &gradient_positions,
+                &new_gradient_samples,
+                &gradient_counts,
+                values_mean,
+                values_std,
+                sample_size(values)?,
+            )?;
+        }
+    }
+
+    let mut builder = LabelsBuilder::new(gradient.samples.names());
+    for entry in &new_gradient_samples {
+        builder.add(entry)?;
+    }
+
+    return Ok((new_gradient, builder.finish()));
+}
+
+/// Transform `mean_gradient` (containing the mean of the gradients `∇X`) into
+/// the gradient of the variance `2 (E[X ∇X] - E[X] E[∇X])` or of the standard
+/// deviation `(E[X ∇X] - E[X] E[∇X]) / Std(X)`, depending on whether
+/// `values_std` is empty or not.
+#[allow(clippy::too_many_arguments)]
+fn reduce_variance_gradient(
+    mean_gradient: &mut eqs_array_t,
+    gradient: &BasicBlock,
+    values: &eqs_array_t,
+    gradient_positions: &[usize],
+    new_gradient_samples: &[Vec<LabelValue>],
+    gradient_counts: &[usize],
+    values_mean: &[f64],
+    values_std: &[f64],
+    values_size: usize,
+) -> Result<(), Error> {
+    let gradient_size = sample_size(&gradient.data)?;
+    if gradient_size == 0 || values_size == 0 {
+        return Ok(());
+    }
+
+    // compute X ∇X for all gradient samples, and then E[X ∇X]
+    let values_data = values.data::<f64>()?;
+    let mut values_times_gradient = gradient.data.clone();
+    let data = values_times_gradient.data_mut::<f64>()?;
+    for (grad_row, grad_sample) in data.chunks_mut(gradient_size).zip(gradient.samples.iter()) {
+        let sample_i = grad_sample[0].usize();
+        let values_row = &values_data[sample_i * values_size..(sample_i + 1) * values_size];
+        for chunk in grad_row.chunks_mut(values_size) {
+            for (g, v) in chunk.iter_mut().zip(values_row) {
+                *g *= v;
+            }
+        }
+    }
+
+    let mut mean_values_times_gradient = sum_samples(
+        &values_times_gradient, gradient_positions, new_gradient_samples.len()
+    )?;
+    divide_samples(&mut mean_values_times_gradient, gradient_counts)?;
+
+    let mean_values_times_gradient = mean_values_times_gradient.data::<f64>()?;
+    let mean_gradient = mean_gradient.data_mut::<f64>()?;
+    let rows = mean_gradient.chunks_mut(gradient_size)
+        .zip(mean_values_times_gradient.chunks(gradient_size))
+        .zip(new_gradient_samples);
+
+    for ((grad_row, values_times_grad_row), grad_sample) in rows {
+        let sample_i = grad_sample[0].usize();
+        let range = sample_i * values_size..(sample_i + 1) * values_size;
+        let mean_row = &values_mean[range.clone()];
+
+        let chunks = grad_row.chunks_mut(values_size).zip(values_times_grad_row.chunks(values_size));
+        for (grad_chunk, values_times_grad_chunk) in chunks {
+            for (i, (g, vg)) in grad_chunk.iter_mut().zip(values_times_grad_chunk).enumerate() {
+                let covariance = vg - *g * mean_row[i];
+                if values_std.is_empty() {
+                    *g = 2.0 * covariance;
+                } else {
+                    let std = values_std[range.start + i];
+                    let result = covariance / std;
+                    *g = if result.is_finite() { result } else { 0.0 };
+                }
+            }
+        }
+    }
+
+    return Ok(());
+}
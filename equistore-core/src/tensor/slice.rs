@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use crate::labels::{Labels, LabelsBuilder, LabelValue};
+use crate::{Error, TensorBlock, Axis};
+
+use super::TensorMap;
+use super::utils::gather;
+
+impl TensorMap {
+    /// Slice all the blocks in this tensor map along the given `axis`, keeping
+    /// only the samples or properties matching the `selection`.
+    ///
+    /// See [`TensorBlock::slice`] for more information. The keys of the new
+    /// tensor map are the same as the keys of this one, and blocks can end up
+    /// with zero samples or properties if none of them match the `selection`.
+    pub fn slice(&self, axis: Axis, selection: &Labels) -> Result<TensorMap, Error> {
+        let mut new_blocks = Vec::new();
+        for block in &self.blocks {
+            new_blocks.push(block.slice(axis, selection)?);
+        }
+
+        return TensorMap::new((*self.keys).clone(), new_blocks);
+    }
+
+    /// Split this tensor map into multiple tensor maps along the given `axis`,
+    /// creating one new tensor map for each of the `selections`.
+    ///
+    /// This is equivalent to calling [`TensorMap::slice`] with each of the
+    /// `selections`, which must all have the same names.
+    pub fn split(&self, axis: Axis, selections: &[Labels]) -> Result<Vec<TensorMap>, Error> {
+        if let Some(first) = selections.first() {
+            for selection in &selections[1..] {
+                if selection.names() != first.names() {
+                    return Err(Error::InvalidParameter(format!(
+                        "all selections must have the same names to split a \
+                        tensor map, got [{}] and [{}]",
+                        first.names().join(", "),
+                        selection.names().join(", "),
+                    )));
+                }
+            }
+        }
+
+        let mut tensors = Vec::with_capacity(selections.len());
+        for selection in selections {
+            tensors.push(self.slice(axis, selection)?);
+        }
+
+        return Ok(tensors);
+    }
+}
+
+impl TensorBlock {
+    /// Slice this block along the given `axis`, keeping only the samples or
+    /// properties matching the `selection`.
+    ///
+    /// The `selection` must contain a subset of the names of the samples or
+    /// properties labels, and an entry is kept if the values of these
+    /// variables match one of the entries in the `selection`. The order of
+    /// the kept entries is not modified.
+    ///
+    /// When slicing along samples, the gradient samples referring to removed
+    /// samples are removed as well, and the remaining gradient samples are
+    /// updated to refer to the new samples.
+    pub fn slice(&self, axis: Axis, selection: &Labels) -> Result<TensorBlock, Error> {
+        let values = self.values();
+
+        let (labels, axis_name) = match axis {
+            Axis::Samples => (&values.samples, "samples"),
+            Axis::Properties => (&values.properties, "properties"),
+        };
+
+        let kept = matching_entries(labels, selection, axis_name)?;
+
+        let mut builder = LabelsBuilder::new(labels.names());
+        builder.reserve(kept.len());
+        for &i in &kept {
+            builder.add(&labels[i])?;
+        }
+        let new_labels = Arc::new(builder.finish());
+
+        return match axis {
+            Axis::Samples => self.slice_samples(&kept, new_labels),
+            Axis::Properties => self.slice_properties(&kept, new_labels),
+        };
+    }
+
+    /// Keep only the samples at the given indices (in `kept`), with the
+    /// corresponding `new_samples` labels.
+    pub(super) fn slice_samples(&self, kept: &[usize], new_samples: Arc<Labels>) -> Result<TensorBlock, Error> {
+        let values = self.values();
+
+        let mut new_block = TensorBlock::new(
+            gather(&values.data, 0, kept)?,
+            new_samples,
+            values.components.to_vec(),
+            Arc::clone(&values.properties),
+        )?;
+        new_block.values_mut().info = values.info.clone();
+
+        // position of each old sample in the new samples, if it was kept
+        let mut samples_mapping = vec![None; values.samples.count()];
+        for (new_sample_i, &sample_i) in kept.iter().enumerate() {
+            samples_mapping[sample_i] = Some(new_sample_i);
+        }
+
+        for parameter in self.gradient_parameters_c() {
+            let parameter = parameter.as_str();
+            let gradient = self.gradient(parameter).expect("missing gradient");
+
+            let mut gradient_kept = Vec::new();
+            let mut builder = LabelsBuilder::new(gradient.samples.names());
+            for (grad_sample_i, grad_sample) in gradient.samples.iter().enumerate() {
+                if let Some(new_sample_i) = samples_mapping[grad_sample[0].usize()] {
+                    let mut grad_sample = grad_sample.to_vec();
+                    grad_sample[0] = LabelValue::from(new_sample_i);
+                    builder.add(&grad_sample)?;
+
+                    gradient_kept.push(grad_sample_i);
+                }
+            }
+
+            new_block.add_gradient(
+                parameter,
+                gather(&gradient.data, 0, &gradient_kept)?,
+                Arc::new(builder.finish()),
+                gradient.components.to_vec(),
+            )?;
+            new_block.gradient_mut(parameter).expect("missing gradient").info = gradient.info.clone();
+        }
+
+        return Ok(new_block);
+    }
+
+    /// Keep only the properties at the given indices (in `kept`), with the
+    /// corresponding `new_properties` labels.
+    pub(super) fn slice_properties(&self, kept: &[usize], new_properties: Arc<Labels>) -> Result<TensorBlock, Error> {
+        let values = self.values();
+
+        let mut new_block = TensorBlock::new(
+            gather(&values.data, values.components.len() + 1, kept)?,
+            Arc::clone(&values.samples),
+            values.components.to_vec(),
+            new_properties,
+        )?;
+        new_block.values_mut().info = values.info.clone();
+
+        for parameter in self.gradient_parameters_c() {
+            let parameter = parameter.as_str();
+            let gradient = self.gradient(parameter).expect("missing gradient");
+
+            new_block.add_gradient(
+                parameter,
+                gather(&gradient.data, gradient.components.len() + 1, kept)?,
+                Arc::clone(&gradient.samples),
+                gradient.components.to_vec(),
+            )?;
+            new_block.gradient_mut(parameter).expect("missing gradient").info = gradient.info.clone();
+        }
+
+        return Ok(new_block);
+    }
+}
+
+/// Get the indices of the entries in `labels` matching one of the entries in
+/// `selection`, only considering the variables present in `selection`.
+fn matching_entries(labels: &Labels, selection: &Labels, axis_name: &str) -> Result<Vec<usize>, Error> {
+    let names = labels.names();
+    for name in selection.names() {
+        if !names.contains(&name) {
+            return Err(Error::InvalidParameter(format!(
+                "'{}' is not part of the {} for this block", name, axis_name
+            )));
+        }
+    }
+
+    return labels.select(selection);
+}
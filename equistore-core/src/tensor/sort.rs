@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use crate::labels::{Labels, LabelsBuilder, LabelValue};
+use crate::{Error, TensorBlock, Axis};
+
+use super::TensorMap;
+use super::utils::gather;
+
+impl TensorMap {
+    /// Create a new `TensorMap` with the same blocks as this one, but with the
+    /// keys (and the corresponding blocks) sorted in lexicographic order.
+    pub fn sort_keys(&self) -> Result<TensorMap, Error> {
+        let (keys, permutation) = self.keys.sort()?;
+
+        let mut blocks = self.blocks.iter().map(Some).collect::<Vec<_>>();
+        let mut new_blocks = Vec::with_capacity(blocks.len());
+        for i in permutation {
+            let block = blocks[i].take().expect("permutation should not repeat entries");
+            new_blocks.push(block.clone());
+        }
+
+        let mut tensor = TensorMap::new(keys, new_blocks)?;
+        *tensor.metadata_mut() = self.metadata.clone();
+
+        return Ok(tensor);
+    }
+}
+
+impl TensorBlock {
+    /// Create a new block with the same data as this one, where the entries
+    /// along the given `axis` are sorted in lexicographic order.
+    ///
+    /// When sorting the samples, the gradient samples are updated to refer to
+    /// the new samples, and then sorted as well.
+    pub fn sort(&self, axis: Axis) -> Result<TensorBlock, Error> {
+        let values = self.values();
+
+        return match axis {
+            Axis::Samples => {
+                let (samples, permutation) = values.samples.sort()?;
+                self.sort_samples(Arc::new(samples), &permutation)
+            }
+            Axis::Properties => {
+                let (properties, permutation) = values.properties.sort()?;
+                self.slice_properties(&permutation, Arc::new(properties))
+            }
+        };
+    }
+
+    /// Sort the samples of this block following the `permutation`, which
+    /// gives the new `samples`.
+    fn sort_samples(&self, samples: Arc<Labels>, permutation: &[usize]) -> Result<TensorBlock, Error> {
+        let values = self.values();
+
+        let mut new_block = TensorBlock::new(
+            gather(&values.data, 0, permutation)?,
+            samples,
+            values.components.to_vec(),
+            Arc::clone(&values.properties),
+        )?;
+        new_block.values_mut().info = values.info.clone();
+
+        // position of each old sample in the new samples
+        let mut samples_mapping = vec![0; permutation.len()];
+        for (new_sample_i, &sample_i) in permutation.iter().enumerate() {
+            samples_mapping[sample_i] = new_sample_i;
+        }
+
+        for parameter in self.gradient_parameters_c() {
+            let parameter = parameter.as_str();
+            let gradient = self.gradient(parameter).expect("missing gradient");
+
+            let mut builder = LabelsBuilder::new(gradient.samples.names());
+            builder.reserve(gradient.samples.count());
+            for grad_sample in gradient.samples.iter() {
+                let mut grad_sample = grad_sample.to_vec();
+                grad_sample[0] = LabelValue::from(samples_mapping[grad_sample[0].usize()]);
+                builder.add(&grad_sample)?;
+            }
+            let (gradient_samples, gradient_permutation) = builder.finish().sort()?;
+
+            new_block.add_gradient(
+                parameter,
+                gather(&gradient.data, 0, &gradient_permutation)?,
+                Arc::new(gradient_samples),
+                gradient.components.to_vec(),
+            )?;
+            new_block.gradient_mut(parameter).expect("missing gradient").info = gradient.info.clone();
+        }
+
+        return Ok(new_block);
+    }
+}
+
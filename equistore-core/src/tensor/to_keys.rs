@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::labels::{Labels, LabelsBuilder, LabelValue};
+use crate::{Error, TensorBlock, Axis};
+
+use super::TensorMap;
+
+impl TensorMap {
+    /// Split the blocks in this tensor map according to the values of the
+    /// given sample `variables`, moving these variables from the samples to
+    /// the keys.
+    ///
+    /// This is the inverse operation of [`TensorMap::keys_to_samples`]. Each
+    /// block is split into one new block for each of the different values
+    /// taken by `variables` in its samples, and the corresponding key is
+    /// extended with these values. The remaining sample variables are kept in
+    /// the new blocks, or replaced by a single `"_"` variable if all sample
+    /// variables are moved. Gradients are split accordingly.
+    ///
+    /// The new blocks are ordered by the original blocks, and then by the
+    /// first appearance of the values of `variables` in the samples. Blocks
+    /// without any sample do not produce any new block.
+    pub fn samples_to_keys(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        return self.axis_to_keys(variables, Axis::Samples);
+    }
+
+    /// Split the blocks in this tensor map according to the values of the
+    /// given property `variables`, moving these variables from the properties
+    /// to the keys.
+    ///
+    /// This is the inverse operation of [`TensorMap::keys_to_properties`].
+    /// Each block is split into one new block for each of the different values
+    /// taken by `variables` in its properties, and the corresponding key is
+    /// extended with these values. The remaining property variables are kept
+    /// in the new blocks, or replaced by a single `"_"` variable if all
+    /// property variables are moved. Gradients are split accordingly.
+    ///
+    /// The new blocks are ordered by the original blocks, and then by the
+    /// first appearance of the values of `variables` in the properties. Blocks
+    /// without any property do not produce any new block.
+    pub fn properties_to_keys(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        return self.axis_to_keys(variables, Axis::Properties);
+    }
+
+    fn axis_to_keys(&self, variables: &[&str], axis: Axis) -> Result<TensorMap, Error> {
+        let keys_names = self.keys.names();
+        for variable in variables {
+            if keys_names.contains(variable) {
+                return Err(Error::InvalidParameter(format!(
+                    "'{}' is already part of the keys for this tensor map",
+                    variable
+                )));
+            }
+        }
+
+        let mut new_keys_names = keys_names.clone();
+        new_keys_names.extend_from_slice(variables);
+        let mut new_keys = LabelsBuilder::new(new_keys_names);
+
+        let mut new_blocks = Vec::new();
+        for (key, block) in self.keys.iter().zip(&self.blocks) {
+            for (moved_values, new_block) in split_block(block, variables, axis)? {
+                let mut new_key = key.to_vec();
+                new_key.extend_from_slice(&moved_values);
+                new_keys.add(&new_key)?;
+
+                new_blocks.push(new_block);
+            }
+        }
+
+        return TensorMap::new(new_keys.finish(), new_blocks);
+    }
+}
+
+/// Split a single `block` along `axis` according to the values taken by
+/// `variables`, returning these values together with the corresponding new
+/// blocks.
+fn split_block(
+    block: &TensorBlock,
+    variables: &[&str],
+    axis: Axis,
+) -> Result<Vec<(Vec<LabelValue>, TensorBlock)>, Error> {
+    let (labels, axis_name) = match axis {
+        Axis::Samples => (&block.values().samples, "samples"),
+        Axis::Properties => (&block.values().properties, "properties"),
+    };
+
+    let names = labels.names();
+    let mut moved_i = Vec::new();
+    for variable in variables {
+        let i = names.iter().position(|name| name == variable).ok_or_else(|| Error::InvalidParameter(format!(
+            "'{}' is not part of the {} for this tensor map", variable, axis_name
+        )))?;
+        moved_i.push(i);
+    }
+
+    let remaining_i = (0..names.len()).filter(|i| !moved_i.contains(i)).collect::<Vec<_>>();
+
+    // group the entries in `labels` by the values of the moved variables
+    let mut groups = IndexMap::<Vec<LabelValue>, Vec<usize>>::new();
+    for (entry_i, entry) in labels.iter().enumerate() {
+        let moved = moved_i.iter().map(|&i| entry[i]).collect();
+        groups.entry(moved).or_default().push(entry_i);
+    }
+
+    let mut new_blocks = Vec::with_capacity(groups.len());
+    for (moved_values, entries) in groups {
+        let new_labels = remaining_labels(labels, &remaining_i, &entries)?;
+
+        let new_block = match axis {
+            Axis::Samples => block.slice_samples(&entries, new_labels)?,
+            Axis::Properties => block.slice_properties(&entries, new_labels)?,
+        };
+        new_blocks.push((moved_values, new_block));
+    }
+
+    return Ok(new_blocks);
+}
+
+/// Create new labels containing only the variables at `remaining_i` for the
+/// given `entries` of `labels`. If there are no remaining variables, this
+/// creates labels with a single `"_"` variable instead.
+fn remaining_labels(labels: &Labels, remaining_i: &[usize], entries: &[usize]) -> Result<Arc<Labels>, Error> {
+    if remaining_i.is_empty() {
+        debug_assert_eq!(entries.len(), 1);
+        let mut builder = LabelsBuilder::new(vec!["_"]);
+        builder.add(&[0])?;
+        return Ok(Arc::new(builder.finish()));
+    }
+
+    let names = labels.names();
+    let mut builder = LabelsBuilder::new(remaining_i.iter().map(|&i| names[i]).collect());
+    builder.reserve(entries.len());
+    for &entry_i in entries {
+        let entry = &labels[entry_i];
+        let remaining = remaining_i.iter().map(|&i| entry[i]).collect::<Vec<_>>();
+        builder.add(&remaining)?;
+    }
+
+    return Ok(Arc::new(builder.finish()));
+}
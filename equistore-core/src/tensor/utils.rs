@@ -4,7 +4,7 @@ use std::sync::Arc;
 use indexmap::IndexSet;
 
 use crate::labels::{Labels, LabelsBuilder, LabelValue};
-use crate::{Error, TensorBlock, eqs_sample_mapping_t};
+use crate::{Error, TensorBlock, BasicBlock, Metadata, eqs_array_t, eqs_sample_mapping_t};
 
 /// single block and part of the associated key, this is used for the various
 /// `keys_to_xxx` functions
@@ -165,6 +165,28 @@ pub fn merge_samples(
     return (merged_samples, samples_mappings)
 }
 
+/// Merge the `info` of all the given basic `blocks`, returning an error if the
+/// same key is associated with different values in different blocks.
+pub fn merge_info<'a>(blocks: impl IntoIterator<Item=&'a BasicBlock>) -> Result<Metadata, Error> {
+    let mut info = Metadata::default();
+    for block in blocks {
+        info.merge(&block.info)?;
+    }
+    return Ok(info);
+}
+
+/// Create a new array containing the entries of `data` at the given `indices`
+/// along `axis`
+pub fn gather(data: &eqs_array_t, axis: usize, indices: &[usize]) -> Result<eqs_array_t, Error> {
+    let mut shape = data.shape()?.to_vec();
+    shape[axis] = indices.len();
+
+    let mut new_data = data.create(&shape)?;
+    new_data.gather_from(data, axis, indices)?;
+
+    return Ok(new_data);
+}
+
 /******************************************************************************/
 
 #[cfg(test)]
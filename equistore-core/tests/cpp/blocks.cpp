@@ -97,4 +97,35 @@ TEST_CASE("Blocks") {
             "invalid parameter: can not find gradients with respect to 'not there' in this block"
         );
     }
+
+    SECTION("info") {
+        auto block = TensorBlock(
+            std::unique_ptr<SimpleDataArray>(new SimpleDataArray({3, 2})),
+            Labels({"samples"}, {{0}, {1}, {4}}),
+            {},
+            Labels({"properties"}, {{5}, {3}})
+        );
+
+        block.add_gradient(
+            "parameter",
+            std::unique_ptr<SimpleDataArray>(new SimpleDataArray({1, 2})),
+            Labels({"sample", "parameter"}, {{0, -2}}),
+            {}
+        );
+
+        CHECK(block.info_keys().empty());
+        CHECK_THROWS_WITH(block.info("units"), "there is no info associated with 'units'");
+
+        block.set_info("units", "eV");
+        block.set_info("to_remove", "");
+        block.remove_info("to_remove");
+        CHECK(block.info_keys() == std::vector<std::string>{"units"});
+        CHECK(block.info("units") == "eV");
+
+        auto gradient = block.gradient("parameter");
+        CHECK(gradient.info_keys().empty());
+        gradient.set_info("units", "eV/A");
+        CHECK(gradient.info("units") == "eV/A");
+        CHECK(block.info("units") == "eV");
+    }
 }
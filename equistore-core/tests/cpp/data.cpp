@@ -1,4 +1,5 @@
 #include <memory>
+#include <cstring>
 
 #include <catch.hpp>
 
@@ -24,11 +25,16 @@ TEST_CASE("Data Array") {
         auto view = static_cast<SimpleDataArray*>(array.ptr)->view();
         view(1, 1, 0) = 3;
 
-        double* data_ptr = nullptr;
-        auto status = array.data(array.ptr, &data_ptr);
+        eqs_dtype_t dtype = 0;
+        auto status = array.dtype(array.ptr, &dtype);
         CHECK(status == EQS_SUCCESS);
-        CHECK(data_ptr[0] == 0);
-        CHECK(data_ptr[16] == 3);
+        CHECK(dtype == EQS_DTYPE_FLOAT64);
+
+        void* data_ptr = nullptr;
+        status = array.data(array.ptr, &data_ptr);
+        CHECK(status == EQS_SUCCESS);
+        CHECK(static_cast<double*>(data_ptr)[0] == 0);
+        CHECK(static_cast<double*>(data_ptr)[16] == 3);
     }
 
     SECTION("shape") {
@@ -69,6 +75,20 @@ TEST_CASE("Data Array") {
         CHECK(shape[3] == 4);
     }
 
+    SECTION("swap axes values") {
+        auto view = static_cast<SimpleDataArray*>(array.ptr)->view();
+        view(0, 1, 2) = 1;
+        view(1, 2, 3) = 2;
+
+        auto status = array.swap_axes(array.ptr, 0, 2);
+        CHECK(status == EQS_SUCCESS);
+
+        auto swapped = static_cast<SimpleDataArray*>(array.ptr)->view();
+        CHECK(swapped(2, 1, 0) == 1);
+        CHECK(swapped(3, 2, 1) == 2);
+        CHECK(swapped(2, 1, 1) == 0);
+    }
+
     SECTION("new arrays") {
         eqs_array_t new_array;
         std::memset(&new_array, 0, sizeof(new_array));
@@ -103,5 +123,113 @@ TEST_CASE("Data Array") {
         new_array.destroy(new_array.ptr);
     }
 
+    SECTION("gather and scatter-add") {
+        auto view = static_cast<SimpleDataArray*>(array.ptr)->view();
+        for (size_t i=0; i<3; i++) {
+            view(0, i, 0) = static_cast<double>(i + 1);
+            view(1, i, 3) = static_cast<double>(10 * (i + 1));
+        }
+
+        eqs_array_t gathered;
+        std::memset(&gathered, 0, sizeof(gathered));
+        uintptr_t shape[] = {2, 2, 4};
+        auto status = array.create(array.ptr, shape, 3, &gathered);
+        CHECK(status == EQS_SUCCESS);
+
+        uintptr_t indices[] = {2, 0};
+        status = gathered.gather_from(gathered.ptr, array.ptr, 1, indices, 2);
+        CHECK(status == EQS_SUCCESS);
+
+        auto gathered_view = static_cast<SimpleDataArray*>(gathered.ptr)->view();
+        CHECK(gathered_view(0, 0, 0) == 3);
+        CHECK(gathered_view(0, 1, 0) == 1);
+        CHECK(gathered_view(1, 0, 3) == 30);
+        CHECK(gathered_view(1, 1, 3) == 10);
+
+        eqs_array_t scattered;
+        std::memset(&scattered, 0, sizeof(scattered));
+        shape[1] = 1;
+        status = array.create(array.ptr, shape, 3, &scattered);
+        CHECK(status == EQS_SUCCESS);
+
+        uintptr_t scatter_indices[] = {0, 0, 0};
+        status = scattered.scatter_add_from(scattered.ptr, array.ptr, 1, scatter_indices, 3);
+        CHECK(status == EQS_SUCCESS);
+
+        auto scattered_view = static_cast<SimpleDataArray*>(scattered.ptr)->view();
+        CHECK(scattered_view(0, 0, 0) == 6);
+        CHECK(scattered_view(1, 0, 3) == 60);
+        CHECK(scattered_view(1, 0, 0) == 0);
+
+        gathered.destroy(gathered.ptr);
+        scattered.destroy(scattered.ptr);
+    }
+
+    SECTION("move data") {
+        auto view = static_cast<SimpleDataArray*>(array.ptr)->view();
+        for (size_t i=0; i<4; i++) {
+            view(1, 2, i) = static_cast<double>(i + 1);
+        }
+
+        eqs_array_t moved;
+        std::memset(&moved, 0, sizeof(moved));
+        uintptr_t shape[] = {3, 3, 8};
+        auto status = array.create(array.ptr, shape, 3, &moved);
+        CHECK(status == EQS_SUCCESS);
+
+        eqs_sample_mapping_t samples[] = {{1, 2}};
+        uintptr_t properties[] = {7, 0, 5, 2};
+        status = moved.move_data(moved.ptr, array.ptr, samples, 1, properties, 4);
+        CHECK(status == EQS_SUCCESS);
+
+        auto moved_view = static_cast<SimpleDataArray*>(moved.ptr)->view();
+        CHECK(moved_view(2, 2, 7) == 1);
+        CHECK(moved_view(2, 2, 0) == 2);
+        CHECK(moved_view(2, 2, 5) == 3);
+        CHECK(moved_view(2, 2, 2) == 4);
+        CHECK(moved_view(2, 2, 1) == 0);
+        CHECK(moved_view(1, 2, 7) == 0);
+
+        moved.destroy(moved.ptr);
+    }
+
+    SECTION("check") {
+        auto status = eqs_array_check(&array);
+        INFO(eqs_last_error());
+        CHECK(status == EQS_SUCCESS);
+    }
+
+    array.destroy(array.ptr);
+}
+
+
+/// SimpleDataArray where `swap_axes` does nothing, used to check that
+/// `eqs_array_check` reports invalid implementations
+class NoSwapDataArray: public SimpleDataArray {
+public:
+    NoSwapDataArray(std::vector<uintptr_t> shape): SimpleDataArray(std::move(shape)) {}
+
+    std::unique_ptr<DataArrayBase> copy() const override {
+        return std::unique_ptr<DataArrayBase>(new NoSwapDataArray(*this));
+    }
+
+    std::unique_ptr<DataArrayBase> create(std::vector<uintptr_t> shape) const override {
+        return std::unique_ptr<DataArrayBase>(new NoSwapDataArray(std::move(shape)));
+    }
+
+    void swap_axes(uintptr_t, uintptr_t) override {}
+};
+
+TEST_CASE("Invalid Data Array") {
+    auto data = std::unique_ptr<NoSwapDataArray>(new NoSwapDataArray({2, 3}));
+    auto array = DataArrayBase::to_eqs_array_t(std::move(data));
+
+    auto status = eqs_array_check(&array);
+    CHECK(status == EQS_INVALID_PARAMETER_ERROR);
+    CHECK(std::string(eqs_last_error()) ==
+        "invalid parameter: invalid implementation of eqs_array_t.swap_axes: "
+        "expected the array to have shape [4, 2, 3], got [3, 2, 4]"
+    );
+
     array.destroy(array.ptr);
 }
@@ -42,3 +42,115 @@ TEST_CASE("Labels") {
         "invalid parameter: 'not an ident' is not a valid label name"
     );
 }
+
+TEST_CASE("Positions") {
+    auto labels = Labels({"foo", "bar"}, {{1, 2}, {3, 4}, {5, 6}});
+
+    auto positions = labels.positions({3, 4, 1, 4, 5, 6, 1, 2});
+    CHECK(positions == std::vector<int64_t>{1, -1, 2, 0});
+    CHECK(labels.positions({}).empty());
+
+    positions = labels.positions({3, 4, 1, 4, 5, 6, 1, 2}, /*parallel*/ true);
+    CHECK(positions == std::vector<int64_t>{1, -1, 2, 0});
+    CHECK(labels.positions({}, /*parallel*/ true).empty());
+
+    CHECK_THROWS_WITH(
+        labels.positions({3, 4, 5}),
+        "invalid size for values in Labels::positions: expected a multiple of 2, got 3"
+    );
+
+    // labels which did not go through eqs_labels_create
+    const char* names[] = {"foo", "bar"};
+    int64_t values[] = {1, 2, 3, 4, 5, 6};
+    eqs_labels_t raw_labels;
+    std::memset(&raw_labels, 0, sizeof(raw_labels));
+    raw_labels.names = names;
+    raw_labels.values = values;
+    raw_labels.size = 2;
+    raw_labels.count = 3;
+
+    int64_t entries[] = {5, 6, 0, 0};
+    int64_t results[] = {0, 0};
+    auto status = eqs_labels_positions(raw_labels, entries, 2, false, results);
+    CHECK(status == EQS_SUCCESS);
+    CHECK(results[0] == 2);
+    CHECK(results[1] == -1);
+
+    results[0] = 0;
+    results[1] = 0;
+    status = eqs_labels_positions(raw_labels, entries, 2, true, results);
+    CHECK(status == EQS_SUCCESS);
+    CHECK(results[0] == 2);
+    CHECK(results[1] == -1);
+}
+
+TEST_CASE("Sorting") {
+    auto labels = Labels({"aa", "bb"}, {{1, 2}, {0, 3}, {1, 0}});
+    CHECK_FALSE(labels.is_sorted());
+
+    auto permutation = std::vector<int64_t>();
+    auto sorted = labels.sort(permutation);
+    CHECK(sorted == Labels({"aa", "bb"}, {{0, 3}, {1, 0}, {1, 2}}));
+    CHECK(permutation == std::vector<int64_t>{1, 2, 0});
+    CHECK(sorted.is_sorted());
+    CHECK(labels.sort() == sorted);
+}
+
+TEST_CASE("Variables manipulation") {
+    auto labels = Labels({"aa", "bb", "cc"}, {{1, 2, 3}, {1, 4, 3}});
+
+    CHECK(labels.project({"cc", "aa"}) == Labels({"cc", "aa"}, {{3, 1}}));
+    CHECK(labels.insert(0, "dd", 5) == Labels({"dd", "aa", "bb", "cc"}, {{5, 1, 2, 3}, {5, 1, 4, 3}}));
+    CHECK(labels.insert(3, "dd", {-1, -2}) == Labels({"aa", "bb", "cc", "dd"}, {{1, 2, 3, -1}, {1, 4, 3, -2}}));
+    CHECK(labels.remove("aa") == Labels({"bb", "cc"}, {{2, 3}, {4, 3}}));
+    CHECK(labels.rename("bb", "dd") == Labels({"aa", "dd", "cc"}, {{1, 2, 3}, {1, 4, 3}}));
+    CHECK(labels.reorder({"cc", "aa", "bb"}) == Labels({"cc", "aa", "bb"}, {{3, 1, 2}, {3, 1, 4}}));
+
+    CHECK_THROWS_WITH(
+        labels.remove("bb"),
+        "invalid parameter: can not have the same label value multiple time: [1, 3] is already present at position 0"
+    );
+}
+
+TEST_CASE("Selection") {
+    auto labels = Labels({"aa", "bb"}, {{1, 1}, {1, 2}, {2, 1}, {3, 2}});
+
+    CHECK(labels.select(Labels({"aa"}, {{1}})) == std::vector<uintptr_t>{0, 1});
+    CHECK(labels.select(Labels({"bb", "aa"}, {{2, 3}, {1, 2}, {1, 1}})) == std::vector<uintptr_t>{0, 2, 3});
+    CHECK(labels.select(Labels({"bb"})).empty());
+
+    CHECK_THROWS_WITH(
+        labels.select(Labels({"cc"}, {{1}})),
+        "invalid parameter: 'cc' is not part of these labels"
+    );
+}
+
+TEST_CASE("Set operations") {
+    auto first = Labels({"aa", "bb"}, {{0, 1}, {1, 2}, {4, 5}});
+    auto second = Labels({"aa", "bb"}, {{2, 3}, {1, 2}});
+
+    auto first_mapping = std::vector<int64_t>();
+    auto second_mapping = std::vector<int64_t>();
+
+    auto union_ = first.set_union(second, first_mapping, second_mapping);
+    CHECK(union_ == Labels({"aa", "bb"}, {{0, 1}, {1, 2}, {4, 5}, {2, 3}}));
+    CHECK(first_mapping == std::vector<int64_t>{0, 1, 2});
+    CHECK(second_mapping == std::vector<int64_t>{3, 1});
+    CHECK(first.set_union(second) == union_);
+
+    auto intersection = first.set_intersection(second, first_mapping, second_mapping);
+    CHECK(intersection == Labels({"aa", "bb"}, {{1, 2}}));
+    CHECK(first_mapping == std::vector<int64_t>{-1, 0, -1});
+    CHECK(second_mapping == std::vector<int64_t>{-1, 0});
+    CHECK(first.set_intersection(second) == intersection);
+
+    auto difference = first.set_difference(second, first_mapping);
+    CHECK(difference == Labels({"aa", "bb"}, {{0, 1}, {4, 5}}));
+    CHECK(first_mapping == std::vector<int64_t>{0, -1, 1});
+    CHECK(first.set_difference(second) == difference);
+
+    CHECK_THROWS_WITH(
+        first.set_union(Labels({"aa"}, {{0}})),
+        "invalid parameter: can not take the union of these Labels, they have different names: [aa, bb] and [aa]"
+    );
+}
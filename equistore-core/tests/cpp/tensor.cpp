@@ -1,3 +1,6 @@
+#include <cstdio>
+#include <cstring>
+
 #include <catch.hpp>
 
 #include <equistore.hpp>
@@ -161,6 +164,309 @@ TEST_CASE("TensorMap") {
 
         CHECK(block.properties() == Labels({"component", "properties"}, {{0, 0}}));
     }
+
+    SECTION("join") {
+        auto tensor = test_tensor_map();
+        auto joined = TensorMap::join({&tensor, &tensor}, "samples");
+
+        CHECK(joined.keys() == tensor.keys());
+
+        auto block = joined.block_by_id(0);
+        CHECK(block.samples() == Labels({"tensor", "samples"}, {
+            {0, 0}, {0, 2}, {0, 4}, {1, 0}, {1, 2}, {1, 4}
+        }));
+        CHECK(block.properties() == Labels({"properties"}, {{0}}));
+
+        auto& values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(values == SimpleDataArray({6, 1, 1}, 1.0));
+
+        joined = TensorMap::join({&tensor, &tensor}, "properties");
+
+        block = joined.block_by_id(1);
+        CHECK(block.samples() == Labels({"samples"}, {{0}, {1}, {3}}));
+        CHECK(block.properties() == Labels({"tensor", "properties"}, {
+            {0, 3}, {0, 4}, {0, 5}, {1, 3}, {1, 4}, {1, 5}
+        }));
+
+        CHECK_THROWS_WITH(
+            TensorMap::join({&tensor, &tensor}, "components"),
+            "invalid parameter: invalid axis 'components', expected 'samples' or 'properties'"
+        );
+    }
+
+    SECTION("reduce_over_samples") {
+        auto tensor = test_tensor_map();
+        auto reduced = tensor.reduce_over_samples({"samples"}, "sum");
+
+        CHECK(reduced.keys() == tensor.keys());
+
+        auto block = reduced.block_by_id(0);
+        CHECK(block.samples() == Labels({"_"}, {{0}}));
+        CHECK(block.properties() == Labels({"properties"}, {{0}}));
+
+        auto& values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(values == SimpleDataArray({1, 1, 1}, 3.0));
+
+        reduced = tensor.reduce_over_samples({"samples"}, "mean");
+        block = reduced.block_by_id(1);
+        auto& mean = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(mean == SimpleDataArray({1, 1, 3}, 2.0));
+
+        CHECK_THROWS_WITH(
+            tensor.reduce_over_samples({"samples"}, "max"),
+            "invalid parameter: invalid reduction 'max', expected 'sum', 'mean', 'variance' or 'std'"
+        );
+    }
+
+    SECTION("sort") {
+        auto tensor = test_tensor_map();
+        auto sorted = tensor.sort_keys();
+        CHECK(sorted.keys() == tensor.keys().sort());
+
+        auto block = tensor.block_by_id(1).sort("properties");
+        CHECK(block.properties().is_sorted());
+        CHECK(block.properties() == tensor.block_by_id(1).properties().sort());
+
+        block = tensor.block_by_id(2).sort("samples");
+        CHECK(block.samples().is_sorted());
+        CHECK(block.gradient("parameter").samples().is_sorted());
+    }
+
+    SECTION("slice") {
+        auto tensor = test_tensor_map();
+        auto sliced = tensor.slice("samples", Labels({"samples"}, {{0}, {3}}));
+
+        CHECK(sliced.keys() == tensor.keys());
+
+        auto block = sliced.block_by_id(2);
+        CHECK(block.samples() == Labels({"samples"}, {{0}, {3}}));
+        auto& values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(values == SimpleDataArray({2, 3, 1}, 3.0));
+
+        auto gradient = block.gradient("parameter");
+        CHECK(gradient.samples() == Labels({"sample", "parameter"}, {{1, -2}}));
+
+        sliced = tensor.slice("properties", Labels({"properties"}, {{5}, {3}}));
+        block = sliced.block_by_id(1);
+        CHECK(block.properties() == Labels({"properties"}, {{3}, {5}}));
+        auto& properties_values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(properties_values == SimpleDataArray({3, 1, 2}, 2.0));
+
+        block = sliced.block_by_id(0);
+        CHECK(block.properties().count() == 0);
+
+        auto sliced_block = tensor.block_by_id(1).slice("samples", Labels({"samples"}, {{1}}));
+        CHECK(sliced_block.samples() == Labels({"samples"}, {{1}}));
+
+        CHECK_THROWS_WITH(
+            tensor.slice("samples", Labels({"not_there"}, {{0}})),
+            "invalid parameter: 'not_there' is not part of the samples for this block"
+        );
+    }
+
+    SECTION("split") {
+        auto tensor = test_tensor_map();
+        auto selections = std::vector<Labels>();
+        selections.emplace_back(Labels({"samples"}, {{0}, {1}}));
+        selections.emplace_back(Labels({"samples"}, {{2}, {3}, {6}}));
+
+        auto split = tensor.split("samples", selections);
+
+        CHECK(split.size() == 2);
+        CHECK(split[0].keys() == tensor.keys());
+
+        auto block = split[0].block_by_id(1);
+        CHECK(block.samples() == Labels({"samples"}, {{0}, {1}}));
+
+        block = split[1].block_by_id(2);
+        CHECK(block.samples() == Labels({"samples"}, {{3}, {6}}));
+
+        selections = std::vector<Labels>();
+        selections.emplace_back(Labels({"samples"}, {{0}}));
+        selections.emplace_back(Labels({"properties"}, {{0}}));
+        CHECK_THROWS_WITH(
+            tensor.split("samples", selections),
+            "invalid parameter: all selections must have the same names to "
+            "split a tensor map, got [samples] and [properties]"
+        );
+    }
+
+    SECTION("metadata") {
+        auto tensor = test_tensor_map();
+        CHECK(tensor.metadata_keys().empty());
+
+        tensor.set_metadata("units", "eV");
+        tensor.set_metadata("cutoff", "3.5");
+        CHECK(tensor.metadata("units") == "eV");
+        CHECK(tensor.metadata_keys() == std::vector<std::string>{"cutoff", "units"});
+
+        tensor.remove_metadata("units");
+        CHECK(tensor.metadata_keys() == std::vector<std::string>{"cutoff"});
+        CHECK_THROWS_WITH(
+            tensor.metadata("units"),
+            "there is no metadata associated with 'units'"
+        );
+    }
+
+    SECTION("components_to_samples") {
+        auto tensor = test_tensor_map().components_to_samples("component");
+
+        auto block = tensor.block_by_id(2);
+        CHECK(block.samples() == Labels({"samples", "component"}, {
+            {0, 0}, {0, 1}, {0, 2}, {3, 0}, {3, 1}, {3, 2},
+            {6, 0}, {6, 1}, {6, 2}, {8, 0}, {8, 1}, {8, 2},
+        }));
+        auto& values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(values == SimpleDataArray({12, 1}, 3.0));
+
+        auto gradient = block.gradient("parameter");
+        CHECK(gradient.samples() == Labels({"sample", "parameter"}, {{3, -2}, {4, -2}, {5, -2}}));
+    }
+
+    SECTION("properties_to_components") {
+        auto tensor = test_tensor_map().properties_to_components("properties");
+
+        auto block = tensor.block_by_id(1);
+        CHECK(block.properties() == Labels({"_"}, {{0}}));
+        auto& values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(values == SimpleDataArray({3, 1, 3, 1}, 2.0));
+
+        CHECK_THROWS_WITH(
+            tensor.properties_to_components("not_there"),
+            "invalid parameter: 'not_there' is not part of the properties for this block"
+        );
+    }
+
+    SECTION("samples_to_keys") {
+        auto moved = test_tensor_map().keys_to_samples("key_2", /* sort_samples */ true);
+        auto tensor = moved.samples_to_keys("key_2");
+
+        CHECK(tensor.keys() == Labels({"key_1", "key_2"}, {{0, 0}, {1, 0}, {2, 2}, {2, 3}}));
+
+        auto block = tensor.block_by_id(2);
+        CHECK(block.samples() == Labels({"samples"}, {{0}, {3}, {6}, {8}}));
+        auto& values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(values == SimpleDataArray({4, 3, 1}, 3.0));
+
+        auto gradient = block.gradient("parameter");
+        CHECK(gradient.samples() == Labels({"sample", "parameter"}, {{1, -2}}));
+
+        CHECK_THROWS_WITH(
+            tensor.samples_to_keys("key_1"),
+            "invalid parameter: 'key_1' is already part of the keys for this tensor map"
+        );
+    }
+
+    SECTION("properties_to_keys") {
+        auto tensor = test_tensor_map().properties_to_keys(std::vector<std::string>{"properties"});
+
+        CHECK(tensor.keys() == Labels({"key_1", "key_2", "properties"}, {
+            {0, 0, 0}, {1, 0, 3}, {1, 0, 4}, {1, 0, 5}, {2, 2, 0}, {2, 3, 0}
+        }));
+
+        auto block = tensor.block_by_id(2);
+        CHECK(block.properties() == Labels({"_"}, {{0}}));
+        auto& values = SimpleDataArray::from_eqs_array(block.eqs_array("values"));
+        CHECK(values == SimpleDataArray({3, 1, 1}, 2.0));
+    }
+}
+
+
+/// SimpleDataArray pretending to contain 32-bit floating point values, used to
+/// check the handling of data types
+class Float32DataArray: public SimpleDataArray {
+public:
+    Float32DataArray(std::vector<uintptr_t> shape): SimpleDataArray(std::move(shape)) {}
+
+    eqs_dtype_t dtype() const override {
+        return EQS_DTYPE_FLOAT32;
+    }
+};
+
+TEST_CASE("TensorMap data types") {
+    auto components = std::vector<Labels>();
+
+    auto block_1 = TensorBlock(
+        std::unique_ptr<SimpleDataArray>(new SimpleDataArray({1, 1})),
+        Labels({"samples"}, {{0}}),
+        components,
+        Labels({"properties"}, {{0}})
+    );
+
+    auto block_2 = TensorBlock(
+        std::unique_ptr<SimpleDataArray>(new Float32DataArray({1, 1})),
+        Labels({"samples"}, {{0}}),
+        components,
+        Labels({"properties"}, {{0}})
+    );
+
+    CHECK_THROWS_WITH(
+        block_2.values(),
+        "can only access arrays containing 64-bit floating point values as NDArray<double>"
+    );
+
+    CHECK_THROWS_WITH(
+        block_1.add_gradient(
+            "parameter",
+            std::unique_ptr<SimpleDataArray>(new Float32DataArray({1, 1})),
+            Labels({"sample"}, {{0}}),
+            components
+        ),
+        "invalid parameter: the gradient array has a different data type (float32) "
+        "than the value array (float64)"
+    );
+
+    auto blocks = std::vector<TensorBlock>();
+    blocks.emplace_back(std::move(block_1));
+    blocks.emplace_back(std::move(block_2));
+
+    CHECK_THROWS_WITH(
+        TensorMap(Labels({"key"}, {{0}, {1}}), std::move(blocks)),
+        "invalid parameter: all blocks must have the same data type, got float32 and float64"
+    );
+}
+
+
+/// SimpleDataArray pretending to live outside of host memory, used to check
+/// that the data can still be accessed through `copy_to_host`
+class OtherDeviceDataArray: public SimpleDataArray {
+public:
+    OtherDeviceDataArray(std::vector<uintptr_t> shape, double value): SimpleDataArray(std::move(shape), value) {}
+
+    eqs_device_t device() const override {
+        return EQS_DEVICE_OTHER;
+    }
+
+    void copy_to_host(void* buffer, size_t size) const override {
+        auto data = const_cast<OtherDeviceDataArray*>(this)->SimpleDataArray::data();
+        std::memcpy(buffer, data, size);
+    }
+};
+
+TEST_CASE("TensorMap devices") {
+    auto blocks = std::vector<TensorBlock>();
+    blocks.emplace_back(TensorBlock(
+        std::unique_ptr<SimpleDataArray>(new OtherDeviceDataArray({2, 3}, 4.5)),
+        Labels({"samples"}, {{0}, {1}}),
+        std::vector<Labels>(),
+        Labels({"properties"}, {{0}, {1}, {2}})
+    ));
+
+    CHECK_THROWS_WITH(
+        blocks[0].values(),
+        "can only access arrays living in host memory as NDArray<double>"
+    );
+
+    auto tensor = TensorMap(Labels({"key"}, {{0}}), std::move(blocks));
+
+    TensorMap::save("test-devices.npz", tensor);
+    auto loaded = TensorMap::load("test-devices.npz");
+    std::remove("test-devices.npz");
+
+    auto values = loaded.block_by_id(0).values();
+    CHECK(values.shape() == std::vector<size_t>{2, 3});
+    CHECK(values(0, 0) == 4.5);
+    CHECK(values(1, 2) == 4.5);
 }
 
 
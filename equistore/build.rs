@@ -54,8 +54,10 @@ fn main() {
         equistore_core.push(splitted[..splitted.len() - 1].join("."));
     }
 
+    let enable_rayon = if cfg!(feature = "rayon") { "ON" } else { "OFF" };
     let build = cmake::Config::new(equistore_core)
         .define("CARGO_EXE", env!("CARGO"))
+        .define("EQUISTORE_ENABLE_RAYON", enable_rayon)
         .build();
 
     println!("cargo:rustc-link-search=native={}/lib", build.display());
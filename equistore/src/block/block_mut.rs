@@ -2,7 +2,8 @@ use std::ffi::{CString, CStr};
 use std::iter::FusedIterator;
 
 use crate::c_api::eqs_block_t;
-use crate::{ArrayRefMut, Labels};
+use crate::errors::check_status;
+use crate::{ArrayRefMut, Labels, Error};
 
 use super::TensorBlockRef;
 use super::block_ref::{block_array, block_metadata};
@@ -98,6 +99,47 @@ impl<'a> TensorBlockRefMut<'a> {
         }
     }
 
+    /// Set the value associated with `key` in the info of the values (if
+    /// `values_gradient` is `"values"`) or of the gradient with respect to
+    /// `values_gradient` in this block, overwriting any existing value.
+    #[inline]
+    pub fn set_info(&mut self, values_gradient: &str, key: &str, value: &str) -> Result<(), Error> {
+        let values_gradient = CString::new(values_gradient).expect("invalid C string");
+        let key = CString::new(key).expect("invalid C string");
+        let value = CString::new(value).expect("invalid C string");
+
+        unsafe {
+            check_status(crate::c_api::eqs_block_set_info(
+                self.as_mut_ptr(),
+                values_gradient.as_ptr(),
+                key.as_ptr(),
+                value.as_ptr(),
+            ))?;
+        }
+
+        return Ok(());
+    }
+
+    /// Remove the value associated with `key` in the info of the values (if
+    /// `values_gradient` is `"values"`) or of the gradient with respect to
+    /// `values_gradient` in this block, if any.
+    #[inline]
+    pub fn remove_info(&mut self, values_gradient: &str, key: &str) -> Result<(), Error> {
+        let values_gradient = CString::new(values_gradient).expect("invalid C string");
+        let key = CString::new(key).expect("invalid C string");
+
+        unsafe {
+            check_status(crate::c_api::eqs_block_set_info(
+                self.as_mut_ptr(),
+                values_gradient.as_ptr(),
+                key.as_ptr(),
+                std::ptr::null(),
+            ))?;
+        }
+
+        return Ok(());
+    }
+
     /// Get an iterator over parameter/[`BasicBlockMut`] pairs for all gradients
     /// in this block
     #[inline]
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::ffi::{CStr, CString};
 use std::iter::FusedIterator;
 
@@ -7,7 +8,7 @@ use crate::c_api::EQS_INVALID_PARAMETER_ERROR;
 use crate::errors::check_status;
 use crate::{ArrayRef, Labels, Error};
 
-use super::TensorBlock;
+use super::{TensorBlock, Axis};
 
 /// Reference to a [`TensorBlock`]
 #[derive(Debug, Clone, Copy)]
@@ -44,6 +45,11 @@ pub struct BasicBlock<'a> {
     pub components: Vec<Labels>,
     /// Labels describing the properties, i.e. the last dimension of the array
     pub properties: Labels,
+    /// Additional information about the data (e.g. the physical units), as
+    /// key/value pairs. Use [`TensorBlockRefMut::set_info`] to modify it.
+    ///
+    /// [`TensorBlockRefMut::set_info`]: crate::TensorBlockRefMut::set_info
+    pub info: BTreeMap<String, String>,
 }
 
 impl<'a> BasicBlock<'a> {
@@ -111,6 +117,45 @@ pub(super) fn block_metadata(
     return (samples, components, properties);
 }
 
+/// Get the info for the values or one gradient, depending on
+/// `values_gradient`. See `eqs_block_info` for more information.
+pub(super) fn block_info(block: *const eqs_block_t, values_gradient: &CStr) -> BTreeMap<String, String> {
+    let mut keys_ptr = std::ptr::null();
+    let mut keys_count = 0;
+    unsafe {
+        check_status(crate::c_api::eqs_block_info_keys(
+            block,
+            values_gradient.as_ptr(),
+            &mut keys_ptr,
+            &mut keys_count,
+        )).expect("failed to get info keys");
+    }
+
+    let mut info = BTreeMap::new();
+    if keys_count == 0 {
+        return info;
+    }
+
+    let keys = unsafe { std::slice::from_raw_parts(keys_ptr, keys_count) };
+    for &key in keys {
+        let mut value = std::ptr::null();
+        unsafe {
+            check_status(crate::c_api::eqs_block_info(
+                block,
+                values_gradient.as_ptr(),
+                key,
+                &mut value,
+            )).expect("failed to get info");
+
+            info.insert(
+                CStr::from_ptr(key).to_str().expect("invalid UTF8").to_owned(),
+                CStr::from_ptr(value).to_str().expect("invalid UTF8").to_owned(),
+            );
+        }
+    }
+
+    return info;
+}
 
 /// Get the array associated with `values_gradient` in this block
 pub(super) fn block_array(block: *mut eqs_block_t, values_gradient: &CStr) -> Option<eqs_array_t> {
@@ -157,6 +202,7 @@ impl<'a> TensorBlockRef<'a> {
             samples,
             components,
             properties,
+            info: block_info(self.as_ptr(), values),
         }
     }
 
@@ -177,6 +223,10 @@ impl<'a> TensorBlockRef<'a> {
             )).expect("failed to get gradient list");
         }
 
+        if parameters_count == 0 {
+            return Vec::new();
+        }
+
         unsafe {
             let parameters = std::slice::from_raw_parts(parameters_ptr, parameters_count);
             return parameters.iter()
@@ -205,6 +255,7 @@ impl<'a> TensorBlockRef<'a> {
                 samples,
                 components,
                 properties,
+                info: block_info(self.as_ptr(), &parameter),
             })
         } else {
             None
@@ -228,6 +279,46 @@ impl<'a> TensorBlockRef<'a> {
         return Ok(unsafe { TensorBlock::from_raw(ptr) });
     }
 
+    /// Slice this block along the given `axis`, keeping only the samples or
+    /// properties matching the `selection`.
+    ///
+    /// The `selection` must contain a subset of the names of the samples or
+    /// properties labels, and an entry is kept if the values of these
+    /// variables match one of the entries in the `selection`. When slicing
+    /// along samples, the gradient samples referring to removed samples are
+    /// removed as well.
+    #[inline]
+    pub fn slice(&self, axis: Axis, selection: &Labels) -> Result<TensorBlock, Error> {
+        let ptr = unsafe {
+            crate::c_api::eqs_block_slice(
+                self.as_ptr(),
+                axis.as_c_str().as_ptr(),
+                selection.as_eqs_labels_t(),
+            )
+        };
+        crate::errors::check_ptr(ptr)?;
+
+        return Ok(unsafe { TensorBlock::from_raw(ptr) });
+    }
+
+    /// Create a new block with the same data as this one, where the entries
+    /// along the given `axis` are sorted in lexicographic order.
+    ///
+    /// When sorting the samples, the gradient samples are updated to refer to
+    /// the new samples, and then sorted as well.
+    #[inline]
+    pub fn sort(&self, axis: Axis) -> Result<TensorBlock, Error> {
+        let ptr = unsafe {
+            crate::c_api::eqs_block_sort(
+                self.as_ptr(),
+                axis.as_c_str().as_ptr(),
+            )
+        };
+        crate::errors::check_ptr(ptr)?;
+
+        return Ok(unsafe { TensorBlock::from_raw(ptr) });
+    }
+
     /// Get an iterator over parameter/[`BasicBlock`] pairs for all gradients in
     /// this block
     #[inline]
@@ -266,6 +357,7 @@ impl<'a> Iterator for GradientsIter<'a> {
                 samples,
                 components,
                 properties,
+                info: block_info(self.block, &parameter_c),
             };
 
             return (parameter, block);
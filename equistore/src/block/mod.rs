@@ -11,3 +11,24 @@ pub use self::block_mut::{TensorBlockRefMut, BasicBlockMut, GradientsMutIter};
 
 mod owned;
 pub use self::owned::TensorBlock;
+
+/// Axis of the data arrays in a block, used to select which labels an
+/// operation should act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// The first axis of the arrays, described by the samples labels
+    Samples,
+    /// The last axis of the arrays, described by the properties labels
+    Properties,
+}
+
+impl Axis {
+    /// Get the name of this axis as expected by the C API
+    pub(crate) fn as_c_str(self) -> &'static std::ffi::CStr {
+        let name: &[u8] = match self {
+            Axis::Samples => b"samples\0",
+            Axis::Properties => b"properties\0",
+        };
+        return std::ffi::CStr::from_bytes_with_nul(name).expect("invalid C string");
+    }
+}
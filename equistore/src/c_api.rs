@@ -8,6 +8,13 @@
 #[cfg_attr(not(feature="static"), link(name="equistore", kind = "dylib"))]
 extern "C" {}
 
+pub const EQS_DTYPE_FLOAT64: i32 = 1;
+pub const EQS_DTYPE_FLOAT32: i32 = 2;
+pub const EQS_DTYPE_FLOAT16: i32 = 3;
+pub const EQS_DTYPE_INT32: i32 = 4;
+pub const EQS_DTYPE_INT64: i32 = 5;
+pub const EQS_DEVICE_CPU: i32 = 1;
+pub const EQS_DEVICE_OTHER: i32 = 2;
 pub const EQS_SUCCESS: i32 = 0;
 pub const EQS_INVALID_PARAMETER_ERROR: i32 = 1;
 pub const EQS_IO_ERROR: i32 = 2;
@@ -108,6 +115,10 @@ fn bindgen_test_layout_eqs_labels_t() {
 }
 #[doc = " A single 64-bit integer representing a data origin (numpy ndarray, rust\n ndarray, torch tensor, fortran array, ...)."]
 pub type eqs_data_origin_t = u64;
+#[doc = " Type of the data stored inside an `eqs_array_t`, as one of the\n `EQS_DTYPE_XXX` constants."]
+pub type eqs_dtype_t = i32;
+#[doc = " Device where the data of an `eqs_array_t` lives, as one of the\n `EQS_DEVICE_XXX` constants."]
+pub type eqs_device_t = i32;
 #[doc = " Representation of a single sample moved from an array to another one"]
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -152,7 +163,7 @@ fn bindgen_test_layout_eqs_sample_mapping_t() {
         )
     );
 }
-#[doc = " `eqs_array_t` manages n-dimensional arrays used as data in a block or tensor\n map. The array itself if opaque to this library and can come from multiple\n sources: Rust program, a C/C++ program, a Fortran program, Python with numpy\n or torch. The data does not have to live on CPU, or even on the same machine\n where this code is executed.\n\n This struct contains a C-compatible manual implementation of a virtual table\n (vtable, i.e. trait in Rust, pure virtual class in C++); allowing\n manipulation of the array in an opaque way.\n\n **WARNING**: all function implementations **MUST** be thread-safe, and can\n be called from multiple threads at the same time. The `eqs_array_t` itself\n might be moved from one thread to another."]
+#[doc = " `eqs_array_t` manages n-dimensional arrays used as data in a block or tensor\n map. The array itself if opaque to this library and can come from multiple\n sources: Rust program, a C/C++ program, a Fortran program, Python with numpy\n or torch. The data does not have to live on CPU, or even on the same machine\n where this code is executed: arrays living elsewhere should report it with\n `device`, and implement `copy_to_host` to make their data available.\n\n This struct contains a C-compatible manual implementation of a virtual table\n (vtable, i.e. trait in Rust, pure virtual class in C++); allowing\n manipulation of the array in an opaque way.\n\n **WARNING**: all function implementations **MUST** be thread-safe, and can\n be called from multiple threads at the same time. The `eqs_array_t` itself\n might be moved from one thread to another."]
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct eqs_array_t {
@@ -165,11 +176,33 @@ pub struct eqs_array_t {
             origin: *mut eqs_data_origin_t,
         ) -> eqs_status_t,
     >,
-    #[doc = " Get a pointer to the underlying data storage.\n\n This function is allowed to fail if the data is not accessible in RAM,\n not stored as 64-bit floating point values, or not stored as a\n C-contiguous array."]
+    #[doc = " Get the type of the data stored in this array in `dtype`, as one of the\n `EQS_DTYPE_XXX` constants. This function can be set to `NULL`, in\n which case the data is assumed to contain 64-bit floating point values."]
+    pub dtype: ::std::option::Option<
+        unsafe extern "C" fn(
+            array: *const ::std::os::raw::c_void,
+            dtype: *mut eqs_dtype_t,
+        ) -> eqs_status_t,
+    >,
+    #[doc = " Get a pointer to the underlying data storage. The data is interpreted\n according to the type given by `eqs_array_t.dtype`.\n\n This function is allowed to fail if the data is not accessible in RAM,\n or not stored as a C-contiguous array."]
     pub data: ::std::option::Option<
         unsafe extern "C" fn(
             array: *mut ::std::os::raw::c_void,
-            data: *mut *mut f64,
+            data: *mut *mut ::std::os::raw::c_void,
+        ) -> eqs_status_t,
+    >,
+    #[doc = " Get the device where the data of this array lives in `device`, as one\n of the `EQS_DEVICE_XXX` constants. This function can be set to `NULL`,\n in which case the data is assumed to live in host memory\n (`EQS_DEVICE_CPU`)."]
+    pub device: ::std::option::Option<
+        unsafe extern "C" fn(
+            array: *const ::std::os::raw::c_void,
+            device: *mut eqs_device_t,
+        ) -> eqs_status_t,
+    >,
+    #[doc = " Copy all the data of this array to host memory in `buffer`, as a\n C-contiguous array with the type given by `eqs_array_t.dtype`.\n `buffer_size` is the size of `buffer` in bytes, and will always be the\n number of elements in the array times the size of the data type.\n\n This function is only used for arrays which are not on\n `EQS_DEVICE_CPU`, and can be set to `NULL` for arrays living in host\n memory."]
+    pub copy_to_host: ::std::option::Option<
+        unsafe extern "C" fn(
+            array: *const ::std::os::raw::c_void,
+            buffer: *mut ::std::os::raw::c_void,
+            buffer_size: usize,
         ) -> eqs_status_t,
     >,
     #[doc = " Get the shape of the array managed by this `eqs_array_t` in the `*shape`\n pointer, and the number of dimension (size of the `*shape` array) in\n `*shape_count`."]
@@ -225,6 +258,46 @@ pub struct eqs_array_t {
             property_end: usize,
         ) -> eqs_status_t,
     >,
+    #[doc = " Set entries in the `output` array (the current array) by selecting data
+ from the `input` array along a single `axis`. The `output` array is
+ guaranteed to be created by calling `eqs_array_t::create` with one of
+ the arrays in the same block or tensor map as the `input`, and has the
+ same shape as `input` except along `axis`, where it has `indices_count`
+ entries.
+
+ This function should copy data from `input[..., indices[i], ...]` to
+ `array[..., i, ...]` (where the indexing is done along `axis`) for `i`
+ up to `indices_count`. All indexes are 0-based."]
+    pub gather_from: ::std::option::Option<
+        unsafe extern "C" fn(
+            output: *mut ::std::os::raw::c_void,
+            input: *const ::std::os::raw::c_void,
+            axis: usize,
+            indices: *const usize,
+            indices_count: usize,
+        ) -> eqs_status_t,
+    >,
+    #[doc = " Add entries from the `input` array to the `output` array (the current\n array) along a single `axis`. The `output` array is guaranteed to be\n created by calling `eqs_array_t::create` with one of the arrays in the\n same block or tensor map as the `input`, and has the same shape as\n `input` except along `axis`. The `input` array has `indices_count`\n entries along `axis`.\n\n This function should add data from `input[..., i, ...]` to\n `array[..., indices[i], ...]` (where the indexing is done along `axis`)\n for `i` up to `indices_count`. Multiple entries in `indices` can have\n the same value, in which case the data from all the corresponding\n `input` entries should be summed together. All indexes are 0-based."]
+    pub scatter_add_from: ::std::option::Option<
+        unsafe extern "C" fn(
+            output: *mut ::std::os::raw::c_void,
+            input: *const ::std::os::raw::c_void,
+            axis: usize,
+            indices: *const usize,
+            indices_count: usize,
+        ) -> eqs_status_t,
+    >,
+    #[doc = " Set entries in the `output` array (the current array) taking data from\n the `input` array, remapping both samples and properties. The `output`\n array is guaranteed to be created by calling `eqs_array_t::create` with\n one of the arrays in the same block or tensor map as the `input`, and\n all dimensions except the first (samples) and last (properties) have\n the same size in `input` and `output`.\n\n The `samples` array of size `samples_count` indicate where the data\n should be moved along the sample axis. The `properties` array of size\n `properties_count` contains one entry for each property in `input`,\n and indicates where this property should be moved in `output`.\n\n This function should copy data from `input[samples[i].input, ..., j]`\n to `array[samples[i].output, ..., properties[j]]` for `i` up to\n `samples_count` and `j` up to `properties_count`. All indexes are\n 0-based."]
+    pub move_data: ::std::option::Option<
+        unsafe extern "C" fn(
+            output: *mut ::std::os::raw::c_void,
+            input: *const ::std::os::raw::c_void,
+            samples: *const eqs_sample_mapping_t,
+            samples_count: usize,
+            properties: *const usize,
+            properties_count: usize,
+        ) -> eqs_status_t,
+    >,
 }
 #[test]
 fn bindgen_test_layout_eqs_array_t() {
@@ -232,7 +305,7 @@ fn bindgen_test_layout_eqs_array_t() {
     let ptr = UNINIT.as_ptr();
     assert_eq!(
         ::std::mem::size_of::<eqs_array_t>(),
-        80usize,
+        128usize,
         concat!("Size of: ", stringify!(eqs_array_t))
     );
     assert_eq!(
@@ -261,8 +334,18 @@ fn bindgen_test_layout_eqs_array_t() {
         )
     );
     assert_eq!(
-        unsafe { ::std::ptr::addr_of!((*ptr).data) as usize - ptr as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).dtype) as usize - ptr as usize },
         16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(eqs_array_t),
+            "::",
+            stringify!(dtype)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).data) as usize - ptr as usize },
+        24usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -270,9 +353,29 @@ fn bindgen_test_layout_eqs_array_t() {
             stringify!(data)
         )
     );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).device) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(eqs_array_t),
+            "::",
+            stringify!(device)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).copy_to_host) as usize - ptr as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(eqs_array_t),
+            "::",
+            stringify!(copy_to_host)
+        )
+    );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).shape) as usize - ptr as usize },
-        24usize,
+        48usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -282,7 +385,7 @@ fn bindgen_test_layout_eqs_array_t() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).reshape) as usize - ptr as usize },
-        32usize,
+        56usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -292,7 +395,7 @@ fn bindgen_test_layout_eqs_array_t() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).swap_axes) as usize - ptr as usize },
-        40usize,
+        64usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -302,7 +405,7 @@ fn bindgen_test_layout_eqs_array_t() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).create) as usize - ptr as usize },
-        48usize,
+        72usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -312,7 +415,7 @@ fn bindgen_test_layout_eqs_array_t() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).copy) as usize - ptr as usize },
-        56usize,
+        80usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -322,7 +425,7 @@ fn bindgen_test_layout_eqs_array_t() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).destroy) as usize - ptr as usize },
-        64usize,
+        88usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -332,7 +435,7 @@ fn bindgen_test_layout_eqs_array_t() {
     );
     assert_eq!(
         unsafe { ::std::ptr::addr_of!((*ptr).move_samples_from) as usize - ptr as usize },
-        72usize,
+        96usize,
         concat!(
             "Offset of field: ",
             stringify!(eqs_array_t),
@@ -340,12 +443,43 @@ fn bindgen_test_layout_eqs_array_t() {
             stringify!(move_samples_from)
         )
     );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).gather_from) as usize - ptr as usize },
+        104usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(eqs_array_t),
+            "::",
+            stringify!(gather_from)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).scatter_add_from) as usize - ptr as usize },
+        112usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(eqs_array_t),
+            "::",
+            stringify!(scatter_add_from)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).move_data) as usize - ptr as usize },
+        120usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(eqs_array_t),
+            "::",
+            stringify!(move_data)
+        )
+    );
 }
-#[doc = " Function pointer to create a new `eqs_array_t` when de-serializing tensor\n maps.\n\n This function gets the `shape` of the array (the `shape` contains\n `shape_count` elements) and should return a new valid `eqs_array_t` or a\n non-zero `eqs_status_t`.\n\n The newly created array should contains 64-bit floating points (`double`)\n data, and live on CPU, since equistore will use `eqs_array_t.data` to get\n the data pointer and write to it."]
+#[doc = " Function pointer to create a new `eqs_array_t` when de-serializing tensor\n maps.\n\n This function gets the `shape` of the array (the `shape` contains\n `shape_count` elements) and the data type of the array (`dtype`, one of the\n `EQS_DTYPE_XXX` constants), and should return a new valid `eqs_array_t` or\n a non-zero `eqs_status_t`.\n\n The newly created array should contains data of the requested type, and\n live on CPU, since equistore will use `eqs_array_t.data` to get the data\n pointer and write to it."]
 pub type eqs_create_array_callback_t = ::std::option::Option<
     unsafe extern "C" fn(
         shape: *const usize,
         shape_count: usize,
+        dtype: eqs_dtype_t,
         array: *mut eqs_array_t,
     ) -> eqs_status_t,
 >;
@@ -360,17 +494,117 @@ extern "C" {
     #[doc = " Get the position of the entry defined by the `values` array in the given set\n of `labels`. This operation is only available if the labels correspond to a\n set of Rust Labels (i.e. `labels.internal_ptr_` is not NULL).\n\n @param labels set of labels with an associated Rust data structure\n @param values array containing the label to lookup\n @param values_count size of the values array\n @param result position of the values in the labels or -1 if the values\n               were not found\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_labels_position(
         labels: eqs_labels_t,
-        values: *const i32,
+        values: *const i64,
         values_count: usize,
         result: *mut i64,
     ) -> eqs_status_t;
     #[must_use]
+    #[doc = " Get the positions of multiple entries in the given set of `labels` at once.\n\n `values` should contain the `count` entries to look for, stored as a 2D\n row-major array of shape `(count, labels.size)`. The position of each entry\n is written in the corresponding element of `results`, or -1 if this entry\n is not part of the labels.\n\n Unlike `eqs_labels_position`, this function also accepts labels which did\n not go through `eqs_labels_create`. The data of such labels is copied on\n each call, so you should still use `eqs_labels_create` first if you need to\n call this function multiple times.\n\n If `parallel` is true, the entries are looked for using multiple threads.\n This is only supported if equistore-core was compiled with the `rayon`\n feature (which is enabled by default), and the lookup happens on the\n current thread otherwise.\n\n @param labels set of labels in which to look for the entries\n @param values pointer to the first element of a 2D array containing the\n               entries to look for\n @param count number of entries (i.e. rows) in `values`\n @param parallel whether to look for the entries in parallel\n @param results pointer to an array containing `count` elements, to be\n                filled with the positions of the entries in `labels`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_positions(
+        labels: eqs_labels_t,
+        values: *const i64,
+        count: usize,
+        parallel: bool,
+        results: *mut i64,
+    ) -> eqs_status_t;
+    #[must_use]
     #[doc = " Finish the creation of `eqs_labels_t` by associating it to Rust-owned\n labels.\n\n This allows using the `eqs_labels_positions` and `eqs_labels_clone`\n functions on the `eqs_labels_t`.\n\n This function allocates memory which must be released `eqs_labels_free` when\n you don't need it anymore.\n\n @param labels new set of labels containing pointers to user-managed memory\n        on input, and pointers to Rust-managed memory on output.\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_labels_create(labels: *mut eqs_labels_t) -> eqs_status_t;
     #[must_use]
     #[doc = " Make a copy of `labels` inside `clone`.\n\n Since `eqs_labels_t` are immutable, the copy is actually just a reference\n count increase, and as such should not be an expensive operation.\n\n `eqs_labels_free` must be used with `clone` to decrease the reference count\n and release the memory when you don't need it anymore.\n\n @param labels set of labels with an associated Rust data structure\n @param clone empty labels, on output will contain a copy of `labels`\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_labels_clone(labels: eqs_labels_t, clone: *mut eqs_labels_t) -> eqs_status_t;
     #[must_use]
+    #[doc = " Check whether the entries in `labels` are sorted in lexicographic order.\n\n This information is tracked when creating the labels, making this check\n cheap for labels with a non-NULL `internal_ptr_`. Lookup in sorted labels\n (with `eqs_labels_position` and similar functions) uses binary search\n instead of a hash table, and bindings can rely on this ordering as well.\n\n @param labels set of labels to check\n @param is_sorted pointer to a boolean, will be set to `true` if the labels\n                  are sorted and `false` otherwise\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_is_sorted(labels: eqs_labels_t, is_sorted: *mut bool) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Sort the entries in `labels` in lexicographic order.\n\n If requested, this function can also give the permutation used to sort\n the labels: the entry at position `i` in `result` was at position\n `permutation[i]` in `labels`.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param labels set of labels to sort\n @param result empty labels, on output will contain the sorted labels\n @param permutation if you want the permutation used to sort the labels,\n        this should be a pointer to an array containing `labels.count`\n        elements, to be filled by this function. Otherwise it should be a\n        `NULL` pointer.\n @param permutation_count number of elements in `permutation`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_sort(
+        labels: eqs_labels_t,
+        result: *mut eqs_labels_t,
+        permutation: *mut i64,
+        permutation_count: usize,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Select entries in the `labels` that match the `selection`.\n\n The `selection` should have a subset of the names/variables of the\n `labels`, and an entry of the `labels` is selected if the values of these\n variables match any of the entries in the `selection`. If the `selection`\n does not contain any variable, all entries are selected.\n\n When calling this function, `*selected_count` should contain the number of\n entries in `selected`, which must be the same as `labels.count`. When the\n function returns successfully, `*selected_count` will contain the number of\n selected entries, i.e. how many values were written to `selected`. The\n positions are written in increasing order.\n\n @param labels labels on which to run the selection\n @param selection definition of the selection criteria\n @param selected array to be filled with the positions of the selected\n                 entries in `labels`\n @param selected_count number of entries in `selected`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_select(
+        labels: eqs_labels_t,
+        selection: eqs_labels_t,
+        selected: *mut usize,
+        selected_count: *mut usize,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Take the union of two `eqs_labels_t`.\n\n The result contains all the entries of `first`, followed by the entries of\n `second` which are not in `first`. Both labels must have the same names.\n\n If requested, this function can also give the positions in the union where\n each entry of the input `eqs_labels_t` ended up.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param first first set of labels\n @param second second set of labels\n @param result empty labels, on output will contain the union of `first` and\n        `second`\n @param first_mapping if you want the mapping from the positions of entries\n        in `first` to the positions in `result`, this should be a pointer\n        to an array containing `first.count` elements, to be filled by this\n        function. Otherwise it should be a `NULL` pointer.\n @param first_mapping_count number of elements in `first_mapping`\n @param second_mapping if you want the mapping from the positions of entries\n        in `second` to the positions in `result`, this should be a pointer\n        to an array containing `second.count` elements, to be filled by this\n        function. Otherwise it should be a `NULL` pointer.\n @param second_mapping_count number of elements in `second_mapping`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_union(
+        first: eqs_labels_t,
+        second: eqs_labels_t,
+        result: *mut eqs_labels_t,
+        first_mapping: *mut i64,
+        first_mapping_count: usize,
+        second_mapping: *mut i64,
+        second_mapping_count: usize,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Take the intersection of two `eqs_labels_t`.\n\n The result contains all the entries of `first` which are also in `second`,\n in the same order as in `first`. Both labels must have the same names.\n\n If requested, this function can also give the positions in the\n intersection where each entry of the input `eqs_labels_t` ended up, or -1\n if the entry is not part of the intersection.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param first first set of labels\n @param second second set of labels\n @param result empty labels, on output will contain the intersection of\n        `first` and `second`\n @param first_mapping if you want the mapping from the positions of entries\n        in `first` to the positions in `result`, this should be a pointer\n        to an array containing `first.count` elements, to be filled by this\n        function. Otherwise it should be a `NULL` pointer.\n @param first_mapping_count number of elements in `first_mapping`\n @param second_mapping if you want the mapping from the positions of entries\n        in `second` to the positions in `result`, this should be a pointer\n        to an array containing `second.count` elements, to be filled by this\n        function. Otherwise it should be a `NULL` pointer.\n @param second_mapping_count number of elements in `second_mapping`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_intersection(
+        first: eqs_labels_t,
+        second: eqs_labels_t,
+        result: *mut eqs_labels_t,
+        first_mapping: *mut i64,
+        first_mapping_count: usize,
+        second_mapping: *mut i64,
+        second_mapping_count: usize,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Take the difference of two `eqs_labels_t`.\n\n The result contains all the entries of `first` which are not in `second`,\n in the same order as in `first`. Both labels must have the same names.\n\n If requested, this function can also give the positions in the difference\n where each entry of `first` ended up, or -1 if the entry is not part of the\n difference.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param first first set of labels\n @param second second set of labels\n @param result empty labels, on output will contain the entries of `first`\n        which are not in `second`\n @param first_mapping if you want the mapping from the positions of entries\n        in `first` to the positions in `result`, this should be a pointer\n        to an array containing `first.count` elements, to be filled by this\n        function. Otherwise it should be a `NULL` pointer.\n @param first_mapping_count number of elements in `first_mapping`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_difference(
+        first: eqs_labels_t,
+        second: eqs_labels_t,
+        result: *mut eqs_labels_t,
+        first_mapping: *mut i64,
+        first_mapping_count: usize,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Project `labels` onto the variables with the given `names`, keeping only\n these variables (in the given order) and removing duplicated entries. The\n remaining entries are kept in the order of their first appearance.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param labels set of labels to project\n @param names names of the variables to keep, as an array of NULL-terminated\n              UTF-8 strings\n @param names_count number of entries in `names`\n @param result empty labels, on output will contain the projected labels\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_project(
+        labels: eqs_labels_t,
+        names: *const *const ::std::os::raw::c_char,
+        names_count: usize,
+        result: *mut eqs_labels_t,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Insert a new variable with the given `name` at position `index` in the\n `labels`.\n\n `values` must either contain a single value, used for all entries, or one\n value for each entry in the `labels`.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param labels set of labels in which to insert a new variable\n @param index position of the new variable, between 0 and `labels.size`\n @param name NULL-terminated UTF-8 string containing the name of the new\n             variable\n @param values values of the new variable\n @param values_count number of entries in `values`, either 1 or\n                     `labels.count`\n @param result empty labels, on output will contain the new labels\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_insert(
+        labels: eqs_labels_t,
+        index: usize,
+        name: *const ::std::os::raw::c_char,
+        values: *const i64,
+        values_count: usize,
+        result: *mut eqs_labels_t,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Remove the variable with the given `name` from the `labels`.\n\n This returns an error if removing the variable would create duplicated\n entries, use `eqs_labels_project` to remove the duplicates instead.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param labels set of labels from which to remove a variable\n @param name NULL-terminated UTF-8 string containing the name of the\n             variable to remove\n @param result empty labels, on output will contain the new labels\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_remove(
+        labels: eqs_labels_t,
+        name: *const ::std::os::raw::c_char,
+        result: *mut eqs_labels_t,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Rename the variable `old_name` to `new_name` in the `labels`.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param labels set of labels in which to rename a variable\n @param old_name NULL-terminated UTF-8 string containing the current name of\n                 the variable\n @param new_name NULL-terminated UTF-8 string containing the new name of the\n                 variable\n @param result empty labels, on output will contain the new labels\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_rename(
+        labels: eqs_labels_t,
+        old_name: *const ::std::os::raw::c_char,
+        new_name: *const ::std::os::raw::c_char,
+        result: *mut eqs_labels_t,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Reorder the variables in the `labels`, following the given `names`. The\n `names` must contain all the variables of the `labels` exactly once. The\n order of the entries is not modified.\n\n This function allocates memory for `result` which must be released with\n `eqs_labels_free` when you don't need it anymore.\n\n @param labels set of labels to reorder\n @param names new order of the variables, as an array of NULL-terminated\n              UTF-8 strings\n @param names_count number of entries in `names`\n @param result empty labels, on output will contain the reordered labels\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_labels_reorder(
+        labels: eqs_labels_t,
+        names: *const *const ::std::os::raw::c_char,
+        names_count: usize,
+        result: *mut eqs_labels_t,
+    ) -> eqs_status_t;
+    #[must_use]
     #[doc = " Decrease the reference count of `labels`, and release the corresponding\n memory once the reference count reaches 0.\n\n @param labels set of labels with an associated Rust data structure\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_labels_free(labels: *mut eqs_labels_t) -> eqs_status_t;
     #[must_use]
@@ -386,6 +620,9 @@ extern "C" {
         buffer: *mut ::std::os::raw::c_char,
         buffer_size: usize,
     ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Check that the given `array` correctly implements the functions in\n `eqs_array_t`, by creating new arrays from it and exercising `origin`,\n `dtype`, `device`, `data`/`copy_to_host`, `shape`, `reshape`, `swap_axes`,\n `create`, `copy`, `move_samples_from`, `move_data` (if it is set),\n `gather_from`, `scatter_add_from` and `destroy`.\n\n The `array` itself is not modified by this function. The checks on the\n values stored in the arrays are only meaningful for arrays on\n `EQS_DEVICE_CPU`, since equistore can not write data to other devices.\n\n This function is intended to be used in the test suite of code\n implementing `eqs_array_t`.\n\n @param array array to check\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, the array does not correctly implement\n          `eqs_array_t`, and you can use `eqs_last_error()` to get an error\n          message describing which contract was violated."]
+    pub fn eqs_array_check(array: *const eqs_array_t) -> eqs_status_t;
     #[doc = " Create a new `eqs_block_t` with the given `data` and `samples`, `components`\n and `properties` labels.\n\n The memory allocated by this function and the blocks should be released\n using `eqs_block_free`, or moved into a tensor map using `eqs_tensormap`.\n\n @param data array handle containing the data for this block. The block takes\n             ownership of the array, and will release it with\n             `array.destroy(array.ptr)` when it no longer needs it.\n @param samples sample labels corresponding to the first dimension of the data\n @param components array of component labels corresponding to intermediary\n                   dimensions of the data\n @param components_count number of entries in the `components` array\n @param properties property labels corresponding to the last dimension of the data\n\n @returns A pointer to the newly allocated block, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
     pub fn eqs_block(
         data: eqs_array_t,
@@ -431,6 +668,41 @@ extern "C" {
         parameters: *mut *const *const ::std::os::raw::c_char,
         parameters_count: *mut usize,
     ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Get the value associated with `key` in the info of the values or one of\n the gradients of this `block`.\n\n The `values_gradients` parameter controls whether this function looks up\n the info for `\"values\"` or one of the gradients in this block. `value` is\n set to a NULL-terminated UTF-8 string, or to `NULL` if there is no info\n associated with `key`. The string memory is still managed by the block,\n and is invalidated when the block is freed or its info modified with\n `eqs_block_set_info`.\n\n @param block pointer to an existing block\n @param values_gradients either `\"values\"` or the name of gradients to lookup\n @param key NULL-terminated UTF-8 string containing the info key\n @param value pointer to be filled with the info value\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_block_info(
+        block: *const eqs_block_t,
+        values_gradients: *const ::std::os::raw::c_char,
+        key: *const ::std::os::raw::c_char,
+        value: *mut *const ::std::os::raw::c_char,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Set the value associated with `key` in the info of the values or one of\n the gradients of this `block`, overwriting any existing value.\n\n If `value` is `NULL`, the info associated with `key` is removed instead.\n\n @param block pointer to an existing block\n @param values_gradients either `\"values\"` or the name of gradients to modify\n @param key NULL-terminated UTF-8 string containing the info key\n @param value NULL-terminated UTF-8 string containing the info value, or\n              `NULL` to remove the info\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_block_set_info(
+        block: *mut eqs_block_t,
+        values_gradients: *const ::std::os::raw::c_char,
+        key: *const ::std::os::raw::c_char,
+        value: *const ::std::os::raw::c_char,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Get the list of all keys in the info of the values or one of the gradients\n of this `block`, sorted alphabetically.\n\n The strings memory is still managed by the block, and is invalidated when\n the block is freed or its info modified with `eqs_block_set_info`.\n\n @param block pointer to an existing block\n @param values_gradients either `\"values\"` or the name of gradients to lookup\n @param keys will be set to the first element of an array of NULL-terminated\n             UTF-8 strings containing all the info keys\n @param keys_count will be set to the number of elements in `keys`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_block_info_keys(
+        block: *const eqs_block_t,
+        values_gradients: *const ::std::os::raw::c_char,
+        keys: *mut *const *const ::std::os::raw::c_char,
+        keys_count: *mut usize,
+    ) -> eqs_status_t;
+    #[doc = " Slice this `block` along the given `axis`, keeping only the samples or\n properties matching the `selection`.\n\n The `selection` must contain a subset of the names of the samples or\n properties labels, and an entry is kept if the values of these variables\n match one of the entries in the `selection`. When slicing along samples,\n the gradient samples referring to removed samples are removed as well.\n\n This function requires `eqs_array_t.gather_from` to be implemented. The\n result is a new block, which should be freed with `eqs_block_free`.\n\n @param block pointer to an existing block\n @param axis name of the axis along which the block should be sliced, either\n             `\"samples\"` or `\"properties\"`\n @param selection labels describing which entries should be kept\n\n @returns A pointer to the newly allocated block, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_block_slice(
+        block: *const eqs_block_t,
+        axis: *const ::std::os::raw::c_char,
+        selection: eqs_labels_t,
+    ) -> *mut eqs_block_t;
+    #[doc = " Sort the entries of this `block` along the given `axis` in lexicographic\n order.\n\n When sorting along samples, the gradient samples are updated to refer to the\n new samples, and then sorted as well.\n\n This function requires `eqs_array_t.gather_from` to be implemented. The\n result is a new block, which should be freed with `eqs_block_free`.\n\n @param block pointer to an existing block\n @param axis axis along which to sort the block, either `\"samples\"` or\n             `\"properties\"`\n\n @returns A pointer to the newly allocated block, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_block_sort(
+        block: *const eqs_block_t,
+        axis: *const ::std::os::raw::c_char,
+    ) -> *mut eqs_block_t;
     #[doc = " Create a new `eqs_tensormap_t` with the given `keys` and `blocks`.\n `blocks_count` must be set to the number of entries in the blocks array.\n\n The new tensor map takes ownership of the blocks, which should not be\n released separately.\n\n The memory allocated by this function and the blocks should be released\n using `eqs_tensormap_free`.\n\n @param keys labels containing the keys associated with each block\n @param blocks pointer to the first element of an array of blocks\n @param blocks_count number of elements in the `blocks` array\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
     pub fn eqs_tensormap(
         keys: eqs_labels_t,
@@ -447,6 +719,27 @@ extern "C" {
         keys: *mut eqs_labels_t,
     ) -> eqs_status_t;
     #[must_use]
+    #[doc = " Get the value associated with `key` in the tensor-level metadata of this\n `tensor` map.\n\n `value` is set to a NULL-terminated UTF-8 string, or to `NULL` if there is\n no metadata associated with `key`. The string memory is still managed by\n the tensor map, and is invalidated when the tensor map is freed or its\n metadata modified with `eqs_tensormap_set_metadata`.\n\n @param tensor pointer to an existing tensor map\n @param key NULL-terminated UTF-8 string containing the metadata key\n @param value pointer to be filled with the metadata value\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_tensormap_metadata(
+        tensor: *const eqs_tensormap_t,
+        key: *const ::std::os::raw::c_char,
+        value: *mut *const ::std::os::raw::c_char,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Set the value associated with `key` in the tensor-level metadata of this\n `tensor` map, overwriting any existing value.\n\n If `value` is `NULL`, the metadata associated with `key` is removed\n instead. The metadata is saved and loaded together with the data by\n `eqs_tensormap_save` and `eqs_tensormap_load`.\n\n @param tensor pointer to an existing tensor map\n @param key NULL-terminated UTF-8 string containing the metadata key\n @param value NULL-terminated UTF-8 string containing the metadata value,\n              or `NULL` to remove the metadata\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_tensormap_set_metadata(
+        tensor: *mut eqs_tensormap_t,
+        key: *const ::std::os::raw::c_char,
+        value: *const ::std::os::raw::c_char,
+    ) -> eqs_status_t;
+    #[must_use]
+    #[doc = " Get the list of all keys in the tensor-level metadata of this `tensor` map,\n sorted alphabetically.\n\n The strings memory is still managed by the tensor map, and is invalidated\n when the tensor map is freed or its metadata modified with\n `eqs_tensormap_set_metadata`.\n\n @param tensor pointer to an existing tensor map\n @param keys will be set to the first element of an array of NULL-terminated\n             UTF-8 strings containing all the metadata keys\n @param keys_count will be set to the number of elements in `keys`\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_tensormap_metadata_keys(
+        tensor: *const eqs_tensormap_t,
+        keys: *mut *const *const ::std::os::raw::c_char,
+        keys_count: *mut usize,
+    ) -> eqs_status_t;
+    #[must_use]
     #[doc = " Get a pointer to the `index`-th block in this tensor map.\n\n The block memory is still managed by the tensor map, this block should not\n be freed. The block is invalidated when the tensor map is freed with\n `eqs_tensormap_free` or the set of keys is modified by calling one\n of the `eqs_tensormap_keys_to_XXX` function.\n\n @param tensor pointer to an existing tensor map\n @param block pointer to be filled with a block\n @param index index of the block to get\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_tensormap_block_by_id(
         tensor: *mut eqs_tensormap_t,
@@ -454,18 +747,19 @@ extern "C" {
         index: usize,
     ) -> eqs_status_t;
     #[must_use]
-    #[doc = " Get indices of the blocks in this `tensor` corresponding to the given\n `selection`. The `selection` should have a subset of the names/variables of\n the keys for this tensor map, and only one entry, describing the requested\n blocks.\n\n When calling this function, `*count` should contain the number of entries in\n `block_indexes`. When the function returns successfully, `*count` will\n contain the number of blocks matching the selection, i.e. how many values\n were written to `block_indexes`.\n\n @param tensor pointer to an existing tensor map\n @param block_indexes array to be filled with indexes of blocks in the tensor\n                      map matching the `selection`\n @param count number of entries in `block_indexes`\n @param selection labels with a single entry describing which blocks are requested\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    #[doc = " Get indices of the blocks in this `tensor` corresponding to the given\n `selection`. The `selection` should have a subset of the names/variables of\n the keys for this tensor map, and one or more entries describing the\n requested blocks. A block is selected if its key matches any of the entries\n in the `selection`.\n\n When calling this function, `*count` should contain the number of entries in\n `block_indexes`. When the function returns successfully, `*count` will\n contain the number of blocks matching the selection, i.e. how many values\n were written to `block_indexes`.\n\n @param tensor pointer to an existing tensor map\n @param block_indexes array to be filled with indexes of blocks in the tensor\n                      map matching the `selection`\n @param count number of entries in `block_indexes`\n @param selection labels describing which blocks are requested\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_tensormap_blocks_matching(
         tensor: *const eqs_tensormap_t,
         block_indexes: *mut usize,
         count: *mut usize,
         selection: eqs_labels_t,
     ) -> eqs_status_t;
-    #[doc = " Merge blocks with the same value for selected keys variables along the\n property axis.\n\n The variables (names) of `keys_to_move` will be moved from the keys to\n the property labels, and blocks with the same remaining keys variables\n will be merged together along the property axis.\n\n If `keys_to_move` does not contains any entries (`keys_to_move.count\n == 0`), then the new property labels will contain entries corresponding\n to the merged blocks only. For example, merging a block with key `a=0`\n and properties `p=1, 2` with a block with key `a=2` and properties `p=1,\n 3` will produce a block with properties `a, p = (0, 1), (0, 2), (2, 1),\n (2, 3)`.\n\n If `keys_to_move` contains entries, then the property labels must be the\n same for all the merged blocks. In that case, the merged property labels\n will contains each of the entries of `keys_to_move` and then the current\n property labels. For example, using `a=2, 3` in `keys_to_move`, and\n blocks with properties `p=1, 2` will result in `a, p = (2, 1), (2, 2),\n (3, 1), (3, 2)`.\n\n The new sample labels will contains all of the merged blocks sample\n labels. The order of the samples is controlled by `sort_samples`. If\n `sort_samples` is true, samples are re-ordered to keep them\n lexicographically sorted. Otherwise they are kept in the order in which\n they appear in the blocks.\n\n The result is a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n @param keys_to_move description of the keys to move\n @param sort_samples whether to sort the samples lexicographically after\n                     merging blocks\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    #[doc = " Merge blocks with the same value for selected keys variables along the\n property axis.\n\n The variables (names) of `keys_to_move` will be moved from the keys to\n the property labels, and blocks with the same remaining keys variables\n will be merged together along the property axis.\n\n If `keys_to_move` does not contains any entries (`keys_to_move.count\n == 0`), then the new property labels will contain entries corresponding\n to the merged blocks only. For example, merging a block with key `a=0`\n and properties `p=1, 2` with a block with key `a=2` and properties `p=1,\n 3` will produce a block with properties `a, p = (0, 1), (0, 2), (2, 1),\n (2, 3)`.\n\n If `keys_to_move` contains entries, then the property labels must be the\n same for all the merged blocks. In that case, the merged property labels\n will contains each of the entries of `keys_to_move` and then the current\n property labels. For example, using `a=2, 3` in `keys_to_move`, and\n blocks with properties `p=1, 2` will result in `a, p = (2, 1), (2, 2),\n (3, 1), (3, 2)`.\n\n The new sample labels will contains all of the merged blocks sample\n labels. The order of the samples is controlled by `sort_samples`. If\n `sort_samples` is true, samples are re-ordered to keep them\n lexicographically sorted. Otherwise they are kept in the order in which\n they appear in the blocks.\n\n Similarly, if `sort_properties` is true, the new property labels are\n re-ordered to keep them lexicographically sorted. Otherwise they are kept\n in the order in which they appear in the blocks.\n\n This function uses `eqs_array_t.move_data` to move the data of all arrays\n in this tensor map. If this function is NULL, `eqs_array_t.move_samples_from`\n is used instead, which only works if the properties of each block are moved\n to a contiguous range in the new block.\n\n The result is a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n @param keys_to_move description of the keys to move\n @param sort_samples whether to sort the samples lexicographically after\n                     merging blocks\n @param sort_properties whether to sort the properties lexicographically\n                        after merging blocks\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
     pub fn eqs_tensormap_keys_to_properties(
         tensor: *const eqs_tensormap_t,
         keys_to_move: eqs_labels_t,
         sort_samples: bool,
+        sort_properties: bool,
     ) -> *mut eqs_tensormap_t;
     #[doc = " Move the given variables from the component labels to the property labels\n for each block in this tensor map.\n\n `variables` must be an array of `variables_count` NULL-terminated strings,\n encoded as UTF-8.\n\n @param tensor pointer to an existing tensor map\n @param variables names of the key variables to move to the properties\n @param variables_count number of entries in the `variables` array\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_tensormap_components_to_properties(
@@ -473,13 +767,67 @@ extern "C" {
         variables: *const *const ::std::os::raw::c_char,
         variables_count: usize,
     ) -> *mut eqs_tensormap_t;
-    #[doc = " Merge blocks with the same value for selected keys variables along the\n samples axis.\n\n The variables (names) of `keys_to_move` will be moved from the keys to\n the sample labels, and blocks with the same remaining keys variables\n will be merged together along the sample axis.\n\n `keys_to_move` must be empty (`keys_to_move.count == 0`), and the new\n sample labels will contain entries corresponding to the merged blocks'\n keys.\n\n The new sample labels will contains all of the merged blocks sample\n labels. The order of the samples is controlled by `sort_samples`. If\n `sort_samples` is true, samples are re-ordered to keep them\n lexicographically sorted. Otherwise they are kept in the order in which\n they appear in the blocks.\n\n This function is only implemented if all merged block have the same\n property labels.\n\n @param tensor pointer to an existing tensor map\n @param keys_to_move description of the keys to move\n @param sort_samples whether to sort the samples lexicographically after\n                     merging blocks or not\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    #[doc = " Move the given variables from the component labels to the sample labels\n for each block in this tensor map.\n\n `variables` must be an array of `variables_count` NULL-terminated strings,\n encoded as UTF-8. The component is appended to the samples, with the\n component values varying faster than the old samples. Each gradient sample\n is duplicated for all the values of the component.\n\n @param tensor pointer to an existing tensor map\n @param variables names of the component variables to move to the samples\n @param variables_count number of entries in the `variables` array\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_components_to_samples(
+        tensor: *const eqs_tensormap_t,
+        variables: *const *const ::std::os::raw::c_char,
+        variables_count: usize,
+    ) -> *mut eqs_tensormap_t;
+    #[doc = " Move the given variables from the property labels to a new component for\n each block in this tensor map.\n\n `variables` must be an array of `variables_count` NULL-terminated strings,\n encoded as UTF-8. This is the inverse of\n `eqs_tensormap_components_to_properties`: the new component is added after\n the existing ones, and the properties must contain all the combinations of\n the values of `variables` with the values of the remaining property\n variables, with the moved variables varying either slower or faster than\n the remaining ones. If all the property variables are moved, the new\n properties contain a single `\"_\"` variable.\n\n @param tensor pointer to an existing tensor map\n @param variables names of the property variables to move to the components\n @param variables_count number of entries in the `variables` array\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_properties_to_components(
+        tensor: *const eqs_tensormap_t,
+        variables: *const *const ::std::os::raw::c_char,
+        variables_count: usize,
+    ) -> *mut eqs_tensormap_t;
+    #[doc = " Merge blocks with the same value for selected keys variables along the\n samples axis.\n\n The variables (names) of `keys_to_move` will be moved from the keys to\n the sample labels, and blocks with the same remaining keys variables\n will be merged together along the sample axis.\n\n If `keys_to_move` does not contains any entries (`keys_to_move.count\n == 0`), then the new sample labels will contain entries corresponding\n to the merged blocks' keys only.\n\n If `keys_to_move` contains entries, then the new sample labels will\n contain all the merged samples, combined with each of the entries of\n `keys_to_move`. For example, using `a=2, 3` in `keys_to_move` with blocks\n with samples `s=1, 2` and `s=1, 3` will result in `s, a = (1, 2), (2, 2),\n (3, 2), (1, 3), (2, 3), (3, 3)`. The data for samples which were not\n present in any of the merged blocks is filled with zeros, and blocks with\n a key not in `keys_to_move` are ignored.\n\n The order of the samples is controlled by `sort_samples`. If\n `sort_samples` is true, samples are re-ordered to keep them\n lexicographically sorted. Otherwise they are kept in the order in which\n they appear in the blocks (and in `keys_to_move`).\n\n This function is only implemented if all merged block have the same\n property labels.\n\n @param tensor pointer to an existing tensor map\n @param keys_to_move description of the keys to move\n @param sort_samples whether to sort the samples lexicographically after\n                     merging blocks or not\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
     pub fn eqs_tensormap_keys_to_samples(
         tensor: *const eqs_tensormap_t,
         keys_to_move: eqs_labels_t,
         sort_samples: bool,
     ) -> *mut eqs_tensormap_t;
-    #[doc = " Load a tensor map from the file at the given path.\n\n Arrays for the values and gradient data will be created with the given\n `create_array` callback, and filled by this function with the corresponding\n data.\n\n The memory allocated by this function should be released using\n `eqs_tensormap_free`.\n\n `TensorMap` are serialized using numpy's `.npz` format, i.e. a ZIP file\n without compression (storage method is STORED), where each file is stored as\n a `.npy` array. Both the ZIP and NPY format are well documented:\n\n - ZIP: <https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT>\n - NPY: <https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html>\n\n We add other restriction on top of these formats when saving/loading data.\n First, `Labels` instances are saved as structured array, see the `labels`\n module for more information. Only 32-bit integers are supported for Labels,\n and only 64-bit floats are supported for data (values and gradients).\n\n Second, the path of the files in the archive also carry meaning. The keys of\n the `TensorMap` are stored in `/keys.npy`, and then different blocks are\n stored as\n\n ```bash\n /  blocks / <block_id>  / values / samples.npy\n                         / values / components  / 0.npy\n                                                / <...>.npy\n                                                / <n_components>.npy\n                         / values / properties.npy\n                         / values / data.npy\n\n                         # optional sections for gradients, one by parameter\n                         /   gradients / <parameter> / samples.npy\n                                                     /   components  / 0.npy\n                                                                     / <...>.npy\n                                                                     / <n_components>.npy\n                                                     /   data.npy\n ```\n\n @param path path to the file as a NULL-terminated UTF-8 string\n @param create_array callback function that will be used to create data\n                     arrays inside each block\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    #[doc = " Join the `tensors_count` tensor maps in `tensors` along the given `axis`,\n creating a single tensor map.\n\n `axis` must be either `\"samples\"` or `\"properties\"`. All tensor maps must\n have the same keys, and blocks with the same key must have the same\n components, gradients, and the same samples or properties labels (i.e. the\n labels for the axis which is not joined).\n\n Clashes between the labels along the joined `axis` are resolved by keeping\n the same names if all entries are unique; adding a new `\"tensor\"` variable\n containing the index of the tensor map if the names are the same but some\n entries are duplicated; and using `\"tensor\", \"sample\"` or `\"tensor\",\n \"property\"` (depending on `axis`) as new names if the labels have different\n names.\n\n The result is a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensors pointer to the first element of an array of tensor maps\n @param tensors_count number of elements in the `tensors` array\n @param axis name of the axis along which the tensor maps should be joined\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_join(
+        tensors: *const *const eqs_tensormap_t,
+        tensors_count: usize,
+        axis: *const ::std::os::raw::c_char,
+    ) -> *mut eqs_tensormap_t;
+    #[doc = " Reduce the blocks in this `tensor` over the sample variables in `names`,\n combining together all the samples which only differ by the value of these\n variables.\n\n `names` must be an array of `names_count` NULL-terminated strings, encoded\n as UTF-8. `reduction` must be one of `\"sum\"`, `\"mean\"`, `\"variance\"` or\n `\"std\"`.\n\n The new samples contain the remaining sample variables (or a single `\"_\"`\n variable if all variables are reduced over), and are sorted\n lexicographically. The gradients are reduced accordingly.\n\n The `\"sum\"` reduction only requires `eqs_array_t.scatter_add_from`, while\n the other reductions also need `eqs_array_t.data` to be available, and only\n support arrays containing 64-bit floating point values.\n\n The result is a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n @param names names of the sample variables to reduce over\n @param names_count number of entries in the `names` array\n @param reduction name of the reduction to perform\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_reduce_over_samples(
+        tensor: *const eqs_tensormap_t,
+        names: *const *const ::std::os::raw::c_char,
+        names_count: usize,
+        reduction: *const ::std::os::raw::c_char,
+    ) -> *mut eqs_tensormap_t;
+    #[doc = " Slice all the blocks in this `tensor` along the given `axis`, keeping only\n the samples or properties matching the `selection`.\n\n The `selection` must contain a subset of the names of the samples or\n properties labels, and an entry is kept if the values of these variables\n match one of the entries in the `selection`. The keys of the new tensor map\n are the same as the keys of `tensor`, and blocks can end up with zero\n samples or properties if none of them match the `selection`.\n\n This function requires `eqs_array_t.gather_from` to be implemented. The\n result is a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n @param axis name of the axis along which the tensor map should be sliced,\n             either `\"samples\"` or `\"properties\"`\n @param selection labels describing which entries should be kept\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_slice(
+        tensor: *const eqs_tensormap_t,
+        axis: *const ::std::os::raw::c_char,
+        selection: eqs_labels_t,
+    ) -> *mut eqs_tensormap_t;
+    #[must_use]
+    #[doc = " Split this `tensor` into multiple tensor maps along the given `axis`,\n creating one new tensor map for each of the `selections`.\n\n This is equivalent to calling `eqs_tensormap_slice` with each of the\n `selections`, which must all have the same names. On success, `results`\n will contain `selections_count` newly allocated tensor maps, which should\n be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n @param axis name of the axis along which the tensor map should be split,\n             either `\"samples\"` or `\"properties\"`\n @param selections array of labels describing which entries should be kept\n                   in each of the new tensor maps\n @param selections_count number of entries in the `selections` array\n @param results array of size `selections_count`, which will be filled with\n                pointers to the new tensor maps\n\n @returns The status code of this operation. If the status is not\n          `EQS_SUCCESS`, you can use `eqs_last_error()` to get the full\n          error message."]
+    pub fn eqs_tensormap_split(
+        tensor: *const eqs_tensormap_t,
+        axis: *const ::std::os::raw::c_char,
+        selections: *const eqs_labels_t,
+        selections_count: usize,
+        results: *mut *mut eqs_tensormap_t,
+    ) -> eqs_status_t;
+    #[doc = " Split the blocks in this `tensor` according to the values of the sample\n `variables`, moving these variables from the samples to the keys.\n\n `variables` must be an array of `variables_count` NULL-terminated strings,\n encoded as UTF-8. Each block is split into one new block for each of the\n different values taken by `variables` in its samples, and the\n corresponding key is extended with these values. The remaining sample\n variables are kept in the new blocks, or replaced by a single `\"_\"`\n variable if all sample variables are moved. Gradients are split\n accordingly.\n\n This function requires `eqs_array_t.gather_from` to be implemented. The\n result is a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n @param variables names of the sample variables to move to the keys\n @param variables_count number of entries in the `variables` array\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_samples_to_keys(
+        tensor: *const eqs_tensormap_t,
+        variables: *const *const ::std::os::raw::c_char,
+        variables_count: usize,
+    ) -> *mut eqs_tensormap_t;
+    #[doc = " Split the blocks in this `tensor` according to the values of the property\n `variables`, moving these variables from the properties to the keys.\n\n `variables` must be an array of `variables_count` NULL-terminated strings,\n encoded as UTF-8. Each block is split into one new block for each of the\n different values taken by `variables` in its properties, and the\n corresponding key is extended with these values. The remaining property\n variables are kept in the new blocks, or replaced by a single `\"_\"`\n variable if all property variables are moved. Gradients are split\n accordingly.\n\n This function requires `eqs_array_t.gather_from` to be implemented. The\n result is a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n @param variables names of the property variables to move to the keys\n @param variables_count number of entries in the `variables` array\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_properties_to_keys(
+        tensor: *const eqs_tensormap_t,
+        variables: *const *const ::std::os::raw::c_char,
+        variables_count: usize,
+    ) -> *mut eqs_tensormap_t;
+    #[doc = " Sort the keys of this `tensor` map in lexicographic order, reordering the\n blocks accordingly.\n\n This function requires `eqs_array_t.copy` to be implemented. The result is\n a new tensor map, which should be freed with `eqs_tensormap_free`.\n\n @param tensor pointer to an existing tensor map\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
+    pub fn eqs_tensormap_sort_keys(tensor: *const eqs_tensormap_t) -> *mut eqs_tensormap_t;
+    #[doc = " Load a tensor map from the file at the given path.\n\n Arrays for the values and gradient data will be created with the given\n `create_array` callback, and filled by this function with the corresponding\n data.\n\n The memory allocated by this function should be released using\n `eqs_tensormap_free`.\n\n `TensorMap` are serialized using numpy's `.npz` format, i.e. a ZIP file\n without compression (storage method is STORED), where each file is stored as\n a `.npy` array. Both the ZIP and NPY format are well documented:\n\n - ZIP: <https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT>\n - NPY: <https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html>\n\n We add other restriction on top of these formats when saving/loading data.\n First, `Labels` instances are saved as structured array, see the `labels`\n module for more information. Labels are saved using 64-bit integers (32-bit\n integers are also supported when loading older files). Data (values and\n gradients) can contain 64-bit, 32-bit or 16-bit floating point values\n (`<f8`, `<f4` and `<f2` in numpy notation), as well as 32-bit or 64-bit\n signed integers (`<i4` and `<i8`).\n\n Second, the path of the files in the archive also carry meaning. The keys of\n the `TensorMap` are stored in `/keys.npy`, and then different blocks are\n stored as\n\n ```bash\n /  blocks / <block_id>  / values / samples.npy\n                         / values / components  / 0.npy\n                                                / <...>.npy\n                                                / <n_components>.npy\n                         / values / properties.npy\n                         / values / data.npy\n\n                         # optional sections for gradients, one by parameter\n                         /   gradients / <parameter> / samples.npy\n                                                     /   components  / 0.npy\n                                                                     / <...>.npy\n                                                                     / <n_components>.npy\n                                                     /   data.npy\n ```\n\n @param path path to the file as a NULL-terminated UTF-8 string\n @param create_array callback function that will be used to create data\n                     arrays inside each block\n\n @returns A pointer to the newly allocated tensor map, or a `NULL` pointer in\n          case of error. In case of error, you can use `eqs_last_error()`\n          to get the error message."]
     pub fn eqs_tensormap_load(
         path: *const ::std::os::raw::c_char,
         create_array: eqs_create_array_callback_t,
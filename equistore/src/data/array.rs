@@ -4,6 +4,81 @@ use std::os::raw::c_void;
 use once_cell::sync::Lazy;
 
 use crate::c_api::{eqs_array_t, eqs_data_origin_t, eqs_sample_mapping_t, eqs_status_t};
+use crate::c_api::{eqs_dtype_t, EQS_DTYPE_FLOAT64, EQS_DTYPE_FLOAT32, EQS_DTYPE_FLOAT16};
+use crate::c_api::{EQS_DTYPE_INT32, EQS_DTYPE_INT64};
+use crate::c_api::{eqs_device_t, EQS_DEVICE_CPU};
+
+use crate::Error;
+
+/// Mutable reference to the data of an array, for each of the data types
+/// supported by equistore.
+#[derive(Debug)]
+pub enum ArrayDataMut<'a> {
+    /// 64-bit floating point data
+    Float64(&'a mut [f64]),
+    /// 32-bit floating point data
+    Float32(&'a mut [f32]),
+    /// 16-bit floating point data, stored as the bit representation of IEEE
+    /// 754 half-precision values
+    Float16(&'a mut [u16]),
+    /// 32-bit signed integer data
+    Int32(&'a mut [i32]),
+    /// 64-bit signed integer data
+    Int64(&'a mut [i64]),
+}
+
+impl<'a> ArrayDataMut<'a> {
+    /// Create an `ArrayDataMut` from a raw pointer to `len` values of type
+    /// `dtype`.
+    pub(crate) unsafe fn from_raw(dtype: eqs_dtype_t, ptr: *mut c_void, len: usize) -> ArrayDataMut<'a> {
+        match dtype {
+            EQS_DTYPE_FLOAT64 => ArrayDataMut::Float64(std::slice::from_raw_parts_mut(ptr.cast(), len)),
+            EQS_DTYPE_FLOAT32 => ArrayDataMut::Float32(std::slice::from_raw_parts_mut(ptr.cast(), len)),
+            EQS_DTYPE_FLOAT16 => ArrayDataMut::Float16(std::slice::from_raw_parts_mut(ptr.cast(), len)),
+            EQS_DTYPE_INT32 => ArrayDataMut::Int32(std::slice::from_raw_parts_mut(ptr.cast(), len)),
+            EQS_DTYPE_INT64 => ArrayDataMut::Int64(std::slice::from_raw_parts_mut(ptr.cast(), len)),
+            _ => panic!("unknown data type {}", dtype),
+        }
+    }
+
+    /// Get the type of this data, as one of the `EQS_DTYPE_XXX` constants
+    pub fn dtype(&self) -> eqs_dtype_t {
+        match self {
+            ArrayDataMut::Float64(_) => EQS_DTYPE_FLOAT64,
+            ArrayDataMut::Float32(_) => EQS_DTYPE_FLOAT32,
+            ArrayDataMut::Float16(_) => EQS_DTYPE_FLOAT16,
+            ArrayDataMut::Int32(_) => EQS_DTYPE_INT32,
+            ArrayDataMut::Int64(_) => EQS_DTYPE_INT64,
+        }
+    }
+
+    /// Get the number of elements in this data
+    pub fn len(&self) -> usize {
+        match self {
+            ArrayDataMut::Float64(data) => data.len(),
+            ArrayDataMut::Float32(data) => data.len(),
+            ArrayDataMut::Float16(data) => data.len(),
+            ArrayDataMut::Int32(data) => data.len(),
+            ArrayDataMut::Int64(data) => data.len(),
+        }
+    }
+
+    /// Check if this data contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get a raw pointer to the start of this data
+    pub fn as_mut_ptr(&mut self) -> *mut c_void {
+        match self {
+            ArrayDataMut::Float64(data) => data.as_mut_ptr().cast(),
+            ArrayDataMut::Float32(data) => data.as_mut_ptr().cast(),
+            ArrayDataMut::Float16(data) => data.as_mut_ptr().cast(),
+            ArrayDataMut::Int32(data) => data.as_mut_ptr().cast(),
+            ArrayDataMut::Int64(data) => data.as_mut_ptr().cast(),
+        }
+    }
+}
 
 /// The Array trait is used by equistore to manage different kind of data array
 /// with a single API. Equistore only knows about `Box<dyn Array>`, and
@@ -29,12 +104,35 @@ pub trait Array: std::any::Any + Send + Sync {
     /// (data type, data location, etc.)
     fn copy(&self) -> Box<dyn Array>;
 
-    /// Get the underlying data storage as a contiguous slice
+    /// Get the type of the data stored in this array, as one of the
+    /// `EQS_DTYPE_XXX` constants.
+    fn dtype(&self) -> eqs_dtype_t;
+
+    /// Get the underlying data storage as a contiguous slice, with the type
+    /// given by [`Array::dtype`].
     ///
     /// This function is allowed to panic if the data is not accessible in RAM,
-    /// not stored as 64-bit floating point values, or not stored as a
-    /// C-contiguous array.
-    fn data(&mut self) -> &mut [f64];
+    /// or not stored as a C-contiguous array.
+    fn data(&mut self) -> ArrayDataMut<'_>;
+
+    /// Get the device where the data of this array lives, as one of the
+    /// `EQS_DEVICE_XXX` constants. The default implementation returns
+    /// `EQS_DEVICE_CPU`.
+    fn device(&self) -> eqs_device_t {
+        EQS_DEVICE_CPU
+    }
+
+    /// Copy all the data of this array to host memory in `buffer`, as a
+    /// C-contiguous array with the type given by [`Array::dtype`]. `buffer`
+    /// contains exactly as many bytes as needed for the data.
+    ///
+    /// This function is only called for arrays which are not on
+    /// `EQS_DEVICE_CPU`, and must be implemented by such arrays. The default
+    /// implementation panics.
+    fn copy_to_host(&self, buffer: &mut [u8]) {
+        let _ = buffer;
+        panic!("copy_to_host is not implemented for this array");
+    }
 
     /// Get the shape of the array
     fn shape(&self) -> &[usize];
@@ -63,6 +161,91 @@ pub trait Array: std::any::Any + Send + Sync {
         samples: &[eqs_sample_mapping_t],
         properties: Range<usize>,
     );
+
+    /// Set entries in `self` by selecting data from the `input` array along a
+    /// single `axis`.
+    ///
+    /// The `output` array is guaranteed to be created by calling
+    /// `eqs_array_t::create` with one of the arrays in the same block or tensor
+    /// map as the `input`, and has the same shape as `input` except along
+    /// `axis`.
+    ///
+    /// This function should copy data from `input[..., indices[i], ...]` to
+    /// `array[..., i, ...]` (indexing along `axis`) for all `i` in `indices`.
+    /// All indexes are 0-based.
+    fn gather_from(
+        &mut self,
+        input: &dyn Array,
+        axis: usize,
+        indices: &[usize],
+    );
+
+    /// Add entries to `self` taking data from the `input` array along a single
+    /// `axis`.
+    ///
+    /// The `output` array is guaranteed to be created by calling
+    /// `eqs_array_t::create` with one of the arrays in the same block or tensor
+    /// map as the `input`, and has the same shape as `input` except along
+    /// `axis`.
+    ///
+    /// This function should add data from `input[..., i, ...]` to
+    /// `array[..., indices[i], ...]` (indexing along `axis`) for all `i` in
+    /// `indices`. Multiple entries in `indices` can have the same value, in
+    /// which case the corresponding data should be summed together. All
+    /// indexes are 0-based.
+    fn scatter_add_from(
+        &mut self,
+        input: &dyn Array,
+        axis: usize,
+        indices: &[usize],
+    );
+
+    /// Set entries in `self` taking data from the `input` array, remapping
+    /// both samples and properties.
+    ///
+    /// The `output` array is guaranteed to be created by calling
+    /// `eqs_array_t::create` with one of the arrays in the same block or tensor
+    /// map as the `input`, and all dimensions except the first (samples) and
+    /// last (properties) have the same size in `input` and `output`.
+    ///
+    /// This function should copy data from `input[sample.input, ..., i]` to
+    /// `array[sample.output, ..., properties[i]]` for each sample in `samples`
+    /// and all `i` in `0..properties.len()`. All indexes are 0-based.
+    ///
+    /// The default implementation uses [`Array::move_samples_from`] for each
+    /// contiguous range of properties in `properties`, selecting the
+    /// corresponding properties in `input` with [`Array::gather_from`].
+    fn move_data(
+        &mut self,
+        input: &dyn Array,
+        samples: &[eqs_sample_mapping_t],
+        properties: &[usize],
+    ) {
+        let mut first = 0;
+        while first < properties.len() {
+            let mut last = first + 1;
+            while last < properties.len() && properties[last] == properties[last - 1] + 1 {
+                last += 1;
+            }
+
+            let output_properties = properties[first]..(properties[first] + last - first);
+            if first == 0 && last == properties.len() {
+                // all the properties are moved to a contiguous range
+                self.move_samples_from(input, samples, output_properties);
+            } else {
+                let property_axis = input.shape().len() - 1;
+                let mut shape = input.shape().to_vec();
+                shape[property_axis] = last - first;
+
+                let indices = (first..last).collect::<Vec<_>>();
+                let mut selected = input.create(&shape);
+                selected.gather_from(input, property_axis, &indices);
+                self.move_samples_from(&*selected, samples, output_properties);
+            }
+
+            first = last;
+        }
+    }
 }
 
 impl From<Box<dyn Array>> for eqs_array_t {
@@ -75,7 +258,10 @@ impl From<Box<dyn Array>> for eqs_array_t {
         return eqs_array_t {
             ptr: Box::into_raw(array).cast(),
             origin: Some(rust_array_origin),
+            dtype: Some(rust_array_dtype),
             data: Some(rust_array_data),
+            device: Some(rust_array_device),
+            copy_to_host: Some(rust_array_copy_to_host),
             shape: Some(rust_array_shape),
             reshape: Some(rust_array_reshape),
             swap_axes: Some(rust_array_swap_axes),
@@ -83,10 +269,29 @@ impl From<Box<dyn Array>> for eqs_array_t {
             copy: Some(rust_array_copy),
             destroy: Some(rust_array_destroy),
             move_samples_from: Some(rust_array_move_samples_from),
+            gather_from: Some(rust_array_gather_from),
+            scatter_add_from: Some(rust_array_scatter_add_from),
+            move_data: Some(rust_array_move_data),
         }
     }
 }
 
+/// Check that `array` correctly implements the [`Array`] trait, by running
+/// `eqs_array_check` on a copy of it. The returned error describes which
+/// contract of the trait was violated.
+///
+/// This is intended to be used in the tests of custom [`Array`]
+/// implementations.
+pub fn check_array(array: &dyn Array) -> Result<(), Error> {
+    let array = eqs_array_t::from(array.copy());
+    let result = array.check();
+
+    let destroy = array.destroy.expect("eqs_array_t.destroy function is NULL");
+    unsafe { destroy(array.ptr) };
+
+    return result;
+}
+
 macro_rules! check_pointers {
     ($pointer: ident) => {
         if $pointer.is_null() {
@@ -116,6 +321,18 @@ unsafe extern fn rust_array_origin(
     })
 }
 
+/// Implementation of `eqs_array_t.dtype` using `Box<dyn Array>`
+unsafe extern fn rust_array_dtype(
+    array: *const c_void,
+    dtype: *mut eqs_dtype_t,
+) -> eqs_status_t {
+    crate::errors::catch_unwind(|| {
+        check_pointers!(array, dtype);
+        let array = array.cast::<Box<dyn Array>>();
+        *dtype = (*array).dtype();
+    })
+}
+
 /// Implementation of `eqs_array_t.shape` using `Box<dyn Array>`
 unsafe extern fn rust_array_shape(
     array: *const c_void,
@@ -183,15 +400,58 @@ unsafe extern fn rust_array_create(
 /// Implementation of `eqs_array_t.data` for `Box<dyn Array>`
 unsafe extern fn rust_array_data(
     array: *mut c_void,
-    data: *mut *mut f64,
+    data: *mut *mut c_void,
 ) -> eqs_status_t {
     crate::errors::catch_unwind(|| {
         check_pointers!(array, data);
+        let array = &mut *array.cast::<Box<dyn Array>>();
+
+        // equistore-core reads the data according to the dtype and shape of
+        // the array, make sure these match the slice given by `Array::data`
+        let dtype = array.dtype();
+        let size = array.shape().iter().product::<usize>();
+
+        let mut array_data = array.data();
+        assert!(
+            array_data.dtype() == dtype,
+            "Array::data() returned data with dtype {}, but Array::dtype() is {}",
+            array_data.dtype(), dtype
+        );
+        assert!(
+            array_data.len() == size,
+            "Array::data() returned {} elements, but the array shape contains {} elements",
+            array_data.len(), size
+        );
+
+        *data = array_data.as_mut_ptr();
+    })
+}
+
+/// Implementation of `eqs_array_t.device` using `Box<dyn Array>`
+unsafe extern fn rust_array_device(
+    array: *const c_void,
+    device: *mut eqs_device_t,
+) -> eqs_status_t {
+    crate::errors::catch_unwind(|| {
+        check_pointers!(array, device);
         let array = array.cast::<Box<dyn Array>>();
-        *data = (*array).data().as_mut_ptr();
+        *device = (*array).device();
     })
 }
 
+/// Implementation of `eqs_array_t.copy_to_host` using `Box<dyn Array>`
+unsafe extern fn rust_array_copy_to_host(
+    array: *const c_void,
+    buffer: *mut c_void,
+    buffer_size: usize,
+) -> eqs_status_t {
+    crate::errors::catch_unwind(|| {
+        check_pointers!(array, buffer);
+        let array = array.cast::<Box<dyn Array>>();
+        let buffer = std::slice::from_raw_parts_mut(buffer.cast(), buffer_size);
+        (*array).copy_to_host(buffer);
+    })
+}
 
 /// Implementation of `eqs_array_t.copy` using `Box<dyn Array>`
 unsafe extern fn rust_array_copy(
@@ -236,68 +496,209 @@ unsafe extern fn rust_array_move_samples_from(
     })
 }
 
-/******************************************************************************/
-
-impl Array for ndarray::ArrayD<f64> {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
-    }
-
-    fn create(&self, shape: &[usize]) -> Box<dyn Array> {
-        return Box::new(ndarray::Array::from_elem(shape, 0.0));
-    }
-
-    fn copy(&self) -> Box<dyn Array> {
-        return Box::new(self.clone());
-    }
-
-    fn data(&mut self) -> &mut [f64] {
-        return self.as_slice_mut().expect("array is not contiguous")
-    }
-
-    fn shape(&self) -> &[usize] {
-        return self.shape();
-    }
+/// Implementation of `eqs_array_t.gather_from` using `Box<dyn Array>`
+unsafe extern fn rust_array_gather_from(
+    output: *mut c_void,
+    input: *const c_void,
+    axis: usize,
+    indices: *const usize,
+    indices_count: usize,
+) -> eqs_status_t {
+    crate::errors::catch_unwind(|| {
+        check_pointers!(output, input);
+        let output = output.cast::<Box<dyn Array>>();
+        let input = input.cast::<Box<dyn Array>>();
 
-    fn reshape(&mut self, shape: &[usize]) {
-        let mut array = std::mem::take(self);
-        array = array.to_shape(shape).expect("invalid shape").to_owned();
-        std::mem::swap(self, &mut array);
-    }
+        let indices = if indices_count == 0 {
+            &[]
+        } else {
+            check_pointers!(indices);
+            std::slice::from_raw_parts(indices, indices_count)
+        };
+        (*output).gather_from(&**input, axis, indices);
+    })
+}
 
-    fn swap_axes(&mut self, axis_1: usize, axis_2: usize) {
-        self.swap_axes(axis_1, axis_2);
-    }
+/// Implementation of `eqs_array_t.scatter_add_from` using `Box<dyn Array>`
+unsafe extern fn rust_array_scatter_add_from(
+    output: *mut c_void,
+    input: *const c_void,
+    axis: usize,
+    indices: *const usize,
+    indices_count: usize,
+) -> eqs_status_t {
+    crate::errors::catch_unwind(|| {
+        check_pointers!(output, input);
+        let output = output.cast::<Box<dyn Array>>();
+        let input = input.cast::<Box<dyn Array>>();
 
-    fn move_samples_from(
-        &mut self,
-        input: &dyn Array,
-        samples: &[eqs_sample_mapping_t],
-        property: Range<usize>,
-    ) {
-        use ndarray::{Axis, Slice};
+        let indices = if indices_count == 0 {
+            &[]
+        } else {
+            check_pointers!(indices);
+            std::slice::from_raw_parts(indices, indices_count)
+        };
+        (*output).scatter_add_from(&**input, axis, indices);
+    })
+}
 
-        // -2 since we also remove one axis with `index_axis_mut` below
-        let property_axis = self.shape().len() - 2;
+/// Implementation of `eqs_array_t.move_data` using `Box<dyn Array>`
+unsafe extern fn rust_array_move_data(
+    output: *mut c_void,
+    input: *const c_void,
+    samples: *const eqs_sample_mapping_t,
+    samples_count: usize,
+    properties: *const usize,
+    properties_count: usize,
+) -> eqs_status_t {
+    crate::errors::catch_unwind(|| {
+        check_pointers!(output, input);
+        let output = output.cast::<Box<dyn Array>>();
+        let input = input.cast::<Box<dyn Array>>();
 
-        let input = input.as_any().downcast_ref::<ndarray::ArrayD<f64>>().expect("input must be a ndarray");
-        for sample in samples {
-            let value = input.index_axis(Axis(0), sample.input);
+        let samples = if samples_count == 0 {
+            &[]
+        } else {
+            check_pointers!(samples);
+            std::slice::from_raw_parts(samples, samples_count)
+        };
+
+        let properties = if properties_count == 0 {
+            &[]
+        } else {
+            check_pointers!(properties);
+            std::slice::from_raw_parts(properties, properties_count)
+        };
+        (*output).move_data(&**input, samples, properties);
+    })
+}
 
-            let mut output_location = self.index_axis_mut(Axis(0), sample.output);
-            let mut output_location = output_location.slice_axis_mut(
-                Axis(property_axis), Slice::from(property.clone())
-            );
+/******************************************************************************/
 
-            output_location.assign(&value);
+macro_rules! impl_array_for_ndarray {
+    ($type: ty, $dtype: expr, $variant: ident) => {
+        impl Array for ndarray::ArrayD<$type> {
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+                self
+            }
+
+            fn create(&self, shape: &[usize]) -> Box<dyn Array> {
+                return Box::new(ndarray::ArrayD::<$type>::zeros(shape));
+            }
+
+            fn copy(&self) -> Box<dyn Array> {
+                return Box::new(self.clone());
+            }
+
+            fn dtype(&self) -> eqs_dtype_t {
+                return $dtype;
+            }
+
+            fn data(&mut self) -> ArrayDataMut<'_> {
+                return ArrayDataMut::$variant(self.as_slice_mut().expect("array is not contiguous"));
+            }
+
+            fn shape(&self) -> &[usize] {
+                return self.shape();
+            }
+
+            fn reshape(&mut self, shape: &[usize]) {
+                let mut array = std::mem::take(self);
+                array = array.to_shape(shape).expect("invalid shape").to_owned();
+                std::mem::swap(self, &mut array);
+            }
+
+            fn swap_axes(&mut self, axis_1: usize, axis_2: usize) {
+                self.swap_axes(axis_1, axis_2);
+            }
+
+            fn move_samples_from(
+                &mut self,
+                input: &dyn Array,
+                samples: &[eqs_sample_mapping_t],
+                property: Range<usize>,
+            ) {
+                use ndarray::{Axis, Slice};
+
+                // -2 since we also remove one axis with `index_axis_mut` below
+                let property_axis = self.shape().len() - 2;
+
+                let input = input.as_any().downcast_ref::<ndarray::ArrayD<$type>>().expect("input must be a ndarray");
+                for sample in samples {
+                    let value = input.index_axis(Axis(0), sample.input);
+
+                    let mut output_location = self.index_axis_mut(Axis(0), sample.output);
+                    let mut output_location = output_location.slice_axis_mut(
+                        Axis(property_axis), Slice::from(property.clone())
+                    );
+
+                    output_location.assign(&value);
+                }
+            }
+
+            fn gather_from(
+                &mut self,
+                input: &dyn Array,
+                axis: usize,
+                indices: &[usize],
+            ) {
+                let input = input.as_any().downcast_ref::<ndarray::ArrayD<$type>>().expect("input must be a ndarray");
+                self.assign(&input.select(ndarray::Axis(axis), indices));
+            }
+
+            fn scatter_add_from(
+                &mut self,
+                input: &dyn Array,
+                axis: usize,
+                indices: &[usize],
+            ) {
+                use ndarray::Axis;
+
+                let input = input.as_any().downcast_ref::<ndarray::ArrayD<$type>>().expect("input must be a ndarray");
+                for (i, &index) in indices.iter().enumerate() {
+                    let value = input.index_axis(Axis(axis), i);
+
+                    let mut output_location = self.index_axis_mut(Axis(axis), index);
+                    output_location += &value;
+                }
+            }
+
+            fn move_data(
+                &mut self,
+                input: &dyn Array,
+                samples: &[eqs_sample_mapping_t],
+                properties: &[usize],
+            ) {
+                use ndarray::Axis;
+
+                // -2 since we also remove one axis with `index_axis_mut` below
+                let property_axis = self.shape().len() - 2;
+
+                let input = input.as_any().downcast_ref::<ndarray::ArrayD<$type>>().expect("input must be a ndarray");
+                for sample in samples {
+                    let value = input.index_axis(Axis(0), sample.input);
+
+                    let mut output_location = self.index_axis_mut(Axis(0), sample.output);
+                    for (input_property, &output_property) in properties.iter().enumerate() {
+                        output_location.index_axis_mut(Axis(property_axis), output_property).assign(
+                            &value.index_axis(Axis(property_axis), input_property)
+                        );
+                    }
+                }
+            }
         }
-    }
+    };
 }
 
+impl_array_for_ndarray!(f64, EQS_DTYPE_FLOAT64, Float64);
+impl_array_for_ndarray!(f32, EQS_DTYPE_FLOAT32, Float32);
+impl_array_for_ndarray!(i32, EQS_DTYPE_INT32, Int32);
+impl_array_for_ndarray!(i64, EQS_DTYPE_INT64, Int64);
+
 /******************************************************************************/
 
 /// An implementation of the [`Array`] trait without any data.
@@ -324,7 +725,11 @@ impl Array for EmptyArray {
         self
     }
 
-    fn data(&mut self) -> &mut [f64] {
+    fn dtype(&self) -> eqs_dtype_t {
+        EQS_DTYPE_FLOAT64
+    }
+
+    fn data(&mut self) -> ArrayDataMut<'_> {
         panic!("can not call Array::data() for EmptyArray");
     }
 
@@ -351,4 +756,16 @@ impl Array for EmptyArray {
     fn move_samples_from(&mut self, _: &dyn Array, _: &[eqs_sample_mapping_t], _: Range<usize>) {
         panic!("can not call Array::move_samples_from() for EmptyArray");
     }
+
+    fn gather_from(&mut self, _: &dyn Array, _: usize, _: &[usize]) {
+        panic!("can not call Array::gather_from() for EmptyArray");
+    }
+
+    fn scatter_add_from(&mut self, _: &dyn Array, _: usize, _: &[usize]) {
+        panic!("can not call Array::scatter_add_from() for EmptyArray");
+    }
+
+    fn move_data(&mut self, _: &dyn Array, _: &[eqs_sample_mapping_t], _: &[usize]) {
+        panic!("can not call Array::move_data() for EmptyArray");
+    }
 }
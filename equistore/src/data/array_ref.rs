@@ -1,12 +1,13 @@
 use std::ffi::CStr;
 
-use crate::c_api::{eqs_array_t, eqs_data_origin_t, eqs_status_t};
-use crate::c_api::{EQS_SUCCESS};
+use crate::c_api::{eqs_array_t, eqs_data_origin_t, eqs_status_t, eqs_dtype_t, eqs_device_t};
+use crate::c_api::{EQS_SUCCESS, EQS_DTYPE_FLOAT64, EQS_DTYPE_FLOAT32, EQS_DTYPE_INT32, EQS_DTYPE_INT64};
+use crate::c_api::EQS_DEVICE_CPU;
 
 use crate::Error;
 use crate::data::origin::get_data_origin;
 
-use super::Array;
+use super::{Array, ArrayDataMut};
 
 /// Reference to a data array in equistore-core
 ///
@@ -93,6 +94,46 @@ impl<'a> ArrayRef<'a> {
     pub fn as_raw(&self) -> &eqs_array_t {
         &self.array
     }
+
+    /// Get the data in this `ArrayRef` as a slice of 64-bit floating point
+    /// values, using `eqs_array_t.data`. This works with arrays from any
+    /// origin, as long as they can give access to their data in this form.
+    ///
+    /// This function returns an error if the array does not contain 64-bit
+    /// floating point values.
+    pub fn data(&self) -> Result<&'a [f64], Error> {
+        if self.array.dtype()? != EQS_DTYPE_FLOAT64 {
+            return Err(Error {
+                code: None,
+                message: "expected an array containing 64-bit floating point values".into(),
+            });
+        }
+
+        let len = self.array.shape()?.iter().product::<usize>();
+        if len == 0 {
+            return Ok(&[]);
+        }
+
+        if self.array.device()? != EQS_DEVICE_CPU {
+            return Err(Error {
+                code: None,
+                message: "can not directly access the data of an array which is not on CPU".into(),
+            });
+        }
+
+        let function = self.array.data.expect("eqs_array_t.data function is NULL");
+
+        let mut data_ptr = std::ptr::null_mut();
+        let data = unsafe {
+            check_status_external(
+                function(self.array.ptr, &mut data_ptr),
+                "eqs_array_t.data"
+            )?;
+            std::slice::from_raw_parts(data_ptr.cast(), len)
+        };
+
+        return Ok(data);
+    }
 }
 
 /// Mutable reference to a data array in equistore-core
@@ -253,7 +294,10 @@ impl eqs_array_t {
         eqs_array_t {
             ptr: std::ptr::null_mut(),
             origin: None,
+            dtype: None,
             data: None,
+            device: None,
+            copy_to_host: None,
             shape: None,
             reshape: None,
             swap_axes: None,
@@ -261,6 +305,17 @@ impl eqs_array_t {
             copy: None,
             destroy: None,
             move_samples_from: None,
+            gather_from: None,
+            scatter_add_from: None,
+            move_data: None,
+        }
+    }
+
+    /// call `eqs_array_check` on this array, to check that it correctly
+    /// implements all the functions in `eqs_array_t`
+    pub fn check(&self) -> Result<(), Error> {
+        unsafe {
+            return crate::errors::check_status(crate::c_api::eqs_array_check(self));
         }
     }
 
@@ -279,6 +334,88 @@ impl eqs_array_t {
         return Ok(origin);
     }
 
+    /// call `eqs_array_t.dtype` with a more convenient API. Arrays without a
+    /// `dtype` function contain 64-bit floating point values.
+    pub fn dtype(&self) -> Result<eqs_dtype_t, Error> {
+        let function = match self.dtype {
+            Some(function) => function,
+            None => return Ok(EQS_DTYPE_FLOAT64),
+        };
+
+        let mut dtype = 0;
+        unsafe {
+            check_status_external(
+                function(self.ptr, &mut dtype),
+                "eqs_array_t.dtype",
+            )?;
+        }
+
+        if !(EQS_DTYPE_FLOAT64..=EQS_DTYPE_INT64).contains(&dtype) {
+            return Err(Error {
+                code: None,
+                message: format!("eqs_array_t.dtype returned an unknown data type ({})", dtype),
+            });
+        }
+
+        return Ok(dtype);
+    }
+
+    /// call `eqs_array_t.device` with a more convenient API. Arrays without a
+    /// `device` function live in host memory.
+    pub fn device(&self) -> Result<eqs_device_t, Error> {
+        let function = match self.device {
+            Some(function) => function,
+            None => return Ok(EQS_DEVICE_CPU),
+        };
+
+        let mut device = 0;
+        unsafe {
+            check_status_external(
+                function(self.ptr, &mut device),
+                "eqs_array_t.device",
+            )?;
+        }
+
+        return Ok(device);
+    }
+
+    /// Copy all the data of this array to a new host memory buffer, as raw
+    /// bytes, using either `eqs_array_t.data` for arrays on CPU or
+    /// `eqs_array_t.copy_to_host` for arrays on other devices.
+    pub fn copy_to_host(&mut self) -> Result<Vec<u8>, Error> {
+        let element_size = match self.dtype()? {
+            EQS_DTYPE_FLOAT64 | EQS_DTYPE_INT64 => 8,
+            EQS_DTYPE_FLOAT32 | EQS_DTYPE_INT32 => 4,
+            _ => 2,
+        };
+        let mut buffer = vec![0_u8; self.shape()?.iter().product::<usize>() * element_size];
+        if buffer.is_empty() {
+            return Ok(buffer);
+        }
+
+        if self.device()? == EQS_DEVICE_CPU {
+            let data = self.data()?.as_mut_ptr();
+            unsafe {
+                std::ptr::copy_nonoverlapping(data.cast::<u8>(), buffer.as_mut_ptr(), buffer.len());
+            }
+            return Ok(buffer);
+        }
+
+        let function = self.copy_to_host.ok_or_else(|| Error {
+            code: None,
+            message: "eqs_array_t.copy_to_host function is NULL for an array which is not on CPU".into(),
+        })?;
+
+        unsafe {
+            check_status_external(
+                function(self.ptr, buffer.as_mut_ptr().cast(), buffer.len()),
+                "eqs_array_t.copy_to_host",
+            )?;
+        }
+
+        return Ok(buffer);
+    }
+
     /// call `eqs_array_t.shape` with a more convenient API
     #[allow(clippy::cast_possible_truncation)]
     pub fn shape(&self) -> Result<&[usize], Error> {
@@ -301,12 +438,22 @@ impl eqs_array_t {
         return Ok(shape);
     }
 
-    /// call `eqs_array_t.data` with a more convenient API
-    pub fn data(&mut self) -> Result<&mut [f64], Error> {
-        let shape = self.shape()?;
-        let mut len = 1;
-        for s in shape {
-            len *= s;
+    /// call `eqs_array_t.data` with a more convenient API, using
+    /// `eqs_array_t.dtype` to get the type of the data.
+    pub fn data(&mut self) -> Result<ArrayDataMut<'_>, Error> {
+        let dtype = self.dtype()?;
+        let len = self.shape()?.iter().product::<usize>();
+        if len == 0 {
+            return Ok(unsafe {
+                ArrayDataMut::from_raw(dtype, std::ptr::NonNull::<u64>::dangling().as_ptr().cast(), 0)
+            });
+        }
+
+        if self.device()? != EQS_DEVICE_CPU {
+            return Err(Error {
+                code: None,
+                message: "can not directly access the data of an array which is not on CPU".into(),
+            });
         }
 
         let function = self.data.expect("eqs_array_t.data function is NULL");
@@ -317,7 +464,7 @@ impl eqs_array_t {
                 function(self.ptr, &mut data_ptr),
                 "eqs_array_t.data"
             )?;
-            std::slice::from_raw_parts_mut(data_ptr, len)
+            ArrayDataMut::from_raw(dtype, data_ptr, len)
         };
 
         return Ok(data);
@@ -355,18 +502,7 @@ impl eqs_array_t {
     pub fn create(&self, shape: &[usize]) -> Result<eqs_array_t, Error> {
         let function = self.create.expect("eqs_array_t.create function is NULL");
 
-        let mut data_storage = eqs_array_t {
-            ptr: std::ptr::null_mut(),
-            origin: None,
-            data: None,
-            shape: None,
-            reshape: None,
-            swap_axes: None,
-            create: None,
-            copy: None,
-            destroy: None,
-            move_samples_from: None
-        };
+        let mut data_storage = eqs_array_t::null();
         unsafe {
             check_status_external(
                 function(self.ptr, shape.as_ptr(), shape.len(), &mut data_storage),
@@ -402,6 +538,82 @@ impl eqs_array_t {
 
         return Ok(());
     }
+
+    /// call `eqs_array_t.gather_from` with a more convenient API
+    pub fn gather_from(
+        &mut self,
+        input: &eqs_array_t,
+        axis: usize,
+        indices: &[usize],
+    ) -> Result<(), Error> {
+        let function = self.gather_from.expect("eqs_array_t.gather_from function is NULL");
+
+        unsafe {
+            check_status_external(
+                function(
+                    self.ptr,
+                    input.ptr,
+                    axis,
+                    indices.as_ptr(),
+                    indices.len(),
+                ),
+                "eqs_array_t.gather_from",
+            )?;
+        }
+
+        return Ok(());
+    }
+
+    /// call `eqs_array_t.scatter_add_from` with a more convenient API
+    pub fn scatter_add_from(
+        &mut self,
+        input: &eqs_array_t,
+        axis: usize,
+        indices: &[usize],
+    ) -> Result<(), Error> {
+        let function = self.scatter_add_from.expect("eqs_array_t.scatter_add_from function is NULL");
+
+        unsafe {
+            check_status_external(
+                function(
+                    self.ptr,
+                    input.ptr,
+                    axis,
+                    indices.as_ptr(),
+                    indices.len(),
+                ),
+                "eqs_array_t.scatter_add_from",
+            )?;
+        }
+
+        return Ok(());
+    }
+
+    /// call `eqs_array_t.move_data` with a more convenient API
+    pub fn move_data(
+        &mut self,
+        input: &eqs_array_t,
+        samples: &[crate::c_api::eqs_sample_mapping_t],
+        properties: &[usize],
+    ) -> Result<(), Error> {
+        let function = self.move_data.expect("eqs_array_t.move_data function is NULL");
+
+        unsafe {
+            check_status_external(
+                function(
+                    self.ptr,
+                    input.ptr,
+                    samples.as_ptr(),
+                    samples.len(),
+                    properties.as_ptr(),
+                    properties.len(),
+                ),
+                "eqs_array_t.move_data",
+            )?;
+        }
+
+        return Ok(());
+    }
 }
 
 /// Check the status code returned by arbitrary functions inside an
@@ -4,7 +4,7 @@ mod array_ref;
 pub use self::array_ref::{ArrayRef, ArrayRefMut};
 
 mod array;
-pub use self::array::Array;
+pub use self::array::{Array, ArrayDataMut, check_array};
 pub use self::array::EmptyArray;
 
 
@@ -66,4 +66,58 @@ mod tests {
         ]).unwrap();
         assert_eq!(other.as_array(), expected);
     }
+
+    #[test]
+    fn gather_from() {
+        let array = ArrayD::from_shape_vec(vec![2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let array = Box::new(array) as Box<dyn Array>;
+        let array = unsafe { ArrayRef::from_raw(array.into()) };
+
+        let mut other = unsafe { ArrayRefMut::new(array.as_raw().create(&[2, 2]).unwrap()) };
+        other.as_raw_mut().gather_from(array.as_raw(), 1, &[2, 0]).unwrap();
+        let expected = ArrayD::from_shape_vec(vec![2, 2], vec![3.0, 1.0, 6.0, 4.0]).unwrap();
+        assert_eq!(other.as_array(), expected);
+
+        let mut other = unsafe { ArrayRefMut::new(array.as_raw().create(&[1, 3]).unwrap()) };
+        other.as_raw_mut().gather_from(array.as_raw(), 0, &[1]).unwrap();
+        let expected = ArrayD::from_shape_vec(vec![1, 3], vec![4.0, 5.0, 6.0]).unwrap();
+        assert_eq!(other.as_array(), expected);
+    }
+
+    #[test]
+    fn scatter_add_from() {
+        let array = ArrayD::from_shape_vec(vec![2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let array = Box::new(array) as Box<dyn Array>;
+        let array = unsafe { ArrayRef::from_raw(array.into()) };
+
+        let mut other = unsafe { ArrayRefMut::new(array.as_raw().create(&[2, 2]).unwrap()) };
+        other.as_raw_mut().scatter_add_from(array.as_raw(), 1, &[1, 0, 1]).unwrap();
+        let expected = ArrayD::from_shape_vec(vec![2, 2], vec![2.0, 4.0, 5.0, 10.0]).unwrap();
+        assert_eq!(other.as_array(), expected);
+
+        let mut other = unsafe { ArrayRefMut::new(array.as_raw().create(&[1, 3]).unwrap()) };
+        other.as_raw_mut().scatter_add_from(array.as_raw(), 0, &[0, 0]).unwrap();
+        let expected = ArrayD::from_shape_vec(vec![1, 3], vec![5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(other.as_array(), expected);
+    }
+
+    #[test]
+    fn move_data() {
+        let array = ArrayD::from_shape_vec(vec![2, 1, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let array = Box::new(array) as Box<dyn Array>;
+        let array = unsafe { ArrayRef::from_raw(array.into()) };
+
+        let mut other = unsafe { ArrayRefMut::new(array.as_raw().create(&[3, 1, 4]).unwrap()) };
+        let samples = [
+            eqs_sample_mapping_t { input: 0, output: 2 },
+            eqs_sample_mapping_t { input: 1, output: 0 },
+        ];
+        other.as_raw_mut().move_data(array.as_raw(), &samples, &[3, 0, 1]).unwrap();
+        let expected = ArrayD::from_shape_vec(vec![3, 1, 4], vec![
+            5.0, 6.0, 0.0, 4.0,
+            0.0, 0.0, 0.0, 0.0,
+            2.0, 3.0, 0.0, 1.0,
+        ]).unwrap();
+        assert_eq!(other.as_array(), expected);
+    }
 }
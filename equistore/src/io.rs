@@ -2,15 +2,17 @@
 
 use std::ffi::CString;
 
-use crate::c_api::{eqs_array_t, eqs_status_t};
+use crate::c_api::{eqs_array_t, eqs_status_t, eqs_dtype_t};
+use crate::c_api::{EQS_DTYPE_FLOAT64, EQS_DTYPE_FLOAT32, EQS_DTYPE_INT32, EQS_DTYPE_INT64};
 use crate::errors::{check_status, check_ptr};
 use crate::{TensorMap, Error, Array};
 
 /// Load the serialized tensor map from the given path.
 ///
-/// Arrays for the values and gradient data will be created with the given
-/// `create_array` callback, and filled by this function with the corresponding
-/// data.
+/// Arrays for the values and gradient data will be created as `ndarray::ArrayD`
+/// with the same data type as in the file (`f64`, `f32`, `i32` or `i64`), and
+/// filled by this function with the corresponding data. Files containing
+/// 16-bit floating point data can not be loaded with this function.
 ///
 /// `TensorMap` are serialized using numpy's `.npz` format, i.e. a ZIP file
 /// without compression (storage method is STORED), where each file is stored as
@@ -21,8 +23,11 @@ use crate::{TensorMap, Error, Array};
 ///
 /// We add other restriction on top of these formats when saving/loading data.
 /// First, `Labels` instances are saved as structured array, see the `labels`
-/// module for more information. Only 32-bit integers are supported for Labels,
-/// and only 64-bit floats are supported for data (values and gradients).
+/// module for more information. Labels are saved using 64-bit integers (32-bit
+/// integers are also supported when loading older files). Data (values and
+/// gradients) can contain 64-bit, 32-bit or 16-bit floating point values
+/// (`<f8`, `<f4` and `<f2` in numpy notation), as well as 32-bit or 64-bit
+/// signed integers (`<i4` and `<i8`).
 ///
 /// Second, the path of the files in the archive also carry meaning. The keys of
 /// the `TensorMap` are stored in `/keys.npy`, and then different blocks are
@@ -77,11 +82,18 @@ pub fn save(path: impl AsRef<std::path::Path>, tensor: &TensorMap) -> Result<(),
 unsafe extern fn create_ndarray(
     shape_ptr: *const usize,
     shape_count: usize,
+    dtype: eqs_dtype_t,
     c_array: *mut eqs_array_t,
 ) -> eqs_status_t {
     crate::errors::catch_unwind(|| {
         let shape = std::slice::from_raw_parts(shape_ptr, shape_count);
-        let array = ndarray::ArrayD::from_elem(shape, 0.0);
-        *c_array = (Box::new(array) as Box<dyn Array>).into();
+        let array: Box<dyn Array> = match dtype {
+            EQS_DTYPE_FLOAT64 => Box::new(ndarray::ArrayD::<f64>::zeros(shape)),
+            EQS_DTYPE_FLOAT32 => Box::new(ndarray::ArrayD::<f32>::zeros(shape)),
+            EQS_DTYPE_INT32 => Box::new(ndarray::ArrayD::<i32>::zeros(shape)),
+            EQS_DTYPE_INT64 => Box::new(ndarray::ArrayD::<i64>::zeros(shape)),
+            _ => panic!("can not load data with type {} in ndarray::ArrayD", dtype),
+        };
+        *c_array = array.into();
     })
 }
@@ -7,6 +7,7 @@ use smallvec::SmallVec;
 
 use crate::c_api::eqs_labels_t;
 use crate::errors::check_status;
+use crate::Error;
 
 impl eqs_labels_t {
     /// Create an `eqs_labels_t` with all members set to null pointers/zero
@@ -23,20 +24,34 @@ impl eqs_labels_t {
 
 /// A single value inside a label.
 ///
-/// This is represented as a 32-bit signed integer, with a couple of helper
+/// This is represented as a 64-bit signed integer, with a couple of helper
 /// function to get its value as usize/isize.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
-pub struct LabelValue(i32);
+pub struct LabelValue(i64);
 
 impl PartialEq<i32> for LabelValue {
     #[inline]
     fn eq(&self, other: &i32) -> bool {
-        self.0 == *other
+        self.0 == i64::from(*other)
     }
 }
 
 impl PartialEq<LabelValue> for i32 {
+    #[inline]
+    fn eq(&self, other: &LabelValue) -> bool {
+        i64::from(*self) == other.0
+    }
+}
+
+impl PartialEq<i64> for LabelValue {
+    #[inline]
+    fn eq(&self, other: &i64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<LabelValue> for i64 {
     #[inline]
     fn eq(&self, other: &LabelValue) -> bool {
         *self == other.0
@@ -55,50 +70,58 @@ impl std::fmt::Display for LabelValue {
     }
 }
 
-#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
 impl From<u32> for LabelValue {
     #[inline]
     fn from(value: u32) -> LabelValue {
-        assert!(value < i32::MAX as u32);
-        LabelValue(value as i32)
+        LabelValue(i64::from(value))
     }
 }
 
 impl From<i32> for LabelValue {
     #[inline]
     fn from(value: i32) -> LabelValue {
+        LabelValue(i64::from(value))
+    }
+}
+
+impl From<u64> for LabelValue {
+    #[inline]
+    fn from(value: u64) -> LabelValue {
+        LabelValue(i64::try_from(value).expect("label value does not fit in a 64-bit integer"))
+    }
+}
+
+impl From<i64> for LabelValue {
+    #[inline]
+    fn from(value: i64) -> LabelValue {
         LabelValue(value)
     }
 }
 
-#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
 impl From<usize> for LabelValue {
     #[inline]
     fn from(value: usize) -> LabelValue {
-        assert!(value < i32::MAX as usize);
-        LabelValue(value as i32)
+        LabelValue(i64::try_from(value).expect("label value does not fit in a 64-bit integer"))
     }
 }
 
-#[allow(clippy::cast_possible_truncation)]
 impl From<isize> for LabelValue {
     #[inline]
     fn from(value: isize) -> LabelValue {
-        assert!(value < i32::MAX as isize && value > i32::MIN as isize);
-        LabelValue(value as i32)
+        LabelValue(value as i64)
     }
 }
 
 impl LabelValue {
     /// Create a `LabelValue` with the given `value`
     #[inline]
-    pub fn new(value: i32) -> LabelValue {
+    pub fn new(value: i64) -> LabelValue {
         LabelValue(value)
     }
 
     /// Get the integer value of this `LabelValue` as a usize
     #[inline]
-    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
     pub fn usize(self) -> usize {
         debug_assert!(self.0 >= 0);
         self.0 as usize
@@ -106,13 +129,23 @@ impl LabelValue {
 
     /// Get the integer value of this `LabelValue` as an isize
     #[inline]
+    #[allow(clippy::cast_possible_truncation)]
     pub fn isize(self) -> isize {
         self.0 as isize
     }
 
-    /// Get the integer value of this `LabelValue` as an i32
+    /// Get the integer value of this `LabelValue` as an i32.
+    ///
+    /// This function panics if the value does not fit in 32 bits, use
+    /// [`LabelValue::i64`] to get the full value.
     #[inline]
     pub fn i32(self) -> i32 {
+        i32::try_from(self.0).expect("label value does not fit in a 32-bit integer")
+    }
+
+    /// Get the integer value of this `LabelValue` as an i64
+    #[inline]
+    pub fn i64(self) -> i64 {
         self.0
     }
 }
@@ -277,6 +310,46 @@ impl Labels {
         return result.try_into().ok();
     }
 
+    /// Get the positions of multiple entries in these labels at once, or None
+    /// for the entries which are not part of these labels.
+    ///
+    /// `entries` should contain the values of all the entries to look for, as
+    /// a row-major 2D array with `self.size()` columns.
+    pub fn positions(&self, entries: &[LabelValue]) -> Vec<Option<usize>> {
+        return self.positions_impl(entries, false);
+    }
+
+    /// Same as [`Labels::positions`], but looking for the entries in parallel
+    /// using multiple threads.
+    #[cfg(feature = "rayon")]
+    pub fn par_positions(&self, entries: &[LabelValue]) -> Vec<Option<usize>> {
+        return self.positions_impl(entries, true);
+    }
+
+    /// Implementation of `positions` and `par_positions`
+    fn positions_impl(&self, entries: &[LabelValue], parallel: bool) -> Vec<Option<usize>> {
+        if self.size() == 0 {
+            assert!(entries.is_empty(), "invalid size of entries in Labels::positions");
+            return Vec::new();
+        }
+
+        assert!(entries.len() % self.size() == 0, "invalid size of entries in Labels::positions");
+        let count = entries.len() / self.size();
+
+        let mut results = vec![-1; count];
+        unsafe {
+            check_status(crate::c_api::eqs_labels_positions(
+                self.raw,
+                entries.as_ptr().cast(),
+                count,
+                parallel,
+                results.as_mut_ptr(),
+            )).expect("failed to check labels positions");
+        }
+
+        return results.into_iter().map(|i| i.try_into().ok()).collect();
+    }
+
     /// Iterate over the entries in this set of labels
     #[inline]
     pub fn iter(&self) -> LabelsIter<'_> {
@@ -295,6 +368,29 @@ impl Labels {
         };
     }
 
+    /// Iterate over the entries in this set of labels as values of type `T`,
+    /// created with [`FromLabelsRow`].
+    ///
+    /// This returns an error if some of the variables required by `T` are not
+    /// part of these labels.
+    #[inline]
+    pub fn iter_as<T: FromLabelsRow>(&self) -> Result<LabelsTypedIter<'_, T>, Error> {
+        return Ok(LabelsTypedIter {
+            positions: T::positions(self)?,
+            chunks: self.values().chunks_exact(self.raw.size),
+        });
+    }
+
+    /// Get the position of the variable with the given `name` in these labels,
+    /// or an error if there is no such variable.
+    #[inline]
+    pub fn variable_position(&self, name: &str) -> Result<usize, Error> {
+        return self.names().iter().position(|&n| n == name).ok_or_else(|| Error {
+            code: None,
+            message: format!("'{}' is not part of these labels", name),
+        });
+    }
+
     /// Iterate over the entries in this set of labels as fixed-size arrays
     #[inline]
     pub fn iter_fixed_size<const N: usize>(&self) -> LabelsFixedSizeIter<N> {
@@ -309,7 +405,268 @@ impl Labels {
         };
     }
 
+    /// Check whether the entries in these labels are sorted in lexicographic
+    /// order.
+    #[inline]
+    pub fn is_sorted(&self) -> bool {
+        let mut is_sorted = false;
+        unsafe {
+            check_status(crate::c_api::eqs_labels_is_sorted(
+                self.raw,
+                &mut is_sorted,
+            )).expect("failed to check if labels are sorted");
+        }
+        return is_sorted;
+    }
+
+    /// Sort the entries in these labels in lexicographic order.
+    ///
+    /// This returns the sorted labels, and the permutation used to sort them:
+    /// the entry at position `i` in the sorted labels was at position
+    /// `permutation[i]` in the original labels.
+    #[inline]
+    pub fn sort(&self) -> Result<(Labels, Vec<usize>), Error> {
+        let mut result = eqs_labels_t::null();
+        let mut permutation = vec![0_i64; self.count()];
+
+        unsafe {
+            check_status(crate::c_api::eqs_labels_sort(
+                self.raw,
+                &mut result,
+                permutation.as_mut_ptr(),
+                permutation.len(),
+            ))?;
+        }
+
+        let result = unsafe { Labels::from_raw(result) };
+        let permutation = permutation.into_iter().map(|i| usize::try_from(i).expect("invalid permutation")).collect();
+
+        return Ok((result, permutation));
+    }
+
+    /// Project these labels onto the variables with the given `names`, i.e.
+    /// only keep these variables (in the given order), removing duplicated
+    /// entries. The remaining entries are kept in the order of their first
+    /// appearance.
+    #[inline]
+    pub fn project(&self, names: &[&str]) -> Result<Labels, Error> {
+        let names = names.iter().map(|&name| CString::new(name).expect("invalid C string")).collect::<Vec<_>>();
+        let names_ptr = names.iter().map(|name| name.as_ptr()).collect::<Vec<_>>();
+
+        let mut result = eqs_labels_t::null();
+        unsafe {
+            check_status(crate::c_api::eqs_labels_project(
+                self.raw,
+                names_ptr.as_ptr(),
+                names_ptr.len(),
+                &mut result,
+            ))?;
+            return Ok(Labels::from_raw(result));
+        }
+    }
+
+    /// Insert a new variable with the given `name` at position `index` in
+    /// these labels.
+    ///
+    /// `values` must either contain a single value, used for all entries, or
+    /// one value for each entry in these labels.
+    #[inline]
+    pub fn insert(&self, index: usize, name: &str, values: &[LabelValue]) -> Result<Labels, Error> {
+        let name = CString::new(name).expect("invalid C string");
+
+        let mut result = eqs_labels_t::null();
+        unsafe {
+            check_status(crate::c_api::eqs_labels_insert(
+                self.raw,
+                index,
+                name.as_ptr(),
+                values.as_ptr().cast(),
+                values.len(),
+                &mut result,
+            ))?;
+            return Ok(Labels::from_raw(result));
+        }
+    }
+
+    /// Remove the variable with the given `name` from these labels.
+    ///
+    /// This returns an error if removing the variable would create duplicated
+    /// entries, use [`Labels::project`] to remove the duplicates instead.
+    #[inline]
+    pub fn remove(&self, name: &str) -> Result<Labels, Error> {
+        let name = CString::new(name).expect("invalid C string");
+
+        let mut result = eqs_labels_t::null();
+        unsafe {
+            check_status(crate::c_api::eqs_labels_remove(
+                self.raw,
+                name.as_ptr(),
+                &mut result,
+            ))?;
+            return Ok(Labels::from_raw(result));
+        }
+    }
+
+    /// Rename the variable `old_name` to `new_name` in these labels.
+    #[inline]
+    pub fn rename(&self, old_name: &str, new_name: &str) -> Result<Labels, Error> {
+        let old_name = CString::new(old_name).expect("invalid C string");
+        let new_name = CString::new(new_name).expect("invalid C string");
+
+        let mut result = eqs_labels_t::null();
+        unsafe {
+            check_status(crate::c_api::eqs_labels_rename(
+                self.raw,
+                old_name.as_ptr(),
+                new_name.as_ptr(),
+                &mut result,
+            ))?;
+            return Ok(Labels::from_raw(result));
+        }
+    }
+
+    /// Reorder the variables in these labels, following the given `names`.
+    /// The `names` must contain all the variables of these labels exactly
+    /// once. The order of the entries is not modified.
+    #[inline]
+    pub fn reorder(&self, names: &[&str]) -> Result<Labels, Error> {
+        let names = names.iter().map(|&name| CString::new(name).expect("invalid C string")).collect::<Vec<_>>();
+        let names_ptr = names.iter().map(|name| name.as_ptr()).collect::<Vec<_>>();
+
+        let mut result = eqs_labels_t::null();
+        unsafe {
+            check_status(crate::c_api::eqs_labels_reorder(
+                self.raw,
+                names_ptr.as_ptr(),
+                names_ptr.len(),
+                &mut result,
+            ))?;
+            return Ok(Labels::from_raw(result));
+        }
+    }
+
+    /// Get the positions of all the entries in these labels matching the
+    /// `selection`.
+    ///
+    /// The `selection` must contain a subset of the names of these labels, and
+    /// an entry is selected if the values of these variables match any of the
+    /// entries in the `selection`. If the `selection` does not contain any
+    /// variable, all entries are selected. The positions are returned in
+    /// increasing order.
+    #[inline]
+    pub fn select(&self, selection: &Labels) -> Result<Vec<usize>, Error> {
+        let mut selected = vec![0; self.count()];
+        let mut selected_count = selected.len();
+
+        unsafe {
+            check_status(crate::c_api::eqs_labels_select(
+                self.raw,
+                selection.raw,
+                selected.as_mut_ptr(),
+                &mut selected_count,
+            ))?;
+        }
+        selected.truncate(selected_count);
+
+        return Ok(selected);
+    }
+
+    /// Get the union of `self` and `other`, i.e. all the entries which are in
+    /// either of them. The entries of `self` come first in the result, followed
+    /// by the entries of `other` which are not in `self`.
+    ///
+    /// This also returns the mapping from each entry of `self` (first mapping)
+    /// and `other` (second mapping) to the position of the same entry in the
+    /// union.
+    #[inline]
+    pub fn union(&self, other: &Labels) -> Result<(Labels, Vec<usize>, Vec<usize>), Error> {
+        let mut result = eqs_labels_t::null();
+        let mut first_mapping = vec![0_i64; self.count()];
+        let mut second_mapping = vec![0_i64; other.count()];
+
+        unsafe {
+            check_status(crate::c_api::eqs_labels_union(
+                self.raw,
+                other.raw,
+                &mut result,
+                first_mapping.as_mut_ptr(),
+                first_mapping.len(),
+                second_mapping.as_mut_ptr(),
+                second_mapping.len(),
+            ))?;
+        }
+
+        let result = unsafe { Labels::from_raw(result) };
+        let first_mapping = first_mapping.into_iter().map(|i| usize::try_from(i).expect("invalid mapping")).collect();
+        let second_mapping = second_mapping.into_iter().map(|i| usize::try_from(i).expect("invalid mapping")).collect();
+
+        return Ok((result, first_mapping, second_mapping));
+    }
+
+    /// Get the intersection of `self` and `other`, i.e. all the entries which
+    /// are in both of them, in the same order as in `self`.
+    ///
+    /// This also returns the mapping from each entry of `self` (first mapping)
+    /// and `other` (second mapping) to the position of the same entry in the
+    /// intersection, or `None` if the entry is not part of the intersection.
+    #[allow(clippy::type_complexity)]
+    #[inline]
+    pub fn intersection(&self, other: &Labels) -> Result<(Labels, Vec<Option<usize>>, Vec<Option<usize>>), Error> {
+        let mut result = eqs_labels_t::null();
+        let mut first_mapping = vec![0_i64; self.count()];
+        let mut second_mapping = vec![0_i64; other.count()];
+
+        unsafe {
+            check_status(crate::c_api::eqs_labels_intersection(
+                self.raw,
+                other.raw,
+                &mut result,
+                first_mapping.as_mut_ptr(),
+                first_mapping.len(),
+                second_mapping.as_mut_ptr(),
+                second_mapping.len(),
+            ))?;
+        }
+
+        let result = unsafe { Labels::from_raw(result) };
+        let first_mapping = first_mapping.into_iter().map(|i| i.try_into().ok()).collect();
+        let second_mapping = second_mapping.into_iter().map(|i| i.try_into().ok()).collect();
+
+        return Ok((result, first_mapping, second_mapping));
+    }
+
+    /// Get the difference of `self` and `other`, i.e. all the entries in
+    /// `self` which are not in `other`, in the same order as in `self`.
+    ///
+    /// This also returns the mapping from each entry of `self` to the position
+    /// of the same entry in the difference, or `None` if the entry is not part
+    /// of the difference.
+    #[inline]
+    pub fn difference(&self, other: &Labels) -> Result<(Labels, Vec<Option<usize>>), Error> {
+        let mut result = eqs_labels_t::null();
+        let mut mapping = vec![0_i64; self.count()];
+
+        unsafe {
+            check_status(crate::c_api::eqs_labels_difference(
+                self.raw,
+                other.raw,
+                &mut result,
+                mapping.as_mut_ptr(),
+                mapping.len(),
+            ))?;
+        }
+
+        let result = unsafe { Labels::from_raw(result) };
+        let mapping = mapping.into_iter().map(|i| i.try_into().ok()).collect();
+
+        return Ok((result, mapping));
+    }
+
     pub(crate) fn values(&self) -> &[LabelValue] {
+        if self.count() == 0 || self.size() == 0 {
+            return &[];
+        }
+
         unsafe {
             std::slice::from_raw_parts(self.raw.values.cast(), self.count() * self.size())
         }
@@ -465,6 +822,86 @@ impl<'a, const N: usize> ExactSizeIterator for LabelsFixedSizeIter<'a, N> {
     }
 }
 
+/// Trait for types which can be created from a single entry in [`Labels`],
+/// using the names of the variables instead of their order.
+///
+/// The position of the variables is resolved once with
+/// [`FromLabelsRow::positions`], and then used to create a value for each
+/// entry with [`FromLabelsRow::from_row`]. Use [`Labels::iter_as`] to iterate
+/// over labels as values of a type implementing this trait.
+///
+/// ```
+/// # use equistore::{Error, FromLabelsRow, Labels, LabelValue};
+/// struct Pair {
+///     first: i64,
+///     second: i64,
+/// }
+///
+/// impl FromLabelsRow for Pair {
+///     type Positions = [usize; 2];
+///
+///     fn positions(labels: &Labels) -> Result<[usize; 2], Error> {
+///         return Ok([
+///             labels.variable_position("first")?,
+///             labels.variable_position("second")?,
+///         ]);
+///     }
+///
+///     fn from_row(positions: &[usize; 2], row: &[LabelValue]) -> Pair {
+///         return Pair {
+///             first: row[positions[0]].i64(),
+///             second: row[positions[1]].i64(),
+///         };
+///     }
+/// }
+///
+/// let labels = Labels::new(["second", "first"], &[[1, 2], [3, 4]]);
+/// let pairs = labels.iter_as::<Pair>().unwrap().collect::<Vec<_>>();
+/// assert_eq!(pairs[1].first, 4);
+/// assert_eq!(pairs[1].second, 3);
+/// ```
+pub trait FromLabelsRow: Sized {
+    /// Positions of the variables used to create values of this type, for
+    /// example as an array of `usize`.
+    type Positions;
+
+    /// Find the positions of the variables used by this type in `labels`,
+    /// returning an error if some of them are missing.
+    fn positions(labels: &Labels) -> Result<Self::Positions, Error>;
+
+    /// Create a new value from a single entry in the labels, using the
+    /// `positions` computed by [`FromLabelsRow::positions`].
+    fn from_row(positions: &Self::Positions, row: &[LabelValue]) -> Self;
+}
+
+/// Iterator over [`Labels`] entries as values of a type implementing
+/// [`FromLabelsRow`]
+pub struct LabelsTypedIter<'a, T: FromLabelsRow> {
+    positions: T::Positions,
+    chunks: std::slice::ChunksExact<'a, LabelValue>,
+}
+
+impl<T: FromLabelsRow> Iterator for LabelsTypedIter<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.chunks.next()?;
+        return Some(T::from_row(&self.positions, row));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<T: FromLabelsRow> ExactSizeIterator for LabelsTypedIter<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
 /// Builder for [`Labels`]
 #[derive(Debug, Clone)]
 pub struct LabelsBuilder {
@@ -519,6 +956,12 @@ impl LabelsBuilder {
     /// Finish building the `Labels`
     #[inline]
     pub fn finish(self) -> Labels {
+        return self.try_finish().expect("invalid labels?");
+    }
+
+    /// Finish building the `Labels`, returning an error if the labels are
+    /// invalid (for example if they contain the same entry multiple times).
+    pub(crate) fn try_finish(self) -> Result<Labels, Error> {
         let mut raw_names = Vec::new();
         let mut raw_names_ptr = Vec::new();
         for name in &self.names {
@@ -527,21 +970,25 @@ impl LabelsBuilder {
             raw_names.push(name);
         }
 
+        let count = if self.size() == 0 {
+            0
+        } else {
+            self.values.len() / self.size()
+        };
+
         let mut raw_labels = eqs_labels_t {
             internal_ptr_: std::ptr::null_mut(),
             names: raw_names_ptr.as_ptr(),
             values: self.values.as_ptr().cast(),
             size: self.size(),
-            count: self.values.len() / self.size(),
+            count: count,
         };
 
         unsafe {
-            check_status(
-                crate::c_api::eqs_labels_create(&mut raw_labels)
-            ).expect("invalid labels?");
+            check_status(crate::c_api::eqs_labels_create(&mut raw_labels))?;
         }
 
-        return unsafe { Labels::from_raw(raw_labels) };
+        return Ok(unsafe { Labels::from_raw(raw_labels) });
     }
 }
 
@@ -648,6 +1095,108 @@ mod tests {
         assert_eq!(labels[2], [-4, -2413]);
     }
 
+    #[test]
+    fn position() {
+        let mut builder = LabelsBuilder::new(vec!["foo", "bar"]);
+        for i in 0..1000 {
+            builder.add(&[i / 10, i % 10]);
+        }
+        let labels = builder.finish();
+
+        assert_eq!(labels.position(&[0.into(), 0.into()]), Some(0));
+        assert_eq!(labels.position(&[42.into(), 3.into()]), Some(423));
+        assert_eq!(labels.position(&[99.into(), 9.into()]), Some(999));
+        assert_eq!(labels.position(&[100.into(), 0.into()]), None);
+
+        assert!(labels.contains(&[12.into(), 5.into()]));
+        assert!(!labels.contains(&[12.into(), 10.into()]));
+        assert!(labels.is_sorted());
+
+        // unsorted labels
+        let mut builder = LabelsBuilder::new(vec!["foo", "bar"]);
+        for i in 0..1000 {
+            builder.add(&[i % 10, i / 10]);
+        }
+        let labels = builder.finish();
+        assert!(!labels.is_sorted());
+
+        assert_eq!(labels.position(&[0.into(), 0.into()]), Some(0));
+        assert_eq!(labels.position(&[3.into(), 42.into()]), Some(423));
+        assert_eq!(labels.position(&[9.into(), 99.into()]), Some(999));
+        assert_eq!(labels.position(&[0.into(), 100.into()]), None);
+    }
+
+    #[test]
+    fn positions() {
+        let labels = Labels::new(["foo", "bar"], &[[2, 3], [1, 243], [-4, -2413]]);
+
+        let entries = [1, 243, 2, 2, -4, -2413, 2, 3].map(LabelValue::new);
+        assert_eq!(labels.positions(&entries), [Some(1), None, Some(2), Some(0)]);
+        assert_eq!(labels.positions(&[]), []);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_positions() {
+        let labels = Labels::new(["foo", "bar"], &[[2, 3], [1, 243], [-4, -2413]]);
+
+        let entries = [1, 243, 2, 2, -4, -2413, 2, 3].map(LabelValue::new);
+        assert_eq!(labels.par_positions(&entries), [Some(1), None, Some(2), Some(0)]);
+        assert_eq!(labels.par_positions(&[]), []);
+
+        // larger number of entries, to make sure the work is actually split
+        // between multiple threads
+        let mut builder = LabelsBuilder::new(vec!["a", "b"]);
+        for i in 0..100 {
+            for j in 0..100 {
+                builder.add(&[i, j]);
+            }
+        }
+        let labels = builder.finish();
+
+        let entries = (0..200).flat_map(|i| [i, 99 - i % 100]).map(LabelValue::new).collect::<Vec<_>>();
+        assert_eq!(labels.par_positions(&entries), labels.positions(&entries));
+    }
+
+    #[test]
+    fn iter_as() {
+        struct Atom {
+            structure: usize,
+            center: usize,
+        }
+
+        impl FromLabelsRow for Atom {
+            type Positions = (usize, usize);
+
+            fn positions(labels: &Labels) -> Result<(usize, usize), Error> {
+                let structure = labels.variable_position("structure")?;
+                let center = labels.variable_position("center")?;
+                return Ok((structure, center));
+            }
+
+            fn from_row(positions: &(usize, usize), row: &[LabelValue]) -> Atom {
+                return Atom {
+                    structure: row[positions.0].usize(),
+                    center: row[positions.1].usize(),
+                };
+            }
+        }
+
+        let labels = Labels::new(["center", "spam", "structure"], &[[1, 0, 2], [3, 0, 4]]);
+        let mut iter = labels.iter_as::<Atom>().unwrap();
+        assert_eq!(iter.len(), 2);
+
+        let atom = iter.next().unwrap();
+        assert_eq!((atom.structure, atom.center), (2, 1));
+        let atom = iter.next().unwrap();
+        assert_eq!((atom.structure, atom.center), (4, 3));
+        assert!(iter.next().is_none());
+
+        let labels = Labels::new(["center"], &[[1]]);
+        let error = labels.iter_as::<Atom>().err().unwrap();
+        assert_eq!(error.message, "'structure' is not part of these labels");
+    }
+
     #[test]
     fn iter() {
         let labels = Labels::new(
@@ -667,6 +1216,176 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn sort() {
+        let labels = Labels::new(["aa", "bb"], &[[1, 2], [0, 3], [1, 0], [-2, 5]]);
+        assert!(!labels.is_sorted());
+
+        let (sorted, permutation) = labels.sort().unwrap();
+        assert_eq!(sorted, Labels::new(["aa", "bb"], &[[-2, 5], [0, 3], [1, 0], [1, 2]]));
+        assert_eq!(permutation, [3, 1, 2, 0]);
+        assert!(sorted.is_sorted());
+
+        assert!(Labels::empty(vec!["aa"]).is_sorted());
+    }
+
+    #[test]
+    fn project() {
+        let labels = Labels::new(["aa", "bb", "cc"], &[[1, 2, 3], [1, 4, 3], [2, 2, 3]]);
+
+        let projected = labels.project(&["cc", "aa"]).unwrap();
+        assert_eq!(projected, Labels::new(["cc", "aa"], &[[3, 1], [3, 2]]));
+
+        let error = labels.project(&["dd"]).unwrap_err();
+        assert_eq!(error.message, "invalid parameter: 'dd' is not part of these labels");
+
+        let error = labels.project(&["aa", "aa"]).unwrap_err();
+        assert_eq!(error.message, "invalid parameter: 'aa' is used multiple times");
+    }
+
+    #[test]
+    fn insert() {
+        let labels = Labels::new(["aa", "bb"], &[[1, 2], [3, 4]]);
+
+        let inserted = labels.insert(0, "structure", &[LabelValue::new(5)]).unwrap();
+        assert_eq!(inserted, Labels::new(["structure", "aa", "bb"], &[[5, 1, 2], [5, 3, 4]]));
+
+        let values = [LabelValue::new(-1), LabelValue::new(-2)];
+        let inserted = labels.insert(2, "cc", &values).unwrap();
+        assert_eq!(inserted, Labels::new(["aa", "bb", "cc"], &[[1, 2, -1], [3, 4, -2]]));
+
+        let error = labels.insert(1, "aa", &values).unwrap_err();
+        assert_eq!(error.message, "invalid parameter: 'aa' is already part of these labels");
+
+        let error = labels.insert(3, "cc", &values).unwrap_err();
+        assert_eq!(error.message, "invalid parameter: can not insert a variable at index 3 in labels with 2 variables");
+
+        let error = labels.insert(0, "cc", &[]).unwrap_err();
+        assert_eq!(error.message, "invalid parameter: expected 1 or 2 values to insert, got 0");
+
+        let error = labels.insert(0, "not valid", &values).unwrap_err();
+        assert_eq!(error.message, "invalid parameter: 'not valid' is not a valid label name");
+    }
+
+    #[test]
+    fn remove() {
+        let labels = Labels::new(["aa", "bb", "cc"], &[[1, 2, 3], [1, 4, 3]]);
+
+        let removed = labels.remove("cc").unwrap();
+        assert_eq!(removed, Labels::new(["aa", "bb"], &[[1, 2], [1, 4]]));
+
+        let error = labels.remove("bb").unwrap_err();
+        assert_eq!(
+            error.message,
+            "invalid parameter: can not have the same label value multiple time: [1, 3] is already present at position 0"
+        );
+    }
+
+    #[test]
+    fn rename() {
+        let labels = Labels::new(["species_neighbor", "center"], &[[1, 2], [3, 4]]);
+
+        let renamed = labels.rename("species_neighbor", "species").unwrap();
+        assert_eq!(renamed, Labels::new(["species", "center"], &[[1, 2], [3, 4]]));
+        assert_eq!(renamed.position(&[LabelValue::new(3), LabelValue::new(4)]), Some(1));
+
+        let error = labels.rename("species_neighbor", "center").unwrap_err();
+        assert_eq!(error.message, "invalid parameter: 'center' is already part of these labels");
+
+        let error = labels.rename("species", "center").unwrap_err();
+        assert_eq!(error.message, "invalid parameter: 'species' is not part of these labels");
+    }
+
+    #[test]
+    fn reorder() {
+        let labels = Labels::new(["aa", "bb", "cc"], &[[1, 2, 3], [4, 5, 6]]);
+
+        let reordered = labels.reorder(&["cc", "aa", "bb"]).unwrap();
+        assert_eq!(reordered, Labels::new(["cc", "aa", "bb"], &[[3, 1, 2], [6, 4, 5]]));
+
+        let error = labels.reorder(&["cc", "aa"]).unwrap_err();
+        assert_eq!(error.message, "invalid parameter: expected 3 names to reorder these labels, got 2");
+    }
+
+    #[test]
+    fn select() {
+        let labels = Labels::new(["aa", "bb", "cc"], &[
+            [1, 1, 1],
+            [1, 2, 3],
+            [2, 1, 4],
+            [2, 2, 1],
+            [3, 1, 1],
+        ]);
+
+        let selection = Labels::new(["aa"], &[[2]]);
+        assert_eq!(labels.select(&selection).unwrap(), [2, 3]);
+
+        let selection = Labels::new(["cc", "aa"], &[[1, 3], [1, 1], [3, 2]]);
+        assert_eq!(labels.select(&selection).unwrap(), [0, 4]);
+
+        let selection = Labels::empty(vec!["bb"]);
+        assert!(labels.select(&selection).unwrap().is_empty());
+
+        let selection = Labels::new(["dd"], &[[1]]);
+        assert_eq!(
+            labels.select(&selection).unwrap_err().message,
+            "invalid parameter: 'dd' is not part of these labels"
+        );
+    }
+
+    #[test]
+    fn union() {
+        let first = Labels::new(["aa", "bb"], &[[0, 1], [1, 2]]);
+        let second = Labels::new(["aa", "bb"], &[[2, 3], [1, 2], [4, 5]]);
+
+        let (union, first_mapping, second_mapping) = first.union(&second).unwrap();
+        assert_eq!(union, Labels::new(["aa", "bb"], &[[0, 1], [1, 2], [2, 3], [4, 5]]));
+        assert_eq!(first_mapping, [0, 1]);
+        assert_eq!(second_mapping, [2, 1, 3]);
+
+        let (union, first_mapping, second_mapping) = first.union(&Labels::empty(vec!["aa", "bb"])).unwrap();
+        assert_eq!(union, first);
+        assert_eq!(first_mapping, [0, 1]);
+        assert!(second_mapping.is_empty());
+
+        let error = first.union(&Labels::new(["aa"], &[[0]])).unwrap_err();
+        assert_eq!(
+            error.message,
+            "invalid parameter: can not take the union of these Labels, they have different names: [aa, bb] and [aa]"
+        );
+    }
+
+    #[test]
+    fn intersection() {
+        let first = Labels::new(["aa", "bb"], &[[0, 1], [1, 2], [4, 5]]);
+        let second = Labels::new(["aa", "bb"], &[[2, 3], [4, 5], [1, 2]]);
+
+        let (intersection, first_mapping, second_mapping) = first.intersection(&second).unwrap();
+        assert_eq!(intersection, Labels::new(["aa", "bb"], &[[1, 2], [4, 5]]));
+        assert_eq!(first_mapping, [None, Some(0), Some(1)]);
+        assert_eq!(second_mapping, [None, Some(1), Some(0)]);
+
+        let error = first.intersection(&Labels::new(["bb", "aa"], &[[0, 1]])).unwrap_err();
+        assert_eq!(
+            error.message,
+            "invalid parameter: can not take the intersection of these Labels, they have different names: [aa, bb] and [bb, aa]"
+        );
+    }
+
+    #[test]
+    fn difference() {
+        let first = Labels::new(["aa", "bb"], &[[0, 1], [1, 2], [4, 5]]);
+        let second = Labels::new(["aa", "bb"], &[[2, 3], [1, 2]]);
+
+        let (difference, mapping) = first.difference(&second).unwrap();
+        assert_eq!(difference, Labels::new(["aa", "bb"], &[[0, 1], [4, 5]]));
+        assert_eq!(mapping, [Some(0), None, Some(1)]);
+
+        let (difference, mapping) = second.difference(&second).unwrap();
+        assert_eq!(difference, Labels::empty(vec!["aa", "bb"]));
+        assert_eq!(mapping, [None, None]);
+    }
+
     #[test]
     fn debug() {
         let labels = Labels::new(
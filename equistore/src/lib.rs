@@ -19,6 +19,11 @@
 //! [dependencies]
 //! equistore = {version = "...", features = ["static"]}
 //! ```
+//!
+//! The `serde` feature implements `serde::Serialize` and `serde::Deserialize`
+//! for [`Labels`], [`TensorBlock`] and [`TensorMap`]. See [`TensorMapSeed`]
+//! and [`TensorBlockSeed`] to control how the data arrays are created when
+//! deserializing.
 
 #![warn(clippy::all, clippy::pedantic)]
 
@@ -36,11 +41,13 @@ pub use self::errors::Error;
 
 mod data;
 pub use self::data::{ArrayRef, ArrayRefMut};
-pub use self::data::{Array, EmptyArray};
+pub use self::data::{Array, ArrayDataMut, EmptyArray};
+pub use self::data::check_array;
 
 mod labels;
 pub use self::labels::{Labels, LabelsBuilder, LabelValue};
 pub use self::labels::{LabelsIter, LabelsFixedSizeIter};
+pub use self::labels::{FromLabelsRow, LabelsTypedIter};
 
 #[cfg(feature = "rayon")]
 pub use self::labels::LabelsParIter;
@@ -49,15 +56,23 @@ mod block;
 pub use self::block::{TensorBlock, TensorBlockRef, TensorBlockRefMut};
 pub use self::block::{BasicBlock, BasicBlockMut};
 pub use self::block::{GradientsIter, GradientsMutIter};
+pub use self::block::Axis;
 
 mod tensor;
-pub use self::tensor::TensorMap;
+pub use self::tensor::{TensorMap, Reduction};
 pub use self::tensor::{TensorMapIter, TensorMapIterMut};
 #[cfg(feature = "rayon")]
 pub use self::tensor::{TensorMapParIter, TensorMapParIterMut};
 
+mod operations;
+
 pub mod io;
 
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "serde")]
+pub use self::serde::{TensorMapSeed, TensorBlockSeed};
+
 
 /// Path where the equistore shared library has been built
 pub fn c_api_install_dir() -> &'static str {
@@ -0,0 +1,136 @@
+use crate::c_api::EQS_INVALID_PARAMETER_ERROR;
+use crate::{Error, Labels, TensorBlockRef, TensorMap};
+
+/// Different metadata of a block that can be compared with [`check_blocks`]
+/// and [`check_same_gradients`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Metadata {
+    Samples,
+    Components,
+    Properties,
+}
+
+fn invalid_parameter(mut message: String) -> Error {
+    message.insert_str(0, "invalid parameter: ");
+    return Error {
+        code: Some(EQS_INVALID_PARAMETER_ERROR),
+        message: message,
+    };
+}
+
+fn format_names(labels: &Labels) -> String {
+    return format!("[{}]", labels.names().join(", "));
+}
+
+/// Check if two `TensorMap` have the same keys (in any order), and can be used
+/// together in the operation `fname`.
+pub(super) fn check_maps(first: &TensorMap, second: &TensorMap, fname: &str) -> Result<(), Error> {
+    if first.keys().names() != second.keys().names() {
+        return Err(invalid_parameter(format!(
+            "inputs to '{}' should have the same keys names, got {} and {}",
+            fname, format_names(first.keys()), format_names(second.keys())
+        )));
+    }
+
+    if first.keys().count() != second.keys().count() {
+        return Err(invalid_parameter(format!(
+            "inputs to '{}' should have the same number of blocks, got {} and {}",
+            fname, first.keys().count(), second.keys().count()
+        )));
+    }
+
+    for key in first.keys() {
+        if !second.keys().contains(key) {
+            return Err(invalid_parameter(format!(
+                "inputs to '{}' should have the same keys, the key ({}) is \
+                missing from the second tensor map",
+                fname, key.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+            )));
+        }
+    }
+
+    return Ok(());
+}
+
+/// Check that the values in two blocks have the same `metadata`, and can be
+/// used together in the operation `fname`.
+pub(super) fn check_blocks(
+    first: TensorBlockRef<'_>,
+    second: TensorBlockRef<'_>,
+    metadata: &[Metadata],
+    fname: &str,
+) -> Result<(), Error> {
+    let first = first.values();
+    let second = second.values();
+
+    for &kind in metadata {
+        match kind {
+            Metadata::Samples => {
+                if first.samples != second.samples {
+                    return Err(invalid_parameter(format!(
+                        "inputs to '{}' should have the same samples", fname
+                    )));
+                }
+            }
+            Metadata::Components => {
+                if first.components != second.components {
+                    return Err(invalid_parameter(format!(
+                        "inputs to '{}' should have the same components", fname
+                    )));
+                }
+            }
+            Metadata::Properties => {
+                if first.properties != second.properties {
+                    return Err(invalid_parameter(format!(
+                        "inputs to '{}' should have the same properties", fname
+                    )));
+                }
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+/// Check that two blocks have the same gradients, and that the gradients have
+/// the same `metadata`, to be used together in the operation `fname`.
+pub(super) fn check_same_gradients(
+    first: TensorBlockRef<'_>,
+    second: TensorBlockRef<'_>,
+    metadata: &[Metadata],
+    fname: &str,
+) -> Result<(), Error> {
+    let mut first_parameters = first.gradient_list();
+    let mut second_parameters = second.gradient_list();
+    first_parameters.sort_unstable();
+    second_parameters.sort_unstable();
+
+    if first_parameters != second_parameters {
+        return Err(invalid_parameter(format!(
+            "inputs to '{}' should have the same gradients, got [{}] and [{}]",
+            fname, first_parameters.join(", "), second_parameters.join(", ")
+        )));
+    }
+
+    for parameter in first_parameters {
+        let first_gradient = first.gradient(parameter).expect("missing gradient");
+        let second_gradient = second.gradient(parameter).expect("missing gradient");
+
+        for &kind in metadata {
+            let (same, name) = match kind {
+                Metadata::Samples => (first_gradient.samples == second_gradient.samples, "samples"),
+                Metadata::Components => (first_gradient.components == second_gradient.components, "components"),
+                Metadata::Properties => (first_gradient.properties == second_gradient.properties, "properties"),
+            };
+
+            if !same {
+                return Err(invalid_parameter(format!(
+                    "gradients with respect to '{}' in inputs to '{}' should \
+                    have the same {}", parameter, fname, name
+                )));
+            }
+        }
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,85 @@
+use ndarray::Axis;
+
+use crate::{Error, TensorBlock, TensorBlockRef, TensorMap};
+
+use super::{check_compatible_blocks, map_block, map_gradient_rows, map_tensor, zip_tensors};
+
+impl TensorMap {
+    /// Divide this `TensorMap` by `other`, element-wise and block by block.
+    ///
+    /// Both tensor maps must have the same keys, and the blocks with the same
+    /// key must have the same metadata and gradients. The gradients of the
+    /// result are computed with the quotient rule.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn divide(&self, other: &TensorMap) -> Result<TensorMap, Error> {
+        return zip_tensors(self, other, "divide", |first, second| first.divide(&second));
+    }
+
+    /// Divide all the values and gradients in this `TensorMap` by the scalar
+    /// `value`.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn divide_scalar(&self, value: f64) -> Result<TensorMap, Error> {
+        return map_tensor(self, |block| block.divide_scalar(value));
+    }
+}
+
+impl TensorBlockRef<'_> {
+    /// Divide this block by `other` element-wise, creating a new
+    /// [`TensorBlock`].
+    ///
+    /// Both blocks must have the same metadata and gradients. The gradients of
+    /// the result are computed with the quotient rule:
+    /// `∇(A / B) = ∇A / B - A * ∇B / B²`.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn divide(&self, other: &TensorBlockRef<'_>) -> Result<TensorBlock, Error> {
+        check_compatible_blocks(*self, *other, "divide")?;
+
+        let self_values = self.values();
+        let self_values = self_values.data.as_array();
+        let other_values = other.values();
+        let other_values = other_values.data.as_array();
+
+        return map_block(
+            *self,
+            |values| values / other_values,
+            |parameter, gradient| {
+                let self_gradient = gradient.data.as_array();
+                let other_gradient = other.gradient(parameter).expect("missing gradient");
+                let other_gradient = other_gradient.data.as_array();
+
+                return map_gradient_rows(gradient, |grad_sample_i, sample_i| {
+                    let self_gradient = self_gradient.index_axis(Axis(0), grad_sample_i);
+                    let other_gradient = other_gradient.index_axis(Axis(0), grad_sample_i);
+                    let self_values = self_values.index_axis(Axis(0), sample_i);
+                    let other_values = other_values.index_axis(Axis(0), sample_i);
+
+                    return &self_gradient / &other_values
+                        - &self_values * &other_gradient / other_values.mapv(|v| v * v);
+                });
+            },
+        );
+    }
+
+    /// Divide all the values and gradients in this block by the scalar
+    /// `value`, creating a new [`TensorBlock`].
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn divide_scalar(&self, value: f64) -> Result<TensorBlock, Error> {
+        return map_block(
+            *self,
+            |values| values / value,
+            |_, gradient| gradient.data.as_array() / value,
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Block-wise arithmetic operations on [`TensorMap`] and [`TensorBlockRef`].
+//!
+//! All the operations in this module work with data stored in
+//! `ndarray::ArrayD<f64>`, and will panic if the values or gradients of the
+//! blocks are stored in a different kind of array. Gradients are propagated
+//! through the operations following the usual differentiation rules, and the
+//! info of the values and gradients is copied from the first block to the
+//! result.
+
+use ndarray::ArrayD;
+
+use crate::{BasicBlock, Error, TensorBlock, TensorBlockRef, TensorMap};
+
+mod checks;
+use self::checks::{check_maps, check_blocks, check_same_gradients, Metadata};
+
+mod add;
+mod subtract;
+mod multiply;
+mod divide;
+mod pow;
+
+/// Apply `operation` to all the blocks in `tensor`, and collect the results in
+/// a new `TensorMap` with the same keys.
+fn map_tensor(
+    tensor: &TensorMap,
+    mut operation: impl FnMut(TensorBlockRef<'_>) -> Result<TensorBlock, Error>,
+) -> Result<TensorMap, Error> {
+    let mut blocks = Vec::new();
+    for block in tensor.blocks() {
+        blocks.push(operation(block)?);
+    }
+
+    return TensorMap::new(tensor.keys().clone(), blocks);
+}
+
+/// Apply `operation` to all the pairs of blocks with the same key in `first`
+/// and `second`, and collect the results in a new `TensorMap` with the same
+/// keys as `first`.
+fn zip_tensors(
+    first: &TensorMap,
+    second: &TensorMap,
+    fname: &str,
+    mut operation: impl FnMut(TensorBlockRef<'_>, TensorBlockRef<'_>) -> Result<TensorBlock, Error>,
+) -> Result<TensorMap, Error> {
+    check_maps(first, second, fname)?;
+
+    let mut blocks = Vec::new();
+    for (key, first_block) in first {
+        let position = second.keys().position(key).expect("missing key after check_maps");
+        let second_block = second.block_by_id(position);
+        blocks.push(operation(first_block, second_block)?);
+    }
+
+    return TensorMap::new(first.keys().clone(), blocks);
+}
+
+/// Check that `first` and `second` blocks have the same metadata and the same
+/// gradients, and can be used together in `fname`.
+fn check_compatible_blocks(
+    first: TensorBlockRef<'_>,
+    second: TensorBlockRef<'_>,
+    fname: &str,
+) -> Result<(), Error> {
+    let metadata = [Metadata::Samples, Metadata::Components, Metadata::Properties];
+    check_blocks(first, second, &metadata, fname)?;
+    check_same_gradients(first, second, &metadata, fname)?;
+    return Ok(());
+}
+
+/// Create a new block with the same metadata and info as `block`. The new
+/// values are computed by calling `values` with the current values, and the
+/// new gradients by calling `gradient` with the parameter and current gradient.
+fn map_block(
+    block: TensorBlockRef<'_>,
+    values: impl FnOnce(&ArrayD<f64>) -> ArrayD<f64>,
+    mut gradient: impl FnMut(&str, &BasicBlock<'_>) -> ArrayD<f64>,
+) -> Result<TensorBlock, Error> {
+    let block_values = block.values();
+    let mut new_block = TensorBlock::new(
+        values(block_values.data.as_array()),
+        block_values.samples.clone(),
+        &block_values.components,
+        block_values.properties.clone(),
+    )?;
+
+    for (key, value) in &block_values.info {
+        new_block.as_ref_mut().set_info("values", key, value)?;
+    }
+
+    for (parameter, block_gradient) in block.gradients() {
+        new_block.add_gradient(
+            parameter,
+            gradient(parameter, &block_gradient),
+            block_gradient.samples.clone(),
+            &block_gradient.components,
+        )?;
+
+        for (key, value) in &block_gradient.info {
+            new_block.as_ref_mut().set_info(parameter, key, value)?;
+        }
+    }
+
+    return Ok(new_block);
+}
+
+/// Compute a new gradient array by applying `operation` to each row of
+/// `gradient`, together with the index of the corresponding sample in the
+/// values (i.e. the first entry of the gradient sample).
+fn map_gradient_rows(
+    gradient: &BasicBlock<'_>,
+    mut operation: impl FnMut(usize, usize) -> ArrayD<f64>,
+) -> ArrayD<f64> {
+    let mut result = ArrayD::zeros(gradient.data.as_array().shape());
+    for (grad_sample_i, grad_sample) in gradient.samples.iter().enumerate() {
+        let sample_i = grad_sample[0].usize();
+        result.index_axis_mut(ndarray::Axis(0), grad_sample_i)
+            .assign(&operation(grad_sample_i, sample_i));
+    }
+    return result;
+}
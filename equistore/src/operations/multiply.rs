@@ -0,0 +1,84 @@
+use ndarray::Axis;
+
+use crate::{Error, TensorBlock, TensorBlockRef, TensorMap};
+
+use super::{check_compatible_blocks, map_block, map_gradient_rows, map_tensor, zip_tensors};
+
+impl TensorMap {
+    /// Multiply this `TensorMap` by `other`, element-wise and block by block.
+    ///
+    /// Both tensor maps must have the same keys, and the blocks with the same
+    /// key must have the same metadata and gradients. The gradients of the
+    /// result are computed with the product rule.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn multiply(&self, other: &TensorMap) -> Result<TensorMap, Error> {
+        return zip_tensors(self, other, "multiply", |first, second| first.multiply(&second));
+    }
+
+    /// Multiply all the values and gradients in this `TensorMap` by the scalar
+    /// `value`.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn multiply_scalar(&self, value: f64) -> Result<TensorMap, Error> {
+        return map_tensor(self, |block| block.multiply_scalar(value));
+    }
+}
+
+impl TensorBlockRef<'_> {
+    /// Multiply this block by `other` element-wise, creating a new
+    /// [`TensorBlock`].
+    ///
+    /// Both blocks must have the same metadata and gradients. The gradients of
+    /// the result are computed with the product rule:
+    /// `∇(A * B) = A * ∇B + ∇A * B`.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn multiply(&self, other: &TensorBlockRef<'_>) -> Result<TensorBlock, Error> {
+        check_compatible_blocks(*self, *other, "multiply")?;
+
+        let self_values = self.values();
+        let self_values = self_values.data.as_array();
+        let other_values = other.values();
+        let other_values = other_values.data.as_array();
+
+        return map_block(
+            *self,
+            |values| values * other_values,
+            |parameter, gradient| {
+                let self_gradient = gradient.data.as_array();
+                let other_gradient = other.gradient(parameter).expect("missing gradient");
+                let other_gradient = other_gradient.data.as_array();
+
+                return map_gradient_rows(gradient, |grad_sample_i, sample_i| {
+                    let self_gradient = self_gradient.index_axis(Axis(0), grad_sample_i);
+                    let other_gradient = other_gradient.index_axis(Axis(0), grad_sample_i);
+                    let self_values = self_values.index_axis(Axis(0), sample_i);
+                    let other_values = other_values.index_axis(Axis(0), sample_i);
+
+                    return &self_values * &other_gradient + &self_gradient * &other_values;
+                });
+            },
+        );
+    }
+
+    /// Multiply all the values and gradients in this block by the scalar
+    /// `value`, creating a new [`TensorBlock`].
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn multiply_scalar(&self, value: f64) -> Result<TensorBlock, Error> {
+        return map_block(
+            *self,
+            |values| values * value,
+            |_, gradient| gradient.data.as_array() * value,
+        );
+    }
+}
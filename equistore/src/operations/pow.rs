@@ -0,0 +1,49 @@
+use ndarray::Axis;
+
+use crate::{Error, TensorBlock, TensorBlockRef, TensorMap};
+
+use super::{map_block, map_gradient_rows, map_tensor};
+
+impl TensorMap {
+    /// Raise all the values in this `TensorMap` to the power `exponent`.
+    ///
+    /// The gradients of the result are computed with the power rule.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn pow(&self, exponent: f64) -> Result<TensorMap, Error> {
+        return map_tensor(self, |block| block.pow(exponent));
+    }
+}
+
+impl TensorBlockRef<'_> {
+    /// Raise all the values in this block to the power `exponent`, creating a
+    /// new [`TensorBlock`].
+    ///
+    /// The gradients of the result are computed with the power rule: `∇(A^n) =
+    /// n * A^(n - 1) * ∇A`.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn pow(&self, exponent: f64) -> Result<TensorBlock, Error> {
+        let block_values = self.values();
+        let block_values = block_values.data.as_array();
+
+        return map_block(
+            *self,
+            |values| values.mapv(|v| v.powf(exponent)),
+            |_, gradient| {
+                let block_gradient = gradient.data.as_array();
+                return map_gradient_rows(gradient, |grad_sample_i, sample_i| {
+                    let block_gradient = block_gradient.index_axis(Axis(0), grad_sample_i);
+                    let values = block_values.index_axis(Axis(0), sample_i);
+                    let values = values.mapv(|v| exponent * v.powf(exponent - 1.0));
+
+                    return &block_gradient * &values;
+                });
+            },
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{Error, TensorBlock, TensorBlockRef, TensorMap};
+
+use super::{check_compatible_blocks, map_block, map_tensor, zip_tensors};
+
+impl TensorMap {
+    /// Subtract `other` from this `TensorMap`, block by block.
+    ///
+    /// Both tensor maps must have the same keys, and the blocks with the same
+    /// key must have the same metadata and gradients. The gradients of the
+    /// result are the difference of the gradients of the inputs.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn subtract(&self, other: &TensorMap) -> Result<TensorMap, Error> {
+        return zip_tensors(self, other, "subtract", |first, second| first.subtract(&second));
+    }
+
+    /// Subtract the scalar `value` from all the values in this `TensorMap`.
+    /// The gradients are left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn subtract_scalar(&self, value: f64) -> Result<TensorMap, Error> {
+        return map_tensor(self, |block| block.subtract_scalar(value));
+    }
+}
+
+impl TensorBlockRef<'_> {
+    /// Subtract `other` from this block, creating a new [`TensorBlock`].
+    ///
+    /// Both blocks must have the same metadata and gradients. The gradients of
+    /// the result are the difference of the gradients of the inputs.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn subtract(&self, other: &TensorBlockRef<'_>) -> Result<TensorBlock, Error> {
+        check_compatible_blocks(*self, *other, "subtract")?;
+
+        let other_values = other.values();
+        return map_block(
+            *self,
+            |values| values - other_values.data.as_array(),
+            |parameter, gradient| {
+                let other_gradient = other.gradient(parameter).expect("missing gradient");
+                return gradient.data.as_array() - other_gradient.data.as_array();
+            },
+        );
+    }
+
+    /// Subtract the scalar `value` from all the values in this block, creating
+    /// a new [`TensorBlock`]. The gradients are left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If the values or gradients are not stored in `ndarray::ArrayD<f64>`
+    pub fn subtract_scalar(&self, value: f64) -> Result<TensorBlock, Error> {
+        return map_block(
+            *self,
+            |values| values - value,
+            |_, gradient| gradient.data.as_array().clone(),
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Implementation of `serde::Serialize` and `serde::Deserialize` for the types
+//! in this crate, enabled with the `serde` cargo feature.
+//!
+//! [`Labels`] are serialized as a structure containing the `names` of the
+//! variables and the `values` of all entries (as a list of lists of integers).
+//! Blocks are serialized as a structure containing the `values` and a map of
+//! `gradients`, each one containing the `data` (with its `shape` and a flat
+//! list of all the values in row-major order), `samples`, `components`,
+//! `properties` (for values only) and `info`. Finally, tensor maps are
+//! serialized as a structure containing the `keys`, the list of `blocks` and
+//! the tensor-level `metadata`.
+//!
+//! The data arrays are read through `eqs_array_t.data` (or
+//! `eqs_array_t.copy_to_host` for arrays which are not on CPU), and can come
+//! from any origin. When deserializing, arrays are created as `ndarray::ArrayD` by
+//! default (like [`crate::io::load`]), or with a custom `create_array` function
+//! using [`TensorMapSeed`] and [`TensorBlockSeed`].
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::de::{self, DeserializeSeed, Deserializer};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::c_api::{EQS_DEVICE_CPU, EQS_DTYPE_FLOAT64};
+use crate::{Array, ArrayDataMut, Error, Labels, LabelsBuilder, LabelValue};
+use crate::{BasicBlock, TensorBlock, TensorBlockRef, TensorMap};
+
+impl Serialize for LabelValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.i64())
+    }
+}
+
+impl<'de> Deserialize<'de> for LabelValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return i64::deserialize(deserializer).map(LabelValue::new);
+    }
+}
+
+/// Serialize the entries of some `Labels` as a list of lists
+struct LabelsEntries<'a>(&'a Labels);
+
+impl Serialize for LabelsEntries<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.count()))?;
+        if self.0.size() != 0 {
+            for entry in self.0 {
+                seq.serialize_element(entry)?;
+            }
+        }
+        return seq.end();
+    }
+}
+
+impl Serialize for Labels {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Labels", 2)?;
+        state.serialize_field("names", &self.names())?;
+        state.serialize_field("values", &LabelsEntries(self))?;
+        return state.end();
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Labels")]
+struct LabelsData {
+    names: Vec<String>,
+    values: Vec<Vec<LabelValue>>,
+}
+
+impl<'de> Deserialize<'de> for Labels {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = LabelsData::deserialize(deserializer)?;
+
+        let unique_names = data.names.iter().collect::<BTreeSet<_>>();
+        if unique_names.len() != data.names.len() {
+            return Err(de::Error::custom("invalid labels: the same name is used multiple times"));
+        }
+
+        let mut builder = LabelsBuilder::new(data.names.iter().map(|s| &**s).collect());
+        for entry in &data.values {
+            if entry.len() != builder.size() {
+                return Err(de::Error::custom(format!(
+                    "invalid labels: expected entries with {} values, got {}",
+                    builder.size(), entry.len()
+                )));
+            }
+            builder.add(entry);
+        }
+
+        return builder.try_finish().map_err(de::Error::custom);
+    }
+}
+
+/// Serialize the data of an array as a shape and flat list of values
+struct ArrayData<'a>(crate::ArrayRef<'a>);
+
+impl ArrayData<'_> {
+    /// Get the values of this array in host memory, copying them with
+    /// `eqs_array_t.copy_to_host` if the array is not on CPU
+    fn host_data(&self) -> Result<Cow<'_, [f64]>, Error> {
+        let array = self.0.as_raw();
+        if array.device()? == EQS_DEVICE_CPU {
+            return self.0.data().map(Cow::Borrowed);
+        }
+
+        if array.dtype()? != EQS_DTYPE_FLOAT64 {
+            return Err(Error {
+                code: None,
+                message: "expected an array containing 64-bit floating point values".into(),
+            });
+        }
+
+        // this only copies the pointers to the array and its functions
+        let mut array = *array;
+        let data = array.copy_to_host()?
+            .chunks_exact(std::mem::size_of::<f64>())
+            .map(|chunk| f64::from_ne_bytes(chunk.try_into().expect("wrong chunk size")))
+            .collect();
+
+        return Ok(Cow::Owned(data));
+    }
+}
+
+impl Serialize for ArrayData<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let shape = self.0.as_raw().shape().map_err(serde::ser::Error::custom)?;
+        let data = self.host_data().map_err(serde::ser::Error::custom)?;
+
+        let mut state = serializer.serialize_struct("Array", 2)?;
+        state.serialize_field("shape", shape)?;
+        state.serialize_field("data", &*data)?;
+        return state.end();
+    }
+}
+
+/// Serialize a `BasicBlock`, including the properties only for values
+struct BasicBlockData<'a> {
+    block: BasicBlock<'a>,
+    properties: bool,
+}
+
+impl Serialize for BasicBlockData<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let n_fields = if self.properties { 5 } else { 4 };
+        let mut state = serializer.serialize_struct("BasicBlock", n_fields)?;
+        state.serialize_field("data", &ArrayData(self.block.data))?;
+        state.serialize_field("samples", &self.block.samples)?;
+        state.serialize_field("components", &self.block.components)?;
+        if self.properties {
+            state.serialize_field("properties", &self.block.properties)?;
+        } else {
+            state.skip_field("properties")?;
+        }
+        state.serialize_field("info", &self.block.info)?;
+        return state.end();
+    }
+}
+
+/// Serialize the gradients of a block as a map
+struct GradientsData<'a>(TensorBlockRef<'a>);
+
+impl Serialize for GradientsData<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.gradient_list().len()))?;
+        for (parameter, gradient) in self.0.gradients() {
+            map.serialize_entry(parameter, &BasicBlockData {
+                block: gradient,
+                properties: false,
+            })?;
+        }
+        return map.end();
+    }
+}
+
+impl Serialize for TensorBlockRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TensorBlock", 2)?;
+        state.serialize_field("values", &BasicBlockData {
+            block: self.values(),
+            properties: true,
+        })?;
+        state.serialize_field("gradients", &GradientsData(*self))?;
+        return state.end();
+    }
+}
+
+impl Serialize for TensorBlock {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return self.as_ref().serialize(serializer);
+    }
+}
+
+/// Serialize the tensor-level metadata of a `TensorMap` as a map
+struct TensorMetadata<'a>(&'a TensorMap);
+
+impl Serialize for TensorMetadata<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let keys = self.0.metadata_keys();
+        let mut map = serializer.serialize_map(Some(keys.len()))?;
+        for key in keys {
+            map.serialize_entry(key, self.0.metadata(key).expect("missing metadata"))?;
+        }
+        return map.end();
+    }
+}
+
+impl Serialize for TensorMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TensorMap", 3)?;
+        state.serialize_field("keys", self.keys())?;
+        state.serialize_field("blocks", &self.blocks())?;
+        state.serialize_field("metadata", &TensorMetadata(self))?;
+        return state.end();
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Array")]
+struct ArrayOwnedData {
+    shape: Vec<usize>,
+    data: Vec<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "BasicBlock")]
+struct BasicBlockOwnedData {
+    data: ArrayOwnedData,
+    samples: Labels,
+    components: Vec<Labels>,
+    #[serde(default)]
+    properties: Option<Labels>,
+    #[serde(default)]
+    info: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "TensorBlock")]
+struct TensorBlockOwnedData {
+    values: BasicBlockOwnedData,
+    #[serde(default)]
+    gradients: BTreeMap<String, BasicBlockOwnedData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "TensorMap")]
+struct TensorMapOwnedData {
+    keys: Labels,
+    blocks: Vec<TensorBlockOwnedData>,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+}
+
+/// Create an array with `create_array`, and fill it with the deserialized data
+fn create_array<F, A>(create_array: &F, array: &ArrayOwnedData) -> Result<A, Error>
+    where F: Fn(&[usize]) -> A, A: Array
+{
+    let mut result = create_array(&array.shape);
+    if result.shape() != array.shape {
+        return Err(Error {
+            code: None,
+            message: format!(
+                "create_array returned an array with the wrong shape: expected {:?}, got {:?}",
+                array.shape, result.shape()
+            ),
+        });
+    }
+
+    let data = match result.data() {
+        ArrayDataMut::Float64(data) => data,
+        _ => {
+            return Err(Error {
+                code: None,
+                message: "create_array must return an array containing 64-bit floating point values".into(),
+            });
+        }
+    };
+
+    if data.len() != array.data.len() {
+        return Err(Error {
+            code: None,
+            message: format!(
+                "expected {} values for an array of shape {:?}, got {}",
+                data.len(), array.shape, array.data.len()
+            ),
+        });
+    }
+    data.copy_from_slice(&array.data);
+
+    return Ok(result);
+}
+
+/// Check that `value` can be stored in the metadata of a block or tensor map
+fn check_metadata_string(value: &str) -> Result<(), Error> {
+    if value.contains('\0') {
+        return Err(Error {
+            code: None,
+            message: format!("metadata can not contain a NULL byte, got {:?}", value),
+        });
+    }
+    return Ok(());
+}
+
+fn tensor_block_from_data<F, A>(create: &F, data: TensorBlockOwnedData) -> Result<TensorBlock, Error>
+    where F: Fn(&[usize]) -> A, A: Array
+{
+    let properties = data.values.properties.ok_or_else(|| Error {
+        code: None,
+        message: "missing properties for the values of this block".into(),
+    })?;
+
+    let mut block = TensorBlock::new(
+        create_array(create, &data.values.data)?,
+        data.values.samples,
+        &data.values.components,
+        properties,
+    )?;
+
+    for (parameter, gradient) in data.gradients {
+        block.add_gradient(
+            &parameter,
+            create_array(create, &gradient.data)?,
+            gradient.samples,
+            &gradient.components,
+        )?;
+
+        for (key, value) in &gradient.info {
+            check_metadata_string(key)?;
+            check_metadata_string(value)?;
+            block.as_ref_mut().set_info(&parameter, key, value)?;
+        }
+    }
+
+    for (key, value) in &data.values.info {
+        check_metadata_string(key)?;
+        check_metadata_string(value)?;
+        block.as_ref_mut().set_info("values", key, value)?;
+    }
+
+    return Ok(block);
+}
+
+fn create_ndarray(shape: &[usize]) -> ndarray::ArrayD<f64> {
+    return ndarray::ArrayD::from_elem(shape, 0.0);
+}
+
+/// [`DeserializeSeed`] implementation for [`TensorBlock`], creating the data
+/// arrays with a user-provided `create_array` function.
+///
+/// The `create_array` function is called with the shape of each array (values
+/// and gradients), and should return an array with this shape. The data of the
+/// array is then filled with the deserialized values.
+pub struct TensorBlockSeed<F> {
+    create_array: F,
+}
+
+impl<F, A> TensorBlockSeed<F> where F: Fn(&[usize]) -> A, A: Array {
+    /// Create a new `TensorBlockSeed` with the given `create_array` function
+    pub fn new(create_array: F) -> TensorBlockSeed<F> {
+        TensorBlockSeed { create_array }
+    }
+}
+
+impl<'de, F, A> DeserializeSeed<'de> for TensorBlockSeed<F> where F: Fn(&[usize]) -> A, A: Array {
+    type Value = TensorBlock;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let data = TensorBlockOwnedData::deserialize(deserializer)?;
+        return tensor_block_from_data(&self.create_array, data).map_err(de::Error::custom);
+    }
+}
+
+impl<'de> Deserialize<'de> for TensorBlock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return TensorBlockSeed::new(create_ndarray).deserialize(deserializer);
+    }
+}
+
+/// [`DeserializeSeed`] implementation for [`TensorMap`], creating the data
+/// arrays with a user-provided `create_array` function.
+///
+/// The `create_array` function is called with the shape of each array (values
+/// and gradients), and should return an array with this shape. The data of the
+/// array is then filled with the deserialized values.
+pub struct TensorMapSeed<F> {
+    create_array: F,
+}
+
+impl<F, A> TensorMapSeed<F> where F: Fn(&[usize]) -> A, A: Array {
+    /// Create a new `TensorMapSeed` with the given `create_array` function
+    pub fn new(create_array: F) -> TensorMapSeed<F> {
+        TensorMapSeed { create_array }
+    }
+}
+
+impl<'de, F, A> DeserializeSeed<'de> for TensorMapSeed<F> where F: Fn(&[usize]) -> A, A: Array {
+    type Value = TensorMap;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let data = TensorMapOwnedData::deserialize(deserializer)?;
+
+        let mut blocks = Vec::new();
+        for block in data.blocks {
+            blocks.push(tensor_block_from_data(&self.create_array, block).map_err(de::Error::custom)?);
+        }
+
+        let mut tensor = TensorMap::new(data.keys, blocks).map_err(de::Error::custom)?;
+        for (key, value) in &data.metadata {
+            check_metadata_string(key).map_err(de::Error::custom)?;
+            check_metadata_string(value).map_err(de::Error::custom)?;
+            tensor.set_metadata(key, value).map_err(de::Error::custom)?;
+        }
+
+        return Ok(tensor);
+    }
+}
+
+impl<'de> Deserialize<'de> for TensorMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return TensorMapSeed::new(create_ndarray).deserialize(deserializer);
+    }
+}
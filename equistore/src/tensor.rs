@@ -1,11 +1,11 @@
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::iter::FusedIterator;
 
 use crate::block::{TensorBlockRefMut};
 use crate::c_api::{eqs_tensormap_t, eqs_labels_t};
 
 use crate::errors::{check_status, check_ptr};
-use crate::{Error, TensorBlock, TensorBlockRef, Labels, LabelValue};
+use crate::{Error, TensorBlock, TensorBlockRef, Labels, LabelValue, Axis};
 
 /// [`TensorMap`] is the main user-facing struct of this library, and can
 /// store any kind of data used in atomistic machine learning.
@@ -22,6 +22,33 @@ pub struct TensorMap {
     keys: Labels,
 }
 
+/// Different ways to reduce multiple samples into a single one in
+/// [`TensorMap::reduce_over_samples`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    /// Sum all the samples together
+    Sum,
+    /// Compute the average of all the samples
+    Mean,
+    /// Compute the variance of all the samples
+    Variance,
+    /// Compute the standard deviation of all the samples
+    Std,
+}
+
+impl Reduction {
+    /// Get the name of this reduction as expected by the C API
+    fn as_c_str(self) -> &'static std::ffi::CStr {
+        let name: &[u8] = match self {
+            Reduction::Sum => b"sum\0",
+            Reduction::Mean => b"mean\0",
+            Reduction::Variance => b"variance\0",
+            Reduction::Std => b"std\0",
+        };
+        return std::ffi::CStr::from_bytes_with_nul(name).expect("invalid C string");
+    }
+}
+
 // SAFETY: Send is fine since we can free a TensorMap from any thread
 unsafe impl Send for TensorMap {}
 // SAFETY: Sync is fine since there is no internal mutability in TensorMap
@@ -121,6 +148,92 @@ impl TensorMap {
         &self.keys
     }
 
+    /// Get the value associated with `key` in the tensor-level metadata of
+    /// this `TensorMap`, if any.
+    #[inline]
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        let key = CString::new(key).expect("invalid C string");
+
+        let mut value = std::ptr::null();
+        unsafe {
+            check_status(crate::c_api::eqs_tensormap_metadata(
+                self.ptr,
+                key.as_ptr(),
+                &mut value,
+            )).expect("failed to get metadata");
+        }
+
+        if value.is_null() {
+            return None;
+        }
+
+        unsafe {
+            return Some(CStr::from_ptr(value).to_str().expect("invalid UTF8"));
+        }
+    }
+
+    /// Set the value associated with `key` in the tensor-level metadata of
+    /// this `TensorMap`, overwriting any existing value.
+    ///
+    /// The metadata is saved and loaded together with the data by
+    /// [`crate::io::save`] and [`crate::io::load`].
+    #[inline]
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let key = CString::new(key).expect("invalid C string");
+        let value = CString::new(value).expect("invalid C string");
+
+        unsafe {
+            check_status(crate::c_api::eqs_tensormap_set_metadata(
+                self.ptr,
+                key.as_ptr(),
+                value.as_ptr(),
+            ))?;
+        }
+
+        return Ok(());
+    }
+
+    /// Remove the value associated with `key` in the tensor-level metadata of
+    /// this `TensorMap`, if any.
+    #[inline]
+    pub fn remove_metadata(&mut self, key: &str) {
+        let key = CString::new(key).expect("invalid C string");
+
+        unsafe {
+            check_status(crate::c_api::eqs_tensormap_set_metadata(
+                self.ptr,
+                key.as_ptr(),
+                std::ptr::null(),
+            )).expect("failed to remove metadata");
+        }
+    }
+
+    /// Get the list of all keys in the tensor-level metadata of this
+    /// `TensorMap`, sorted alphabetically.
+    #[inline]
+    pub fn metadata_keys(&self) -> Vec<&str> {
+        let mut keys_ptr = std::ptr::null();
+        let mut keys_count = 0;
+        unsafe {
+            check_status(crate::c_api::eqs_tensormap_metadata_keys(
+                self.ptr,
+                &mut keys_ptr,
+                &mut keys_count
+            )).expect("failed to get metadata keys");
+        }
+
+        if keys_count == 0 {
+            return Vec::new();
+        }
+
+        unsafe {
+            let keys = std::slice::from_raw_parts(keys_ptr, keys_count);
+            return keys.iter()
+                .map(|&ptr| CStr::from_ptr(ptr).to_str().unwrap())
+                .collect();
+        }
+    }
+
     /// Get a reference to the block at the given `index` in this `TensorMap`
     ///
     /// # Panics
@@ -177,9 +290,11 @@ impl TensorMap {
 
     /// Get the index of blocks matching the given selection.
     ///
-    /// The selection must contains a single entry, defining the requested key
-    /// or keys. If the selection contains only a subset of the variables of the
-    /// keys, there can be multiple matching blocks.
+    /// The selection must contain a subset of the variables of the keys, and
+    /// can contain multiple entries. A block is selected if its key matches
+    /// any of the entries in the selection. If the selection contains only a
+    /// subset of the variables of the keys, there can be multiple matching
+    /// blocks for a single entry.
     #[inline]
     pub fn blocks_matching(&self, selection: &Labels) -> Result<Vec<usize>, Error> {
         let mut indexes = vec![0; self.keys().count()];
@@ -205,11 +320,16 @@ impl TensorMap {
     pub fn block_matching(&self, selection: &Labels) -> Result<usize, Error> {
         let matching = self.blocks_matching(selection)?;
         if matching.len() != 1 {
-            let selection_str = selection.names()
-                .iter().zip(&selection[0])
-                .map(|(name, value)| format!("{} = {}", name, value))
+            let names = selection.names();
+            let selection_str = selection.iter()
+                .map(|entry| {
+                    names.iter().zip(entry)
+                        .map(|(name, value)| format!("{} = {}", name, value))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
                 .collect::<Vec<_>>()
-                .join(", ");
+                .join("; ");
 
 
             if matching.is_empty() {
@@ -272,15 +392,22 @@ impl TensorMap {
     /// the sample labels, and blocks with the same remaining keys variables
     /// will be merged together along the sample axis.
     ///
-    /// `keys_to_move` must be empty (`keys_to_move.count() == 0`), and the new
-    /// sample labels will contain entries corresponding to the merged blocks'
-    /// keys.
+    /// If `keys_to_move` does not contains any entries (`keys_to_move.count()
+    /// == 0`), then the new sample labels will contain entries corresponding
+    /// to the merged blocks' keys only.
     ///
-    /// The new sample labels will contains all of the merged blocks sample
-    /// labels. The order of the samples is controlled by `sort_samples`. If
+    /// If `keys_to_move` contains entries, then the new sample labels will
+    /// contain all the merged samples, combined with each of the entries of
+    /// `keys_to_move`. For example, using `a=2, 3` in `keys_to_move` with blocks
+    /// with samples `s=1, 2` and `s=1, 3` will result in `s, a = (1, 2), (2, 2),
+    /// (3, 2), (1, 3), (2, 3), (3, 3)`. The data for samples which were not
+    /// present in any of the merged blocks is filled with zeros, and blocks with
+    /// a key not in `keys_to_move` are ignored.
+    ///
+    /// The order of the samples is controlled by `sort_samples`. If
     /// `sort_samples` is true, samples are re-ordered to keep them
     /// lexicographically sorted. Otherwise they are kept in the order in which
-    /// they appear in the blocks.
+    /// they appear in the blocks (and in `keys_to_move`).
     ///
     /// This function is only implemented if all merged block have the same
     /// property labels.
@@ -324,13 +451,18 @@ impl TensorMap {
     /// `sort_samples` is true, samples are re-ordered to keep them
     /// lexicographically sorted. Otherwise they are kept in the order in which
     /// they appear in the blocks.
+    ///
+    /// Similarly, if `sort_properties` is true, the new property labels are
+    /// re-ordered to keep them lexicographically sorted. Otherwise they are
+    /// kept in the order in which they appear in the blocks.
     #[inline]
-    pub fn keys_to_properties(&self, keys_to_move: &Labels, sort_samples: bool) -> Result<TensorMap, Error> {
+    pub fn keys_to_properties(&self, keys_to_move: &Labels, sort_samples: bool, sort_properties: bool) -> Result<TensorMap, Error> {
         let ptr = unsafe {
             crate::c_api::eqs_tensormap_keys_to_properties(
                 self.ptr,
                 keys_to_move.as_eqs_labels_t(),
                 sort_samples,
+                sort_properties,
             )
         };
 
@@ -363,6 +495,266 @@ impl TensorMap {
         return Ok(unsafe { TensorMap::from_raw(ptr) });
     }
 
+    /// Move the given variables from the component labels to the sample
+    /// labels for each block in this `TensorMap`.
+    ///
+    /// The component is appended to the samples, with the component values
+    /// varying faster than the old samples. Each gradient sample is duplicated
+    /// for all the values of the component.
+    #[inline]
+    pub fn components_to_samples(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        let variables_c = variables.iter()
+            .map(|&v| CString::new(v).expect("unexpected NULL byte"))
+            .collect::<Vec<_>>();
+
+        let variables_ptr = variables_c.iter()
+            .map(|v| v.as_ptr())
+            .collect::<Vec<_>>();
+
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_components_to_samples(
+                self.ptr,
+                variables_ptr.as_ptr(),
+                variables.len(),
+            )
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
+    /// Move the given variables from the property labels to a new component
+    /// for each block in this `TensorMap`.
+    ///
+    /// This is the inverse of [`TensorMap::components_to_properties`]. The new
+    /// component is added after the existing ones, and the properties must
+    /// contain all the combinations of the values of `variables` with the
+    /// values of the remaining property variables, with the moved variables
+    /// varying either slower or faster than the remaining ones. If all the
+    /// property variables are moved, the new properties contain a single `"_"`
+    /// variable.
+    #[inline]
+    pub fn properties_to_components(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        let variables_c = variables.iter()
+            .map(|&v| CString::new(v).expect("unexpected NULL byte"))
+            .collect::<Vec<_>>();
+
+        let variables_ptr = variables_c.iter()
+            .map(|v| v.as_ptr())
+            .collect::<Vec<_>>();
+
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_properties_to_components(
+                self.ptr,
+                variables_ptr.as_ptr(),
+                variables.len(),
+            )
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
+    /// Split the blocks in this `TensorMap` according to the values of the
+    /// sample `variables`, moving these variables from the samples to the
+    /// keys.
+    ///
+    /// This is the inverse of [`TensorMap::keys_to_samples`]. Each block is
+    /// split into one new block for each of the different values taken by
+    /// `variables` in its samples, and the corresponding key is extended with
+    /// these values. The remaining sample variables are kept in the new
+    /// blocks, or replaced by a single `"_"` variable if all sample variables
+    /// are moved. Gradients are split accordingly.
+    ///
+    /// This requires [`Array::gather_from`] to be implemented.
+    ///
+    /// [`Array::gather_from`]: crate::Array::gather_from
+    #[inline]
+    pub fn samples_to_keys(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        let variables_c = variables.iter()
+            .map(|&v| CString::new(v).expect("unexpected NULL byte"))
+            .collect::<Vec<_>>();
+
+        let variables_ptr = variables_c.iter()
+            .map(|v| v.as_ptr())
+            .collect::<Vec<_>>();
+
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_samples_to_keys(
+                self.ptr,
+                variables_ptr.as_ptr(),
+                variables.len(),
+            )
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
+    /// Split the blocks in this `TensorMap` according to the values of the
+    /// property `variables`, moving these variables from the properties to the
+    /// keys.
+    ///
+    /// This is the inverse of [`TensorMap::keys_to_properties`]. Each block is
+    /// split into one new block for each of the different values taken by
+    /// `variables` in its properties, and the corresponding key is extended
+    /// with these values. The remaining property variables are kept in the new
+    /// blocks, or replaced by a single `"_"` variable if all property
+    /// variables are moved. Gradients are split accordingly.
+    ///
+    /// This requires [`Array::gather_from`] to be implemented.
+    ///
+    /// [`Array::gather_from`]: crate::Array::gather_from
+    #[inline]
+    pub fn properties_to_keys(&self, variables: &[&str]) -> Result<TensorMap, Error> {
+        let variables_c = variables.iter()
+            .map(|&v| CString::new(v).expect("unexpected NULL byte"))
+            .collect::<Vec<_>>();
+
+        let variables_ptr = variables_c.iter()
+            .map(|v| v.as_ptr())
+            .collect::<Vec<_>>();
+
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_properties_to_keys(
+                self.ptr,
+                variables_ptr.as_ptr(),
+                variables.len(),
+            )
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
+    /// Join the given `tensors` along `axis`, creating a single `TensorMap`.
+    ///
+    /// All the tensor maps must have the same keys, and blocks with the same
+    /// key must have the same components, gradients, and the same samples or
+    /// properties labels (i.e. the labels for the axis which is not joined).
+    ///
+    /// Clashes between the labels along the joined `axis` are resolved by
+    /// keeping the same names if all entries are unique; adding a new
+    /// `"tensor"` variable containing the index of the tensor map if the names
+    /// are the same but some entries are duplicated; and using `"tensor",
+    /// "property"` as new names if the labels have different names.
+    #[inline]
+    pub fn join(tensors: &[&TensorMap], axis: Axis) -> Result<TensorMap, Error> {
+        let tensors_ptr = tensors.iter()
+            .map(|tensor| tensor.ptr as *const eqs_tensormap_t)
+            .collect::<Vec<_>>();
+
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_join(
+                tensors_ptr.as_ptr(),
+                tensors_ptr.len(),
+                axis.as_c_str().as_ptr(),
+            )
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
+    /// Split this `TensorMap` into multiple tensor maps along the given `axis`,
+    /// creating one new tensor map for each of the `selections`.
+    ///
+    /// This is equivalent to calling [`TensorMap::slice`] with each of the
+    /// `selections`, which must all have the same names.
+    #[inline]
+    pub fn split(&self, axis: Axis, selections: &[Labels]) -> Result<Vec<TensorMap>, Error> {
+        let selections_c = selections.iter()
+            .map(|selection| selection.as_eqs_labels_t())
+            .collect::<Vec<_>>();
+
+        let mut results = vec![std::ptr::null_mut(); selections.len()];
+        unsafe {
+            check_status(crate::c_api::eqs_tensormap_split(
+                self.ptr,
+                axis.as_c_str().as_ptr(),
+                selections_c.as_ptr(),
+                selections_c.len(),
+                results.as_mut_ptr(),
+            ))?;
+        }
+
+        let tensors = results.into_iter()
+            .map(|ptr| unsafe { TensorMap::from_raw(ptr) })
+            .collect();
+
+        return Ok(tensors);
+    }
+
+    /// Reduce the blocks in this `TensorMap` over the sample variables in
+    /// `names`, combining together all the samples which only differ by the
+    /// value of these variables.
+    ///
+    /// The new samples contain the remaining sample variables (or a single
+    /// `"_"` variable if all variables are reduced over), and are sorted
+    /// lexicographically. The gradients are reduced accordingly.
+    ///
+    /// [`Reduction::Sum`] only requires [`Array::scatter_add_from`], while the
+    /// other reductions also need [`Array::data`] to be available.
+    ///
+    /// [`Array::scatter_add_from`]: crate::Array::scatter_add_from
+    /// [`Array::data`]: crate::Array::data
+    #[inline]
+    pub fn reduce_over_samples(&self, names: &[&str], reduction: Reduction) -> Result<TensorMap, Error> {
+        let names_c = names.iter()
+            .map(|&v| CString::new(v).expect("unexpected NULL byte"))
+            .collect::<Vec<_>>();
+
+        let names_ptr = names_c.iter()
+            .map(|v| v.as_ptr())
+            .collect::<Vec<_>>();
+
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_reduce_over_samples(
+                self.ptr,
+                names_ptr.as_ptr(),
+                names.len(),
+                reduction.as_c_str().as_ptr(),
+            )
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
+    /// Slice all the blocks in this `TensorMap` along the given `axis`, keeping
+    /// only the samples or properties matching the `selection`.
+    ///
+    /// See [`TensorBlockRef::slice`] for more information. The keys of the new
+    /// tensor map are the same as the keys of this one, and blocks can end up
+    /// with zero samples or properties if none of them match the `selection`.
+    ///
+    /// [`TensorBlockRef::slice`]: crate::TensorBlockRef::slice
+    #[inline]
+    pub fn slice(&self, axis: Axis, selection: &Labels) -> Result<TensorMap, Error> {
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_slice(
+                self.ptr,
+                axis.as_c_str().as_ptr(),
+                selection.as_eqs_labels_t(),
+            )
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
+    /// Create a new `TensorMap` with the keys (and the corresponding blocks)
+    /// sorted in lexicographic order.
+    #[inline]
+    pub fn sort_keys(&self) -> Result<TensorMap, Error> {
+        let ptr = unsafe {
+            crate::c_api::eqs_tensormap_sort_keys(self.ptr)
+        };
+
+        check_ptr(ptr)?;
+        return Ok(unsafe { TensorMap::from_raw(ptr) });
+    }
+
     /// Get an iterator over the keys and associated blocks
     #[inline]
     pub fn iter(&self) -> TensorMapIter<'_> {
@@ -0,0 +1,155 @@
+#![allow(clippy::needless_return)]
+
+use std::ops::Range;
+
+use equistore::{Array, ArrayDataMut, check_array};
+use equistore::c_api::{eqs_dtype_t, eqs_sample_mapping_t, EQS_DTYPE_FLOAT32};
+
+use ndarray::{ArrayD, Axis};
+
+#[test]
+fn ndarray() {
+    check_array(&ArrayD::<f64>::zeros(vec![2, 3])).unwrap();
+    check_array(&ArrayD::<f32>::zeros(vec![2, 3])).unwrap();
+    check_array(&ArrayD::<i32>::zeros(vec![2, 3])).unwrap();
+    check_array(&ArrayD::<i64>::zeros(vec![2, 3])).unwrap();
+}
+
+/// Array where `swap_axes` does nothing
+struct NoSwapArray(ArrayD<f64>);
+
+impl NoSwapArray {
+    fn inner(array: &dyn Array) -> &ArrayD<f64> {
+        &array.as_any().downcast_ref::<NoSwapArray>().expect("input must be a NoSwapArray").0
+    }
+}
+
+impl Array for NoSwapArray {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
+    fn create(&self, shape: &[usize]) -> Box<dyn Array> {
+        Box::new(NoSwapArray(ArrayD::zeros(shape)))
+    }
+    fn copy(&self) -> Box<dyn Array> {
+        Box::new(NoSwapArray(self.0.clone()))
+    }
+    fn dtype(&self) -> eqs_dtype_t { self.0.dtype() }
+    fn data(&mut self) -> ArrayDataMut<'_> { self.0.data() }
+    fn shape(&self) -> &[usize] { self.0.shape() }
+    fn reshape(&mut self, shape: &[usize]) { Array::reshape(&mut self.0, shape) }
+    fn swap_axes(&mut self, _: usize, _: usize) {}
+    fn move_samples_from(&mut self, input: &dyn Array, samples: &[eqs_sample_mapping_t], properties: Range<usize>) {
+        self.0.move_samples_from(NoSwapArray::inner(input), samples, properties);
+    }
+    fn gather_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        self.0.gather_from(NoSwapArray::inner(input), axis, indices);
+    }
+    fn scatter_add_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        self.0.scatter_add_from(NoSwapArray::inner(input), axis, indices);
+    }
+    fn move_data(&mut self, input: &dyn Array, samples: &[eqs_sample_mapping_t], properties: &[usize]) {
+        self.0.move_data(NoSwapArray::inner(input), samples, properties);
+    }
+}
+
+#[test]
+fn invalid_implementation() {
+    let error = check_array(&NoSwapArray(ArrayD::zeros(vec![2, 3]))).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: invalid implementation of eqs_array_t.swap_axes: \
+        expected the array to have shape [4, 2, 3], got [3, 2, 4]"
+    );
+}
+
+/// Array where `scatter_add_from` overwrites the data instead of adding to it,
+/// and using the default implementation of `move_data`
+struct NoAddArray(ArrayD<f64>);
+
+impl NoAddArray {
+    fn inner(array: &dyn Array) -> &ArrayD<f64> {
+        &array.as_any().downcast_ref::<NoAddArray>().expect("input must be a NoAddArray").0
+    }
+}
+
+impl Array for NoAddArray {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
+    fn create(&self, shape: &[usize]) -> Box<dyn Array> {
+        Box::new(NoAddArray(ArrayD::zeros(shape)))
+    }
+    fn copy(&self) -> Box<dyn Array> {
+        Box::new(NoAddArray(self.0.clone()))
+    }
+    fn dtype(&self) -> eqs_dtype_t { self.0.dtype() }
+    fn data(&mut self) -> ArrayDataMut<'_> { self.0.data() }
+    fn shape(&self) -> &[usize] { self.0.shape() }
+    fn reshape(&mut self, shape: &[usize]) { Array::reshape(&mut self.0, shape) }
+    fn swap_axes(&mut self, axis_1: usize, axis_2: usize) { Array::swap_axes(&mut self.0, axis_1, axis_2) }
+    fn move_samples_from(&mut self, input: &dyn Array, samples: &[eqs_sample_mapping_t], properties: Range<usize>) {
+        self.0.move_samples_from(NoAddArray::inner(input), samples, properties);
+    }
+    fn gather_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        self.0.gather_from(NoAddArray::inner(input), axis, indices);
+    }
+    fn scatter_add_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        let input = NoAddArray::inner(input);
+        for (i, &index) in indices.iter().enumerate() {
+            self.0.index_axis_mut(Axis(axis), index).assign(&input.index_axis(Axis(axis), i));
+        }
+    }
+}
+
+#[test]
+fn invalid_scatter_add() {
+    let error = check_array(&NoAddArray(ArrayD::zeros(vec![2, 3]))).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: invalid implementation of eqs_array_t.scatter_add_from: \
+        the data should be summed in the requested indices, but the array \
+        contains different values"
+    );
+}
+
+/// Array where `dtype` does not match the type of the data returned by `data`
+struct WrongDtypeArray(ArrayD<f64>);
+
+impl WrongDtypeArray {
+    fn inner(array: &dyn Array) -> &ArrayD<f64> {
+        &array.as_any().downcast_ref::<WrongDtypeArray>().expect("input must be a WrongDtypeArray").0
+    }
+}
+
+impl Array for WrongDtypeArray {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
+    fn create(&self, shape: &[usize]) -> Box<dyn Array> {
+        Box::new(WrongDtypeArray(ArrayD::zeros(shape)))
+    }
+    fn copy(&self) -> Box<dyn Array> {
+        Box::new(WrongDtypeArray(self.0.clone()))
+    }
+    fn dtype(&self) -> eqs_dtype_t { EQS_DTYPE_FLOAT32 }
+    fn data(&mut self) -> ArrayDataMut<'_> { self.0.data() }
+    fn shape(&self) -> &[usize] { self.0.shape() }
+    fn reshape(&mut self, shape: &[usize]) { Array::reshape(&mut self.0, shape) }
+    fn swap_axes(&mut self, axis_1: usize, axis_2: usize) { Array::swap_axes(&mut self.0, axis_1, axis_2) }
+    fn move_samples_from(&mut self, input: &dyn Array, samples: &[eqs_sample_mapping_t], properties: Range<usize>) {
+        self.0.move_samples_from(WrongDtypeArray::inner(input), samples, properties);
+    }
+    fn gather_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        self.0.gather_from(WrongDtypeArray::inner(input), axis, indices);
+    }
+    fn scatter_add_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        self.0.scatter_add_from(WrongDtypeArray::inner(input), axis, indices);
+    }
+}
+
+#[test]
+fn invalid_dtype() {
+    let error = check_array(&WrongDtypeArray(ArrayD::zeros(vec![2, 3]))).unwrap_err();
+    assert_eq!(
+        error.message,
+        "Array::data() returned data with dtype 1, but Array::dtype() is 2"
+    );
+}
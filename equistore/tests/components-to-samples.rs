@@ -0,0 +1,135 @@
+use equistore::{TensorBlock, TensorMap, Labels};
+
+use ndarray::ArrayD;
+
+mod utils;
+use utils::example_labels;
+
+#[test]
+fn one_component() {
+    let data = ArrayD::from_shape_vec(vec![2, 3, 1], vec![
+        1.0, 2.0, 3.0,
+        4.0, 5.0, 6.0,
+    ]).unwrap();
+
+    let components = [example_labels(vec!["components"], vec![[0], [1], [2]])];
+    let mut block = TensorBlock::new(
+        data,
+        example_labels(vec!["samples"], vec![[0], [3]]),
+        &components,
+        example_labels(vec!["properties"], vec![[0]]),
+    ).unwrap();
+
+    block.add_gradient(
+        "parameter",
+        ArrayD::from_shape_vec(vec![2, 3, 1], vec![
+            -1.0, -2.0, -3.0,
+            -4.0, -5.0, -6.0,
+        ]).unwrap(),
+        example_labels(vec!["sample", "parameter"], vec![[1, 0], [1, 2]]),
+        &components,
+    ).unwrap();
+
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+    let tensor = tensor.components_to_samples(&["components"]).unwrap();
+
+    let block = tensor.block_by_id(0);
+    let values = block.values();
+    assert_eq!(values.samples, Labels::new(["samples", "components"], &[
+        [0, 0], [0, 1], [0, 2], [3, 0], [3, 1], [3, 2],
+    ]));
+    assert_eq!(values.components.len(), 0);
+    assert_eq!(values.properties, Labels::new(["properties"], &[[0]]));
+
+    let expected = ArrayD::from_shape_vec(vec![6, 1], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    assert_eq!(values.data.as_array(), expected);
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[
+        [3, 0], [4, 0], [5, 0], [3, 2], [4, 2], [5, 2],
+    ]));
+    assert_eq!(gradient.components.len(), 0);
+
+    let expected = ArrayD::from_shape_vec(vec![6, 1], vec![-1.0, -2.0, -3.0, -4.0, -5.0, -6.0]).unwrap();
+    assert_eq!(gradient.data.as_array(), expected);
+}
+
+#[test]
+fn multiple_components() {
+    let data = ArrayD::from_shape_vec(vec![1, 2, 3, 2], vec![
+        1.0, -1.0, 2.0, -2.0, 3.0, -3.0,
+        4.0, -4.0, 5.0, -5.0, 6.0, -6.0,
+    ]).unwrap();
+
+    let components = [
+        example_labels(vec!["component_1"], vec![[0], [1]]),
+        example_labels(vec!["component_2"], vec![[0], [1], [2]]),
+    ];
+
+    let mut block = TensorBlock::new(
+        data,
+        example_labels(vec!["samples"], vec![[0]]),
+        &components,
+        example_labels(vec!["properties"], vec![[0], [1]]),
+    ).unwrap();
+
+    let gradient_components = [
+        example_labels(vec!["direction"], vec![[0], [1], [2]]),
+        components[0].clone(),
+        components[1].clone(),
+    ];
+    block.add_gradient(
+        "positions",
+        ArrayD::from_elem(vec![1, 3, 2, 3, 2], 11.0),
+        example_labels(vec!["sample", "atom"], vec![[0, 2]]),
+        &gradient_components,
+    ).unwrap();
+
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+    let tensor = tensor.components_to_samples(&["component_2"]).unwrap();
+
+    let block = tensor.block_by_id(0);
+    let values = block.values();
+    assert_eq!(values.samples, Labels::new(["samples", "component_2"], &[[0, 0], [0, 1], [0, 2]]));
+    assert_eq!(values.components.len(), 1);
+    assert_eq!(values.components[0].names(), ["component_1"]);
+
+    let expected = ArrayD::from_shape_vec(vec![3, 2, 2], vec![
+        1.0, -1.0, 4.0, -4.0,
+        2.0, -2.0, 5.0, -5.0,
+        3.0, -3.0, 6.0, -6.0,
+    ]).unwrap();
+    assert_eq!(values.data.as_array(), expected);
+
+    let gradient = block.gradient("positions").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "atom"], &[[0, 2], [1, 2], [2, 2]]));
+    assert_eq!(gradient.components.len(), 2);
+    assert_eq!(gradient.components[0].names(), ["direction"]);
+    assert_eq!(gradient.components[1].names(), ["component_1"]);
+    assert_eq!(gradient.data.as_array(), ArrayD::from_elem(vec![3, 3, 2, 2], 11.0));
+}
+
+#[test]
+fn errors() {
+    let block = TensorBlock::new(
+        ArrayD::from_elem(vec![2, 3, 1], 1.0),
+        example_labels(vec!["samples"], vec![[0], [1]]),
+        &[example_labels(vec!["samples_2"], vec![[0], [1], [2]])],
+        example_labels(vec!["properties"], vec![[0]]),
+    ).unwrap();
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+
+    let error = tensor.components_to_samples(&["not_there"]).unwrap_err();
+    assert_eq!(error.message, "invalid parameter: unable to find [not_there] in the components ");
+
+    let block = TensorBlock::new(
+        ArrayD::from_elem(vec![2, 3, 1], 1.0),
+        example_labels(vec!["samples"], vec![[0], [1]]),
+        &[example_labels(vec!["samples"], vec![[0], [1], [2]])],
+        example_labels(vec!["properties"], vec![[0]]),
+    ).unwrap();
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+
+    let error = tensor.components_to_samples(&["samples"]).unwrap_err();
+    assert_eq!(error.message, "invalid parameter: 'samples' is already part of the samples for this block");
+}
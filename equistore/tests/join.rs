@@ -0,0 +1,203 @@
+use equistore::{Labels, TensorMap, Axis};
+
+mod utils;
+use utils::{example_tensor, example_block};
+
+use ndarray::ArrayD;
+
+#[test]
+fn samples() {
+    let tensor = example_tensor();
+    let joined = TensorMap::join(&[&tensor, &tensor], Axis::Samples).unwrap();
+
+    assert_eq!(joined.keys().count(), tensor.keys().count());
+    for (joined_key, key) in joined.keys().iter().zip(tensor.keys()) {
+        assert_eq!(joined_key, key);
+    }
+
+    // the samples are duplicated, so a new "tensor" variable is added
+    let block_1 = joined.block_by_id(0);
+    assert_eq!(block_1.values().samples.names(), ["tensor", "samples"]);
+    assert_eq!(block_1.values().samples.count(), 6);
+    assert_eq!(block_1.values().samples[0], [0, 0]);
+    assert_eq!(block_1.values().samples[1], [0, 2]);
+    assert_eq!(block_1.values().samples[2], [0, 4]);
+    assert_eq!(block_1.values().samples[3], [1, 0]);
+    assert_eq!(block_1.values().samples[4], [1, 2]);
+    assert_eq!(block_1.values().samples[5], [1, 4]);
+
+    assert_eq!(block_1.values().properties.count(), 1);
+    assert_eq!(block_1.values().data.as_array(), ArrayD::from_elem(vec![6, 1, 1], 1.0));
+
+    let gradient_1 = block_1.gradient("parameter").unwrap();
+    assert_eq!(gradient_1.samples.names(), ["sample", "parameter"]);
+    assert_eq!(gradient_1.samples.count(), 4);
+    assert_eq!(gradient_1.samples[0], [0, -2]);
+    assert_eq!(gradient_1.samples[1], [2, 3]);
+    assert_eq!(gradient_1.samples[2], [3, -2]);
+    assert_eq!(gradient_1.samples[3], [5, 3]);
+
+    assert_eq!(gradient_1.data.as_array(), ArrayD::from_elem(vec![4, 1, 1], 11.0));
+}
+
+#[test]
+fn samples_unique() {
+    let block_1 = example_block(
+        /* samples          */ vec![[0], [2]],
+        /* components       */ vec![[0]],
+        /* properties       */ vec![[0], [1]],
+        /* gradient_samples */ vec![[1, 1]],
+        /* values           */ 1.0,
+        /* gradient_values  */ 11.0,
+    );
+    let first = TensorMap::new(Labels::single(), vec![block_1]).unwrap();
+
+    let block_2 = example_block(
+        /* samples          */ vec![[1], [3], [5]],
+        /* components       */ vec![[0]],
+        /* properties       */ vec![[0], [1]],
+        /* gradient_samples */ vec![[0, 1], [2, 2]],
+        /* values           */ 2.0,
+        /* gradient_values  */ 12.0,
+    );
+    let second = TensorMap::new(Labels::single(), vec![block_2]).unwrap();
+
+    let joined = TensorMap::join(&[&first, &second], Axis::Samples).unwrap();
+    let block = joined.block_by_id(0);
+
+    // the samples are unique, so they are concatenated as-is
+    assert_eq!(block.values().samples.names(), ["samples"]);
+    assert_eq!(block.values().samples.count(), 5);
+    assert_eq!(block.values().samples[0], [0]);
+    assert_eq!(block.values().samples[1], [2]);
+    assert_eq!(block.values().samples[2], [1]);
+    assert_eq!(block.values().samples[3], [3]);
+    assert_eq!(block.values().samples[4], [5]);
+
+    let expected = ArrayD::from_shape_vec(vec![5, 1, 2], vec![
+        1.0, 1.0,
+        1.0, 1.0,
+        2.0, 2.0,
+        2.0, 2.0,
+        2.0, 2.0,
+    ]).unwrap();
+    assert_eq!(block.values().data.as_array(), expected);
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples.count(), 3);
+    assert_eq!(gradient.samples[0], [1, 1]);
+    assert_eq!(gradient.samples[1], [2, 1]);
+    assert_eq!(gradient.samples[2], [4, 2]);
+
+    let expected = ArrayD::from_shape_vec(vec![3, 1, 2], vec![
+        11.0, 11.0,
+        12.0, 12.0,
+        12.0, 12.0,
+    ]).unwrap();
+    assert_eq!(gradient.data.as_array(), expected);
+}
+
+#[test]
+fn properties() {
+    let block_1 = example_block(
+        /* samples          */ vec![[0], [1], [2]],
+        /* components       */ vec![[0]],
+        /* properties       */ vec![[0], [1]],
+        /* gradient_samples */ vec![[0, 1], [2, 1]],
+        /* values           */ 1.0,
+        /* gradient_values  */ 11.0,
+    );
+    let first = TensorMap::new(Labels::single(), vec![block_1]).unwrap();
+
+    let block_2 = example_block(
+        /* samples          */ vec![[0], [1], [2]],
+        /* components       */ vec![[0]],
+        /* properties       */ vec![[1], [2], [3]],
+        /* gradient_samples */ vec![[1, 1], [2, 1]],
+        /* values           */ 2.0,
+        /* gradient_values  */ 12.0,
+    );
+    let second = TensorMap::new(Labels::single(), vec![block_2]).unwrap();
+
+    let joined = TensorMap::join(&[&first, &second], Axis::Properties).unwrap();
+    let block = joined.block_by_id(0);
+
+    assert_eq!(block.values().samples.count(), 3);
+
+    // the properties are duplicated, so a new "tensor" variable is added
+    assert_eq!(block.values().properties.names(), ["tensor", "properties"]);
+    assert_eq!(block.values().properties.count(), 5);
+    assert_eq!(block.values().properties[0], [0, 0]);
+    assert_eq!(block.values().properties[1], [0, 1]);
+    assert_eq!(block.values().properties[2], [1, 1]);
+    assert_eq!(block.values().properties[3], [1, 2]);
+    assert_eq!(block.values().properties[4], [1, 3]);
+
+    let expected = ArrayD::from_shape_vec(vec![3, 1, 5], vec![
+        1.0, 1.0, 2.0, 2.0, 2.0,
+        1.0, 1.0, 2.0, 2.0, 2.0,
+        1.0, 1.0, 2.0, 2.0, 2.0,
+    ]).unwrap();
+    assert_eq!(block.values().data.as_array(), expected);
+
+    // gradient samples are the union of all the gradient samples
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples.count(), 3);
+    assert_eq!(gradient.samples[0], [0, 1]);
+    assert_eq!(gradient.samples[1], [1, 1]);
+    assert_eq!(gradient.samples[2], [2, 1]);
+
+    let expected = ArrayD::from_shape_vec(vec![3, 1, 5], vec![
+        11.0, 11.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 12.0, 12.0, 12.0,
+        11.0, 11.0, 12.0, 12.0, 12.0,
+    ]).unwrap();
+    assert_eq!(gradient.data.as_array(), expected);
+}
+
+#[test]
+fn errors() {
+    let tensor = example_tensor();
+    let keys_to_move = Labels::empty(vec!["key_2"]);
+    let other = tensor.keys_to_samples(&keys_to_move, true).unwrap();
+
+    let error = TensorMap::join(&[&tensor, &other], Axis::Samples).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: all tensor maps must have the same keys to be \
+        joined, got keys with names [key_1, key_2] and [key_1]"
+    );
+
+    let block_1 = example_block(
+        /* samples          */ vec![[0], [1]],
+        /* components       */ vec![[0]],
+        /* properties       */ vec![[0]],
+        /* gradient_samples */ vec![[0, 1]],
+        /* values           */ 1.0,
+        /* gradient_values  */ 11.0,
+    );
+    let first = TensorMap::new(Labels::single(), vec![block_1]).unwrap();
+
+    let block_2 = example_block(
+        /* samples          */ vec![[0], [2]],
+        /* components       */ vec![[0]],
+        /* properties       */ vec![[0]],
+        /* gradient_samples */ vec![[0, 1]],
+        /* values           */ 2.0,
+        /* gradient_values  */ 12.0,
+    );
+    let second = TensorMap::new(Labels::single(), vec![block_2]).unwrap();
+
+    let error = TensorMap::join(&[&first, &second], Axis::Properties).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: can not join blocks along properties if they \
+        have different sample labels"
+    );
+
+    let error = TensorMap::join(&[], Axis::Samples).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: provide at least one tensor map to join"
+    );
+}
@@ -1,6 +1,9 @@
 #![allow(clippy::needless_return)]
 
-use equistore::{Labels, TensorMap};
+use std::ops::Range;
+
+use equistore::{Array, ArrayDataMut, Labels, TensorBlock, TensorMap};
+use equistore::c_api::{eqs_dtype_t, eqs_sample_mapping_t};
 
 mod utils;
 use utils::{example_tensor, example_block, example_labels};
@@ -10,7 +13,7 @@ use ndarray::ArrayD;
 #[test]
 fn sorted_samples() {
     let keys_to_move = Labels::empty(vec!["key_1"]);
-    let tensor = example_tensor().keys_to_properties(&keys_to_move, true).unwrap();
+    let tensor = example_tensor().keys_to_properties(&keys_to_move, true, false).unwrap();
 
     assert_eq!(tensor.keys(), &example_labels(vec!["key_2"], vec![[0], [2], [3]]));
 
@@ -76,7 +79,7 @@ fn sorted_samples() {
 #[test]
 fn unsorted_samples() {
     let keys_to_move = Labels::empty(vec!["key_1"]);
-    let tensor = example_tensor().keys_to_properties(&keys_to_move, false).unwrap();
+    let tensor = example_tensor().keys_to_properties(&keys_to_move, false, false).unwrap();
 
     assert_eq!(tensor.keys().count(), 3);
 
@@ -87,10 +90,161 @@ fn unsorted_samples() {
     );
 }
 
+#[test]
+fn sorted_properties() {
+    let mut block_1 = TensorBlock::new(
+        ArrayD::from_shape_vec(vec![2, 1, 2], vec![1.0, 2.0, 3.0, 4.0]).unwrap(),
+        example_labels(vec!["samples"], vec![[0], [1]]),
+        &[example_labels(vec!["components"], vec![[0]])],
+        example_labels(vec!["properties"], vec![[2], [0]]),
+    ).unwrap();
+    block_1.add_gradient(
+        "parameter",
+        ArrayD::from_shape_vec(vec![1, 1, 2], vec![11.0, 12.0]).unwrap(),
+        example_labels(vec!["sample", "parameter"], vec![[1, 0]]),
+        &[example_labels(vec!["components"], vec![[0]])],
+    ).unwrap();
+
+    let mut block_2 = TensorBlock::new(
+        ArrayD::from_shape_vec(vec![1, 1, 1], vec![5.0]).unwrap(),
+        example_labels(vec!["samples"], vec![[0]]),
+        &[example_labels(vec!["components"], vec![[0]])],
+        example_labels(vec!["properties"], vec![[1]]),
+    ).unwrap();
+    block_2.add_gradient(
+        "parameter",
+        ArrayD::from_shape_vec(vec![1, 1, 1], vec![15.0]).unwrap(),
+        example_labels(vec!["sample", "parameter"], vec![[0, 0]]),
+        &[example_labels(vec!["components"], vec![[0]])],
+    ).unwrap();
+
+    let keys = example_labels(vec!["key"], vec![[1], [0]]);
+    let tensor = TensorMap::new(keys, vec![block_1, block_2]).unwrap();
+
+    let keys_to_move = Labels::empty(vec!["key"]);
+    let unsorted = tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
+    let block = unsorted.block_by_id(0);
+    assert_eq!(
+        block.values().properties,
+        example_labels(vec!["key", "properties"], vec![[1, 2], [1, 0], [0, 1]])
+    );
+
+    let sorted = tensor.keys_to_properties(&keys_to_move, true, true).unwrap();
+    let block = sorted.block_by_id(0);
+    assert_eq!(
+        block.values().properties,
+        example_labels(vec!["key", "properties"], vec![[0, 1], [1, 0], [1, 2]])
+    );
+
+    let expected = ArrayD::from_shape_vec(vec![2, 1, 3], vec![
+        5.0, 2.0, 1.0,
+        0.0, 4.0, 3.0,
+    ]).unwrap();
+    assert_eq!(block.values().data.as_array(), expected);
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(
+        gradient.samples,
+        example_labels(vec!["sample", "parameter"], vec![[0, 0], [1, 0]])
+    );
+
+    let expected = ArrayD::from_shape_vec(vec![2, 1, 3], vec![
+        15.0, 0.0, 0.0,
+        0.0, 12.0, 11.0,
+    ]).unwrap();
+    assert_eq!(gradient.data.as_array(), expected);
+}
+
+/// Array using the default implementation of `Array::move_data`
+struct NoMoveDataArray(ArrayD<f64>);
+
+impl NoMoveDataArray {
+    fn inner(array: &dyn Array) -> &ArrayD<f64> {
+        &array.as_any().downcast_ref::<NoMoveDataArray>().expect("input must be a NoMoveDataArray").0
+    }
+}
+
+impl Array for NoMoveDataArray {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
+    fn create(&self, shape: &[usize]) -> Box<dyn Array> {
+        Box::new(NoMoveDataArray(ArrayD::zeros(shape)))
+    }
+    fn copy(&self) -> Box<dyn Array> {
+        Box::new(NoMoveDataArray(self.0.clone()))
+    }
+    fn dtype(&self) -> eqs_dtype_t { self.0.dtype() }
+    fn data(&mut self) -> ArrayDataMut<'_> { self.0.data() }
+    fn shape(&self) -> &[usize] { self.0.shape() }
+    fn reshape(&mut self, shape: &[usize]) { Array::reshape(&mut self.0, shape) }
+    fn swap_axes(&mut self, axis_1: usize, axis_2: usize) { Array::swap_axes(&mut self.0, axis_1, axis_2) }
+    fn move_samples_from(&mut self, input: &dyn Array, samples: &[eqs_sample_mapping_t], properties: Range<usize>) {
+        self.0.move_samples_from(NoMoveDataArray::inner(input), samples, properties);
+    }
+    fn gather_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        self.0.gather_from(NoMoveDataArray::inner(input), axis, indices);
+    }
+    fn scatter_add_from(&mut self, input: &dyn Array, axis: usize, indices: &[usize]) {
+        self.0.scatter_add_from(NoMoveDataArray::inner(input), axis, indices);
+    }
+}
+
+#[test]
+fn default_move_data() {
+    let block_1 = TensorBlock::new(
+        NoMoveDataArray(ArrayD::from_shape_vec(vec![2, 1, 2], vec![1.0, 2.0, 3.0, 4.0]).unwrap()),
+        example_labels(vec!["samples"], vec![[0], [1]]),
+        &[example_labels(vec!["components"], vec![[0]])],
+        example_labels(vec!["properties"], vec![[2], [0]]),
+    ).unwrap();
+
+    let block_2 = TensorBlock::new(
+        NoMoveDataArray(ArrayD::from_shape_vec(vec![1, 1, 1], vec![5.0]).unwrap()),
+        example_labels(vec!["samples"], vec![[0]]),
+        &[example_labels(vec!["components"], vec![[0]])],
+        example_labels(vec!["properties"], vec![[1]]),
+    ).unwrap();
+
+    let keys = example_labels(vec!["key"], vec![[1], [0]]);
+    let tensor = TensorMap::new(keys, vec![block_1, block_2]).unwrap();
+
+    // without sorting, the properties of each block are moved to a contiguous
+    // range
+    let keys_to_move = Labels::empty(vec!["key"]);
+    let unsorted = tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
+    let block = unsorted.block_by_id(0);
+    assert_eq!(
+        block.values().properties,
+        example_labels(vec!["key", "properties"], vec![[1, 2], [1, 0], [0, 1]])
+    );
+
+    let expected = ArrayD::from_shape_vec(vec![2, 1, 3], vec![
+        1.0, 2.0, 5.0,
+        3.0, 4.0, 0.0,
+    ]).unwrap();
+    let values = block.values().data.to_any().downcast_ref::<NoMoveDataArray>().unwrap();
+    assert_eq!(values.0, expected);
+
+    // sorting the properties requires moving them to non-contiguous positions
+    let sorted = tensor.keys_to_properties(&keys_to_move, true, true).unwrap();
+    let block = sorted.block_by_id(0);
+    assert_eq!(
+        block.values().properties,
+        example_labels(vec!["key", "properties"], vec![[0, 1], [1, 0], [1, 2]])
+    );
+
+    let expected = ArrayD::from_shape_vec(vec![2, 1, 3], vec![
+        5.0, 2.0, 1.0,
+        0.0, 4.0, 3.0,
+    ]).unwrap();
+    let values = block.values().data.to_any().downcast_ref::<NoMoveDataArray>().unwrap();
+    assert_eq!(values.0, expected);
+}
+
 #[test]
 fn user_provided_entries_different_properties() {
     let keys_to_move = Labels::new(["key_1"], &[[0]]);
-    let result = example_tensor().keys_to_properties(&keys_to_move, false);
+    let result = example_tensor().keys_to_properties(&keys_to_move, false, false);
 
     assert_eq!(
         result.unwrap_err().message,
@@ -128,7 +282,7 @@ fn empty_properties() {
     let mut tensor = TensorMap::new(keys, blocks).unwrap();
 
     let keys_to_move = Labels::empty(vec!["key_1"]);
-    tensor = tensor.keys_to_properties(&keys_to_move, true).unwrap();
+    tensor = tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
 
     assert_eq!(
         tensor.block_by_id(0).values().properties,
@@ -183,7 +337,7 @@ fn example_tensor_same_properties_in_all_blocks() -> TensorMap {
 fn keys_to_move_in_different_order() {
     let keys_to_move = Labels::empty(vec!["key_1", "key_2"]);
     let reference_tensor = example_tensor_same_properties_in_all_blocks();
-    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true).unwrap();
+    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
 
     assert_eq!(
         tensor.block_by_id(0).values().properties,
@@ -204,7 +358,7 @@ fn keys_to_move_in_different_order() {
     );
 
     let keys_to_move = Labels::empty(vec!["key_2", "key_1"]);
-    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true).unwrap();
+    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
 
     assert_eq!(
         tensor.block_by_id(0).values().properties,
@@ -231,7 +385,7 @@ fn user_provided_entries() {
     let reference_tensor = example_tensor_same_properties_in_all_blocks();
 
     let keys_to_move = Labels::new(["key_1"], &[[0], [1]]);
-    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true).unwrap();
+    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
 
     // The new first block contains the old first two blocks merged
     let block = tensor.block_by_id(0);
@@ -285,7 +439,7 @@ fn user_provided_entries() {
 
     // only keep a subset of the data
     let keys_to_move = Labels::new(["key_1"], &[[0]]);
-    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true).unwrap();
+    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
 
     let block = tensor.block_by_id(0);
     assert_eq!(
@@ -330,7 +484,7 @@ fn user_provided_entries() {
     /**********************************************************************/
     // request keys not present in the input
     let keys_to_move = Labels::new(["key_1"], &[[0], [1], [2]]);
-    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true).unwrap();
+    let tensor = reference_tensor.keys_to_properties(&keys_to_move, true, false).unwrap();
 
     let block = tensor.block_by_id(0);
     assert_eq!(
@@ -102,15 +102,63 @@ fn unsorted_samples() {
 
 #[test]
 fn user_provided_entries() {
-    let keys_to_move = Labels::new(["key_2"], &[[3]]);
-    let result = example_tensor().keys_to_samples(&keys_to_move, false);
+    let keys_to_move = Labels::new(["key_2"], &[[3], [0]]);
+    let tensor = example_tensor().keys_to_samples(&keys_to_move, false).unwrap();
 
-    assert_eq!(
-        result.unwrap_err().message,
-        "invalid parameter: user provided values for the keys to move is \
-        not yet implemented, `keys_to_move` should not contain any entry \
-        when calling keys_to_samples"
-    );
+    assert_eq!(tensor.keys().count(), 3);
+    assert_eq!(tensor.keys().names(), ["key_1"]);
+
+    // the block with key_2=2 is ignored, and the samples of the block with
+    // key_2=3 are used for all the entries in keys_to_move
+    let block = tensor.block_by_id(2);
+    assert_eq!(block.values().samples, Labels::new(["samples", "key_2"], &[
+        [0, 3], [3, 3], [6, 3], [8, 3], [1, 3], [2, 3], [5, 3],
+        [0, 0], [3, 0], [6, 0], [8, 0], [1, 0], [2, 0], [5, 0],
+    ]));
+
+    let mut expected = ArrayD::from_elem(vec![14, 3, 1], 0.0);
+    for sample in [0, 4, 5, 6] {
+        expected.index_axis_mut(ndarray::Axis(0), sample).fill(4.0);
+    }
+    assert_eq!(block.values().data.as_array(), expected);
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, 1], [6, 3]]));
+    assert_eq!(gradient.data.as_array(), ArrayD::from_elem(vec![2, 3, 1], 14.0));
+}
+
+#[test]
+fn user_provided_entries_sorted() {
+    let keys_to_move = Labels::new(["key_2"], &[[3], [0]]);
+    let tensor = example_tensor().keys_to_samples(&keys_to_move, true).unwrap();
+
+    let block = tensor.block_by_id(0);
+    assert_eq!(block.values().samples, Labels::new(["samples", "key_2"], &[
+        [0, 0], [0, 3], [2, 0], [2, 3], [4, 0], [4, 3],
+    ]));
+
+    let expected = ArrayD::from_shape_vec(vec![6, 1, 1], vec![
+        1.0, 0.0, 1.0, 0.0, 1.0, 0.0
+    ]).unwrap();
+    assert_eq!(block.values().data.as_array(), expected);
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, -2], [4, 3]]));
+    assert_eq!(gradient.data.as_array(), ArrayD::from_elem(vec![2, 1, 1], 11.0));
+
+    // none of the blocks match the requested keys
+    let keys_to_move = Labels::new(["key_2"], &[[42]]);
+    let tensor = example_tensor().keys_to_samples(&keys_to_move, true).unwrap();
+
+    let block = tensor.block_by_id(1);
+    assert_eq!(block.values().samples, Labels::new(["samples", "key_2"], &[
+        [0, 42], [1, 42], [3, 42],
+    ]));
+    assert_eq!(block.values().data.as_array(), ArrayD::from_elem(vec![3, 1, 3], 0.0));
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples.count(), 0);
+    assert_eq!(gradient.samples.names(), ["sample", "parameter"]);
 }
 
 #[test]
@@ -153,3 +201,30 @@ fn empty_samples() {
         ])
     );
 }
+
+#[test]
+fn merge_info() {
+    let mut tensor = example_tensor();
+    for mut block in tensor.blocks_mut() {
+        block.set_info("values", "units", "eV").unwrap();
+        block.set_info("parameter", "units", "eV/A").unwrap();
+    }
+    tensor.block_mut_by_id(2).set_info("values", "origin", "first").unwrap();
+
+    let keys_to_move = Labels::empty(vec!["key_2"]);
+    let moved = tensor.keys_to_samples(&keys_to_move, true).unwrap();
+
+    let block = moved.block_by_id(2);
+    assert_eq!(block.values().info.len(), 2);
+    assert_eq!(block.values().info["units"], "eV");
+    assert_eq!(block.values().info["origin"], "first");
+    assert_eq!(block.gradient("parameter").unwrap().info["units"], "eV/A");
+
+    // the same key with different values in the merged blocks is an error
+    tensor.block_mut_by_id(3).set_info("values", "origin", "second").unwrap();
+    let error = tensor.keys_to_samples(&keys_to_move, true).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: can not merge metadata with different values for 'origin': 'first' and 'second'"
+    );
+}
@@ -0,0 +1,289 @@
+#![allow(clippy::needless_return)]
+
+use equistore::{Labels, TensorBlock, TensorMap};
+
+mod utils;
+use utils::{example_tensor, example_block, example_labels};
+
+use ndarray::ArrayD;
+
+/// Block with different values for each sample, and multiple gradient samples
+/// referring to the same sample
+fn varying_block() -> TensorBlock {
+    let components = [example_labels(vec!["components"], vec![[0]])];
+    let mut block = TensorBlock::new(
+        ArrayD::from_shape_vec(vec![2, 1, 1], vec![2.0, 3.0]).unwrap(),
+        example_labels(vec!["samples"], vec![[0], [1]]),
+        &components,
+        example_labels(vec!["properties"], vec![[0]]),
+    ).unwrap();
+
+    block.add_gradient(
+        "parameter",
+        ArrayD::from_shape_vec(vec![3, 1, 1], vec![1.0, 2.0, 4.0]).unwrap(),
+        example_labels(vec!["sample", "parameter"], vec![[0, 0], [1, 0], [1, 1]]),
+        &components,
+    ).unwrap();
+
+    return block;
+}
+
+fn array(shape: Vec<usize>, values: Vec<f64>) -> ArrayD<f64> {
+    return ArrayD::from_shape_vec(shape, values).unwrap();
+}
+
+#[test]
+fn add() {
+    let tensor = example_tensor();
+
+    let result = tensor.add(&tensor).unwrap();
+    assert_eq!(result.keys(), tensor.keys());
+    for (block, result) in tensor.blocks().iter().zip(result.blocks()) {
+        let values = block.values().data.as_array() * 2.0;
+        assert_eq!(result.values().data.as_array(), values);
+        assert_eq!(result.values().samples, block.values().samples);
+
+        let gradient = block.gradient("parameter").unwrap();
+        let result_gradient = result.gradient("parameter").unwrap();
+        assert_eq!(result_gradient.data.as_array(), gradient.data.as_array() * 2.0);
+        assert_eq!(result_gradient.samples, gradient.samples);
+    }
+
+    let result = tensor.add_scalar(3.0).unwrap();
+    for (block, result) in tensor.blocks().iter().zip(result.blocks()) {
+        let values = block.values().data.as_array() + 3.0;
+        assert_eq!(result.values().data.as_array(), values);
+
+        let gradient = block.gradient("parameter").unwrap();
+        let result_gradient = result.gradient("parameter").unwrap();
+        assert_eq!(result_gradient.data.as_array(), gradient.data.as_array());
+    }
+}
+
+#[test]
+fn subtract() {
+    let tensor = example_tensor();
+
+    let result = tensor.subtract(&tensor).unwrap();
+    for (block, result) in tensor.blocks().iter().zip(result.blocks()) {
+        let shape = block.values().data.as_array().shape().to_vec();
+        assert_eq!(result.values().data.as_array(), ArrayD::zeros(shape));
+
+        let gradient = block.gradient("parameter").unwrap();
+        let shape = gradient.data.as_array().shape().to_vec();
+        let result_gradient = result.gradient("parameter").unwrap();
+        assert_eq!(result_gradient.data.as_array(), ArrayD::zeros(shape));
+    }
+
+    let result = tensor.subtract_scalar(3.0).unwrap();
+    for (block, result) in tensor.blocks().iter().zip(result.blocks()) {
+        let values = block.values().data.as_array() - 3.0;
+        assert_eq!(result.values().data.as_array(), values);
+
+        let gradient = block.gradient("parameter").unwrap();
+        let result_gradient = result.gradient("parameter").unwrap();
+        assert_eq!(result_gradient.data.as_array(), gradient.data.as_array());
+    }
+}
+
+#[test]
+fn multiply() {
+    let block = varying_block();
+    let block = block.as_ref();
+
+    // ∇(A * A) = 2 * A * ∇A
+    let result = block.multiply(&block).unwrap();
+    assert_eq!(result.as_ref().values().data.as_array(), array(vec![2, 1, 1], vec![4.0, 9.0]));
+
+    let gradient = result.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 1, 1], vec![4.0, 12.0, 24.0]));
+
+    let result = block.multiply_scalar(3.0).unwrap();
+    assert_eq!(result.as_ref().values().data.as_array(), array(vec![2, 1, 1], vec![6.0, 9.0]));
+
+    let gradient = result.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 1, 1], vec![3.0, 6.0, 12.0]));
+
+    let tensor = example_tensor();
+    let result = tensor.multiply(&tensor).unwrap();
+    for (block, result) in tensor.blocks().iter().zip(result.blocks()) {
+        let values = block.values().data.as_array().mapv(|v| v * v);
+        assert_eq!(result.values().data.as_array(), values);
+    }
+}
+
+#[test]
+fn divide() {
+    let first = varying_block();
+    let first = first.as_ref();
+    let second = first.multiply_scalar(2.0).unwrap();
+    let second = second.as_ref();
+
+    // A / 2A = 1/2 and ∇(A / 2A) = ∇A / 2A - A * 2∇A / 4A² = 0
+    let result = first.divide(&second).unwrap();
+    assert_eq!(result.as_ref().values().data.as_array(), array(vec![2, 1, 1], vec![0.5, 0.5]));
+
+    let gradient = result.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 1, 1], vec![0.0, 0.0, 0.0]));
+
+    // ∇(2A / A) = 2∇A / A - 2A * ∇A / A² = 0, with non-trivial intermediate values
+    let result = second.divide(&first).unwrap();
+    assert_eq!(result.as_ref().values().data.as_array(), array(vec![2, 1, 1], vec![2.0, 2.0]));
+
+    let gradient = result.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 1, 1], vec![0.0, 0.0, 0.0]));
+
+    let result = first.divide_scalar(2.0).unwrap();
+    assert_eq!(result.as_ref().values().data.as_array(), array(vec![2, 1, 1], vec![1.0, 1.5]));
+
+    let gradient = result.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 1, 1], vec![0.5, 1.0, 2.0]));
+
+    let tensor = example_tensor();
+    let result = tensor.divide(&tensor).unwrap();
+    for (block, result) in tensor.blocks().iter().zip(result.blocks()) {
+        let shape = block.values().data.as_array().shape().to_vec();
+        assert_eq!(result.values().data.as_array(), ArrayD::from_elem(shape, 1.0));
+    }
+}
+
+#[test]
+fn pow() {
+    let block = varying_block();
+
+    // ∇(A^3) = 3 * A² * ∇A
+    let result = block.as_ref().pow(3.0).unwrap();
+    assert_eq!(result.as_ref().values().data.as_array(), array(vec![2, 1, 1], vec![8.0, 27.0]));
+
+    let gradient = result.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 1, 1], vec![12.0, 54.0, 108.0]));
+
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+    let result = tensor.pow(2.0).unwrap();
+    let squared = tensor.multiply(&tensor).unwrap();
+
+    let result = result.block_by_id(0);
+    let squared = squared.block_by_id(0);
+    assert_eq!(result.values().data.as_array(), squared.values().data.as_array());
+    assert_eq!(
+        result.gradient("parameter").unwrap().data.as_array(),
+        squared.gradient("parameter").unwrap().data.as_array()
+    );
+}
+
+#[test]
+fn info() {
+    let mut block = varying_block();
+    block.as_ref_mut().set_info("values", "units", "eV").unwrap();
+    block.as_ref_mut().set_info("parameter", "units", "eV/A").unwrap();
+
+    let other = varying_block();
+    let results = [
+        block.as_ref().add(&other.as_ref()).unwrap(),
+        block.as_ref().subtract_scalar(1.0).unwrap(),
+        block.as_ref().multiply(&other.as_ref()).unwrap(),
+        block.as_ref().divide_scalar(2.0).unwrap(),
+        block.as_ref().pow(2.0).unwrap(),
+    ];
+
+    for result in &results {
+        let result = result.as_ref();
+        assert_eq!(result.values().info, block.as_ref().values().info);
+        assert_eq!(
+            result.gradient("parameter").unwrap().info,
+            block.as_ref().gradient("parameter").unwrap().info
+        );
+    }
+}
+
+#[test]
+fn errors() {
+    let tensor = example_tensor();
+    let keys_to_move = Labels::empty(vec!["key_2"]);
+    let other = tensor.keys_to_samples(&keys_to_move, true).unwrap();
+
+    let error = tensor.add(&other).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: inputs to 'add' should have the same keys names, \
+        got [key_1, key_2] and [key_1]"
+    );
+
+    let first = TensorMap::new(Labels::new(["key"], &[[0], [1]]), vec![
+        example_block(vec![[0]], vec![[0]], vec![[0]], vec![[0, 1]], 1.0, 11.0),
+        example_block(vec![[0]], vec![[0]], vec![[0]], vec![[0, 1]], 1.0, 11.0),
+    ]).unwrap();
+    let second = TensorMap::new(Labels::new(["key"], &[[0]]), vec![
+        example_block(vec![[0]], vec![[0]], vec![[0]], vec![[0, 1]], 1.0, 11.0),
+    ]).unwrap();
+    let error = first.subtract(&second).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: inputs to 'subtract' should have the same number \
+        of blocks, got 2 and 1"
+    );
+
+    let second = TensorMap::new(Labels::new(["key"], &[[0], [2]]), vec![
+        example_block(vec![[0]], vec![[0]], vec![[0]], vec![[0, 1]], 1.0, 11.0),
+        example_block(vec![[0]], vec![[0]], vec![[0]], vec![[0, 1]], 1.0, 11.0),
+    ]).unwrap();
+    let error = first.multiply(&second).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: inputs to 'multiply' should have the same keys, \
+        the key (1) is missing from the second tensor map"
+    );
+
+    /**************************************************************************/
+    let first = example_block(vec![[0], [1]], vec![[0]], vec![[0]], vec![[0, 1]], 1.0, 11.0);
+    let second = example_block(vec![[0], [2]], vec![[0]], vec![[0]], vec![[0, 1]], 1.0, 11.0);
+    let error = first.as_ref().divide(&second.as_ref()).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: inputs to 'divide' should have the same samples"
+    );
+
+    let second = example_block(vec![[0], [1]], vec![[1]], vec![[0]], vec![[0, 1]], 1.0, 11.0);
+    let error = first.as_ref().add(&second.as_ref()).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: inputs to 'add' should have the same components"
+    );
+
+    let second = example_block(vec![[0], [1]], vec![[0]], vec![[1]], vec![[0, 1]], 1.0, 11.0);
+    let error = first.as_ref().add(&second.as_ref()).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: inputs to 'add' should have the same properties"
+    );
+
+    let second = example_block(vec![[0], [1]], vec![[0]], vec![[0]], vec![[1, 1]], 1.0, 11.0);
+    let error = first.as_ref().multiply(&second.as_ref()).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: gradients with respect to 'parameter' in inputs \
+        to 'multiply' should have the same samples"
+    );
+
+    let first = varying_block();
+    let components = [example_labels(vec!["components"], vec![[0]])];
+    let mut second = TensorBlock::new(
+        ArrayD::from_elem(vec![2, 1, 1], 1.0),
+        example_labels(vec!["samples"], vec![[0], [1]]),
+        &components,
+        example_labels(vec!["properties"], vec![[0]]),
+    ).unwrap();
+    second.add_gradient(
+        "other",
+        ArrayD::from_elem(vec![1, 1, 1], 1.0),
+        example_labels(vec!["sample", "other"], vec![[0, 0]]),
+        &components,
+    ).unwrap();
+
+    let error = first.as_ref().subtract(&second.as_ref()).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: inputs to 'subtract' should have the same \
+        gradients, got [parameter] and [other]"
+    );
+}
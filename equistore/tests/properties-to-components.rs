@@ -0,0 +1,148 @@
+#![allow(clippy::needless_return)]
+
+use equistore::{TensorBlock, TensorMap, Labels};
+
+use ndarray::ArrayD;
+
+mod utils;
+use utils::example_labels;
+
+fn example_tensor() -> TensorMap {
+    let data = ArrayD::from_shape_vec(vec![2, 1, 6], vec![
+        1.0, 2.0, 3.0, 4.0, 5.0, 6.0,
+        -1.0, -2.0, -3.0, -4.0, -5.0, -6.0,
+    ]).unwrap();
+
+    let components = [example_labels(vec!["components"], vec![[0]])];
+    let mut block = TensorBlock::new(
+        data,
+        example_labels(vec!["samples"], vec![[0], [1]]),
+        &components,
+        example_labels(vec!["l", "n"], vec![[0, 0], [0, 1], [0, 2], [1, 0], [1, 1], [1, 2]]),
+    ).unwrap();
+
+    block.add_gradient(
+        "parameter",
+        ArrayD::from_shape_vec(vec![1, 1, 6], vec![
+            11.0, 12.0, 13.0, 14.0, 15.0, 16.0,
+        ]).unwrap(),
+        example_labels(vec!["sample", "parameter"], vec![[1, 0]]),
+        &components,
+    ).unwrap();
+
+    return TensorMap::new(Labels::single(), vec![block]).unwrap();
+}
+
+#[test]
+fn slow_variable() {
+    let tensor = example_tensor().properties_to_components(&["l"]).unwrap();
+
+    let block = tensor.block_by_id(0);
+    let values = block.values();
+    assert_eq!(values.samples, Labels::new(["samples"], &[[0], [1]]));
+    assert_eq!(values.components.len(), 2);
+    assert_eq!(values.components[0].names(), ["components"]);
+    assert_eq!(values.components[1], Labels::new(["l"], &[[0], [1]]));
+    assert_eq!(values.properties, Labels::new(["n"], &[[0], [1], [2]]));
+
+    let expected = ArrayD::from_shape_vec(vec![2, 1, 2, 3], vec![
+        1.0, 2.0, 3.0, 4.0, 5.0, 6.0,
+        -1.0, -2.0, -3.0, -4.0, -5.0, -6.0,
+    ]).unwrap();
+    assert_eq!(values.data.as_array(), expected);
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.components.len(), 2);
+    assert_eq!(gradient.components[1], Labels::new(["l"], &[[0], [1]]));
+
+    let expected = ArrayD::from_shape_vec(vec![1, 1, 2, 3], vec![
+        11.0, 12.0, 13.0, 14.0, 15.0, 16.0,
+    ]).unwrap();
+    assert_eq!(gradient.data.as_array(), expected);
+
+    // this is the inverse of components_to_properties
+    let tensor = tensor.components_to_properties(&["l"]).unwrap();
+    let reference = example_tensor();
+    let block = tensor.block_by_id(0);
+    assert_eq!(block.values().properties, reference.block_by_id(0).values().properties);
+    assert_eq!(block.values().data.as_array(), reference.block_by_id(0).values().data.as_array());
+}
+
+#[test]
+fn fast_variable() {
+    let tensor = example_tensor().properties_to_components(&["n"]).unwrap();
+
+    let block = tensor.block_by_id(0);
+    let values = block.values();
+    assert_eq!(values.components[1], Labels::new(["n"], &[[0], [1], [2]]));
+    assert_eq!(values.properties, Labels::new(["l"], &[[0], [1]]));
+
+    let expected = ArrayD::from_shape_vec(vec![2, 1, 3, 2], vec![
+        1.0, 4.0, 2.0, 5.0, 3.0, 6.0,
+        -1.0, -4.0, -2.0, -5.0, -3.0, -6.0,
+    ]).unwrap();
+    assert_eq!(values.data.as_array(), expected);
+
+    let gradient = block.gradient("parameter").unwrap();
+    let expected = ArrayD::from_shape_vec(vec![1, 1, 3, 2], vec![
+        11.0, 14.0, 12.0, 15.0, 13.0, 16.0,
+    ]).unwrap();
+    assert_eq!(gradient.data.as_array(), expected);
+
+    // the data must still be contiguous to be saved
+    let path = std::env::temp_dir().join("equistore-properties-to-components-test.npz");
+    equistore::io::save(&path, &tensor).unwrap();
+    let loaded = equistore::io::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let block = loaded.block_by_id(0);
+    assert_eq!(block.values().data.as_array(), values.data.as_array());
+    assert_eq!(block.gradient("parameter").unwrap().data.as_array(), gradient.data.as_array());
+}
+
+#[test]
+fn all_variables() {
+    let tensor = example_tensor();
+    let tensor = tensor.properties_to_components(&["n"]).unwrap();
+    let tensor = tensor.properties_to_components(&["l"]).unwrap();
+
+    let block = tensor.block_by_id(0);
+    let values = block.values();
+    assert_eq!(values.components.len(), 3);
+    assert_eq!(values.components[2], Labels::new(["l"], &[[0], [1]]));
+    assert_eq!(values.properties, Labels::new(["_"], &[[0]]));
+    assert_eq!(values.data.as_array().shape(), [2, 1, 3, 2, 1]);
+}
+
+#[test]
+fn errors() {
+    let tensor = example_tensor();
+
+    let error = tensor.properties_to_components(&["l", "n"]).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: component labels must have a single variable, got 2: [l, n]"
+    );
+
+    let error = tensor.properties_to_components(&["not_there"]).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: 'not_there' is not part of the properties for this block"
+    );
+
+    let block = TensorBlock::new(
+        ArrayD::from_elem(vec![1, 3], 1.0),
+        example_labels(vec!["samples"], vec![[0]]),
+        &[],
+        example_labels(vec!["l", "n"], vec![[0, 0], [0, 1], [1, 0]]),
+    ).unwrap();
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+
+    let error = tensor.properties_to_components(&["l"]).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: can not move [l] to the components: the properties \
+        must contain all the combinations of these variables with the remaining \
+        ones, in a regular order"
+    );
+}
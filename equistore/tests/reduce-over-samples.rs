@@ -0,0 +1,209 @@
+#![allow(clippy::needless_return)]
+
+use equistore::{Labels, TensorBlock, TensorMap, Reduction};
+
+use ndarray::ArrayD;
+
+fn example_tensor() -> TensorMap {
+    let mut block = TensorBlock::new(
+        ArrayD::from_shape_vec(vec![4, 3], vec![
+            1.0, 2.0, 4.0,
+            3.0, 5.0, 6.0,
+            7.0, 8.0, 9.0,
+            10.0, 11.0, 12.0,
+        ]).unwrap(),
+        Labels::new(["structure", "center"], &[[0, 0], [0, 1], [1, 0], [1, 1]]),
+        &[],
+        Labels::new(["properties"], &[[0], [1], [2]]),
+    ).unwrap();
+
+    block.add_gradient(
+        "parameter",
+        ArrayD::from_shape_vec(vec![4, 3], vec![
+            1.0, 1.0, 1.0,
+            2.0, 2.0, 2.0,
+            3.0, 3.0, 3.0,
+            4.0, 4.0, 4.0,
+        ]).unwrap(),
+        Labels::new(["sample", "parameter"], &[[0, 0], [1, 0], [2, 0], [1, 1]]),
+        &[],
+    ).unwrap();
+
+    return TensorMap::new(Labels::single(), vec![block]).unwrap();
+}
+
+fn array(shape: Vec<usize>, values: Vec<f64>) -> ArrayD<f64> {
+    return ArrayD::from_shape_vec(shape, values).unwrap();
+}
+
+fn check_gradient_samples(tensor: &TensorMap) {
+    let gradient = tensor.block_by_id(0).gradient("parameter").unwrap();
+    assert_eq!(gradient.samples.names(), ["sample", "parameter"]);
+    assert_eq!(gradient.samples.count(), 3);
+    assert_eq!(gradient.samples[0], [0, 0]);
+    assert_eq!(gradient.samples[1], [0, 1]);
+    assert_eq!(gradient.samples[2], [1, 0]);
+}
+
+#[test]
+fn sum() {
+    let tensor = example_tensor();
+    let reduced = tensor.reduce_over_samples(&["center"], Reduction::Sum).unwrap();
+
+    let block = reduced.block_by_id(0);
+    let values = block.values();
+    assert_eq!(values.samples.names(), ["structure"]);
+    assert_eq!(values.samples.count(), 2);
+    assert_eq!(values.samples[0], [0]);
+    assert_eq!(values.samples[1], [1]);
+    assert_eq!(values.data.as_array(), array(vec![2, 3], vec![
+        4.0, 7.0, 10.0,
+        17.0, 19.0, 21.0,
+    ]));
+
+    check_gradient_samples(&reduced);
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 3], vec![
+        3.0, 3.0, 3.0,
+        4.0, 4.0, 4.0,
+        3.0, 3.0, 3.0,
+    ]));
+}
+
+#[test]
+fn mean() {
+    let tensor = example_tensor();
+    let reduced = tensor.reduce_over_samples(&["center"], Reduction::Mean).unwrap();
+
+    let block = reduced.block_by_id(0);
+    assert_eq!(block.values().data.as_array(), array(vec![2, 3], vec![
+        2.0, 3.5, 5.0,
+        8.5, 9.5, 10.5,
+    ]));
+
+    // gradients are averaged over the gradient rows reduced together
+    check_gradient_samples(&reduced);
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 3], vec![
+        1.5, 1.5, 1.5,
+        4.0, 4.0, 4.0,
+        3.0, 3.0, 3.0,
+    ]));
+}
+
+#[test]
+fn variance() {
+    let tensor = example_tensor();
+    let reduced = tensor.reduce_over_samples(&["center"], Reduction::Variance).unwrap();
+
+    let block = reduced.block_by_id(0);
+    assert_eq!(block.values().data.as_array(), array(vec![2, 3], vec![
+        1.0, 2.25, 1.0,
+        2.25, 2.25, 2.25,
+    ]));
+
+    // ∇Var(X) = 2 (E[X ∇X] - E[X] E[∇X])
+    check_gradient_samples(&reduced);
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 3], vec![
+        1.0, 1.5, 1.0,
+        8.0, 12.0, 8.0,
+        -9.0, -9.0, -9.0,
+    ]));
+}
+
+#[test]
+fn std() {
+    let tensor = example_tensor();
+    let reduced = tensor.reduce_over_samples(&["center"], Reduction::Std).unwrap();
+
+    let block = reduced.block_by_id(0);
+    assert_eq!(block.values().data.as_array(), array(vec![2, 3], vec![
+        1.0, 1.5, 1.0,
+        1.5, 1.5, 1.5,
+    ]));
+
+    // ∇Std(X) = (E[X ∇X] - E[X] E[∇X]) / Std(X)
+    check_gradient_samples(&reduced);
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![3, 3], vec![
+        0.5, 0.5, 0.5,
+        4.0, 4.0, 4.0,
+        -3.0, -3.0, -3.0,
+    ]));
+}
+
+#[test]
+fn samples_without_gradients() {
+    let mut block = TensorBlock::new(
+        array(vec![2, 1], vec![1.0, 3.0]),
+        Labels::new(["structure", "center"], &[[0, 0], [0, 1]]),
+        &[],
+        Labels::new(["properties"], &[[0]]),
+    ).unwrap();
+
+    // only the first sample has a gradient row
+    block.add_gradient(
+        "parameter",
+        array(vec![1, 1], vec![2.0]),
+        Labels::new(["sample", "parameter"], &[[0, 0]]),
+        &[],
+    ).unwrap();
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+
+    let reduced = tensor.reduce_over_samples(&["center"], Reduction::Mean).unwrap();
+    let block = reduced.block_by_id(0);
+    assert_eq!(block.values().data.as_array(), array(vec![1, 1], vec![2.0]));
+
+    // the mean is taken over the gradient rows, not over the values samples
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples.count(), 1);
+    assert_eq!(gradient.samples[0], [0, 0]);
+    assert_eq!(gradient.data.as_array(), array(vec![1, 1], vec![2.0]));
+
+    let reduced = tensor.reduce_over_samples(&["center"], Reduction::Variance).unwrap();
+    let block = reduced.block_by_id(0);
+    assert_eq!(block.values().data.as_array(), array(vec![1, 1], vec![1.0]));
+
+    // 2 (E[X ∇X] - E[X] E[∇X]) = 2 (1 * 2 - 2 * 2)
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.data.as_array(), array(vec![1, 1], vec![-4.0]));
+}
+
+#[test]
+fn all_samples() {
+    let tensor = example_tensor();
+    let reduced = tensor.reduce_over_samples(&["structure", "center"], Reduction::Sum).unwrap();
+
+    let block = reduced.block_by_id(0);
+    let values = block.values();
+    assert_eq!(values.samples.names(), ["_"]);
+    assert_eq!(values.samples.count(), 1);
+    assert_eq!(values.samples[0], [0]);
+    assert_eq!(values.data.as_array(), array(vec![1, 3], vec![21.0, 26.0, 31.0]));
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples.count(), 2);
+    assert_eq!(gradient.samples[0], [0, 0]);
+    assert_eq!(gradient.samples[1], [0, 1]);
+    assert_eq!(gradient.data.as_array(), array(vec![2, 3], vec![
+        6.0, 6.0, 6.0,
+        4.0, 4.0, 4.0,
+    ]));
+
+    // reducing over no variables only sorts the samples
+    let reduced = tensor.reduce_over_samples(&[], Reduction::Mean).unwrap();
+    let block = reduced.block_by_id(0);
+    assert_eq!(block.values().samples, tensor.block_by_id(0).values().samples);
+    assert_eq!(block.values().data.as_array(), tensor.block_by_id(0).values().data.as_array());
+}
+
+#[test]
+fn errors() {
+    let tensor = example_tensor();
+    let error = tensor.reduce_over_samples(&["not there"], Reduction::Sum).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: 'not there' is not part of the samples for this tensor map"
+    );
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "serde")]
+#![allow(clippy::needless_return)]
+
+use equistore::{Labels, LabelsBuilder, TensorBlock, TensorMap, TensorMapSeed};
+use serde::de::DeserializeSeed;
+
+mod utils;
+use utils::OtherDeviceArray;
+
+#[test]
+fn labels() {
+    let mut builder = LabelsBuilder::new(vec!["a", "b"]);
+    builder.add(&[0, 1]);
+    builder.add(&[4_000_000_000_i64, -2]);
+    let labels = builder.finish();
+
+    let json = serde_json::to_string(&labels).unwrap();
+    assert_eq!(json, r#"{"names":["a","b"],"values":[[0,1],[4000000000,-2]]}"#);
+
+    let deserialized: Labels = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, labels);
+
+    let empty: Labels = serde_json::from_str(r#"{"names":[],"values":[]}"#).unwrap();
+    assert_eq!(empty.size(), 0);
+    assert_eq!(empty.count(), 0);
+
+    let error = serde_json::from_str::<Labels>(r#"{"names":["a","b"],"values":[[0,1],[0]]}"#).unwrap_err();
+    assert_eq!(error.to_string(), "invalid labels: expected entries with 2 values, got 1");
+
+    let error = serde_json::from_str::<Labels>(r#"{"names":["a","b"],"values":[[0,1],[0,1]]}"#).unwrap_err();
+    assert!(error.to_string().contains("can not have the same label value multiple time"));
+
+    let error = serde_json::from_str::<Labels>(r#"{"names":["a","a"],"values":[]}"#).unwrap_err();
+    assert!(error.to_string().starts_with("invalid labels: the same name is used multiple times"));
+}
+
+#[test]
+fn tensor_map() {
+    let mut tensor = equistore::io::load("equistore-core/tests/data.npz").unwrap();
+    tensor.set_metadata("units", "eV").unwrap();
+    tensor.block_mut_by_id(3).set_info("values", "origin", "test").unwrap();
+
+    let json = serde_json::to_string(&tensor).unwrap();
+    let deserialized: TensorMap = serde_json::from_str(&json).unwrap();
+    check_tensors_equal(&tensor, &deserialized);
+
+    assert_eq!(deserialized.metadata("units"), Some("eV"));
+    assert_eq!(deserialized.block_by_id(3).values().info["origin"], "test");
+
+    // custom array creation
+    let seed = TensorMapSeed::new(|shape: &[usize]| ndarray::ArrayD::from_elem(shape, 0.0));
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    let deserialized = seed.deserialize(&mut deserializer).unwrap();
+    check_tensors_equal(&tensor, &deserialized);
+
+    // wrong number of values in the data
+    let json = r#"{
+        "values": {
+            "data": {"shape": [1, 2], "data": [1.0]},
+            "samples": {"names": ["s"], "values": [[0]]},
+            "components": [],
+            "properties": {"names": ["p"], "values": [[0], [1]]}
+        }
+    }"#;
+    let error = serde_json::from_str::<TensorBlock>(json).unwrap_err();
+    assert!(error.to_string().starts_with("expected 2 values for an array of shape [1, 2], got 1"));
+}
+
+#[test]
+fn other_device() {
+    let values = ndarray::ArrayD::from_shape_vec(vec![2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    let block = TensorBlock::new(
+        OtherDeviceArray(values.clone()),
+        Labels::new(["sample"], &[[0], [1]]),
+        &[],
+        Labels::new(["property"], &[[0], [1], [2]]),
+    ).unwrap();
+
+    // the data is copied to host memory with `eqs_array_t.copy_to_host`
+    let json = serde_json::to_string(&block).unwrap();
+    assert!(json.contains(r#""data":{"shape":[2,3],"data":[1.0,2.0,3.0,4.0,5.0,6.0]}"#));
+
+    let deserialized: TensorBlock = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.as_ref().values().data.as_array(), values);
+}
+
+fn check_tensors_equal(expected: &TensorMap, actual: &TensorMap) {
+    assert_eq!(expected.keys(), actual.keys());
+    for (expected, actual) in expected.blocks().iter().zip(actual.blocks()) {
+        let expected_values = expected.values();
+        let actual_values = actual.values();
+        assert_eq!(expected_values.data.as_array(), actual_values.data.as_array());
+        assert_eq!(expected_values.samples, actual_values.samples);
+        assert_eq!(expected_values.components, actual_values.components);
+        assert_eq!(expected_values.properties, actual_values.properties);
+        assert_eq!(expected_values.info, actual_values.info);
+
+        assert_eq!(expected.gradient_list(), actual.gradient_list());
+        for parameter in expected.gradient_list() {
+            let expected_gradient = expected.gradient(parameter).unwrap();
+            let actual_gradient = actual.gradient(parameter).unwrap();
+            assert_eq!(expected_gradient.data.as_array(), actual_gradient.data.as_array());
+            assert_eq!(expected_gradient.samples, actual_gradient.samples);
+            assert_eq!(expected_gradient.components, actual_gradient.components);
+            assert_eq!(expected_gradient.info, actual_gradient.info);
+        }
+    }
+}
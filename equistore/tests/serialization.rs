@@ -1,3 +1,10 @@
+use equistore::{Labels, LabelsBuilder, TensorBlock, TensorMap};
+use equistore::c_api::EQS_DEVICE_OTHER;
+use ndarray::ArrayD;
+
+mod utils;
+use utils::OtherDeviceArray;
+
 #[test]
 fn load_file() {
     let tensor = equistore::io::load("equistore-core/tests/data.npz").unwrap();
@@ -22,3 +29,158 @@ fn load_file() {
     assert_eq!(gradient.components[1].names(), ["spherical_harmonics_m"]);
     assert_eq!(gradient.properties.names(), ["n"]);
 }
+
+#[test]
+fn metadata() {
+    let mut tensor = equistore::io::load("equistore-core/tests/data.npz").unwrap();
+    // files created before metadata support do not contain any
+    assert!(tensor.metadata_keys().is_empty());
+    assert_eq!(tensor.metadata("units"), None);
+
+    tensor.set_metadata("units", "kcal/mol").unwrap();
+    tensor.set_metadata("code", "équistore → v1").unwrap();
+    tensor.set_metadata("to_remove", "").unwrap();
+    tensor.remove_metadata("to_remove");
+
+    assert_eq!(tensor.metadata_keys(), ["code", "units"]);
+    assert_eq!(tensor.metadata("units"), Some("kcal/mol"));
+
+    let path = std::env::temp_dir().join("equistore-metadata-test.npz");
+    equistore::io::save(&path, &tensor).unwrap();
+    let loaded = equistore::io::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.metadata_keys(), ["code", "units"]);
+    assert_eq!(loaded.metadata("units"), Some("kcal/mol"));
+    assert_eq!(loaded.metadata("code"), Some("équistore → v1"));
+    assert_eq!(loaded.keys(), tensor.keys());
+}
+
+#[test]
+fn block_info() {
+    let mut tensor = equistore::io::load("equistore-core/tests/data.npz").unwrap();
+    assert!(tensor.block_by_id(0).values().info.is_empty());
+
+    let parameter = tensor.block_by_id(0).gradient_list()[0].to_owned();
+    let mut block = tensor.block_mut_by_id(0);
+    block.set_info("values", "units", "eV").unwrap();
+    block.set_info(&parameter, "units", "eV/A").unwrap();
+    block.set_info("values", "to_remove", "").unwrap();
+    block.remove_info("values", "to_remove").unwrap();
+
+    let error = block.set_info("not-there", "units", "eV").unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: can not find gradients with respect to 'not-there' in this block"
+    );
+
+    let path = std::env::temp_dir().join("equistore-block-info-test.npz");
+    equistore::io::save(&path, &tensor).unwrap();
+    let loaded = equistore::io::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let block = loaded.block_by_id(0);
+    assert_eq!(block.values().info.len(), 1);
+    assert_eq!(block.values().info["units"], "eV");
+    assert_eq!(block.gradient(&parameter).unwrap().info["units"], "eV/A");
+    assert!(loaded.block_by_id(1).values().info.is_empty());
+}
+
+#[test]
+fn large_label_values() {
+    let large = i64::from(i32::MAX) + 42;
+
+    let mut samples = LabelsBuilder::new(vec!["atom"]);
+    samples.add(&[large]);
+    samples.add(&[-large]);
+    let block = TensorBlock::new(
+        ArrayD::from_elem(vec![2, 1], 1.0),
+        samples.finish(),
+        &[],
+        Labels::single(),
+    ).unwrap();
+
+    let mut keys = LabelsBuilder::new(vec!["key"]);
+    keys.add(&[large]);
+    let tensor = TensorMap::new(keys.finish(), vec![block]).unwrap();
+
+    let path = std::env::temp_dir().join("equistore-large-labels-test.npz");
+    equistore::io::save(&path, &tensor).unwrap();
+    let loaded = equistore::io::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.keys(), tensor.keys());
+    assert_eq!(loaded.keys()[0], [large]);
+
+    let samples = &loaded.block_by_id(0).values().samples;
+    assert_eq!(samples[0], [large]);
+    assert_eq!(samples[1], [-large]);
+}
+
+#[test]
+fn data_types() {
+    let values = ArrayD::from_shape_vec(vec![2, 1], vec![1.5_f32, -3.25]).unwrap();
+    let block = TensorBlock::new(
+        values.clone(),
+        Labels::new(["sample"], &[[0], [1]]),
+        &[],
+        Labels::single(),
+    ).unwrap();
+    assert_eq!(block.as_ref().values().data.as_raw().dtype().unwrap(), equistore::c_api::EQS_DTYPE_FLOAT32);
+
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+
+    let path = std::env::temp_dir().join("equistore-data-types-test.npz");
+    equistore::io::save(&path, &tensor).unwrap();
+    let loaded = equistore::io::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let block = loaded.block_by_id(0);
+    let loaded_values = block.values();
+    let data = loaded_values.data.as_any().downcast_ref::<ArrayD<f32>>().unwrap();
+    assert_eq!(data, &values);
+
+    // all blocks in a TensorMap must use the same data type
+    let block_f32 = TensorBlock::new(
+        ArrayD::<f32>::zeros(vec![1, 1]),
+        Labels::new(["sample"], &[[0]]),
+        &[],
+        Labels::single(),
+    ).unwrap();
+    let block_f64 = TensorBlock::new(
+        ArrayD::<f64>::zeros(vec![1, 1]),
+        Labels::new(["sample"], &[[0]]),
+        &[],
+        Labels::single(),
+    ).unwrap();
+
+    let error = TensorMap::new(Labels::new(["key"], &[[0], [1]]), vec![block_f32, block_f64]).unwrap_err();
+    assert_eq!(error.message, "invalid parameter: all blocks must have the same data type, got float64 and float32");
+}
+
+#[test]
+fn other_device() {
+    let values = ArrayD::from_shape_vec(vec![2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    let block = TensorBlock::new(
+        OtherDeviceArray(values.clone()),
+        Labels::new(["sample"], &[[0], [1]]),
+        &[],
+        Labels::new(["property"], &[[0], [1], [2]]),
+    ).unwrap();
+
+    let block_ref = block.as_ref();
+    let block_values = block_ref.values();
+    assert_eq!(block_values.data.as_raw().device().unwrap(), EQS_DEVICE_OTHER);
+
+    let error = block_values.data.data().unwrap_err();
+    assert_eq!(error.message, "can not directly access the data of an array which is not on CPU");
+
+    let tensor = TensorMap::new(Labels::single(), vec![block]).unwrap();
+
+    let path = std::env::temp_dir().join("equistore-other-device-test.npz");
+    equistore::io::save(&path, &tensor).unwrap();
+    let loaded = equistore::io::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.block_by_id(0).values().data.as_array(), values);
+}
@@ -0,0 +1,153 @@
+#![allow(clippy::needless_return)]
+
+use equistore::{Labels, TensorBlock, Axis};
+
+mod utils;
+use utils::example_tensor;
+
+use ndarray::ArrayD;
+
+fn example_block() -> TensorBlock {
+    let mut block = TensorBlock::new(
+        ArrayD::from_shape_vec(vec![4, 3], vec![
+            0.0, 1.0, 2.0,
+            3.0, 4.0, 5.0,
+            6.0, 7.0, 8.0,
+            9.0, 10.0, 11.0,
+        ]).unwrap(),
+        Labels::new(["structure", "center"], &[[0, 0], [0, 1], [1, 0], [2, 0]]),
+        &[],
+        Labels::new(["n"], &[[0], [1], [2]]),
+    ).unwrap();
+
+    block.add_gradient(
+        "parameter",
+        ArrayD::from_shape_vec(vec![4, 3], vec![
+            -0.0, -1.0, -2.0,
+            -3.0, -4.0, -5.0,
+            -6.0, -7.0, -8.0,
+            -9.0, -10.0, -11.0,
+        ]).unwrap(),
+        Labels::new(["sample", "parameter"], &[[0, 0], [1, 0], [2, 1], [3, 0]]),
+        &[],
+    ).unwrap();
+
+    return block;
+}
+
+fn array(shape: Vec<usize>, values: Vec<f64>) -> ArrayD<f64> {
+    return ArrayD::from_shape_vec(shape, values).unwrap();
+}
+
+#[test]
+fn samples() {
+    let block = example_block();
+    let selection = Labels::new(["structure"], &[[2], [0]]);
+    let sliced = block.as_ref().slice(Axis::Samples, &selection).unwrap();
+    let sliced = sliced.as_ref();
+
+    let values = sliced.values();
+    assert_eq!(values.samples, Labels::new(["structure", "center"], &[[0, 0], [0, 1], [2, 0]]));
+    assert_eq!(values.properties, block.as_ref().values().properties);
+    assert_eq!(values.data.as_array(), array(vec![3, 3], vec![
+        0.0, 1.0, 2.0,
+        3.0, 4.0, 5.0,
+        9.0, 10.0, 11.0,
+    ]));
+
+    let gradient = sliced.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, 0], [1, 0], [2, 0]]));
+    assert_eq!(gradient.data.as_array(), array(vec![3, 3], vec![
+        -0.0, -1.0, -2.0,
+        -3.0, -4.0, -5.0,
+        -9.0, -10.0, -11.0,
+    ]));
+
+    // selection using all the variables
+    let selection = Labels::new(["center", "structure"], &[[0, 1]]);
+    let sliced = block.as_ref().slice(Axis::Samples, &selection).unwrap();
+    let sliced = sliced.as_ref();
+    assert_eq!(sliced.values().samples, Labels::new(["structure", "center"], &[[1, 0]]));
+    assert_eq!(sliced.values().data.as_array(), array(vec![1, 3], vec![6.0, 7.0, 8.0]));
+
+    let gradient = sliced.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, 1]]));
+}
+
+#[test]
+fn properties() {
+    let block = example_block();
+    let selection = Labels::new(["n"], &[[2], [0], [5]]);
+    let sliced = block.as_ref().slice(Axis::Properties, &selection).unwrap();
+    let sliced = sliced.as_ref();
+
+    let values = sliced.values();
+    assert_eq!(values.samples, block.as_ref().values().samples);
+    assert_eq!(values.properties, Labels::new(["n"], &[[0], [2]]));
+    assert_eq!(values.data.as_array(), array(vec![4, 2], vec![
+        0.0, 2.0,
+        3.0, 5.0,
+        6.0, 8.0,
+        9.0, 11.0,
+    ]));
+
+    let gradient = sliced.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, block.as_ref().gradient("parameter").unwrap().samples);
+    assert_eq!(gradient.data.as_array(), array(vec![4, 2], vec![
+        -0.0, -2.0,
+        -3.0, -5.0,
+        -6.0, -8.0,
+        -9.0, -11.0,
+    ]));
+}
+
+#[test]
+fn tensor() {
+    let tensor = example_tensor();
+
+    let selection = Labels::new(["samples"], &[[0], [3]]);
+    let sliced = tensor.slice(Axis::Samples, &selection).unwrap();
+    assert_eq!(sliced.keys(), tensor.keys());
+
+    let block = sliced.block_by_id(0);
+    assert_eq!(block.values().samples, Labels::new(["samples"], &[[0]]));
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, -2]]));
+
+    let block = sliced.block_by_id(2);
+    assert_eq!(block.values().samples, Labels::new(["samples"], &[[0], [3]]));
+    assert_eq!(block.values().data.as_array(), ArrayD::from_elem(vec![2, 3, 1], 3.0));
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[1, -2]]));
+
+    // blocks without any matching property are kept with zero properties
+    let selection = Labels::new(["properties"], &[[3]]);
+    let sliced = tensor.slice(Axis::Properties, &selection).unwrap();
+
+    let block = sliced.block_by_id(0);
+    assert_eq!(block.values().properties.count(), 0);
+    assert_eq!(block.values().data.as_array().shape(), [3, 1, 0]);
+    assert_eq!(block.gradient("parameter").unwrap().data.as_array().shape(), [2, 1, 0]);
+
+    let block = sliced.block_by_id(1);
+    assert_eq!(block.values().properties, Labels::new(["properties"], &[[3]]));
+    assert_eq!(block.values().data.as_array(), ArrayD::from_elem(vec![3, 1, 1], 2.0));
+}
+
+#[test]
+fn errors() {
+    let tensor = example_tensor();
+
+    let selection = Labels::new(["not_there"], &[[0]]);
+    let error = tensor.slice(Axis::Samples, &selection).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: 'not_there' is not part of the samples for this block"
+    );
+
+    let error = tensor.block_by_id(0).slice(Axis::Properties, &selection).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: 'not_there' is not part of the properties for this block"
+    );
+}
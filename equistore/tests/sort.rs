@@ -0,0 +1,80 @@
+#![allow(clippy::needless_return)]
+
+use equistore::{Labels, TensorBlock, TensorMap, Axis};
+
+use ndarray::ArrayD;
+
+fn array(shape: Vec<usize>, values: Vec<f64>) -> ArrayD<f64> {
+    return ArrayD::from_shape_vec(shape, values).unwrap();
+}
+
+fn example_block(offset: f64) -> TensorBlock {
+    let mut block = TensorBlock::new(
+        array(vec![3, 2], vec![
+            0.0, 1.0,
+            2.0, 3.0,
+            4.0, 5.0,
+        ]) + offset,
+        Labels::new(["structure", "center"], &[[2, 0], [0, 1], [0, 0]]),
+        &[],
+        Labels::new(["n"], &[[1], [0]]),
+    ).unwrap();
+
+    block.add_gradient(
+        "parameter",
+        array(vec![3, 2], vec![
+            -0.0, -1.0,
+            -2.0, -3.0,
+            -4.0, -5.0,
+        ]),
+        Labels::new(["sample", "parameter"], &[[0, 1], [1, 0], [2, 3]]),
+        &[],
+    ).unwrap();
+
+    return block;
+}
+
+#[test]
+fn sort_samples() {
+    let block = example_block(0.0);
+    assert!(!block.as_ref().values().samples.is_sorted());
+
+    let sorted = block.as_ref().sort(Axis::Samples).unwrap();
+    let values = sorted.as_ref().values();
+    assert_eq!(values.samples, Labels::new(["structure", "center"], &[[0, 0], [0, 1], [2, 0]]));
+    assert_eq!(values.properties, Labels::new(["n"], &[[1], [0]]));
+    assert_eq!(values.data.as_array(), array(vec![3, 2], vec![4.0, 5.0, 2.0, 3.0, 0.0, 1.0]));
+
+    // gradient samples are updated to refer to the new samples, and sorted
+    let gradient = sorted.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, 3], [1, 0], [2, 1]]));
+    assert_eq!(gradient.data.as_array(), array(vec![3, 2], vec![-4.0, -5.0, -2.0, -3.0, -0.0, -1.0]));
+}
+
+#[test]
+fn sort_properties() {
+    let block = example_block(0.0);
+
+    let sorted = block.as_ref().sort(Axis::Properties).unwrap();
+    let values = sorted.as_ref().values();
+    assert_eq!(values.samples, block.as_ref().values().samples);
+    assert_eq!(values.properties, Labels::new(["n"], &[[0], [1]]));
+    assert_eq!(values.data.as_array(), array(vec![3, 2], vec![1.0, 0.0, 3.0, 2.0, 5.0, 4.0]));
+
+    let gradient = sorted.as_ref().gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, 1], [1, 0], [2, 3]]));
+    assert_eq!(gradient.data.as_array(), array(vec![3, 2], vec![-1.0, -0.0, -3.0, -2.0, -5.0, -4.0]));
+}
+
+#[test]
+fn sort_keys() {
+    let keys = Labels::new(["key_1", "key_2"], &[[1, 0], [0, 3], [0, 1]]);
+    let blocks = vec![example_block(10.0), example_block(20.0), example_block(30.0)];
+    let tensor = TensorMap::new(keys, blocks).unwrap();
+
+    let sorted = tensor.sort_keys().unwrap();
+    assert_eq!(*sorted.keys(), Labels::new(["key_1", "key_2"], &[[0, 1], [0, 3], [1, 0]]));
+    assert_eq!(sorted.block_by_id(0).values().data.as_array()[[0, 0]], 30.0);
+    assert_eq!(sorted.block_by_id(1).values().data.as_array()[[0, 0]], 20.0);
+    assert_eq!(sorted.block_by_id(2).values().data.as_array()[[0, 0]], 10.0);
+}
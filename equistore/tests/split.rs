@@ -0,0 +1,80 @@
+use equistore::{Labels, Axis};
+
+mod utils;
+use utils::example_tensor;
+
+use ndarray::ArrayD;
+
+#[test]
+fn samples() {
+    let tensor = example_tensor();
+    let selections = [
+        Labels::new(["samples"], &[[0], [1]]),
+        Labels::new(["samples"], &[[2], [3], [6]]),
+    ];
+
+    let split = tensor.split(Axis::Samples, &selections).unwrap();
+    assert_eq!(split.len(), 2);
+    assert_eq!(split[0].keys(), tensor.keys());
+    assert_eq!(split[1].keys(), tensor.keys());
+
+    let block = split[0].block_by_id(1);
+    assert_eq!(block.values().samples, Labels::new(["samples"], &[[0], [1]]));
+    assert_eq!(block.values().data.as_array(), ArrayD::from_elem(vec![2, 1, 3], 2.0));
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, -2], [0, 3]]));
+
+    let block = split[1].block_by_id(1);
+    assert_eq!(block.values().samples, Labels::new(["samples"], &[[3]]));
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, -2]]));
+
+    let block = split[1].block_by_id(2);
+    assert_eq!(block.values().samples, Labels::new(["samples"], &[[3], [6]]));
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, -2]]));
+}
+
+#[test]
+fn properties() {
+    let tensor = example_tensor();
+    let selections = [
+        Labels::new(["properties"], &[[0], [3]]),
+        Labels::new(["properties"], &[[4], [5]]),
+    ];
+
+    let split = tensor.split(Axis::Properties, &selections).unwrap();
+    assert_eq!(split.len(), 2);
+
+    let block = split[0].block_by_id(0);
+    assert_eq!(block.values().properties, Labels::new(["properties"], &[[0]]));
+
+    let block = split[0].block_by_id(1);
+    assert_eq!(block.values().properties, Labels::new(["properties"], &[[3]]));
+    assert_eq!(block.gradient("parameter").unwrap().data.as_array().shape(), [3, 1, 1]);
+
+    let block = split[1].block_by_id(0);
+    assert_eq!(block.values().properties.count(), 0);
+
+    let block = split[1].block_by_id(1);
+    assert_eq!(block.values().properties, Labels::new(["properties"], &[[4], [5]]));
+    assert_eq!(block.values().data.as_array(), ArrayD::from_elem(vec![3, 1, 2], 2.0));
+
+    assert!(tensor.split(Axis::Properties, &[]).unwrap().is_empty());
+}
+
+#[test]
+fn errors() {
+    let tensor = example_tensor();
+    let selections = [
+        Labels::new(["samples"], &[[0]]),
+        Labels::new(["properties"], &[[0]]),
+    ];
+
+    let error = tensor.split(Axis::Samples, &selections).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: all selections must have the same names to split \
+        a tensor map, got [samples] and [properties]"
+    );
+}
@@ -0,0 +1,144 @@
+#![allow(clippy::needless_return)]
+
+use equistore::{Labels, TensorBlock, TensorMap};
+
+mod utils;
+use utils::example_tensor;
+
+use ndarray::ArrayD;
+
+fn array(shape: Vec<usize>, values: Vec<f64>) -> ArrayD<f64> {
+    return ArrayD::from_shape_vec(shape, values).unwrap();
+}
+
+fn example_tensor_multiple_variables() -> TensorMap {
+    let mut block = TensorBlock::new(
+        array(vec![3, 2], vec![
+            0.0, 1.0,
+            2.0, 3.0,
+            4.0, 5.0,
+        ]),
+        Labels::new(["structure", "center"], &[[0, 0], [1, 0], [0, 1]]),
+        &[],
+        Labels::new(["n", "l"], &[[0, 1], [1, 1]]),
+    ).unwrap();
+
+    block.add_gradient(
+        "parameter",
+        array(vec![3, 2], vec![
+            -0.0, -1.0,
+            -2.0, -3.0,
+            -4.0, -5.0,
+        ]),
+        Labels::new(["sample", "parameter"], &[[0, 0], [1, 0], [2, 1]]),
+        &[],
+    ).unwrap();
+
+    return TensorMap::new(Labels::new(["key"], &[[4]]), vec![block]).unwrap();
+}
+
+#[test]
+fn samples_to_keys() {
+    let tensor = example_tensor_multiple_variables();
+    let tensor = tensor.samples_to_keys(&["structure"]).unwrap();
+
+    assert_eq!(*tensor.keys(), Labels::new(["key", "structure"], &[[4, 0], [4, 1]]));
+
+    let block = tensor.block_by_id(0);
+    assert_eq!(block.values().samples, Labels::new(["center"], &[[0], [1]]));
+    assert_eq!(block.values().properties, Labels::new(["n", "l"], &[[0, 1], [1, 1]]));
+    assert_eq!(block.values().data.as_array(), array(vec![2, 2], vec![0.0, 1.0, 4.0, 5.0]));
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, 0], [1, 1]]));
+    assert_eq!(gradient.data.as_array(), array(vec![2, 2], vec![-0.0, -1.0, -4.0, -5.0]));
+
+    let block = tensor.block_by_id(1);
+    assert_eq!(block.values().samples, Labels::new(["center"], &[[0]]));
+    assert_eq!(block.values().data.as_array(), array(vec![1, 2], vec![2.0, 3.0]));
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[0, 0]]));
+    assert_eq!(gradient.data.as_array(), array(vec![1, 2], vec![-2.0, -3.0]));
+
+    // moving all the sample variables
+    let tensor = example_tensor_multiple_variables();
+    let tensor = tensor.samples_to_keys(&["center", "structure"]).unwrap();
+    assert_eq!(*tensor.keys(), Labels::new(["key", "center", "structure"], &[[4, 0, 0], [4, 0, 1], [4, 1, 0]]));
+
+    for block in tensor.blocks() {
+        assert_eq!(block.values().samples, Labels::new(["_"], &[[0]]));
+    }
+    assert_eq!(tensor.block_by_id(2).values().data.as_array(), array(vec![1, 2], vec![4.0, 5.0]));
+    assert_eq!(
+        tensor.block_by_id(2).gradient("parameter").unwrap().samples,
+        Labels::new(["sample", "parameter"], &[[0, 1]])
+    );
+}
+
+#[test]
+fn properties_to_keys() {
+    let tensor = example_tensor_multiple_variables();
+    let tensor = tensor.properties_to_keys(&["n"]).unwrap();
+
+    assert_eq!(*tensor.keys(), Labels::new(["key", "n"], &[[4, 0], [4, 1]]));
+
+    let block = tensor.block_by_id(0);
+    assert_eq!(block.values().samples.count(), 3);
+    assert_eq!(block.values().properties, Labels::new(["l"], &[[1]]));
+    assert_eq!(block.values().data.as_array(), array(vec![3, 1], vec![0.0, 2.0, 4.0]));
+
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples.count(), 3);
+    assert_eq!(gradient.data.as_array(), array(vec![3, 1], vec![-0.0, -2.0, -4.0]));
+
+    let block = tensor.block_by_id(1);
+    assert_eq!(block.values().properties, Labels::new(["l"], &[[1]]));
+    assert_eq!(block.values().data.as_array(), array(vec![3, 1], vec![1.0, 3.0, 5.0]));
+
+    // moving all the property variables
+    let tensor = example_tensor_multiple_variables();
+    let tensor = tensor.properties_to_keys(&["l", "n"]).unwrap();
+    assert_eq!(*tensor.keys(), Labels::new(["key", "l", "n"], &[[4, 1, 0], [4, 1, 1]]));
+    assert_eq!(tensor.block_by_id(1).values().properties, Labels::new(["_"], &[[0]]));
+}
+
+#[test]
+fn inverse_of_keys_to_samples() {
+    let tensor = example_tensor();
+    let keys_to_move = Labels::empty(vec!["key_2"]);
+    let moved = tensor.keys_to_samples(&keys_to_move, true).unwrap();
+
+    let tensor = moved.samples_to_keys(&["key_2"]).unwrap();
+    assert_eq!(*tensor.keys(), Labels::new(["key_1", "key_2"], &[[0, 0], [1, 0], [2, 2], [2, 3]]));
+
+    let block = tensor.block_by_id(2);
+    assert_eq!(block.values().samples, Labels::new(["samples"], &[[0], [3], [6], [8]]));
+    assert_eq!(block.values().data.as_array(), ArrayD::from_elem(vec![4, 3, 1], 3.0));
+    let gradient = block.gradient("parameter").unwrap();
+    assert_eq!(gradient.samples, Labels::new(["sample", "parameter"], &[[1, -2]]));
+    assert_eq!(gradient.data.as_array(), ArrayD::from_elem(vec![1, 3, 1], 13.0));
+}
+
+#[test]
+fn errors() {
+    let tensor = example_tensor();
+
+    let error = tensor.samples_to_keys(&["key_1"]).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: 'key_1' is already part of the keys for this tensor map"
+    );
+
+    let error = tensor.samples_to_keys(&["not_there"]).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: 'not_there' is not part of the samples for this tensor map"
+    );
+
+    let error = tensor.properties_to_keys(&["not_there"]).unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid parameter: 'not_there' is not part of the properties for this tensor map"
+    );
+}
@@ -1,7 +1,10 @@
 #![allow(dead_code)]
 #![allow(clippy::needless_return)]
 
-use equistore::{LabelsBuilder, Labels, TensorBlock, TensorMap};
+use std::ops::Range;
+
+use equistore::{Array, ArrayDataMut, LabelsBuilder, Labels, TensorBlock, TensorMap};
+use equistore::c_api::{eqs_device_t, eqs_dtype_t, eqs_sample_mapping_t, EQS_DEVICE_OTHER};
 
 use ndarray::ArrayD;
 
@@ -97,3 +100,43 @@ pub fn example_tensor() -> TensorMap {
 
     return TensorMap::new(keys, vec![block_1, block_2, block_3, block_4]).unwrap();
 }
+
+/// Array pretending to live outside of host memory, and only giving access to
+/// its data through `copy_to_host`.
+pub struct OtherDeviceArray(pub ArrayD<f64>);
+
+impl Array for OtherDeviceArray {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
+    fn create(&self, shape: &[usize]) -> Box<dyn Array> {
+        Box::new(OtherDeviceArray(ArrayD::zeros(shape)))
+    }
+    fn copy(&self) -> Box<dyn Array> {
+        Box::new(OtherDeviceArray(self.0.clone()))
+    }
+    fn dtype(&self) -> eqs_dtype_t { self.0.dtype() }
+    fn data(&mut self) -> ArrayDataMut<'_> {
+        panic!("the data of this array is not accessible in host memory");
+    }
+    fn device(&self) -> eqs_device_t { EQS_DEVICE_OTHER }
+    fn copy_to_host(&self, buffer: &mut [u8]) {
+        for (chunk, value) in buffer.chunks_exact_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&value.to_ne_bytes());
+        }
+    }
+    fn shape(&self) -> &[usize] { self.0.shape() }
+    fn reshape(&mut self, shape: &[usize]) { Array::reshape(&mut self.0, shape) }
+    fn swap_axes(&mut self, axis_1: usize, axis_2: usize) { Array::swap_axes(&mut self.0, axis_1, axis_2) }
+    fn move_samples_from(&mut self, _: &dyn Array, _: &[eqs_sample_mapping_t], _: Range<usize>) {
+        panic!("can not call Array::move_samples_from() for OtherDeviceArray");
+    }
+    fn gather_from(&mut self, _: &dyn Array, _: usize, _: &[usize]) {
+        panic!("can not call Array::gather_from() for OtherDeviceArray");
+    }
+    fn scatter_add_from(&mut self, _: &dyn Array, _: usize, _: &[usize]) {
+        panic!("can not call Array::scatter_add_from() for OtherDeviceArray");
+    }
+    fn move_data(&mut self, _: &dyn Array, _: &[eqs_sample_mapping_t], _: &[usize]) {
+        panic!("can not call Array::move_data() for OtherDeviceArray");
+    }
+}
@@ -20,6 +20,13 @@ if arch == "32bit":
 elif arch == "64bit":
     c_uintptr_t = ctypes.c_uint64
 
+EQS_DTYPE_FLOAT64 = 1
+EQS_DTYPE_FLOAT32 = 2
+EQS_DTYPE_FLOAT16 = 3
+EQS_DTYPE_INT32 = 4
+EQS_DTYPE_INT64 = 5
+EQS_DEVICE_CPU = 1
+EQS_DEVICE_OTHER = 2
 EQS_SUCCESS = 0
 EQS_INVALID_PARAMETER_ERROR = 1
 EQS_IO_ERROR = 2
@@ -30,6 +37,8 @@ EQS_INTERNAL_ERROR = 255
 
 eqs_status_t = ctypes.c_int32
 eqs_data_origin_t = ctypes.c_uint64
+eqs_dtype_t = ctypes.c_int32
+eqs_device_t = ctypes.c_int32
 
 
 class eqs_block_t(ctypes.Structure):
@@ -46,7 +55,7 @@ class eqs_labels_t(ctypes.Structure):
 eqs_labels_t._fields_ = [
     ("internal_ptr_", ctypes.c_void_p),
     ("names", POINTER(ctypes.c_char_p)),
-    ("values", POINTER(ctypes.c_int32)),
+    ("values", POINTER(ctypes.c_int64)),
     ("size", c_uintptr_t),
     ("count", c_uintptr_t),
 ]
@@ -67,7 +76,10 @@ class eqs_array_t(ctypes.Structure):
 eqs_array_t._fields_ = [
     ("ptr", ctypes.c_void_p),
     ("origin", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(eqs_data_origin_t))),
-    ("data", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(POINTER(ctypes.c_double)))),
+    ("dtype", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(eqs_dtype_t))),
+    ("data", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(ctypes.c_void_p))),
+    ("device", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(eqs_device_t))),
+    ("copy_to_host", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, ctypes.c_void_p, c_uintptr_t)),
     ("shape", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(POINTER(c_uintptr_t)), POINTER(c_uintptr_t))),
     ("reshape", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(c_uintptr_t), c_uintptr_t)),
     ("swap_axes", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, c_uintptr_t, c_uintptr_t)),
@@ -75,10 +87,13 @@ eqs_array_t._fields_ = [
     ("copy", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, POINTER(eqs_array_t))),
     ("destroy", CFUNCTYPE(None, ctypes.c_void_p)),
     ("move_samples_from", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, ctypes.c_void_p, POINTER(eqs_sample_mapping_t), c_uintptr_t, c_uintptr_t, c_uintptr_t)),
+    ("gather_from", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, ctypes.c_void_p, c_uintptr_t, POINTER(c_uintptr_t), c_uintptr_t)),
+    ("scatter_add_from", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, ctypes.c_void_p, c_uintptr_t, POINTER(c_uintptr_t), c_uintptr_t)),
+    ("move_data", CFUNCTYPE(eqs_status_t, ctypes.c_void_p, ctypes.c_void_p, POINTER(eqs_sample_mapping_t), c_uintptr_t, POINTER(c_uintptr_t), c_uintptr_t)),
 ]
 
 
-eqs_create_array_callback_t = CFUNCTYPE(eqs_status_t, POINTER(c_uintptr_t), c_uintptr_t, POINTER(eqs_array_t))
+eqs_create_array_callback_t = CFUNCTYPE(eqs_status_t, POINTER(c_uintptr_t), c_uintptr_t, eqs_dtype_t, POINTER(eqs_array_t))
 
 
 def setup_functions(lib):
@@ -98,12 +113,21 @@ def setup_functions(lib):
 
     lib.eqs_labels_position.argtypes = [
         eqs_labels_t,
-        POINTER(ctypes.c_int32),
+        POINTER(ctypes.c_int64),
         c_uintptr_t,
         POINTER(ctypes.c_int64),
     ]
     lib.eqs_labels_position.restype = _check_status
 
+    lib.eqs_labels_positions.argtypes = [
+        eqs_labels_t,
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+        ctypes.c_bool,
+        POINTER(ctypes.c_int64),
+    ]
+    lib.eqs_labels_positions.restype = _check_status
+
     lib.eqs_labels_create.argtypes = [
         POINTER(eqs_labels_t),
     ]
@@ -115,6 +139,100 @@ def setup_functions(lib):
     ]
     lib.eqs_labels_clone.restype = _check_status
 
+    lib.eqs_labels_is_sorted.argtypes = [
+        eqs_labels_t,
+        POINTER(ctypes.c_bool),
+    ]
+    lib.eqs_labels_is_sorted.restype = _check_status
+
+    lib.eqs_labels_sort.argtypes = [
+        eqs_labels_t,
+        POINTER(eqs_labels_t),
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+    ]
+    lib.eqs_labels_sort.restype = _check_status
+
+    lib.eqs_labels_select.argtypes = [
+        eqs_labels_t,
+        eqs_labels_t,
+        POINTER(c_uintptr_t),
+        POINTER(c_uintptr_t),
+    ]
+    lib.eqs_labels_select.restype = _check_status
+
+    lib.eqs_labels_union.argtypes = [
+        eqs_labels_t,
+        eqs_labels_t,
+        POINTER(eqs_labels_t),
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+    ]
+    lib.eqs_labels_union.restype = _check_status
+
+    lib.eqs_labels_intersection.argtypes = [
+        eqs_labels_t,
+        eqs_labels_t,
+        POINTER(eqs_labels_t),
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+    ]
+    lib.eqs_labels_intersection.restype = _check_status
+
+    lib.eqs_labels_difference.argtypes = [
+        eqs_labels_t,
+        eqs_labels_t,
+        POINTER(eqs_labels_t),
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+    ]
+    lib.eqs_labels_difference.restype = _check_status
+
+    lib.eqs_labels_project.argtypes = [
+        eqs_labels_t,
+        POINTER(ctypes.c_char_p),
+        c_uintptr_t,
+        POINTER(eqs_labels_t),
+    ]
+    lib.eqs_labels_project.restype = _check_status
+
+    lib.eqs_labels_insert.argtypes = [
+        eqs_labels_t,
+        c_uintptr_t,
+        ctypes.c_char_p,
+        POINTER(ctypes.c_int64),
+        c_uintptr_t,
+        POINTER(eqs_labels_t),
+    ]
+    lib.eqs_labels_insert.restype = _check_status
+
+    lib.eqs_labels_remove.argtypes = [
+        eqs_labels_t,
+        ctypes.c_char_p,
+        POINTER(eqs_labels_t),
+    ]
+    lib.eqs_labels_remove.restype = _check_status
+
+    lib.eqs_labels_rename.argtypes = [
+        eqs_labels_t,
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        POINTER(eqs_labels_t),
+    ]
+    lib.eqs_labels_rename.restype = _check_status
+
+    lib.eqs_labels_reorder.argtypes = [
+        eqs_labels_t,
+        POINTER(ctypes.c_char_p),
+        c_uintptr_t,
+        POINTER(eqs_labels_t),
+    ]
+    lib.eqs_labels_reorder.restype = _check_status
+
     lib.eqs_labels_free.argtypes = [
         POINTER(eqs_labels_t),
     ]
@@ -133,6 +251,11 @@ def setup_functions(lib):
     ]
     lib.eqs_get_data_origin.restype = _check_status
 
+    lib.eqs_array_check.argtypes = [
+        POINTER(eqs_array_t),
+    ]
+    lib.eqs_array_check.restype = _check_status
+
     lib.eqs_block.argtypes = [
         eqs_array_t,
         eqs_labels_t,
@@ -184,6 +307,43 @@ def setup_functions(lib):
     ]
     lib.eqs_block_gradients_list.restype = _check_status
 
+    lib.eqs_block_info.argtypes = [
+        POINTER(eqs_block_t),
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        POINTER(ctypes.c_char_p),
+    ]
+    lib.eqs_block_info.restype = _check_status
+
+    lib.eqs_block_set_info.argtypes = [
+        POINTER(eqs_block_t),
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+    ]
+    lib.eqs_block_set_info.restype = _check_status
+
+    lib.eqs_block_info_keys.argtypes = [
+        POINTER(eqs_block_t),
+        ctypes.c_char_p,
+        POINTER(POINTER(ctypes.c_char_p)),
+        POINTER(c_uintptr_t),
+    ]
+    lib.eqs_block_info_keys.restype = _check_status
+
+    lib.eqs_block_slice.argtypes = [
+        POINTER(eqs_block_t),
+        ctypes.c_char_p,
+        eqs_labels_t,
+    ]
+    lib.eqs_block_slice.restype = POINTER(eqs_block_t)
+
+    lib.eqs_block_sort.argtypes = [
+        POINTER(eqs_block_t),
+        ctypes.c_char_p,
+    ]
+    lib.eqs_block_sort.restype = POINTER(eqs_block_t)
+
     lib.eqs_tensormap.argtypes = [
         eqs_labels_t,
         POINTER(POINTER(eqs_block_t)),
@@ -202,6 +362,27 @@ def setup_functions(lib):
     ]
     lib.eqs_tensormap_keys.restype = _check_status
 
+    lib.eqs_tensormap_metadata.argtypes = [
+        POINTER(eqs_tensormap_t),
+        ctypes.c_char_p,
+        POINTER(ctypes.c_char_p),
+    ]
+    lib.eqs_tensormap_metadata.restype = _check_status
+
+    lib.eqs_tensormap_set_metadata.argtypes = [
+        POINTER(eqs_tensormap_t),
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+    ]
+    lib.eqs_tensormap_set_metadata.restype = _check_status
+
+    lib.eqs_tensormap_metadata_keys.argtypes = [
+        POINTER(eqs_tensormap_t),
+        POINTER(POINTER(ctypes.c_char_p)),
+        POINTER(c_uintptr_t),
+    ]
+    lib.eqs_tensormap_metadata_keys.restype = _check_status
+
     lib.eqs_tensormap_block_by_id.argtypes = [
         POINTER(eqs_tensormap_t),
         POINTER(POINTER(eqs_block_t)),
@@ -221,6 +402,7 @@ def setup_functions(lib):
         POINTER(eqs_tensormap_t),
         eqs_labels_t,
         ctypes.c_bool,
+        ctypes.c_bool,
     ]
     lib.eqs_tensormap_keys_to_properties.restype = POINTER(eqs_tensormap_t)
 
@@ -231,6 +413,20 @@ def setup_functions(lib):
     ]
     lib.eqs_tensormap_components_to_properties.restype = POINTER(eqs_tensormap_t)
 
+    lib.eqs_tensormap_components_to_samples.argtypes = [
+        POINTER(eqs_tensormap_t),
+        POINTER(ctypes.c_char_p),
+        c_uintptr_t,
+    ]
+    lib.eqs_tensormap_components_to_samples.restype = POINTER(eqs_tensormap_t)
+
+    lib.eqs_tensormap_properties_to_components.argtypes = [
+        POINTER(eqs_tensormap_t),
+        POINTER(ctypes.c_char_p),
+        c_uintptr_t,
+    ]
+    lib.eqs_tensormap_properties_to_components.restype = POINTER(eqs_tensormap_t)
+
     lib.eqs_tensormap_keys_to_samples.argtypes = [
         POINTER(eqs_tensormap_t),
         eqs_labels_t,
@@ -238,6 +434,56 @@ def setup_functions(lib):
     ]
     lib.eqs_tensormap_keys_to_samples.restype = POINTER(eqs_tensormap_t)
 
+    lib.eqs_tensormap_join.argtypes = [
+        POINTER(POINTER(eqs_tensormap_t)),
+        c_uintptr_t,
+        ctypes.c_char_p,
+    ]
+    lib.eqs_tensormap_join.restype = POINTER(eqs_tensormap_t)
+
+    lib.eqs_tensormap_reduce_over_samples.argtypes = [
+        POINTER(eqs_tensormap_t),
+        POINTER(ctypes.c_char_p),
+        c_uintptr_t,
+        ctypes.c_char_p,
+    ]
+    lib.eqs_tensormap_reduce_over_samples.restype = POINTER(eqs_tensormap_t)
+
+    lib.eqs_tensormap_slice.argtypes = [
+        POINTER(eqs_tensormap_t),
+        ctypes.c_char_p,
+        eqs_labels_t,
+    ]
+    lib.eqs_tensormap_slice.restype = POINTER(eqs_tensormap_t)
+
+    lib.eqs_tensormap_split.argtypes = [
+        POINTER(eqs_tensormap_t),
+        ctypes.c_char_p,
+        POINTER(eqs_labels_t),
+        c_uintptr_t,
+        POINTER(POINTER(eqs_tensormap_t)),
+    ]
+    lib.eqs_tensormap_split.restype = _check_status
+
+    lib.eqs_tensormap_samples_to_keys.argtypes = [
+        POINTER(eqs_tensormap_t),
+        POINTER(ctypes.c_char_p),
+        c_uintptr_t,
+    ]
+    lib.eqs_tensormap_samples_to_keys.restype = POINTER(eqs_tensormap_t)
+
+    lib.eqs_tensormap_properties_to_keys.argtypes = [
+        POINTER(eqs_tensormap_t),
+        POINTER(ctypes.c_char_p),
+        c_uintptr_t,
+    ]
+    lib.eqs_tensormap_properties_to_keys.restype = POINTER(eqs_tensormap_t)
+
+    lib.eqs_tensormap_sort_keys.argtypes = [
+        POINTER(eqs_tensormap_t),
+    ]
+    lib.eqs_tensormap_sort_keys.restype = POINTER(eqs_tensormap_t)
+
     lib.eqs_tensormap_load.argtypes = [
         ctypes.c_char_p,
         eqs_create_array_callback_t,
@@ -2,7 +2,18 @@ import ctypes
 
 import numpy as np
 
-from .._c_api import c_uintptr_t, eqs_array_t, eqs_data_origin_t
+from .._c_api import (
+    EQS_DEVICE_CPU,
+    EQS_DEVICE_OTHER,
+    EQS_DTYPE_FLOAT16,
+    EQS_DTYPE_FLOAT32,
+    EQS_DTYPE_FLOAT64,
+    EQS_DTYPE_INT32,
+    EQS_DTYPE_INT64,
+    c_uintptr_t,
+    eqs_array_t,
+    eqs_data_origin_t,
+)
 from ..utils import catch_exceptions
 
 
@@ -54,6 +65,29 @@ def _origin_pytorch():
     return _TORCH_STORAGE_ORIGIN
 
 
+_NUMPY_DTYPES = {
+    np.dtype(np.float64): EQS_DTYPE_FLOAT64,
+    np.dtype(np.float32): EQS_DTYPE_FLOAT32,
+    np.dtype(np.float16): EQS_DTYPE_FLOAT16,
+    np.dtype(np.int32): EQS_DTYPE_INT32,
+    np.dtype(np.int64): EQS_DTYPE_INT64,
+}
+
+
+def _eqs_dtype(array):
+    """Get the ``eqs_dtype_t`` corresponding to the type of ``array``"""
+    if _is_torch_array(array):
+        # all supported torch dtypes have a numpy equivalent
+        dtype = torch.empty(0, dtype=array.dtype).numpy().dtype
+    else:
+        dtype = array.dtype
+
+    try:
+        return _NUMPY_DTYPES[np.dtype(dtype)]
+    except KeyError:
+        raise ValueError(f"unsupported array type {dtype}")
+
+
 class ArrayWrapper:
     """Small wrapper making Python arrays compatible with ``eqs_array_t``."""
 
@@ -81,7 +115,12 @@ class ArrayWrapper:
         # use storage.XXX.__class__ to get the right type for all functions
         eqs_array.origin = eqs_array.origin.__class__(eqs_array_origin)
 
+        eqs_array.dtype = eqs_array.dtype.__class__(_eqs_array_dtype)
         eqs_array.data = eqs_array.data.__class__(_eqs_array_data)
+        eqs_array.device = eqs_array.device.__class__(_eqs_array_device)
+        eqs_array.copy_to_host = eqs_array.copy_to_host.__class__(
+            _eqs_array_copy_to_host
+        )
 
         eqs_array.shape = eqs_array.shape.__class__(_eqs_array_shape)
         eqs_array.reshape = eqs_array.reshape.__class__(_eqs_array_reshape)
@@ -94,6 +133,11 @@ class ArrayWrapper:
         eqs_array.move_samples_from = eqs_array.move_samples_from.__class__(
             _eqs_array_move_samples_from
         )
+        eqs_array.gather_from = eqs_array.gather_from.__class__(_eqs_array_gather_from)
+        eqs_array.scatter_add_from = eqs_array.scatter_add_from.__class__(
+            _eqs_array_scatter_add_from
+        )
+        eqs_array.move_data = eqs_array.move_data.__class__(_eqs_array_move_data)
 
         self._eqs_array = eqs_array
 
@@ -118,6 +162,12 @@ def _object_from_ptr(ptr):
     return ctypes.cast(ptr, ctypes.POINTER(ctypes.py_object)).contents.value
 
 
+@catch_exceptions
+def _eqs_array_dtype(this, dtype):
+    storage = _object_from_ptr(this)
+    dtype[0] = _eqs_dtype(storage.array)
+
+
 @catch_exceptions
 def _eqs_array_data(this, data):
     storage = _object_from_ptr(this)
@@ -140,10 +190,38 @@ def _eqs_array_data(this, data):
     if not array.data.c_contiguous:
         raise ValueError("can not get data pointer for non contiguous array")
 
-    if not array.dtype == np.float64:
-        raise ValueError(f"can not get data pointer for array type {array.dtype}")
+    # check that the data type is supported
+    _eqs_dtype(array)
+
+    data[0] = array.ctypes.data
+
+
+@catch_exceptions
+def _eqs_array_device(this, device):
+    storage = _object_from_ptr(this)
+
+    if _is_torch_array(storage.array) and storage.array.device.type != "cpu":
+        device[0] = EQS_DEVICE_OTHER
+    else:
+        device[0] = EQS_DEVICE_CPU
+
+
+@catch_exceptions
+def _eqs_array_copy_to_host(this, buffer, buffer_size):
+    storage = _object_from_ptr(this)
 
-    data[0] = array.ctypes.data_as(ctypes.POINTER(ctypes.c_double))
+    if _is_torch_array(storage.array):
+        array = storage.array.detach().cpu().numpy()
+    else:
+        array = storage.array
+
+    array = np.ascontiguousarray(array)
+    if array.nbytes != buffer_size:
+        raise ValueError(
+            f"invalid buffer size: expected {array.nbytes} bytes, got {buffer_size}"
+        )
+
+    ctypes.memmove(buffer, array.ctypes.data, buffer_size)
 
 
 @catch_exceptions
@@ -233,3 +311,53 @@ def _eqs_array_move_samples_from(
 
     properties = slice(property_start, property_end)
     output[output_samples, ..., properties] = input[input_samples, ..., :]
+
+
+@catch_exceptions
+def _eqs_array_gather_from(this, input, axis, indices_ptr, indices_count):
+    output = _object_from_ptr(this).array
+    input = _object_from_ptr(input).array
+
+    indices = [indices_ptr[i] for i in range(indices_count)]
+
+    selection = [slice(None)] * len(input.shape)
+    selection[axis] = indices
+    output[...] = input[tuple(selection)]
+
+
+@catch_exceptions
+def _eqs_array_scatter_add_from(this, input, axis, indices_ptr, indices_count):
+    output = _object_from_ptr(this).array
+    input = _object_from_ptr(input).array
+
+    indices = [indices_ptr[i] for i in range(indices_count)]
+
+    if _is_numpy_array(output):
+        selection = [slice(None)] * len(output.shape)
+        selection[axis] = indices
+        np.add.at(output, tuple(selection), input)
+    elif _is_torch_array(output):
+        index = torch.tensor(indices, dtype=torch.long, device=output.device)
+        output.index_add_(axis, index, input)
+
+
+@catch_exceptions
+def _eqs_array_move_data(
+    this,
+    input,
+    samples_ptr,
+    samples_count,
+    properties_ptr,
+    properties_count,
+):
+    output = _object_from_ptr(this).array
+    input = _object_from_ptr(input).array
+
+    input_samples = []
+    output_samples = []
+    for i in range(samples_count):
+        input_samples.append(samples_ptr[i].input)
+        output_samples.append(samples_ptr[i].output)
+
+    for i in range(properties_count):
+        output[output_samples, ..., properties_ptr[i]] = input[input_samples, ..., i]
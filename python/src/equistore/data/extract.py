@@ -3,7 +3,19 @@ from typing import NewType, Union
 
 import numpy as np
 
-from .._c_api import c_uintptr_t, eqs_array_t, eqs_data_origin_t
+from .._c_api import (
+    EQS_DEVICE_CPU,
+    EQS_DTYPE_FLOAT16,
+    EQS_DTYPE_FLOAT32,
+    EQS_DTYPE_FLOAT64,
+    EQS_DTYPE_INT32,
+    EQS_DTYPE_INT64,
+    c_uintptr_t,
+    eqs_array_t,
+    eqs_data_origin_t,
+    eqs_device_t,
+    eqs_dtype_t,
+)
 from ..status import _check_status
 from ..utils import _call_with_growing_buffer, _ptr_to_ndarray
 from .array import _object_from_ptr, _origin_numpy, _origin_pytorch, _register_origin
@@ -100,6 +112,30 @@ def data_origin(eqs_array):
     return origin.value
 
 
+def data_dtype(eqs_array):
+    """Get the ``eqs_dtype_t`` of the data in an eqs_array"""
+    if not eqs_array.dtype:
+        # arrays without a dtype callback contain 64-bit floating point values
+        return EQS_DTYPE_FLOAT64
+
+    dtype = eqs_dtype_t()
+    status = eqs_array.dtype(eqs_array.ptr, dtype)
+    _check_status(status)
+    return dtype.value
+
+
+def data_device(eqs_array):
+    """Get the ``eqs_device_t`` where the data of an eqs_array lives"""
+    if not eqs_array.device:
+        # arrays without a device callback live in host memory
+        return EQS_DEVICE_CPU
+
+    device = eqs_device_t()
+    status = eqs_array.device(eqs_array.ptr, device)
+    _check_status(status)
+    return device.value
+
+
 def data_origin_name(origin):
     """Get the name of the data origin of an eqs_array"""
     from .._c_lib import _get_library
@@ -113,6 +149,17 @@ def data_origin_name(origin):
 
 # ============================================================================ #
 
+# ctypes and numpy types corresponding to each eqs_dtype_t. There is no ctypes
+# type for 16-bit floats, so we load the data as 16-bit integers and then
+# re-interpret it as floating point values.
+_CTYPES_FOR_DTYPE = {
+    EQS_DTYPE_FLOAT64: (ctypes.c_double, np.float64),
+    EQS_DTYPE_FLOAT32: (ctypes.c_float, np.float32),
+    EQS_DTYPE_FLOAT16: (ctypes.c_uint16, np.uint16),
+    EQS_DTYPE_INT32: (ctypes.c_int32, np.int32),
+    EQS_DTYPE_INT64: (ctypes.c_int64, np.int64),
+}
+
 
 class ExternalCpuArray(np.ndarray):
     """
@@ -135,11 +182,30 @@ class ExternalCpuArray(np.ndarray):
         for i in range(shape_count.value):
             shape.append(shape_ptr[i])
 
-        data = ctypes.POINTER(ctypes.c_double)()
-        status = eqs_array.data(eqs_array.ptr, data)
-        _check_status(status)
+        dtype = data_dtype(eqs_array)
+        if dtype not in _CTYPES_FOR_DTYPE:
+            raise ValueError(f"unknown data type {dtype} for external array")
+        c_type, np_type = _CTYPES_FOR_DTYPE[dtype]
+
+        if data_device(eqs_array) == EQS_DEVICE_CPU:
+            data = ctypes.c_void_p()
+            status = eqs_array.data(eqs_array.ptr, data)
+            _check_status(status)
+
+            data = ctypes.cast(data, ctypes.POINTER(c_type))
+            array = _ptr_to_ndarray(data, shape, np_type)
+        else:
+            # the data does not live in host memory, copy it to a new array
+            array = np.empty(shape, dtype=np_type)
+            if array.size != 0:
+                status = eqs_array.copy_to_host(
+                    eqs_array.ptr, array.ctypes.data, array.nbytes
+                )
+                _check_status(status)
+
+        if dtype == EQS_DTYPE_FLOAT16:
+            array = array.view(np.float16)
 
-        array = _ptr_to_ndarray(data, shape, np.float64)
         obj = array.view(cls)
 
         # keep a reference to the parent object (if any) to prevent it from
@@ -4,7 +4,17 @@ from typing import Callable
 
 import numpy as np
 
-from ._c_api import c_uintptr_t, eqs_array_t, eqs_create_array_callback_t
+from ._c_api import (
+    EQS_DTYPE_FLOAT16,
+    EQS_DTYPE_FLOAT32,
+    EQS_DTYPE_FLOAT64,
+    EQS_DTYPE_INT32,
+    EQS_DTYPE_INT64,
+    c_uintptr_t,
+    eqs_array_t,
+    eqs_create_array_callback_t,
+    eqs_dtype_t,
+)
 from ._c_lib import _get_library
 from .block import TensorBlock
 from .data.array import ArrayWrapper, _is_numpy_array, _is_torch_array
@@ -13,8 +23,17 @@ from .tensor import TensorMap
 from .utils import catch_exceptions
 
 
+_NUMPY_DTYPES = {
+    EQS_DTYPE_FLOAT64: np.float64,
+    EQS_DTYPE_FLOAT32: np.float32,
+    EQS_DTYPE_FLOAT16: np.float16,
+    EQS_DTYPE_INT32: np.int32,
+    EQS_DTYPE_INT64: np.int64,
+}
+
+
 @catch_exceptions
-def create_numpy_array(shape_ptr, shape_count, array):
+def create_numpy_array(shape_ptr, shape_count, dtype, array):
     """
     Callback function that can be used with
     :py:func:`equistore.io.load_custom_array` to load data in numpy arrays.
@@ -23,25 +42,34 @@ def create_numpy_array(shape_ptr, shape_count, array):
     for i in range(shape_count):
         shape.append(shape_ptr[i])
 
-    data = np.empty(shape, dtype=np.float64)
+    data = np.empty(shape, dtype=_NUMPY_DTYPES[dtype])
     wrapper = ArrayWrapper(data)
     array[0] = wrapper.into_eqs_array()
 
 
 @catch_exceptions
-def create_torch_array(shape_ptr, shape_count, array):
+def create_torch_array(shape_ptr, shape_count, dtype, array):
     """
     Callback function that can be used with
     :py:func:`equistore.io.load_custom_array` to load data in torch tensors. The
-    resulting tensors are stored on CPU, and their dtype is ``torch.float64``.
+    resulting tensors are stored on CPU, and their dtype matches the data type
+    in the file.
     """
     import torch
 
+    torch_dtypes = {
+        EQS_DTYPE_FLOAT64: torch.float64,
+        EQS_DTYPE_FLOAT32: torch.float32,
+        EQS_DTYPE_FLOAT16: torch.float16,
+        EQS_DTYPE_INT32: torch.int32,
+        EQS_DTYPE_INT64: torch.int64,
+    }
+
     shape = []
     for i in range(shape_count):
         shape.append(shape_ptr[i])
 
-    data = torch.empty(shape, dtype=torch.float64, device="cpu")
+    data = torch.empty(shape, dtype=torch_dtypes[dtype], device="cpu")
     wrapper = ArrayWrapper(data)
     array[0] = wrapper.into_eqs_array()
 
@@ -58,8 +86,11 @@ def load(path: str, use_numpy=False) -> TensorMap:
     :param path: path of the file to load
     :param use_numpy: should we use numpy or the native implementation? Numpy
         should be able to process more dtypes than the native implementation,
-        which is limited to float64, but the native implementation is usually
-        faster than going through numpy.
+        which is limited to 64, 32 and 16-bit floats and 32 and 64-bit
+        integers, but the native implementation is usually faster than going
+        through numpy. The numpy implementation ignores the tensor-level
+        metadata and the blocks info (``metadata.npy`` and ``info.npy`` files)
+        when loading the data.
     """
     if use_numpy:
         return _read_npz(path)
@@ -68,7 +99,13 @@ def load(path: str, use_numpy=False) -> TensorMap:
 
 
 CreateArrayCallback = Callable[
-    [ctypes.POINTER(c_uintptr_t), c_uintptr_t, ctypes.POINTER(eqs_array_t)], None
+    [
+        ctypes.POINTER(c_uintptr_t),
+        c_uintptr_t,
+        eqs_dtype_t,
+        ctypes.POINTER(eqs_array_t),
+    ],
+    None,
 ]
 
 
@@ -81,8 +118,9 @@ def load_custom_array(path: str, create_array: CreateArrayCallback) -> TensorMap
     This is an advanced functionality, which should not be needed by most users.
 
     This function allows to specify the kind of array to use when loading the
-    data through the create_array callback. This callback should take three
-    arguments: a pointer to the shape, the number of elements in the shape, and
+    data through the create_array callback. This callback should take four
+    arguments: a pointer to the shape, the number of elements in the shape, the
+    data type of the array to create (one of the ``EQS_DTYPE_*`` constants), and
     a pointer to the ``eqs_array_t`` to be filled.
 
     :py:func:`equistore.io.create_numpy_array` and
@@ -114,8 +152,10 @@ def save(path: str, tensor: TensorMap, use_numpy=False):
     :param tensor: tensor to save
     :param use_numpy: should we use numpy or the native implementation? Numpy
         should be able to process more dtypes than the native implementation,
-        which is limited to float64, but the native implementation is usually
-        faster than going through numpy.
+        which is limited to 64, 32 and 16-bit floats and 32 and 64-bit
+        integers, but the native implementation is usually faster than going
+        through numpy. The numpy implementation does not save the tensor-level
+        metadata and the blocks info (``metadata.npy`` and ``info.npy`` files).
     """
     if not path.endswith(".npz"):
         path += ".npz"
@@ -142,6 +182,8 @@ def _array_to_numpy(array):
 
 
 def _tensor_map_to_dict(tensor_map):
+    # the tensor-level metadata and blocks info are not accessible from Python,
+    # and are not included in the result
     result = {"keys": tensor_map.keys}
 
     for block_i, (_, block) in enumerate(tensor_map):
@@ -165,10 +207,14 @@ def _tensor_map_to_dict(tensor_map):
 
 def _labels_from_npz(data):
     names = data.dtype.names
-    return Labels(names=names, values=data.view(dtype=np.int32).reshape(-1, len(names)))
+    # files created with older versions of equistore use 32-bit integers
+    dtype = data.dtype[0] if len(names) != 0 else np.int64
+    return Labels(names=names, values=data.view(dtype=dtype).reshape(-1, len(names)))
 
 
 def _read_npz(path):
+    # the tensor-level metadata and blocks info (`metadata.npy` and `info.npy`)
+    # can not be set from Python, and are ignored here
     dictionary = np.load(path)
 
     keys = _labels_from_npz(dictionary["keys"])
@@ -62,7 +62,7 @@ class Labels(np.ndarray):
         :param names: names of the variables in the new labels, in the case of a single
                       name also a single string can be given: ``names = "name"``
         :param values: values of the variables, this needs to be a 2D array of
-            ``np.int32`` values
+            ``np.int64`` values
         """
 
         for key in kwargs.keys():
@@ -91,7 +91,7 @@ class Labels(np.ndarray):
         try:
             values = np.ascontiguousarray(
                 values.astype(
-                    np.int32,
+                    np.int64,
                     order="C",
                     casting="same_kind",
                     subok=False,
@@ -101,7 +101,7 @@ class Labels(np.ndarray):
         except TypeError as e:
             raise TypeError("Labels values must be convertible to integers") from e
 
-        dtype = [(name, np.int32) for name in names]
+        dtype = [(name, np.int64) for name in names]
 
         if values.shape[1] != 0:
             values = values.view(dtype=dtype).reshape((values.shape[0],))
@@ -149,7 +149,7 @@ class Labels(np.ndarray):
         entry in the corresponding dimension (e.g. keys when a tensor map
         contains a single block).
         """
-        return Labels(names=["_"], values=np.zeros(shape=(1, 1), dtype=np.int32))
+        return Labels(names=["_"], values=np.zeros(shape=(1, 1), dtype=np.int64))
 
     def as_namedtuples(self):
         """
@@ -159,7 +159,7 @@ class Labels(np.ndarray):
 
             labels = Labels(
                 names=["structure", "atom", "center_species"],
-                values=np.array([[0, 2, 4]], dtype=np.int32),
+                values=np.array([[0, 2, 4]], dtype=np.int64),
             )
 
             for label in labels.as_namedtuples():
@@ -195,14 +195,14 @@ class Labels(np.ndarray):
         if eqs_labels.count != 0:
             shape = (eqs_labels.count, eqs_labels.size)
             values = _ptr_to_const_ndarray(
-                ptr=eqs_labels.values, shape=shape, dtype=np.int32
+                ptr=eqs_labels.values, shape=shape, dtype=np.int64
             )
             values.flags.writeable = False
             return Labels(names, values, _eqs_labels_t=eqs_labels)
         else:
             return Labels(
                 names=names,
-                values=np.empty(shape=(0, len(names)), dtype=np.int32),
+                values=np.empty(shape=(0, len(names)), dtype=np.int64),
             )
 
     def position(self, label) -> Optional[int]:
@@ -216,9 +216,9 @@ class Labels(np.ndarray):
         lib = _get_library()
 
         result = ctypes.c_int64()
-        values = ctypes.ARRAY(ctypes.c_int32, len(label))()
+        values = ctypes.ARRAY(ctypes.c_int64, len(label))()
         for i, v in enumerate(label):
-            values[i] = ctypes.c_int32(v)
+            values[i] = ctypes.c_int64(v)
 
         lib.eqs_labels_position(
             self._eqs_labels_t,
@@ -234,7 +234,7 @@ class Labels(np.ndarray):
 
     def asarray(self):
         """Get a view of these ``Labels`` as a raw 2D array of integers"""
-        return self.view(dtype=np.int32).reshape(self.shape[0], -1)
+        return self.view(dtype=np.int64).reshape(self.shape[0], -1)
 
     def __contains__(self, label):
         return self.position(label) is not None
@@ -250,7 +250,7 @@ def _eqs_labels_view(array):
     labels.internal_ptr_ = None
     labels.names = names
     labels.size = len(array.names)
-    labels.values = array.ctypes.data_as(ctypes.POINTER(ctypes.c_int32))
+    labels.values = array.ctypes.data_as(ctypes.POINTER(ctypes.c_int64))
     labels.count = array.shape[0]
 
     return labels
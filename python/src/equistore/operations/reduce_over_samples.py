@@ -34,7 +34,7 @@ def _reduce_over_samples_block(
         block_samples.names.index(sample) for sample in remaining_samples
     ]
     # reshaping the samples in a 2D array
-    samples = block_samples.view(dtype=np.int32).reshape(block_samples.shape[0], -1)
+    samples = block_samples.view(dtype=np.int64).reshape(block_samples.shape[0], -1)
     # get which samples will still be there after reduction
     new_samples, index = np.unique(
         samples[:, sample_selected], return_inverse=True, axis=0
@@ -96,7 +96,7 @@ def _reduce_over_samples_block(
         gradient_samples = gradient.samples
         # here we need to copy because we want to modify the samples array
         samples = (
-            gradient_samples.view(dtype=np.int32)
+            gradient_samples.view(dtype=np.int64)
             .reshape(gradient_samples.shape[0], -1)
             .copy()
         )
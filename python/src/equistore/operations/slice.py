@@ -254,7 +254,7 @@ def _slice_block(
                 # update the "sample" column of the gradient samples
                 # to refer to the new samples
                 new_grad_samples = (
-                    new_grad_samples.view(dtype=np.int32)
+                    new_grad_samples.view(dtype=np.int64)
                     .reshape(new_grad_samples.shape[0], -1)
                     .copy()
                 )
@@ -131,7 +131,7 @@ class TensorMap:
             # with Labels containing a single entry
             labels = Labels(
                 names=["key", "symmetric"],
-                values=np.array([[-3, 4]], dtype=np.int32)
+                values=np.array([[-3, 4]], dtype=np.int64)
             )
             block = tensor.block(labels)
         """
@@ -192,7 +192,7 @@ class TensorMap:
             # with Labels containing a single entry
             labels = Labels(
                 names=["key"],
-                values=np.array([[-3]], dtype=np.int32)
+                values=np.array([[-3]], dtype=np.int64)
             )
             blocks = tensor.blocks(labels)
         """
@@ -253,7 +253,7 @@ class TensorMap:
         if selection is None:
             selection = Labels(
                 kwargs.keys(),
-                np.array(list(kwargs.values()), dtype=np.int32).reshape(1, -1),
+                np.array(list(kwargs.values()), dtype=np.int64).reshape(1, -1),
             )
 
         block_indexes = ctypes.ARRAY(c_uintptr_t, len(self.keys))()
@@ -294,9 +294,13 @@ class TensorMap:
         the sample labels, and blocks with the same remaining keys variables
         will be merged together along the sample axis.
 
-        If ``keys_to_move`` is a set of :py:class:`Labels`, it must be empty
-        (``keys_to_move.shape[0] == 0``). The new sample labels will contain
-        entries corresponding to the merged blocks' keys.
+        If ``keys_to_move`` is a set of :py:class:`Labels` without any entries
+        (``keys_to_move.shape[0] == 0``), the new sample labels will contain
+        entries corresponding to the merged blocks' keys only. If it contains
+        entries, the new sample labels will contain all the merged samples,
+        combined with each of the entries of ``keys_to_move``. The data for
+        samples which were not present in any of the merged blocks is filled
+        with zeros, and blocks with a key not in ``keys_to_move`` are ignored.
 
         The order of the samples is controlled by ``sort_samples``. If
         ``sort_samples`` is true, samples are re-ordered to keep them
@@ -336,6 +340,7 @@ class TensorMap:
         keys_to_move: Union[str, List[str], Labels],
         *,
         sort_samples=True,
+        sort_properties=False,
     ) -> "TensorMap":
         """
         Merge blocks with the same value for selected keys variables along the
@@ -365,13 +370,22 @@ class TensorMap:
         lexicographically sorted. Otherwise they are kept in the order in which
         they appear in the blocks.
 
+        Similarly, if ``sort_properties`` is true, the new property labels are
+        re-ordered to keep them lexicographically sorted. Otherwise they are
+        kept in the order in which they appear in the blocks.
+
         :param keys_to_move: description of the keys to move
         :param sort_samples: whether to sort the merged samples or keep them in
             the order in which they appear in the original blocks
+        :param sort_properties: whether to sort the merged properties or keep
+            them in the order in which they appear in the original blocks
         """
         keys_to_move = _normalize_keys_to_move(keys_to_move)
         ptr = self._lib.eqs_tensormap_keys_to_properties(
-            self._ptr, keys_to_move._as_eqs_labels_t(), sort_samples
+            self._ptr,
+            keys_to_move._as_eqs_labels_t(),
+            sort_samples,
+            sort_properties,
         )
         return TensorMap._from_ptr(ptr)
 
@@ -401,7 +415,7 @@ def _normalize_keys_to_move(keys_to_move: Union[str, List[str], Labels]):
 
         keys_to_move = Labels(
             names=keys_to_move,
-            values=np.zeros((0, len(keys_to_move)), dtype=np.int32),
+            values=np.zeros((0, len(keys_to_move)), dtype=np.int64),
         )
 
     assert isinstance(keys_to_move, Labels)
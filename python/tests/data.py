@@ -18,7 +18,14 @@ import ctypes
 import equistore
 import equistore.io
 from equistore import data
-from equistore._c_api import EQS_SUCCESS, c_uintptr_t, eqs_array_t, eqs_sample_mapping_t
+from equistore._c_api import (
+    EQS_DEVICE_OTHER,
+    EQS_SUCCESS,
+    c_uintptr_t,
+    eqs_array_t,
+    eqs_sample_mapping_t,
+)
+from equistore._c_lib import _get_library
 
 
 ROOT = os.path.dirname(__file__)
@@ -165,6 +172,79 @@ class TestArrayWrapperMixin:
         free_eqs_array(eqs_array)
         free_eqs_array(eqs_array_other)
 
+    def test_scatter_add_from(self):
+        array = self.create_array((2, 2))
+        wrapper = data.ArrayWrapper(array)
+        eqs_array = wrapper.into_eqs_array()
+
+        input = self.create_array((2, 3))
+        input[0, :] = 1.0
+        input[1, :] = 2.0
+        input[:, 2] = 5.0
+        wrapper_input = data.ArrayWrapper(input)
+        eqs_array_input = wrapper_input.into_eqs_array()
+
+        indices = ctypes.ARRAY(c_uintptr_t, 3)(1, 0, 1)
+        status = eqs_array.scatter_add_from(
+            eqs_array.ptr, eqs_array_input.ptr, 1, indices, len(indices)
+        )
+        self.assertEqual(status, EQS_SUCCESS)
+
+        expected = np.array([[1.0, 6.0], [2.0, 7.0]])
+        self.assertTrue(np.all(np.array(array) == expected))
+
+        free_eqs_array(eqs_array)
+        free_eqs_array(eqs_array_input)
+
+    def test_move_data(self):
+        array = self.create_array((3, 2, 4))
+        wrapper = data.ArrayWrapper(array)
+        eqs_array = wrapper.into_eqs_array()
+
+        input = self.create_array((2, 2, 3))
+        input[0, :, :] = 1.0
+        input[1, :, :] = 2.0
+        input[:, :, 1] = 5.0
+        wrapper_input = data.ArrayWrapper(input)
+        eqs_array_input = wrapper_input.into_eqs_array()
+
+        samples = ctypes.ARRAY(eqs_sample_mapping_t, 2)(
+            eqs_sample_mapping_t(input=0, output=2),
+            eqs_sample_mapping_t(input=1, output=0),
+        )
+        properties = ctypes.ARRAY(c_uintptr_t, 3)(3, 0, 1)
+        status = eqs_array.move_data(
+            eqs_array.ptr,
+            eqs_array_input.ptr,
+            samples,
+            len(samples),
+            properties,
+            len(properties),
+        )
+        self.assertEqual(status, EQS_SUCCESS)
+
+        expected = np.array(
+            [
+                [[5.0, 2.0, 0.0, 2.0], [5.0, 2.0, 0.0, 2.0]],
+                [[0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0]],
+                [[5.0, 1.0, 0.0, 1.0], [5.0, 1.0, 0.0, 1.0]],
+            ]
+        )
+        self.assertTrue(np.all(np.array(array) == expected))
+
+        free_eqs_array(eqs_array)
+        free_eqs_array(eqs_array_input)
+
+    def test_check(self):
+        array = self.create_array((2, 3))
+        wrapper = data.ArrayWrapper(array)
+        eqs_array = wrapper.into_eqs_array()
+
+        # this raises an exception if any of the checks fails
+        _get_library().eqs_array_check(eqs_array)
+
+        free_eqs_array(eqs_array)
+
 
 class TestNumpyData(unittest.TestCase, TestArrayWrapperMixin):
     def expected_origin(self):
@@ -206,7 +286,7 @@ equistore.data.register_external_data_wrapper(
 
 
 @equistore.utils.catch_exceptions
-def create_test_array(shape_ptr, shape_count, array):
+def create_test_array(shape_ptr, shape_count, dtype, array):
     shape = []
     for i in range(shape_count):
         shape.append(shape_ptr[i])
@@ -256,6 +336,25 @@ class TestRustData(unittest.TestCase):
 
         self.assertTrue(np.isclose(transformed, 1.1596965632269784))
 
+    def test_other_device(self):
+        array = np.arange(6, dtype=np.float64).reshape(2, 3)
+        eqs_array = equistore.data.array.ArrayWrapper(array).into_eqs_array()
+
+        @equistore.utils.catch_exceptions
+        def other_device(this, device):
+            device[0] = EQS_DEVICE_OTHER
+
+        eqs_array.device = eqs_array.device.__class__(other_device)
+
+        # data not in host memory is copied when accessed
+        values = equistore.data.extract.ExternalCpuArray(eqs_array, parent=None)
+        self.assertTrue(np.all(values == array))
+
+        values[0, 0] = 42.0
+        self.assertEqual(array[0, 0], 0.0)
+
+        free_eqs_array(eqs_array)
+
 
 if __name__ == "__main__":
     unittest.main()
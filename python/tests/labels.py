@@ -59,7 +59,7 @@ class TestLabels(unittest.TestCase):
         self.assertTrue(np.all(labels.asarray() == np.array([[]])))
         self.assertTrue(np.all(labels.asarray() == labels_str.asarray()))
 
-        # check that we can convert from more than strict 2D arrays of int32
+        # check that we can convert from more than strict 2D arrays of int64
         labels = Labels(
             names=["a", "b"],
             values=np.array([[0, 0]]),
@@ -67,7 +67,7 @@ class TestLabels(unittest.TestCase):
 
         labels = Labels(
             names=["a", "b"],
-            values=np.array([[0, 0]], dtype=np.int64),
+            values=np.array([[0, 0]], dtype=np.int32),
         )
 
         with self.assertRaises(TypeError) as cm:
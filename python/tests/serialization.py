@@ -6,7 +6,7 @@ import numpy as np
 from utils import test_tensor_map
 
 import equistore.io
-from equistore import TensorMap
+from equistore import Labels, TensorBlock, TensorMap
 
 
 ROOT = os.path.dirname(__file__)
@@ -78,6 +78,25 @@ class TestIo(unittest.TestCase):
         equistore.io.save(tmpfile, tensor, use_numpy=True)
         check_file(tmpfile, tensor)
 
+    def test_data_types(self):
+        tmpfile = os.path.join(tempfile.gettempdir(), "serialize-dtype-test.npz")
+
+        for dtype in [np.float64, np.float32, np.float16, np.int32, np.int64]:
+            block = TensorBlock(
+                values=np.arange(6, dtype=dtype).reshape(3, 2),
+                samples=Labels(["s"], np.array([[0], [1], [2]], dtype=np.int32)),
+                components=[],
+                properties=Labels(["p"], np.array([[0], [1]], dtype=np.int32)),
+            )
+            tensor = TensorMap(Labels.single(), [block])
+
+            equistore.io.save(tmpfile, tensor, use_numpy=False)
+            loaded = equistore.io.load(tmpfile, use_numpy=False)
+
+            values = loaded.block(0).values
+            self.assertEqual(values.dtype, dtype)
+            self.assertTrue(np.all(values == block.values))
+
 
 if __name__ == "__main__":
     unittest.main()